@@ -0,0 +1,289 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::{Local, NaiveDateTime};
+use thiserror::Error;
+
+use crate::database::{Database, DatabaseError};
+use crate::models::Attachment;
+use crate::store::AttachmentStore;
+
+/// Format of the time in snapshot file names, e.g. app-20240131-083000.db
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] DatabaseError),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("No backup number {0} (see `tnj backups list`)")]
+    NotFound(usize),
+}
+
+/// When the database is snapshotted automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackupFrequency {
+    Off,
+    Startup, // Every time tnj is started
+    #[default]
+    Daily,   // On the first start of a day
+}
+
+impl FromStr for BackupFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "never" => Ok(BackupFrequency::Off),
+            "startup" => Ok(BackupFrequency::Startup),
+            "daily" => Ok(BackupFrequency::Daily),
+            other => Err(format!("Unknown backup frequency '{}' (expected off, startup or daily)", other)),
+        }
+    }
+}
+
+impl BackupFrequency {
+    /// Value stored in the config file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupFrequency::Off => "off",
+            BackupFrequency::Startup => "startup",
+            BackupFrequency::Daily => "daily",
+        }
+    }
+}
+
+/// A snapshot of the database in the backups directory
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub created: NaiveDateTime, // Local time
+    pub size: u64,              // In bytes
+}
+
+/// The outcome of `restore`
+#[derive(Debug)]
+pub struct Restored {
+    pub snapshot: Snapshot,
+    pub missing_attachments: Vec<Attachment>, // Attachments of the restored database whose files are gone
+}
+
+/// Get the directory snapshots of the database file are kept in (next to it)
+/// e.g. app.db keeps its snapshots in app-backups/
+pub fn backups_dir(db_path: &Path) -> PathBuf {
+    db_path.with_file_name(format!("{}-backups", file_stem(db_path)))
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// Get the directory the attachment files of a snapshot are kept in (next to it, the way the
+/// database keeps its own), e.g. app-20240131-083000.db keeps them in app-20240131-083000-attachments/
+fn snapshot_attachments_dir(snapshot_path: &Path) -> PathBuf {
+    snapshot_path.with_file_name(format!("{}-attachments", file_stem(snapshot_path)))
+}
+
+/// Copy the attachment files the database references into `dest`
+/// Files are hard-linked where possible (they are never changed once written); missing ones are skipped.
+fn copy_attachment_files(db: &Database, dest: &Path) -> Result<(), BackupError> {
+    for attachment in db.dump_attachments()? {
+        let Some(stored_file) = attachment.stored_file else {
+            continue;
+        };
+        let source = db.attachments_dir().join(&stored_file);
+        if !source.is_file() {
+            continue;
+        }
+        fs::create_dir_all(dest)?;
+        let target = dest.join(&stored_file);
+        if !target.exists() && fs::hard_link(&source, &target).is_err() {
+            fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Put the attachment files of a snapshot back into `dest`, leaving files already there alone
+fn restore_attachment_files(snapshot_path: &Path, dest: &Path) -> Result<(), BackupError> {
+    let Ok(entries) = fs::read_dir(snapshot_attachments_dir(snapshot_path)) else {
+        return Ok(()); // The snapshot has no attachment files
+    };
+    for entry in entries {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        if entry.path().is_file() && !target.exists() {
+            fs::create_dir_all(dest)?;
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// List the snapshots of the database file, newest first
+pub fn list(db_path: &Path) -> Result<Vec<Snapshot>, BackupError> {
+    let dir = backups_dir(db_path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let prefix = format!("{}-", file_stem(db_path));
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Other files (e.g. an interrupted snapshot) are left alone
+        let Some(stamp) = name.strip_prefix(&prefix).and_then(|rest| rest.strip_suffix(".db")) else {
+            continue;
+        };
+        let Ok(created) = NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT) else {
+            continue;
+        };
+        snapshots.push(Snapshot { path: entry.path(), created, size: entry.metadata()?.len() });
+    }
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created));
+    Ok(snapshots)
+}
+
+/// Snapshot the database (with its attachment files) and delete the oldest snapshots beyond `keep`
+/// The snapshot is written to temporary files first, so an interrupted backup never looks complete.
+pub fn create(db: &Database, keep: u32) -> Result<Snapshot, BackupError> {
+    let dir = backups_dir(db.path());
+    fs::create_dir_all(&dir)?;
+
+    let created = Local::now().naive_local();
+    let name = format!("{}-{}.db", file_stem(db.path()), created.format(TIMESTAMP_FORMAT));
+    let path = dir.join(&name);
+    let partial = dir.join(format!("{}.partial", name));
+    let partial_attachments = dir.join(format!("{}.partial-attachments", name));
+    if partial.exists() {
+        fs::remove_file(&partial)?;
+    }
+    if partial_attachments.exists() {
+        fs::remove_dir_all(&partial_attachments)?;
+    }
+    let written = db.snapshot(&partial).map_err(BackupError::from)
+        .and_then(|_| copy_attachment_files(db, &partial_attachments));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial);
+        let _ = fs::remove_dir_all(&partial_attachments);
+        return Err(e);
+    }
+    let attachments = snapshot_attachments_dir(&path);
+    if attachments.exists() {
+        fs::remove_dir_all(&attachments)?; // Left by a snapshot taken in the same second
+    }
+    if partial_attachments.exists() {
+        fs::rename(&partial_attachments, &attachments)?;
+    }
+    fs::rename(&partial, &path)?;
+
+    prune(db, keep)?;
+    let size = fs::metadata(&path)?.len();
+    Ok(Snapshot { path, created, size })
+}
+
+/// Delete the oldest snapshots so that at most `keep` remain (at least one is always kept)
+pub fn prune(db: &Database, keep: u32) -> Result<(), BackupError> {
+    for snapshot in list(db.path())?.into_iter().skip(keep.max(1) as usize) {
+        fs::remove_file(&snapshot.path)?;
+        let attachments = snapshot_attachments_dir(&snapshot.path);
+        if attachments.exists() {
+            fs::remove_dir_all(attachments)?;
+        }
+    }
+    Ok(())
+}
+
+/// Snapshot the database if `frequency` calls for it; returns the snapshot taken, if any
+pub fn auto_backup(db: &Database, frequency: BackupFrequency, keep: u32) -> Result<Option<Snapshot>, BackupError> {
+    let due = match frequency {
+        BackupFrequency::Off => false,
+        BackupFrequency::Startup => true,
+        BackupFrequency::Daily => {
+            let today = Local::now().date_naive();
+            !list(db.path())?.iter().any(|snapshot| snapshot.created.date() == today)
+        }
+    };
+    if !due {
+        return Ok(None);
+    }
+    create(db, keep).map(Some)
+}
+
+/// Encrypt the unencrypted snapshots with `passphrase` (used when the database is encrypted, so
+/// no readable copy of it is left behind); returns the number of snapshots encrypted
+#[cfg(feature = "encryption")]
+pub fn encrypt(snapshots: &[Snapshot], passphrase: &str) -> Result<usize, BackupError> {
+    let mut encrypted = 0;
+    for snapshot in snapshots {
+        if Database::is_encrypted_file(&snapshot.path) {
+            continue;
+        }
+        Database::encrypt_file(&snapshot.path, passphrase)?;
+        encrypted += 1;
+    }
+    Ok(encrypted)
+}
+
+/// Replace the database with snapshot `number` (1 = newest, as listed by `list`)
+/// The current database is snapshotted first, so a restore can itself be undone. The snapshot's
+/// attachment files are put back too; restored attachments whose files are still missing are reported.
+pub fn restore(db: Database, number: usize, keep: u32) -> Result<Restored, BackupError> {
+    let snapshot = number
+        .checked_sub(1)
+        .and_then(|index| list(db.path()).map(|snapshots| snapshots.into_iter().nth(index)).transpose())
+        .transpose()?
+        .ok_or(BackupError::NotFound(number))?;
+
+    // Copy the snapshot aside first: snapshotting the current database may prune it
+    let file_name = db.path().file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let restoring = db.path().with_file_name(format!("{}.restoring", file_name));
+    fs::copy(&snapshot.path, &restoring)?;
+    // Files the current database doesn't reference are harmless, so this is safe to do before
+    // the snapshot below (which may prune the one being restored)
+    restore_attachment_files(&snapshot.path, db.attachments_dir())?;
+    if let Err(e) = create(&db, keep) {
+        let _ = fs::remove_file(&restoring);
+        return Err(e);
+    }
+    let missing_attachments = match db.replace_with(&restoring) {
+        Ok(db) => db
+            .dump_attachments()?
+            .into_iter()
+            .filter(|a| a.stored_file.as_ref().is_some_and(|f| !db.attachments_dir().join(f).is_file()))
+            .collect(),
+        // A snapshot taken under another passphrase can't be opened with this one to check
+        Err(DatabaseError::WrongPassphrase) => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(Restored { snapshot, missing_attachments })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AttachmentStorage, ItemKind, Task};
+    use crate::store::Store;
+
+    #[test]
+    fn restore_brings_back_attachment_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = tmp.path().join("app.db");
+        let db = Database::new(db_path.to_str().unwrap()).unwrap();
+        let task = db.insert_task(&Task::new("Send invoice".to_string())).unwrap();
+        let attachment = Attachment::new(ItemKind::Task, task, "invoice.pdf".to_string());
+        let id = db.add_attachment(&attachment, b"%PDF", AttachmentStorage::Directory).unwrap();
+        create(&db, 5).unwrap();
+
+        db.delete_attachment(id).unwrap();
+        assert!(fs::read_dir(db.attachments_dir()).unwrap().next().is_none());
+
+        let restored = restore(db, 1, 5).unwrap();
+        assert!(restored.missing_attachments.is_empty());
+        let db = Database::new(db_path.to_str().unwrap()).unwrap();
+        assert_eq!(db.read_attachment(&db.get_attachment(id).unwrap()).unwrap(), b"%PDF");
+    }
+}
@@ -1,9 +1,19 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::backups::BackupError;
+use crate::git_store::{GitError, Storage};
+use crate::server::{ServerError, ServerOptions};
+use crate::sync::SyncError;
+use crate::config::{Config, ConfigError};
 use crate::database::Database;
 use crate::database::DatabaseError;
-use crate::models::{Task, Note, JournalEntry};
+use crate::formats::FormatError;
+use crate::formats::json::RestoreMode;
+use crate::lock::{self, LockError, Unlocker};
+use crate::models::{Task, Note, JournalEntry, ItemKind, Attachment};
+use crate::store::{AttachmentStore, Backend, LockStore, Store};
 use crate::utils::{parse_date, get_current_date_string};
 
 #[derive(Parser)]
@@ -60,6 +70,235 @@ pub enum Commands {
         #[arg(long)]
         tags: Option<String>,
     },
+    /// Move items to another notebook
+    Move {
+        /// Item kind (task, note or journal)
+        kind: ItemKind,
+        /// IDs or UUIDs of the items to move
+        #[arg(required = true)]
+        ids: Vec<String>,
+        /// Name of the destination notebook
+        #[arg(long, required_unless_present = "no_notebook")]
+        notebook: Option<String>,
+        /// Move the items out of any notebook (to "[None]")
+        #[arg(long, conflicts_with = "notebook")]
+        no_notebook: bool,
+    },
+    /// Export items to files
+    Export {
+        #[command(subcommand)]
+        format: ExportFormat,
+    },
+    /// Import items from files
+    Import {
+        #[command(subcommand)]
+        format: ImportFormat,
+    },
+    /// Attach a file to a task, note or journal entry
+    Attach {
+        /// Item kind (task, note or journal)
+        kind: ItemKind,
+        /// ID or UUID of the item
+        id: String,
+        /// File to attach
+        file: PathBuf,
+    },
+    /// Remove an attachment
+    Detach {
+        /// Attachment ID (as shown by `tnj attachments`)
+        attachment_id: i64,
+    },
+    /// List the attachments of a task, note or journal entry
+    Attachments {
+        /// Item kind (task, note or journal)
+        kind: ItemKind,
+        /// ID or UUID of the item
+        id: String,
+    },
+    /// Open an attachment with the default application
+    OpenAttachment {
+        /// Attachment ID (as shown by `tnj attachments`)
+        attachment_id: i64,
+        /// Save a copy to this file instead of opening it
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Back up all notebooks and items (including archived and trashed) to a file
+    Backup {
+        /// Write a JSON backup to this file
+        #[arg(long, value_name = "FILE")]
+        json: PathBuf,
+    },
+    /// Restore a JSON backup created with `tnj backup --json`
+    Restore {
+        /// Backup file
+        file: PathBuf,
+        /// replace: delete all current data first; merge: keep current data and add what is missing
+        #[arg(long, default_value = "merge")]
+        mode: RestoreMode,
+    },
+    /// Encrypt the database with a passphrase (read from TNJ_PASSPHRASE or prompted for)
+    #[cfg_attr(not(feature = "encryption"), command(about = "Encrypt the database (needs tnj built with `--features encryption`)"))]
+    Encrypt,
+    /// Decrypt the database, storing it unencrypted again
+    #[cfg_attr(not(feature = "encryption"), command(about = "Decrypt the database (needs tnj built with `--features encryption`)"))]
+    Decrypt,
+    /// List or restore the rotating snapshots of the database
+    Backups {
+        #[command(subcommand)]
+        action: BackupsAction,
+    },
+    /// Exchange changes with other devices through the shared sync folder
+    Sync {
+        /// Sync through this folder instead of `sync_dir` from the config
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+    /// Serve a JSON API for tasks, notes, journal entries and notebooks on localhost
+    Serve {
+        /// Port to listen on (on 127.0.0.1)
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Require `Authorization: Bearer <TOKEN>` on every request (also read from TNJ_API_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Keep notebooks and items as Markdown files in a git repository and sync it with a remote
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GitAction {
+    /// Switch to git storage: write everything to a new repository and commit it
+    Init {
+        /// URL of the remote to push to and pull from (added as "origin")
+        #[arg(long, value_name = "URL")]
+        remote: Option<String>,
+    },
+    /// Switch to git storage by cloning an existing repository (current data is kept and committed on top)
+    Clone {
+        /// URL of the repository
+        url: String,
+    },
+    /// Commit local changes, merge the remote's and update the database
+    Pull,
+    /// Commit local changes and push them to the remote
+    Push,
+}
+
+#[derive(Subcommand)]
+pub enum BackupsAction {
+    /// List the snapshots of the database, newest first
+    List,
+    /// Replace the database with a snapshot (the current database is snapshotted first)
+    Restore {
+        /// Snapshot number, as shown by `tnj backups list` (1 = newest)
+        number: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportFormat {
+    /// One Markdown file with YAML front matter per item, in per-notebook folders
+    Markdown {
+        /// Output directory (created if missing)
+        dir: PathBuf,
+    },
+    /// All tasks as a todo.txt file (notebooks become +projects, tags become @contexts)
+    Todotxt {
+        /// Output file
+        file: PathBuf,
+    },
+    /// An iCalendar file with tasks as VTODO and journal entries as VJOURNAL components
+    Ics {
+        /// Output file
+        file: PathBuf,
+    },
+    /// All tasks as a CSV file with a header row
+    Csv {
+        /// Output file
+        file: PathBuf,
+    },
+    /// One org-mode file per notebook (tasks as TODO/DONE headlines, journal entries as a datetree)
+    Org {
+        /// Output directory (created if missing)
+        dir: PathBuf,
+    },
+    /// A static HTML site of notes and journal entries, styled with the active theme
+    Html {
+        /// Output directory (created if missing)
+        dir: PathBuf,
+        /// Only export this notebook
+        #[arg(long)]
+        notebook: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportFormat {
+    /// A directory tree of Markdown files with optional YAML front matter (folders become notebooks)
+    Markdown {
+        /// Directory to import
+        dir: PathBuf,
+        /// Show what would be imported without changing the database
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Tasks from a todo.txt file (+projects become notebooks, @contexts and key:value pairs become tags)
+    Todotxt {
+        /// File to import
+        file: PathBuf,
+        /// Show what would be imported without changing the database
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Tasks from VTODO and VEVENT components of an iCalendar file (re-imports update by UID)
+    Ics {
+        /// File to import
+        file: PathBuf,
+        /// Show what would be imported without changing the database
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Tasks from a Taskwarrior `task export` JSON file (projects become notebooks)
+    Taskwarrior {
+        /// File to import
+        file: PathBuf,
+        /// Show what would be imported without changing the database
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Tasks from a CSV file with a header row (rows with errors are reported and skipped)
+    Csv {
+        /// File to import
+        file: PathBuf,
+        /// Map columns to task fields, e.g. "Name=title,Due=due_date" (other columns are matched by name)
+        #[arg(long, value_name = "MAPPING")]
+        map: Option<String>,
+        /// Format of date columns, e.g. "%d/%m/%Y" (default: ISO dates)
+        #[arg(long, value_name = "FORMAT")]
+        date_format: Option<String>,
+        /// Show what would be imported without changing the database
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// An Obsidian or Logseq vault (notes, daily notes as journal entries, folders as notebooks)
+    Obsidian {
+        /// Vault directory
+        vault: PathBuf,
+        /// Folder with daily notes, relative to the vault (detected when not given)
+        #[arg(long, value_name = "FOLDER")]
+        daily_folder: Option<PathBuf>,
+        /// Also create a task for every `- [ ]` checklist line
+        #[arg(long)]
+        tasks: bool,
+        /// Show what would be imported without changing the database
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -68,6 +307,90 @@ pub enum CliError {
     DatabaseError(#[from] DatabaseError),
     #[error("Failed to parse date: {0}")]
     DateParseError(String),
+    #[error("Notebook not found: {0}")]
+    NotebookNotFound(String),
+    #[error("No {0} with UUID {1}")]
+    ItemNotFound(ItemKind, String),
+    #[error("{0}")]
+    FormatError(#[from] FormatError),
+    #[error("File error: {0}")]
+    FileError(String),
+    #[error("Failed to read passphrase: {0}")]
+    PassphraseError(String),
+    #[error("Locked entries: {0}")]
+    LockError(#[from] LockError),
+    #[error("Backup error: {0}")]
+    BackupError(#[from] BackupError),
+    #[error("Sync error: {0}")]
+    SyncError(#[from] SyncError),
+    #[error("Git error: {0}")]
+    GitError(#[from] GitError),
+    #[error("Config error: {0}")]
+    ConfigError(#[from] ConfigError),
+    #[error("Server error: {0}")]
+    ServerError(#[from] ServerError),
+    #[error("This build of tnj has no encryption support; install it with `cargo install tnj-tui --features encryption`")]
+    EncryptionUnsupported,
+}
+
+/// Environment variable the passphrase of an encrypted database is read from, if set
+#[cfg(feature = "encryption")]
+pub const PASSPHRASE_ENV: &str = "TNJ_PASSPHRASE";
+
+/// Environment variable the token of the API server is read from when `--token` is not given
+pub const API_TOKEN_ENV: &str = "TNJ_API_TOKEN";
+
+/// Get the passphrase of an encrypted database from `TNJ_PASSPHRASE`, else from stdin
+#[cfg(feature = "encryption")]
+pub fn read_passphrase() -> Result<String, CliError> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    crate::utils::read_passphrase("Passphrase: ").map_err(|e| CliError::PassphraseError(e.to_string()))
+}
+
+/// Environment variable the passphrase of locked entries is read from, if set
+pub const LOCK_PASSPHRASE_ENV: &str = "TNJ_LOCK_PASSPHRASE";
+
+/// Get the passphrase of locked entries from `TNJ_LOCK_PASSPHRASE`, else from stdin, and check it
+fn unlock(db: &dyn LockStore) -> Result<Unlocker, CliError> {
+    use std::io::IsTerminal;
+
+    let passphrase = match std::env::var(LOCK_PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => {
+            let read = |prompt| crate::utils::read_passphrase(prompt).map_err(|e| CliError::PassphraseError(e.to_string()));
+            let passphrase = read("Passphrase of locked entries: ")?;
+            // The first passphrase given is the one kept, so a typed one is entered twice
+            if std::io::stdin().is_terminal() && !lock::has_passphrase(db)? && read("Repeat passphrase: ")? != passphrase {
+                return Err(CliError::PassphraseError("The passphrases do not match".to_string()));
+            }
+            passphrase
+        }
+    };
+    if passphrase.is_empty() {
+        return Err(CliError::PassphraseError("The passphrase can't be empty".to_string()));
+    }
+    Ok(lock::open(db, &passphrase)?)
+}
+
+/// Get the unlocker for journal entries an import puts into locked notebooks (None if there are no
+/// locked notebooks, or in a dry run)
+fn import_unlocker(db: &dyn LockStore, dry_run: bool) -> Result<Option<Unlocker>, CliError> {
+    if dry_run || !db.get_all_notebooks()?.iter().any(|notebook| notebook.locked) {
+        return Ok(None);
+    }
+    unlock(db).map(Some)
+}
+
+/// Get the ID of an item given by its numeric ID or its UUID
+fn resolve_item_id(kind: ItemKind, id: &str, db: &dyn Store) -> Result<i64, CliError> {
+    let id = id.trim();
+    if let Ok(id) = id.parse::<i64>() {
+        return Ok(id);
+    }
+    db.find_by_uuid(kind.into(), &id.to_lowercase())?
+        .ok_or_else(|| CliError::ItemNotFound(kind, id.to_string()))
 }
 
 /// Handle the add-task command
@@ -75,7 +398,7 @@ pub fn handle_add_task(
     title: String,
     due: Option<String>,
     tags: Option<String>,
-    db: &Database,
+    db: &dyn Store,
 ) -> Result<(), CliError> {
     // Parse due date if provided
     let due_date = if let Some(due_str) = due {
@@ -97,7 +420,8 @@ pub fn handle_add_task(
 
     // Insert into database
     let id = db.insert_task(&task)?;
-    println!("Task created successfully (ID: {})", id);
+    let uuid = db.get_task(id)?.uuid.unwrap_or_default();
+    println!("Task created successfully (ID: {}, UUID: {})", id, uuid);
 
     Ok(())
 }
@@ -107,7 +431,7 @@ pub fn handle_add_note(
     title: String,
     content: Option<String>,
     tags: Option<String>,
-    db: &Database,
+    db: &dyn Store,
 ) -> Result<(), CliError> {
     // Create note
     let mut note = Note::new(title);
@@ -116,7 +440,8 @@ pub fn handle_add_note(
 
     // Insert into database
     let id = db.insert_note(&note)?;
-    println!("Note created successfully (ID: {})", id);
+    let uuid = db.get_note(id)?.uuid.unwrap_or_default();
+    println!("Note created successfully (ID: {}, UUID: {})", id, uuid);
 
     Ok(())
 }
@@ -126,7 +451,7 @@ pub fn handle_add_journal(
     content: String,
     title: Option<String>,
     tags: Option<String>,
-    db: &Database,
+    db: &dyn Store,
 ) -> Result<(), CliError> {
     // Use current date for journal entry
     let date = get_current_date_string();
@@ -139,8 +464,363 @@ pub fn handle_add_journal(
 
     // Insert into database
     let id = db.insert_journal(&journal)?;
-    println!("Journal entry created successfully (ID: {})", id);
+    let uuid = db.get_journal(id)?.uuid.unwrap_or_default();
+    println!("Journal entry created successfully (ID: {}, UUID: {})", id, uuid);
 
     Ok(())
 }
 
+/// Handle the move command
+pub fn handle_move(
+    kind: ItemKind,
+    ids: Vec<String>,
+    notebook: Option<String>,
+    db: &dyn LockStore,
+) -> Result<(), CliError> {
+    let ids = ids
+        .iter()
+        .map(|id| resolve_item_id(kind, id, db))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Resolve the destination notebook by name (None means "[None]")
+    let (notebook_id, notebook_name, locked) = match notebook {
+        Some(name) => {
+            let found = db.get_notebook_by_name(name.trim())?
+                .ok_or_else(|| CliError::NotebookNotFound(name.clone()))?;
+            (found.id, found.name, found.locked)
+        }
+        None => (None, "[None]".to_string(), false),
+    };
+
+    // Journal entries moved into a locked notebook are locked, as in the TUI
+    let mut to_lock = Vec::new();
+    if kind == ItemKind::Journal && locked {
+        let mut unlocker = unlock(db)?;
+        for &id in &ids {
+            let journal = db.get_journal(id)?;
+            if !journal.is_locked() {
+                to_lock.push((id, unlocker.lock(journal.content.as_deref().unwrap_or_default())?));
+            }
+        }
+    }
+
+    let transaction = db.transaction()?;
+    let moved = db.move_items_to_notebook(kind, &ids, notebook_id)?;
+    for (id, content) in &to_lock {
+        db.set_journal_content(*id, Some(content), true)?;
+    }
+    transaction.commit()?;
+    println!("Moved {} {} to {}", moved, kind.label(moved), notebook_name);
+
+    Ok(())
+}
+
+/// Handle the attach command
+pub fn handle_attach(kind: ItemKind, id: String, file: PathBuf, db: &dyn AttachmentStore, config: &Config) -> Result<(), CliError> {
+    let id = resolve_item_id(kind, &id, db)?;
+    let data = std::fs::read(&file).map_err(|e| CliError::FileError(format!("{}: {}", file.display(), e)))?;
+    let file_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| CliError::FileError(format!("{} is not a file", file.display())))?;
+
+    let attachment = Attachment::new(kind, id, file_name.clone());
+    let attachment_id = db.add_attachment(&attachment, &data, config.get_attachment_storage())?;
+    println!(
+        "Attached {} ({}) to {} {} (attachment ID: {})",
+        file_name,
+        crate::utils::format_file_size(data.len() as i64),
+        kind,
+        id,
+        attachment_id
+    );
+
+    Ok(())
+}
+
+/// Handle the detach command
+pub fn handle_detach(attachment_id: i64, db: &dyn AttachmentStore) -> Result<(), CliError> {
+    let attachment = db.delete_attachment(attachment_id)?;
+    println!(
+        "Removed attachment {} from {} {}",
+        attachment.file_name, attachment.item_kind, attachment.item_id
+    );
+
+    Ok(())
+}
+
+/// Handle the attachments command
+pub fn handle_attachments(kind: ItemKind, id: String, db: &dyn AttachmentStore) -> Result<(), CliError> {
+    let id = resolve_item_id(kind, &id, db)?;
+    let attachments = db.get_attachments(kind, id)?;
+    if attachments.is_empty() {
+        println!("No attachments on {} {}", kind, id);
+    }
+    for attachment in attachments {
+        println!(
+            "{:>5}  {}  ({}, added {})",
+            attachment.id.unwrap_or_default(),
+            attachment.file_name,
+            crate::utils::format_file_size(attachment.size),
+            attachment.created_at
+        );
+    }
+
+    Ok(())
+}
+
+/// Handle the open-attachment command
+pub fn handle_open_attachment(attachment_id: i64, output: Option<PathBuf>, db: &dyn AttachmentStore) -> Result<(), CliError> {
+    let attachment = db.get_attachment(attachment_id)?;
+    match output {
+        Some(path) => {
+            let data = db.read_attachment(&attachment)?;
+            std::fs::write(&path, data).map_err(|e| CliError::FileError(format!("{}: {}", path.display(), e)))?;
+            println!("Saved {} to {}", attachment.file_name, path.display());
+        }
+        None => {
+            let path = db.attachment_path(&attachment)?;
+            crate::utils::open_with_default_app(&path)
+                .map_err(|e| CliError::FileError(format!("Failed to open {}: {}", path.display(), e)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the export command
+pub fn handle_export(format: ExportFormat, db: &dyn AttachmentStore, config: &Config) -> Result<(), CliError> {
+    match format {
+        ExportFormat::Markdown { dir } => {
+            let summary = crate::formats::markdown::export(db, &dir)?;
+            println!("Exported {} to {}", summary, dir.display());
+        }
+        ExportFormat::Todotxt { file } => {
+            let summary = crate::formats::todotxt::export(db, &file)?;
+            println!("Exported {} to {}", summary, file.display());
+        }
+        ExportFormat::Ics { file } => {
+            let summary = crate::formats::ics::export(db, &file)?;
+            println!("Exported {} to {}", summary, file.display());
+        }
+        ExportFormat::Csv { file } => {
+            let summary = crate::formats::csv::export(db, &file)?;
+            println!("Exported {} to {}", summary, file.display());
+        }
+        ExportFormat::Org { dir } => {
+            let summary = crate::formats::org::export(db, &dir)?;
+            println!("Exported {} to {}", summary, dir.display());
+        }
+        ExportFormat::Html { dir, notebook } => {
+            let theme = config.get_active_theme();
+            let summary = crate::formats::html::export(db, &dir, notebook.as_deref(), &theme)?;
+            println!("Exported {} to {}", summary, dir.join("index.html").display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the import command
+pub fn handle_import(format: ImportFormat, db: &dyn LockStore) -> Result<(), CliError> {
+    let summary = match format {
+        ImportFormat::Markdown { dir, dry_run } => {
+            crate::formats::markdown::import(db, &dir, dry_run, import_unlocker(db, dry_run)?)?
+        }
+        ImportFormat::Todotxt { file, dry_run } => crate::formats::todotxt::import(db, &file, dry_run)?,
+        ImportFormat::Ics { file, dry_run } => crate::formats::ics::import(db, &file, dry_run)?,
+        ImportFormat::Taskwarrior { file, dry_run } => crate::formats::taskwarrior::import(db, &file, dry_run)?,
+        ImportFormat::Csv { file, map, date_format, dry_run } => {
+            let options = crate::formats::csv::CsvOptions { map, date_format, dry_run };
+            crate::formats::csv::import(db, &file, &options)?
+        }
+        ImportFormat::Obsidian { vault, daily_folder, tasks, dry_run } => {
+            let options = crate::formats::obsidian::ObsidianOptions {
+                daily_folder,
+                extract_tasks: tasks,
+                dry_run,
+            };
+            crate::formats::obsidian::import(db, &vault, &options, import_unlocker(db, dry_run)?)?
+        }
+    };
+
+    for warning in &summary.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    for error in &summary.errors {
+        eprintln!("Error: {}", error);
+    }
+    println!("{}", summary);
+
+    Ok(())
+}
+
+/// Handle the backup command
+pub fn handle_backup(json: PathBuf, db: &dyn Backend) -> Result<(), CliError> {
+    let counts = crate::formats::json::backup(db, &json)?;
+    println!("Backed up {} to {}", counts, json.display());
+
+    Ok(())
+}
+
+/// Handle the restore command
+pub fn handle_restore(file: PathBuf, mode: RestoreMode, db: &dyn Backend, config: &Config) -> Result<(), CliError> {
+    let summary = crate::formats::json::restore(db, &file, mode, config.get_attachment_storage())?;
+    println!("{}", summary);
+
+    Ok(())
+}
+
+/// Handle the encrypt command
+#[cfg(feature = "encryption")]
+pub fn handle_encrypt(db: Database) -> Result<(), CliError> {
+    use std::io::IsTerminal;
+
+    let passphrase = match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => {
+            let read = |prompt| crate::utils::read_passphrase(prompt).map_err(|e| CliError::PassphraseError(e.to_string()));
+            let passphrase = read("New passphrase: ")?;
+            // A typo would lock the data away, so typed passphrases are entered twice
+            if std::io::stdin().is_terminal() && read("Repeat passphrase: ")? != passphrase {
+                return Err(CliError::PassphraseError("The passphrases do not match".to_string()));
+            }
+            passphrase
+        }
+    };
+
+    let snapshots = crate::backups::list(db.path())?;
+    db.encrypt(&passphrase)?;
+    println!("Encrypted the database. Keep the passphrase safe: the data cannot be recovered without it.");
+    let encrypted = crate::backups::encrypt(&snapshots, &passphrase)?;
+    if encrypted > 0 {
+        println!("Encrypted {} backup(s) with the same passphrase", encrypted);
+    }
+
+    Ok(())
+}
+
+/// Handle the decrypt command
+#[cfg(feature = "encryption")]
+pub fn handle_decrypt(db: Database) -> Result<(), CliError> {
+    db.decrypt()?;
+    println!("Decrypted the database");
+
+    Ok(())
+}
+
+/// Handle the encrypt command in a build without encryption support
+#[cfg(not(feature = "encryption"))]
+pub fn handle_encrypt(_db: Database) -> Result<(), CliError> {
+    Err(CliError::EncryptionUnsupported)
+}
+
+/// Handle the decrypt command in a build without encryption support (such a build cannot open an
+/// encrypted database, so there is nothing to decrypt)
+#[cfg(not(feature = "encryption"))]
+pub fn handle_decrypt(_db: Database) -> Result<(), CliError> {
+    Err(CliError::EncryptionUnsupported)
+}
+
+/// Handle the backups command
+pub fn handle_backups(action: BackupsAction, db: Database, config: &Config) -> Result<(), CliError> {
+    match action {
+        BackupsAction::List => {
+            let snapshots = crate::backups::list(db.path())?;
+            if snapshots.is_empty() {
+                println!("No backups in {}", crate::backups::backups_dir(db.path()).display());
+            }
+            for (index, snapshot) in snapshots.iter().enumerate() {
+                println!(
+                    "{:>3}  {}  ({})  {}",
+                    index + 1,
+                    snapshot.created.format("%Y-%m-%d %H:%M:%S"),
+                    crate::utils::format_file_size(snapshot.size as i64),
+                    snapshot.path.display()
+                );
+            }
+        }
+        BackupsAction::Restore { number } => {
+            let restored = crate::backups::restore(db, number, config.backup_count)?;
+            println!(
+                "Restored the backup from {}. The previous database was backed up first.",
+                restored.snapshot.created.format("%Y-%m-%d %H:%M:%S")
+            );
+            for attachment in &restored.missing_attachments {
+                eprintln!(
+                    "Warning: the file of attachment '{}' ({} {}) is missing",
+                    attachment.file_name, attachment.item_kind, attachment.item_id
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the sync command
+pub fn handle_sync(dir: Option<PathBuf>, db: &dyn Backend, config: &Config) -> Result<(), CliError> {
+    let dir = dir.or_else(|| config.get_sync_dir()).ok_or(SyncError::NoSyncDir)?;
+    let summary = crate::sync::sync(db, &dir)?;
+    println!("{}", summary);
+    let conflicts = db.get_sync_conflicts()?.len();
+    if conflicts > 0 {
+        println!(
+            "{} unresolved conflict{}: review them in the TUI with {}",
+            conflicts,
+            if conflicts == 1 { "" } else { "s" },
+            crate::utils::format_key_binding_for_display(&config.key_bindings.sync_conflicts)
+        );
+    }
+
+    Ok(())
+}
+
+/// Handle the serve command (runs until the process is stopped)
+pub fn handle_serve(port: u16, token: Option<String>, db: &dyn Backend, config: &Config) -> Result<(), CliError> {
+    let token = token
+        .or_else(|| std::env::var(API_TOKEN_ENV).ok())
+        .filter(|token| !token.trim().is_empty());
+    let options = ServerOptions {
+        port,
+        git_dir: (config.get_storage() == Storage::Git).then(|| config.get_git_dir()),
+        token,
+    };
+    println!(
+        "Serving the tnj API on http://127.0.0.1:{}{} (press Ctrl+C to stop)",
+        port,
+        if options.token.is_some() { " with token authentication" } else { "" }
+    );
+    crate::server::serve(db, &options)?;
+
+    Ok(())
+}
+
+/// Handle the git subcommands
+pub fn handle_git(action: GitAction, db: &dyn Backend, config: &mut Config, profile: crate::Profile) -> Result<(), CliError> {
+    let dir = config.get_git_dir();
+    match action {
+        GitAction::Init { remote } => {
+            crate::git_store::init(db, &dir, remote.as_deref())?;
+            config.storage = Storage::Git.as_str().to_string();
+            config.save_with_profile(profile)?;
+            println!("Notebooks and items are now stored in {} and committed on every change", dir.display());
+        }
+        GitAction::Clone { url } => {
+            let summary = crate::git_store::clone(db, &url, &dir)?;
+            config.storage = Storage::Git.as_str().to_string();
+            config.save_with_profile(profile)?;
+            println!("Cloned {} into {}: {}", url, dir.display(), summary);
+        }
+        GitAction::Pull => {
+            let summary = crate::git_store::pull(db, &dir)?;
+            println!("{}", summary);
+        }
+        GitAction::Push => {
+            crate::git_store::push(db, &dir)?;
+            println!("Pushed {}", dir.display());
+        }
+    }
+
+    Ok(())
+}
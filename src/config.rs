@@ -29,6 +29,35 @@ pub struct Config {
     pub color_overrides: Option<Theme>,
     #[serde(default = "default_current_notebook_id")]
     pub current_notebook_id: Option<i64>,
+    /// Days a deleted item stays in the trash before it is purged (0 = keep forever)
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// Where attached files are stored: "database" (inside the SQLite file) or "directory"
+    /// (as files in a folder next to it); applies to newly attached files. An encrypted database
+    /// always stores them inside.
+    #[serde(default = "default_attachment_storage")]
+    pub attachment_storage: String,
+    /// Minutes without a key press after which unlocked journal entries are locked again (0 = never)
+    #[serde(default = "default_lock_timeout_minutes")]
+    pub lock_timeout_minutes: u32,
+    /// When the database is snapshotted automatically: "off", "startup" (every launch) or "daily"
+    /// (on the first launch of a day)
+    #[serde(default = "default_auto_backup")]
+    pub auto_backup: String,
+    /// Number of rotating database snapshots to keep; older ones are deleted
+    #[serde(default = "default_backup_count")]
+    pub backup_count: u32,
+    /// Folder shared between devices (e.g. with Syncthing or Dropbox) that `tnj sync` exchanges
+    /// changes through; empty to disable sync
+    #[serde(default)]
+    pub sync_dir: String,
+    /// Where the data lives: "sqlite" (the database) or "git" (Markdown files in a git repository,
+    /// committed on every change, with the database as their index)
+    #[serde(default = "default_storage")]
+    pub storage: String,
+    /// Repository of git storage; empty for a folder next to the database
+    #[serde(default)]
+    pub git_dir: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +98,8 @@ pub struct KeyBindings {
     pub help: String,
     #[serde(default = "default_undo")]
     pub undo: String,
+    #[serde(default = "default_redo")]
+    pub redo: String,
     #[serde(default = "default_word_left")]
     pub word_left: String,
     #[serde(default = "default_word_right")]
@@ -83,6 +114,32 @@ pub struct KeyBindings {
     pub filter: String,
     #[serde(default = "default_notebook_modal")]
     pub notebook_modal: String,
+    #[serde(default = "default_move_to_notebook")]
+    pub move_to_notebook: String,
+    #[serde(default = "default_multi_select")]
+    pub multi_select: String,
+    #[serde(default = "default_toggle_selection")]
+    pub toggle_selection: String,
+    #[serde(default = "default_select_all")]
+    pub select_all: String,
+    #[serde(default = "default_trash")]
+    pub trash: String,
+    #[serde(default = "default_history")]
+    pub history: String,
+    #[serde(default = "default_next_link")]
+    pub next_link: String,
+    #[serde(default = "default_previous_link")]
+    pub previous_link: String,
+    #[serde(default = "default_follow_link")]
+    pub follow_link: String,
+    #[serde(default = "default_attachments")]
+    pub attachments: String,
+    #[serde(default = "default_lock")]
+    pub lock: String,
+    #[serde(default = "default_unlock")]
+    pub unlock: String,
+    #[serde(default = "default_sync_conflicts")]
+    pub sync_conflicts: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,6 +179,14 @@ impl Default for Config {
             config_version: Some(CURRENT_CONFIG_VERSION),
             color_overrides: None,
             current_notebook_id: default_current_notebook_id(),
+            trash_retention_days: default_trash_retention_days(),
+            attachment_storage: default_attachment_storage(),
+            lock_timeout_minutes: default_lock_timeout_minutes(),
+            auto_backup: default_auto_backup(),
+            backup_count: default_backup_count(),
+            sync_dir: String::new(),
+            storage: default_storage(),
+            git_dir: String::new(),
         }
     }
 }
@@ -147,6 +212,7 @@ impl Default for KeyBindings {
             tab_4: default_tab_4(),
             help: default_help(),
             undo: default_undo(),
+            redo: default_redo(),
             word_left: default_word_left(),
             word_right: default_word_right(),
             settings: default_settings(),
@@ -154,6 +220,19 @@ impl Default for KeyBindings {
             toggle_list_view: default_toggle_list_view(),
             filter: default_filter(),
             notebook_modal: default_notebook_modal(),
+            move_to_notebook: default_move_to_notebook(),
+            multi_select: default_multi_select(),
+            toggle_selection: default_toggle_selection(),
+            select_all: default_select_all(),
+            trash: default_trash(),
+            history: default_history(),
+            next_link: default_next_link(),
+            previous_link: default_previous_link(),
+            follow_link: default_follow_link(),
+            attachments: default_attachments(),
+            lock: default_lock(),
+            unlock: default_unlock(),
+            sync_conflicts: default_sync_conflicts(),
         }
     }
 }
@@ -309,6 +388,10 @@ fn default_undo() -> String {
     "Ctrl+z".to_string()
 }
 
+fn default_redo() -> String {
+    "Ctrl+y".to_string()
+}
+
 fn default_word_left() -> String {
     "Ctrl+Left".to_string()
 }
@@ -337,6 +420,58 @@ fn default_notebook_modal() -> String {
     "Ctrl+n".to_string()
 }
 
+fn default_move_to_notebook() -> String {
+    "m".to_string()
+}
+
+fn default_multi_select() -> String {
+    "v".to_string()
+}
+
+fn default_toggle_selection() -> String {
+    "Space".to_string()
+}
+
+fn default_select_all() -> String {
+    "a".to_string()
+}
+
+fn default_trash() -> String {
+    "T".to_string()
+}
+
+fn default_history() -> String {
+    "h".to_string()
+}
+
+fn default_next_link() -> String {
+    "]".to_string()
+}
+
+fn default_previous_link() -> String {
+    "[".to_string()
+}
+
+fn default_follow_link() -> String {
+    "o".to_string()
+}
+
+fn default_attachments() -> String {
+    "A".to_string()
+}
+
+fn default_lock() -> String {
+    "L".to_string()
+}
+
+fn default_unlock() -> String {
+    "U".to_string()
+}
+
+fn default_sync_conflicts() -> String {
+    "C".to_string()
+}
+
 fn default_fg() -> String {
     "white".to_string()
 }
@@ -369,6 +504,30 @@ fn default_current_notebook_id() -> Option<i64> {
     None
 }
 
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_attachment_storage() -> String {
+    "database".to_string()
+}
+
+fn default_lock_timeout_minutes() -> u32 {
+    5
+}
+
+fn default_auto_backup() -> String {
+    "daily".to_string()
+}
+
+fn default_backup_count() -> u32 {
+    7
+}
+
+fn default_storage() -> String {
+    "sqlite".to_string()
+}
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("Failed to read config directory: {0}")]
@@ -483,6 +642,42 @@ impl Config {
         utils::expand_path(&self.database_path)
     }
 
+    /// Get where new attachments are stored (an unknown setting falls back to the database)
+    pub fn get_attachment_storage(&self) -> crate::models::AttachmentStorage {
+        self.attachment_storage.parse().unwrap_or_default()
+    }
+
+    /// Get the expanded sync folder (None when sync is not set up)
+    pub fn get_sync_dir(&self) -> Option<PathBuf> {
+        if self.sync_dir.trim().is_empty() {
+            None
+        } else {
+            Some(utils::expand_path(self.sync_dir.trim()))
+        }
+    }
+
+    /// Get where the data lives (an unknown setting falls back to the database)
+    pub fn get_storage(&self) -> crate::git_store::Storage {
+        self.storage.parse().unwrap_or_default()
+    }
+
+    /// Get the expanded repository of git storage (a folder next to the database when not set)
+    /// e.g. app.db is stored in app-git/
+    pub fn get_git_dir(&self) -> PathBuf {
+        if self.git_dir.trim().is_empty() {
+            let db_path = self.get_database_path();
+            let stem = db_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            db_path.with_file_name(format!("{}-git", stem))
+        } else {
+            utils::expand_path(self.git_dir.trim())
+        }
+    }
+
+    /// Get when the database is snapshotted automatically (an unknown setting falls back to daily)
+    pub fn get_auto_backup(&self) -> crate::backups::BackupFrequency {
+        self.auto_backup.parse().unwrap_or_default()
+    }
+
     /// Get the currently active theme
     /// If highlight_fg is not set (empty string), it will be calculated from highlight_bg
     pub fn get_active_theme(&self) -> Theme {
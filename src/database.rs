@@ -1,8 +1,12 @@
-use rusqlite::Connection;
-use std::path::PathBuf;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::models::{Task, Note, JournalEntry, Notebook};
+use crate::links::{parse_links, LinkTarget};
+use crate::models::{Task, Note, JournalEntry, Notebook, ItemKind, Revision, Attachment, AttachmentStorage, SyncKind, SyncState, SyncConflict};
+use crate::store::{AttachmentStore, Commit, HistoryStore, LinkStore, LockStore, Store, SyncStore, Transaction, temporary_attachment_file, uuid_or_new};
 
 #[derive(Debug, Error)]
 pub enum DatabaseError {
@@ -10,16 +14,117 @@ pub enum DatabaseError {
     SqliteError(#[from] rusqlite::Error),
     #[error("Failed to create database directory: {0}")]
     DirectoryError(String),
+    #[error("Item not found: {0}")]
+    ItemNotFound(String),
+    #[error("The row has no ID")]
+    MissingId,
+    #[error("Attachment file error: {0}")]
+    AttachmentFileError(String),
+    #[error("The database is encrypted; a passphrase is required to open it")]
+    PassphraseRequired,
+    #[error("Wrong passphrase for the encrypted database")]
+    WrongPassphrase,
+    #[error("Encryption error: {0}")]
+    EncryptionError(String),
+    #[error("The database is encrypted, but tnj was built without encryption support (build it with `--features encryption`)")]
+    EncryptionUnsupported,
+}
+
+/// First bytes of every unencrypted SQLite file; encrypted files start with random salt instead
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Check if a column exists in a table (used by schema migrations)
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool, DatabaseError> {
+    let mut stmt = conn.prepare(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2"
+    )?;
+    let count: i64 = stmt.query_row(rusqlite::params![table, column], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
+/// Get the table name that stores items of the given kind
+fn table_name(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Task => "tasks",
+        ItemKind::Note => "notes",
+        ItemKind::Journal => "journals",
+    }
+}
+
+/// Get the temporary file a database file is converted into, e.g. app.db.converting
+#[cfg(feature = "encryption")]
+fn converting_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    path.with_file_name(format!("{}.converting", file_name))
+}
+
+/// Copy the database open on `conn` into a new file at `dest` keyed with `key` (unencrypted for "")
+/// An incomplete copy is removed.
+#[cfg(feature = "encryption")]
+fn export_keyed(conn: &Connection, dest: &Path, key: &str) -> Result<(), DatabaseError> {
+    if dest.exists() {
+        fs::remove_file(dest).map_err(|e| DatabaseError::EncryptionError(e.to_string()))?;
+    }
+
+    conn.execute(
+        "ATTACH DATABASE ?1 AS converted KEY ?2",
+        rusqlite::params![dest.to_string_lossy(), key],
+    )?;
+    let exported = conn.query_row("SELECT sqlcipher_export('converted')", [], |_| Ok(()));
+    conn.execute("DETACH DATABASE converted", [])?;
+    if let Err(e) = exported {
+        let _ = fs::remove_file(dest);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+impl Commit for rusqlite::Transaction<'_> {
+    fn commit(self: Box<Self>) -> Result<(), DatabaseError> {
+        (*self).commit()?;
+        Ok(())
+    }
 }
 
 pub struct Database {
     conn: Connection,
+    path: PathBuf,
+    attachments_dir: PathBuf, // Where attachments stored as files are kept (next to the database file)
+    encrypted: bool,
+    key: Option<String>, // Passphrase of an encrypted database, used to key its snapshots
 }
 
 impl Database {
     /// Create a new database connection and initialize the schema
     pub fn new(path: &str) -> Result<Self, DatabaseError> {
+        Self::connect(path, None)
+    }
+
+    /// Check whether the database file at `path` is encrypted (a missing or empty file is not)
+    pub fn is_encrypted_file(path: &Path) -> bool {
+        let mut header = [0u8; 16];
+        match fs::File::open(path).and_then(|mut file| std::io::Read::read_exact(&mut file, &mut header)) {
+            Ok(()) => header != SQLITE_HEADER,
+            Err(_) => false,
+        }
+    }
+
+    /// Open a database, unlocking it with `passphrase` when it is encrypted
+    /// The key is derived from the passphrase by SQLCipher (PBKDF2-HMAC-SHA512).
+    #[cfg(feature = "encryption")]
+    pub fn open(path: &str, passphrase: Option<&str>) -> Result<Self, DatabaseError> {
+        Self::connect(path, passphrase)
+    }
+
+    fn connect(path: &str, passphrase: Option<&str>) -> Result<Self, DatabaseError> {
         let db_path = PathBuf::from(path);
+        let encrypted = Self::is_encrypted_file(&db_path);
+        if encrypted && !cfg!(feature = "encryption") {
+            return Err(DatabaseError::EncryptionUnsupported);
+        }
+        if encrypted && passphrase.is_none() {
+            return Err(DatabaseError::PassphraseRequired);
+        }
 
         // Create parent directory if it doesn't exist
         if let Some(parent) = db_path.parent() {
@@ -31,13 +136,107 @@ impl Database {
 
         // Open or create the database
         let conn = Connection::open(&db_path)?;
+        // SQLCipher would otherwise log failed decryptions (a wrong passphrase) to stderr
+        #[cfg(feature = "encryption")]
+        conn.query_row("PRAGMA cipher_log_level = NONE", [], |_| Ok(())).optional()?;
+        if encrypted && let Some(passphrase) = passphrase {
+            if passphrase.is_empty() {
+                return Err(DatabaseError::WrongPassphrase);
+            }
+            conn.pragma_update(None, "key", passphrase)?;
+            // A wrong key only shows when the first page is read
+            if conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0)).is_err() {
+                return Err(DatabaseError::WrongPassphrase);
+            }
+        }
+        // e.g. app.db keeps its attachment files in app-attachments/
+        let stem = db_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let attachments_dir = db_path.with_file_name(format!("{}-attachments", stem));
 
-        let db = Database { conn };
+        let key = if encrypted { passphrase.map(str::to_string) } else { None };
+        let db = Database { conn, path: db_path, attachments_dir, encrypted, key };
         db.initialize_schema()?;
 
         Ok(db)
     }
 
+    /// Check whether the database is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Encrypt the database file with a key derived from `passphrase`
+    /// Attachments stored as files are moved into the database first, so their contents are
+    /// encrypted as well.
+    #[cfg(feature = "encryption")]
+    pub fn encrypt(self, passphrase: &str) -> Result<(), DatabaseError> {
+        if self.encrypted {
+            return Err(DatabaseError::EncryptionError("The database is already encrypted".to_string()));
+        }
+        if passphrase.is_empty() {
+            return Err(DatabaseError::EncryptionError("The passphrase must not be empty".to_string()));
+        }
+        self.move_attachment_files_into_database()?;
+        self.convert(passphrase)
+    }
+
+    /// Decrypt the database file, which was opened with its passphrase
+    #[cfg(feature = "encryption")]
+    pub fn decrypt(self) -> Result<(), DatabaseError> {
+        if !self.encrypted {
+            return Err(DatabaseError::EncryptionError("The database is not encrypted".to_string()));
+        }
+        self.convert("")
+    }
+
+    /// Copy the database into a new file keyed with `key` (unencrypted for "") and replace the
+    /// database file with it; the original is only replaced once the copy is complete
+    #[cfg(feature = "encryption")]
+    fn convert(self, key: &str) -> Result<(), DatabaseError> {
+        let converted = converting_path(&self.path);
+        export_keyed(&self.conn, &converted, key)?;
+
+        let Database { conn, path, .. } = self;
+        conn.close().map_err(|(_, e)| e)?;
+        fs::rename(&converted, &path).map_err(|e| DatabaseError::EncryptionError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Encrypt an unencrypted database file that is not open (e.g. a snapshot) with a key derived
+    /// from `passphrase`, replacing the file once the encrypted copy is complete
+    #[cfg(feature = "encryption")]
+    pub fn encrypt_file(path: &Path, passphrase: &str) -> Result<(), DatabaseError> {
+        let converted = converting_path(path);
+        let conn = Connection::open(path)?;
+        export_keyed(&conn, &converted, passphrase)?;
+        conn.close().map_err(|(_, e)| e)?;
+        fs::rename(&converted, path).map_err(|e| DatabaseError::EncryptionError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Move the contents of attachments stored as files into the database
+    #[cfg(feature = "encryption")]
+    fn move_attachment_files_into_database(&self) -> Result<(), DatabaseError> {
+        let stored: Vec<Attachment> = self.dump_attachments()?
+            .into_iter()
+            .filter(|attachment| attachment.stored_file.is_some())
+            .collect();
+        if stored.is_empty() {
+            return Ok(());
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        for attachment in &stored {
+            let data = self.read_attachment(attachment)?;
+            tx.execute(
+                "UPDATE attachments SET data = ?1, stored_file = NULL WHERE id = ?2",
+                rusqlite::params![data, attachment.id],
+            )?;
+        }
+        tx.commit()?;
+        self.remove_unused_attachment_files()
+    }
+
     /// Initialize the database schema (tables and indexes)
     fn initialize_schema(&self) -> Result<(), DatabaseError> {
         // Create tasks table
@@ -92,7 +291,100 @@ impl Database {
                 id              INTEGER PRIMARY KEY AUTOINCREMENT,
                 name            TEXT NOT NULL,
                 created_at      TEXT NOT NULL,
-                updated_at      TEXT NOT NULL
+                updated_at      TEXT NOT NULL,
+                locked          INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // Create settings table (values stored with the data, e.g. the locked entries' passphrase check)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key             TEXT PRIMARY KEY,
+                value           TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create revisions table (earlier versions of notes and journal entries)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS revisions (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                item_kind       TEXT NOT NULL,
+                item_id         INTEGER NOT NULL,
+                title           TEXT,
+                content         TEXT,
+                saved_at        TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create import_sources table (where imported items came from, to avoid duplicate imports)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS import_sources (
+                source          TEXT PRIMARY KEY,
+                item_kind       TEXT NOT NULL,
+                item_id         INTEGER NOT NULL,
+                imported_at     TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create attachments table (files attached to items; the contents are either in `data` or
+        // in the attachments directory under `stored_file`)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS attachments (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                item_kind       TEXT NOT NULL,
+                item_id         INTEGER NOT NULL,
+                file_name       TEXT NOT NULL,
+                size            INTEGER NOT NULL,
+                data            BLOB,
+                stored_file     TEXT,
+                created_at      TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create sync_state table (for every synced item, the version it is at and a hash of its
+        // contents at that version; a NULL hash means the item was deleted)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS sync_state (
+                uuid            TEXT PRIMARY KEY,
+                kind            TEXT NOT NULL,
+                clock           INTEGER NOT NULL,
+                device          TEXT NOT NULL,
+                versions        TEXT NOT NULL,
+                hash            TEXT
+            )",
+            [],
+        )?;
+
+        // Create sync_conflicts table (versions that lost when two devices changed an item at once)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS sync_conflicts (
+                id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind            TEXT NOT NULL,
+                uuid            TEXT NOT NULL,
+                title           TEXT NOT NULL,
+                other_version   TEXT,
+                other_device    TEXT NOT NULL,
+                created_at      TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create links table ([[...]] links in item text, rebuilt whenever an item is saved)
+        let links_table_existed: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'links'",
+            [],
+            |row| row.get(0),
+        )?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS links (
+                source_kind     TEXT NOT NULL,
+                source_id       INTEGER NOT NULL,
+                target          TEXT NOT NULL
             )",
             [],
         )?;
@@ -128,23 +420,62 @@ impl Database {
             [],
         )?;
 
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_revisions_item ON revisions(item_kind, item_id)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_attachments_item ON attachments(item_kind, item_id)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_links_source ON links(source_kind, source_id)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_links_target ON links(target)",
+            [],
+        )?;
+
         // Migrate existing tables to add notebook_id column if it doesn't exist
         self.migrate_add_notebook_id()?;
 
+        // Migrate existing tables to add deleted_at column (trash) if it doesn't exist
+        self.migrate_add_deleted_at()?;
+
+        // Migrate the notebooks table to add the locked column if it doesn't exist
+        if !column_exists(&self.conn, "notebooks", "locked")? {
+            self.conn.execute(
+                "ALTER TABLE notebooks ADD COLUMN locked INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+
+        // Migrate existing tables to add the uuid column (identifies items across databases and devices)
+        for table in ["notebooks", "tasks", "notes", "journals"] {
+            if !column_exists(&self.conn, table, "uuid")? {
+                self.conn.execute(&format!("ALTER TABLE {} ADD COLUMN uuid TEXT", table), [])?;
+            }
+            self.conn.execute(
+                &format!("CREATE UNIQUE INDEX IF NOT EXISTS idx_{}_uuid ON {}(uuid)", table, table),
+                [],
+            )?;
+        }
+        self.assign_missing_uuids()?;
+
+        // Index the links of items saved before the links table existed
+        if links_table_existed == 0 {
+            self.rebuild_links()?;
+        }
+
         Ok(())
     }
 
     /// Migrate existing tables to add notebook_id column
     fn migrate_add_notebook_id(&self) -> Result<(), DatabaseError> {
-        // Helper to check if a column exists
-        fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool, DatabaseError> {
-            let mut stmt = conn.prepare(
-                "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2"
-            )?;
-            let count: i64 = stmt.query_row(rusqlite::params![table, column], |row| row.get(0))?;
-            Ok(count > 0)
-        }
-
         // Add notebook_id to tasks table if it doesn't exist
         if !column_exists(&self.conn, "tasks", "notebook_id")? {
             self.conn.execute(
@@ -184,72 +515,23 @@ impl Database {
         Ok(())
     }
 
-    /// Get a reference to the underlying connection
-    pub fn conn(&self) -> &Connection {
-        &self.conn
-    }
-
-    /// Get a mutable reference to the underlying connection
-    pub fn conn_mut(&mut self) -> &mut Connection {
-        &mut self.conn
-    }
-
-    /// Insert a task into the database and return its ID
-    pub fn insert_task(&self, task: &Task) -> Result<i64, DatabaseError> {
-        self.conn.execute(
-            "INSERT INTO tasks (title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            rusqlite::params![
-                task.title,
-                task.description,
-                task.due_date,
-                task.status,
-                task.tags,
-                task.order,
-                if task.archived { 1 } else { 0 },
-                task.notebook_id,
-                task.created_at,
-                task.updated_at
-            ],
-        )?;
-        Ok(self.conn.last_insert_rowid())
-    }
-
-    /// Insert a note into the database and return its ID
-    pub fn insert_note(&self, note: &Note) -> Result<i64, DatabaseError> {
-        self.conn.execute(
-            "INSERT INTO notes (title, content, tags, archived, notebook_id, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            rusqlite::params![
-                note.title,
-                note.content,
-                note.tags,
-                if note.archived { 1 } else { 0 },
-                note.notebook_id,
-                note.created_at,
-                note.updated_at
-            ],
-        )?;
-        Ok(self.conn.last_insert_rowid())
-    }
+    /// Migrate existing tables to add deleted_at column
+    /// Items with deleted_at set are in the trash and hidden from normal queries
+    fn migrate_add_deleted_at(&self) -> Result<(), DatabaseError> {
+        for table in ["tasks", "notes", "journals"] {
+            if !column_exists(&self.conn, table, "deleted_at")? {
+                self.conn.execute(
+                    &format!("ALTER TABLE {} ADD COLUMN deleted_at TEXT", table),
+                    [],
+                )?;
+                self.conn.execute(
+                    &format!("CREATE INDEX IF NOT EXISTS idx_{}_deleted_at ON {}(deleted_at)", table, table),
+                    [],
+                )?;
+            }
+        }
 
-    /// Insert a journal entry into the database and return its ID
-    pub fn insert_journal(&self, journal: &JournalEntry) -> Result<i64, DatabaseError> {
-        self.conn.execute(
-            "INSERT INTO journals (date, title, content, tags, archived, notebook_id, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            rusqlite::params![
-                journal.date,
-                journal.title,
-                journal.content,
-                journal.tags,
-                if journal.archived { 1 } else { 0 },
-                journal.notebook_id,
-                journal.created_at,
-                journal.updated_at
-            ],
-        )?;
-        Ok(self.conn.last_insert_rowid())
+        Ok(())
     }
 
     /// Helper function to map a row to a Task
@@ -266,83 +548,445 @@ impl Database {
             notebook_id: row.get(8)?,
             created_at: row.get(9)?,
             updated_at: row.get(10)?,
+            deleted_at: row.get(11)?,
+            uuid: row.get(12)?,
         })
     }
 
-    /// Get all tasks ordered by order ASC, optionally filtered by notebook_id
-    pub fn get_all_tasks(&self, notebook_id: Option<i64>) -> Result<Vec<Task>, DatabaseError> {
-        if let Some(nb_id) = notebook_id {
-            let mut stmt = self.conn.prepare(
-                "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at
-                 FROM tasks WHERE archived = 0 AND notebook_id = ?1 ORDER BY \"order\" ASC"
-            )?;
-            let tasks = stmt.query_map(rusqlite::params![nb_id], Self::row_to_task)?
-                .collect::<Result<Vec<_>, _>>()?;
-            return Ok(tasks);
+    /// Helper function to map a row to a Note
+    fn row_to_note(row: &rusqlite::Row) -> Result<Note, rusqlite::Error> {
+        Ok(Note {
+            id: Some(row.get(0)?),
+            title: row.get(1)?,
+            content: row.get(2)?,
+            tags: row.get(3)?,
+            archived: row.get::<_, i64>(4)? != 0,
+            notebook_id: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+            deleted_at: row.get(8)?,
+            uuid: row.get(9)?,
+        })
+    }
+
+    /// Helper function to map a row to a JournalEntry
+    fn row_to_journal(row: &rusqlite::Row) -> Result<JournalEntry, rusqlite::Error> {
+        Ok(JournalEntry {
+            id: Some(row.get(0)?),
+            date: row.get(1)?,
+            title: row.get(2)?,
+            content: row.get(3)?,
+            tags: row.get(4)?,
+            archived: row.get::<_, i64>(5)? != 0,
+            notebook_id: row.get(6)?,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+            deleted_at: row.get(9)?,
+            uuid: row.get(10)?,
+        })
+    }
+
+    /// Archive or unarchive items of one kind in a single transaction
+    fn set_items_archived(&self, kind: ItemKind, ids: &[i64], archived: bool) -> Result<usize, DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let sql = format!(
+            "UPDATE {} SET archived = ?1, updated_at = ?2 WHERE id = ?3",
+            table_name(kind)
+        );
+        
+        let mut changed = 0;
+        for id in ids {
+            changed += tx.execute(&sql, rusqlite::params![archived as i64, now, id])?;
         }
         
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at
-             FROM tasks WHERE archived = 0 AND notebook_id IS NULL ORDER BY \"order\" ASC"
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Save the stored version of a note or journal entry as a revision before it is overwritten
+    /// Nothing is recorded when the title and content are unchanged
+    fn record_revision(conn: &Connection, kind: ItemKind, id: i64, title: Option<&str>, content: Option<&str>) -> Result<(), DatabaseError> {
+        conn.execute(
+            &format!(
+                "INSERT INTO revisions (item_kind, item_id, title, content, saved_at)
+                 SELECT ?1, id, title, content, updated_at FROM {}
+                 WHERE id = ?2 AND (title IS NOT ?3 OR content IS NOT ?4)",
+                table_name(kind)
+            ),
+            rusqlite::params![kind.to_string(), id, title, content],
         )?;
-        let tasks = stmt.query_map([], Self::row_to_task)?
-            .collect::<Result<Vec<_>, _>>()?;
-        
-        Ok(tasks)
+        Ok(())
     }
 
-    /// Get all tasks including archived, ordered by order ASC, optionally filtered by notebook_id
-    pub fn get_all_tasks_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<Task>, DatabaseError> {
-        if let Some(nb_id) = notebook_id {
-            let mut stmt = self.conn.prepare(
-                "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at
-                 FROM tasks WHERE notebook_id = ?1 ORDER BY \"order\" ASC"
+    /// Remove revisions whose note or journal entry no longer exists
+    fn delete_orphaned_revisions(conn: &Connection) -> Result<(), DatabaseError> {
+        for kind in [ItemKind::Note, ItemKind::Journal] {
+            conn.execute(
+                &format!(
+                    "DELETE FROM revisions WHERE item_kind = ?1 AND item_id NOT IN (SELECT id FROM {})",
+                    table_name(kind)
+                ),
+                rusqlite::params![kind.to_string()],
             )?;
-            let tasks = stmt.query_map(rusqlite::params![nb_id], Self::row_to_task)?
-                .collect::<Result<Vec<_>, _>>()?;
-            return Ok(tasks);
         }
-        
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at
-             FROM tasks WHERE notebook_id IS NULL ORDER BY \"order\" ASC"
-        )?;
-        let tasks = stmt.query_map([], Self::row_to_task)?
-            .collect::<Result<Vec<_>, _>>()?;
-        
-        Ok(tasks)
+        Ok(())
     }
 
-    /// Get a single task by ID
-    pub fn get_task(&self, id: i64) -> Result<Task, DatabaseError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at
-             FROM tasks WHERE id = ?1"
+    /// Replace the stored links of an item with the `[[...]]` links in its text
+    fn replace_links(conn: &Connection, kind: ItemKind, id: i64, text: Option<&str>) -> Result<(), DatabaseError> {
+        conn.execute(
+            "DELETE FROM links WHERE source_kind = ?1 AND source_id = ?2",
+            rusqlite::params![kind.to_string(), id],
         )?;
-        
-        stmt.query_row(rusqlite::params![id], |row| {
-            Ok(Task {
-                id: Some(row.get(0)?),
-                title: row.get(1)?,
-                description: row.get(2)?,
-                due_date: row.get(3)?,
-                status: row.get(4)?,
-                tags: row.get(5)?,
-                order: row.get(6)?,
-                archived: row.get::<_, i64>(7)? != 0,
-                notebook_id: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-            })
-        })
+        for link in parse_links(text.unwrap_or("")) {
+            conn.execute(
+                "INSERT INTO links (source_kind, source_id, target) VALUES (?1, ?2, ?3)",
+                rusqlite::params![kind.to_string(), id, link.target.key()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove links whose source item no longer exists
+    fn delete_orphaned_links(conn: &Connection) -> Result<(), DatabaseError> {
+        for kind in [ItemKind::Task, ItemKind::Note, ItemKind::Journal] {
+            conn.execute(
+                &format!(
+                    "DELETE FROM links WHERE source_kind = ?1 AND source_id NOT IN (SELECT id FROM {})",
+                    table_name(kind)
+                ),
+                rusqlite::params![kind.to_string()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove attachments whose item no longer exists (their files are removed after the commit)
+    fn delete_orphaned_attachments(conn: &Connection) -> Result<(), DatabaseError> {
+        for kind in [ItemKind::Task, ItemKind::Note, ItemKind::Journal] {
+            conn.execute(
+                &format!(
+                    "DELETE FROM attachments WHERE item_kind = ?1 AND item_id NOT IN (SELECT id FROM {})",
+                    table_name(kind)
+                ),
+                rusqlite::params![kind.to_string()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Helper function to map a row to a Revision
+    fn row_to_revision(row: &rusqlite::Row) -> Result<Revision, rusqlite::Error> {
+        let kind: String = row.get(1)?;
+        Ok(Revision {
+            id: Some(row.get(0)?),
+            item_kind: kind.parse().map_err(|e: String| {
+                rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+            })?,
+            item_id: row.get(2)?,
+            title: row.get(3)?,
+            content: row.get(4)?,
+            saved_at: row.get(5)?,
+        })
+    }
+
+    /// Re-read the links of every item (used when the links table is first created)
+    pub fn rebuild_links(&self) -> Result<(), DatabaseError> {
+        let tasks = self.dump_tasks()?;
+        let notes = self.dump_notes()?;
+        let journals = self.dump_journals()?;
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM links", [])?;
+        for task in &tasks {
+            if let Some(id) = task.id {
+                Self::replace_links(&tx, ItemKind::Task, id, task.description.as_deref())?;
+            }
+        }
+        for note in &notes {
+            if let Some(id) = note.id {
+                Self::replace_links(&tx, ItemKind::Note, id, note.content.as_deref())?;
+            }
+        }
+        for journal in &journals {
+            if let Some(id) = journal.id {
+                Self::replace_links(&tx, ItemKind::Journal, id, journal.content.as_deref())?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Check whether an item exists and is not in the trash
+    fn is_live_item(&self, kind: ItemKind, id: i64) -> Result<bool, DatabaseError> {
+        let count: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE id = ?1 AND deleted_at IS NULL", table_name(kind)),
+            rusqlite::params![id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Get the directory attachments stored as files are kept in
+    pub fn attachments_dir(&self) -> &Path {
+        &self.attachments_dir
+    }
+
+    /// Get the path of the database file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Copy the database to `dest` with SQLite's online backup API
+    /// A snapshot of an encrypted database is encrypted with the same passphrase.
+    pub fn snapshot(&self, dest: &Path) -> Result<(), DatabaseError> {
+        let mut target = Connection::open(dest)?;
+        if let Some(ref key) = self.key {
+            target.pragma_update(None, "key", key)?;
+        }
+        let backup = rusqlite::backup::Backup::new(&self.conn, &mut target)?;
+        backup.run_to_completion(256, std::time::Duration::ZERO, None)?;
+        Ok(())
+    }
+
+    /// Close the database, replace its file with `file` (moved into place) and open the new one
+    /// The new file is unlocked with the same passphrase.
+    pub fn replace_with(self, file: &Path) -> Result<Database, DatabaseError> {
+        let Database { conn, path, key, .. } = self;
+        conn.close().map_err(|(_, e)| e)?;
+        fs::rename(file, &path).map_err(|e| DatabaseError::DirectoryError(e.to_string()))?;
+        let path = path.to_str().ok_or_else(|| DatabaseError::DirectoryError("Database path contains invalid UTF-8".to_string()))?;
+        Self::connect(path, key.as_deref())
+    }
+
+    /// Helper function to map a row to an Attachment
+    fn row_to_attachment(row: &rusqlite::Row) -> Result<Attachment, rusqlite::Error> {
+        let kind: String = row.get(1)?;
+        Ok(Attachment {
+            id: Some(row.get(0)?),
+            item_kind: kind.parse().map_err(|e: String| {
+                rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+            })?,
+            item_id: row.get(2)?,
+            file_name: row.get(3)?,
+            size: row.get(4)?,
+            stored_file: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }
+
+    /// Rewrite the comma-separated tags of several items in a single transaction
+    /// The closure edits the parsed tag list and returns whether it changed anything
+    fn update_items_tags<F>(&self, kind: ItemKind, ids: &[i64], mut update: F) -> Result<usize, DatabaseError>
+    where
+        F: FnMut(&mut Vec<String>) -> bool,
+    {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let table = table_name(kind);
+        let select_sql = format!("SELECT tags FROM {} WHERE id = ?1", table);
+        let update_sql = format!("UPDATE {} SET tags = ?1, updated_at = ?2 WHERE id = ?3", table);
+        
+        let mut changed = 0;
+        for id in ids {
+            let tags: Option<String> = match tx.query_row(&select_sql, rusqlite::params![id], |row| row.get(0)) {
+                Ok(tags) => tags,
+                Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+                Err(e) => return Err(DatabaseError::from(e)),
+            };
+            
+            let mut tag_list: Vec<String> = tags.unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            
+            if update(&mut tag_list) {
+                let new_tags = if tag_list.is_empty() {
+                    None
+                } else {
+                    Some(tag_list.join(", "))
+                };
+                changed += tx.execute(&update_sql, rusqlite::params![new_tags, now, id])?;
+            }
+        }
+        
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Give every notebook and item without a UUID a new random one (rows saved before UUIDs existed)
+    fn assign_missing_uuids(&self) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        for kind in SyncKind::ALL {
+            let ids: Vec<i64> = tx
+                .prepare(&format!("SELECT id FROM {} WHERE uuid IS NULL", kind.table()))?
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            for id in ids {
+                tx.execute(
+                    &format!("UPDATE {} SET uuid = ?1 WHERE id = ?2", kind.table()),
+                    rusqlite::params![uuid::Uuid::new_v4().to_string(), id],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Helper function to map a row to a SyncConflict
+    fn row_to_sync_conflict(row: &rusqlite::Row) -> Result<SyncConflict, rusqlite::Error> {
+        let kind: String = row.get(1)?;
+        Ok(SyncConflict {
+            id: Some(row.get(0)?),
+            kind: kind.parse().map_err(|e: String| {
+                rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+            })?,
+            uuid: row.get(2)?,
+            title: row.get(3)?,
+            other_version: row.get(4)?,
+            other_device: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }
+
+}
+
+impl Store for Database {
+    fn insert_task(&self, task: &Task) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO tasks (title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                task.title,
+                task.description,
+                task.due_date,
+                task.status,
+                task.tags,
+                task.order,
+                if task.archived { 1 } else { 0 },
+                task.notebook_id,
+                task.created_at,
+                task.updated_at,
+                uuid_or_new(&task.uuid)
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        Self::replace_links(&self.conn, ItemKind::Task, id, task.description.as_deref())?;
+        Ok(id)
+    }
+
+    fn insert_note(&self, note: &Note) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO notes (title, content, tags, archived, notebook_id, created_at, updated_at, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                note.title,
+                note.content,
+                note.tags,
+                if note.archived { 1 } else { 0 },
+                note.notebook_id,
+                note.created_at,
+                note.updated_at,
+                uuid_or_new(&note.uuid)
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        Self::replace_links(&self.conn, ItemKind::Note, id, note.content.as_deref())?;
+        Ok(id)
+    }
+
+    fn insert_journal(&self, journal: &JournalEntry) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO journals (date, title, content, tags, archived, notebook_id, created_at, updated_at, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                journal.date,
+                journal.title,
+                journal.content,
+                journal.tags,
+                if journal.archived { 1 } else { 0 },
+                journal.notebook_id,
+                journal.created_at,
+                journal.updated_at,
+                uuid_or_new(&journal.uuid)
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        Self::replace_links(&self.conn, ItemKind::Journal, id, journal.content.as_deref())?;
+        Ok(id)
+    }
+
+    fn get_all_tasks(&self, notebook_id: Option<i64>) -> Result<Vec<Task>, DatabaseError> {
+        if let Some(nb_id) = notebook_id {
+            let mut stmt = self.conn.prepare(
+                "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at, deleted_at, uuid
+                 FROM tasks WHERE deleted_at IS NULL AND archived = 0 AND notebook_id = ?1 ORDER BY \"order\" ASC"
+            )?;
+            let tasks = stmt.query_map(rusqlite::params![nb_id], Self::row_to_task)?
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(tasks);
+        }
+        
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM tasks WHERE deleted_at IS NULL AND archived = 0 AND notebook_id IS NULL ORDER BY \"order\" ASC"
+        )?;
+        let tasks = stmt.query_map([], Self::row_to_task)?
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        Ok(tasks)
+    }
+
+    fn get_all_tasks_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<Task>, DatabaseError> {
+        if let Some(nb_id) = notebook_id {
+            let mut stmt = self.conn.prepare(
+                "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at, deleted_at, uuid
+                 FROM tasks WHERE deleted_at IS NULL AND notebook_id = ?1 ORDER BY \"order\" ASC"
+            )?;
+            let tasks = stmt.query_map(rusqlite::params![nb_id], Self::row_to_task)?
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(tasks);
+        }
+        
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM tasks WHERE deleted_at IS NULL AND notebook_id IS NULL ORDER BY \"order\" ASC"
+        )?;
+        let tasks = stmt.query_map([], Self::row_to_task)?
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        Ok(tasks)
+    }
+
+    fn get_task(&self, id: i64) -> Result<Task, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM tasks WHERE id = ?1"
+        )?;
+        
+        stmt.query_row(rusqlite::params![id], |row| {
+            Ok(Task {
+                id: Some(row.get(0)?),
+                title: row.get(1)?,
+                description: row.get(2)?,
+                due_date: row.get(3)?,
+                status: row.get(4)?,
+                tags: row.get(5)?,
+                order: row.get(6)?,
+                archived: row.get::<_, i64>(7)? != 0,
+                notebook_id: row.get(8)?,
+                created_at: row.get(9)?,
+                updated_at: row.get(10)?,
+                deleted_at: row.get(11)?,
+                uuid: row.get(12)?,
+            })
+        })
         .map_err(DatabaseError::from)
     }
 
-    /// Update an existing task
-    pub fn update_task(&self, task: &Task) -> Result<(), DatabaseError> {
-        let id = task.id.ok_or_else(|| DatabaseError::SqliteError(
-            rusqlite::Error::InvalidColumnType(0, "id".to_string(), rusqlite::types::Type::Null)
-        ))?;
+    fn update_task(&self, task: &Task) -> Result<(), DatabaseError> {
+        let id = task.id.ok_or(DatabaseError::MissingId)?;
         
         let tx = self.conn.unchecked_transaction()?;
         tx.execute(
@@ -361,12 +1005,12 @@ impl Database {
                 id
             ],
         )?;
+        Self::replace_links(&tx, ItemKind::Task, id, task.description.as_deref())?;
         tx.commit()?;
         Ok(())
     }
 
-    /// Get the maximum order value from all tasks
-    pub fn get_max_task_order(&self) -> Result<i64, DatabaseError> {
+    fn get_max_task_order(&self) -> Result<i64, DatabaseError> {
         let max_order: Option<i64> = self.conn.query_row(
             "SELECT MAX(\"order\") FROM tasks",
             [],
@@ -375,8 +1019,7 @@ impl Database {
         Ok(max_order.unwrap_or(-1))
     }
 
-    /// Update a task's order value
-    pub fn update_task_order(&self, task_id: i64, new_order: i64) -> Result<(), DatabaseError> {
+    fn update_task_order(&self, task_id: i64, new_order: i64) -> Result<(), DatabaseError> {
         let tx = self.conn.unchecked_transaction()?;
         tx.execute(
             "UPDATE tasks SET \"order\" = ?1, updated_at = ?2 WHERE id = ?3",
@@ -390,16 +1033,20 @@ impl Database {
         Ok(())
     }
 
-    /// Delete a task by ID
-    pub fn delete_task(&self, id: i64) -> Result<(), DatabaseError> {
+    fn delete_task(&self, id: i64) -> Result<(), DatabaseError> {
         let tx = self.conn.unchecked_transaction()?;
-        tx.execute("DELETE FROM tasks WHERE id = ?1", rusqlite::params![id])?;
+        tx.execute(
+            "UPDATE tasks SET deleted_at = ?1 WHERE id = ?2",
+            rusqlite::params![
+                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                id
+            ],
+        )?;
         tx.commit()?;
         Ok(())
     }
 
-    /// Archive a task by ID
-    pub fn archive_task(&self, id: i64) -> Result<(), DatabaseError> {
+    fn archive_task(&self, id: i64) -> Result<(), DatabaseError> {
         let tx = self.conn.unchecked_transaction()?;
         tx.execute(
             "UPDATE tasks SET archived = 1, updated_at = ?1 WHERE id = ?2",
@@ -412,26 +1059,11 @@ impl Database {
         Ok(())
     }
 
-    /// Helper function to map a row to a Note
-    fn row_to_note(row: &rusqlite::Row) -> Result<Note, rusqlite::Error> {
-        Ok(Note {
-            id: Some(row.get(0)?),
-            title: row.get(1)?,
-            content: row.get(2)?,
-            tags: row.get(3)?,
-            archived: row.get::<_, i64>(4)? != 0,
-            notebook_id: row.get(5)?,
-            created_at: row.get(6)?,
-            updated_at: row.get(7)?,
-        })
-    }
-
-    /// Get all notes ordered by created_at DESC, optionally filtered by notebook_id
-    pub fn get_all_notes(&self, notebook_id: Option<i64>) -> Result<Vec<Note>, DatabaseError> {
+    fn get_all_notes(&self, notebook_id: Option<i64>) -> Result<Vec<Note>, DatabaseError> {
         if let Some(nb_id) = notebook_id {
             let mut stmt = self.conn.prepare(
-                "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at
-                 FROM notes WHERE archived = 0 AND notebook_id = ?1 ORDER BY created_at DESC"
+                "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+                 FROM notes WHERE deleted_at IS NULL AND archived = 0 AND notebook_id = ?1 ORDER BY created_at DESC"
             )?;
             let notes = stmt.query_map(rusqlite::params![nb_id], Self::row_to_note)?
                 .collect::<Result<Vec<_>, _>>()?;
@@ -439,8 +1071,8 @@ impl Database {
         }
         
         let mut stmt = self.conn.prepare(
-            "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at
-             FROM notes WHERE archived = 0 AND notebook_id IS NULL ORDER BY created_at DESC"
+            "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM notes WHERE deleted_at IS NULL AND archived = 0 AND notebook_id IS NULL ORDER BY created_at DESC"
         )?;
         let notes = stmt.query_map([], Self::row_to_note)?
             .collect::<Result<Vec<_>, _>>()?;
@@ -448,12 +1080,11 @@ impl Database {
         Ok(notes)
     }
 
-    /// Get all notes including archived, ordered by created_at DESC, optionally filtered by notebook_id
-    pub fn get_all_notes_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<Note>, DatabaseError> {
+    fn get_all_notes_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<Note>, DatabaseError> {
         if let Some(nb_id) = notebook_id {
             let mut stmt = self.conn.prepare(
-                "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at
-                 FROM notes WHERE notebook_id = ?1 ORDER BY created_at DESC"
+                "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+                 FROM notes WHERE deleted_at IS NULL AND notebook_id = ?1 ORDER BY created_at DESC"
             )?;
             let notes = stmt.query_map(rusqlite::params![nb_id], Self::row_to_note)?
                 .collect::<Result<Vec<_>, _>>()?;
@@ -461,8 +1092,8 @@ impl Database {
         }
         
         let mut stmt = self.conn.prepare(
-            "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at
-             FROM notes WHERE notebook_id IS NULL ORDER BY created_at DESC"
+            "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM notes WHERE deleted_at IS NULL AND notebook_id IS NULL ORDER BY created_at DESC"
         )?;
         let notes = stmt.query_map([], Self::row_to_note)?
             .collect::<Result<Vec<_>, _>>()?;
@@ -470,10 +1101,9 @@ impl Database {
         Ok(notes)
     }
 
-    /// Get a single note by ID
-    pub fn get_note(&self, id: i64) -> Result<Note, DatabaseError> {
+    fn get_note(&self, id: i64) -> Result<Note, DatabaseError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at
+            "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
              FROM notes WHERE id = ?1"
         )?;
         
@@ -487,18 +1117,18 @@ impl Database {
                 notebook_id: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                deleted_at: row.get(8)?,
+                uuid: row.get(9)?,
             })
         })
         .map_err(DatabaseError::from)
     }
 
-    /// Update an existing note
-    pub fn update_note(&self, note: &Note) -> Result<(), DatabaseError> {
-        let id = note.id.ok_or_else(|| DatabaseError::SqliteError(
-            rusqlite::Error::InvalidColumnType(0, "id".to_string(), rusqlite::types::Type::Null)
-        ))?;
+    fn update_note(&self, note: &Note) -> Result<(), DatabaseError> {
+        let id = note.id.ok_or(DatabaseError::MissingId)?;
         
         let tx = self.conn.unchecked_transaction()?;
+        Self::record_revision(&tx, ItemKind::Note, id, Some(&note.title), note.content.as_deref())?;
         tx.execute(
             "UPDATE notes SET title = ?1, content = ?2, tags = ?3, archived = ?4, notebook_id = ?5, updated_at = ?6 WHERE id = ?7",
             rusqlite::params![
@@ -511,20 +1141,25 @@ impl Database {
                 id
             ],
         )?;
+        Self::replace_links(&tx, ItemKind::Note, id, note.content.as_deref())?;
         tx.commit()?;
         Ok(())
     }
 
-    /// Delete a note by ID
-    pub fn delete_note(&self, id: i64) -> Result<(), DatabaseError> {
+    fn delete_note(&self, id: i64) -> Result<(), DatabaseError> {
         let tx = self.conn.unchecked_transaction()?;
-        tx.execute("DELETE FROM notes WHERE id = ?1", rusqlite::params![id])?;
+        tx.execute(
+            "UPDATE notes SET deleted_at = ?1 WHERE id = ?2",
+            rusqlite::params![
+                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                id
+            ],
+        )?;
         tx.commit()?;
         Ok(())
     }
 
-    /// Archive a note by ID
-    pub fn archive_note(&self, id: i64) -> Result<(), DatabaseError> {
+    fn archive_note(&self, id: i64) -> Result<(), DatabaseError> {
         let tx = self.conn.unchecked_transaction()?;
         tx.execute(
             "UPDATE notes SET archived = 1, updated_at = ?1 WHERE id = ?2",
@@ -537,27 +1172,11 @@ impl Database {
         Ok(())
     }
 
-    /// Helper function to map a row to a JournalEntry
-    fn row_to_journal(row: &rusqlite::Row) -> Result<JournalEntry, rusqlite::Error> {
-        Ok(JournalEntry {
-            id: Some(row.get(0)?),
-            date: row.get(1)?,
-            title: row.get(2)?,
-            content: row.get(3)?,
-            tags: row.get(4)?,
-            archived: row.get::<_, i64>(5)? != 0,
-            notebook_id: row.get(6)?,
-            created_at: row.get(7)?,
-            updated_at: row.get(8)?,
-        })
-    }
-
-    /// Get all journal entries ordered by date DESC (newest first), optionally filtered by notebook_id
-    pub fn get_all_journals(&self, notebook_id: Option<i64>) -> Result<Vec<JournalEntry>, DatabaseError> {
+    fn get_all_journals(&self, notebook_id: Option<i64>) -> Result<Vec<JournalEntry>, DatabaseError> {
         if let Some(nb_id) = notebook_id {
             let mut stmt = self.conn.prepare(
-                "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at
-                 FROM journals WHERE archived = 0 AND notebook_id = ?1 ORDER BY date DESC, created_at DESC"
+                "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+                 FROM journals WHERE deleted_at IS NULL AND archived = 0 AND notebook_id = ?1 ORDER BY date DESC, created_at DESC"
             )?;
             let journals = stmt.query_map(rusqlite::params![nb_id], Self::row_to_journal)?
                 .collect::<Result<Vec<_>, _>>()?;
@@ -565,8 +1184,8 @@ impl Database {
         }
         
         let mut stmt = self.conn.prepare(
-            "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at
-             FROM journals WHERE archived = 0 AND notebook_id IS NULL ORDER BY date DESC, created_at DESC"
+            "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM journals WHERE deleted_at IS NULL AND archived = 0 AND notebook_id IS NULL ORDER BY date DESC, created_at DESC"
         )?;
         let journals = stmt.query_map([], Self::row_to_journal)?
             .collect::<Result<Vec<_>, _>>()?;
@@ -574,12 +1193,11 @@ impl Database {
         Ok(journals)
     }
 
-    /// Get all journal entries including archived, ordered by date DESC, created_at DESC, optionally filtered by notebook_id
-    pub fn get_all_journals_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<JournalEntry>, DatabaseError> {
+    fn get_all_journals_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<JournalEntry>, DatabaseError> {
         if let Some(nb_id) = notebook_id {
             let mut stmt = self.conn.prepare(
-                "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at
-                 FROM journals WHERE notebook_id = ?1 ORDER BY date DESC, created_at DESC"
+                "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+                 FROM journals WHERE deleted_at IS NULL AND notebook_id = ?1 ORDER BY date DESC, created_at DESC"
             )?;
             let journals = stmt.query_map(rusqlite::params![nb_id], Self::row_to_journal)?
                 .collect::<Result<Vec<_>, _>>()?;
@@ -587,8 +1205,8 @@ impl Database {
         }
         
         let mut stmt = self.conn.prepare(
-            "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at
-             FROM journals WHERE notebook_id IS NULL ORDER BY date DESC, created_at DESC"
+            "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM journals WHERE deleted_at IS NULL AND notebook_id IS NULL ORDER BY date DESC, created_at DESC"
         )?;
         let journals = stmt.query_map([], Self::row_to_journal)?
             .collect::<Result<Vec<_>, _>>()?;
@@ -596,10 +1214,9 @@ impl Database {
         Ok(journals)
     }
 
-    /// Get a single journal entry by ID
-    pub fn get_journal(&self, id: i64) -> Result<JournalEntry, DatabaseError> {
+    fn get_journal(&self, id: i64) -> Result<JournalEntry, DatabaseError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at
+            "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
              FROM journals WHERE id = ?1"
         )?;
         
@@ -614,18 +1231,18 @@ impl Database {
                 notebook_id: row.get(6)?,
                 created_at: row.get(7)?,
                 updated_at: row.get(8)?,
+                deleted_at: row.get(9)?,
+                uuid: row.get(10)?,
             })
         })
         .map_err(DatabaseError::from)
     }
 
-    /// Update an existing journal entry
-    pub fn update_journal(&self, journal: &JournalEntry) -> Result<(), DatabaseError> {
-        let id = journal.id.ok_or_else(|| DatabaseError::SqliteError(
-            rusqlite::Error::InvalidColumnType(0, "id".to_string(), rusqlite::types::Type::Null)
-        ))?;
+    fn update_journal(&self, journal: &JournalEntry) -> Result<(), DatabaseError> {
+        let id = journal.id.ok_or(DatabaseError::MissingId)?;
         
         let tx = self.conn.unchecked_transaction()?;
+        Self::record_revision(&tx, ItemKind::Journal, id, journal.title.as_deref(), journal.content.as_deref())?;
         tx.execute(
             "UPDATE journals SET date = ?1, title = ?2, content = ?3, tags = ?4, archived = ?5, notebook_id = ?6, updated_at = ?7 WHERE id = ?8",
             rusqlite::params![
@@ -639,20 +1256,41 @@ impl Database {
                 id
             ],
         )?;
+        Self::replace_links(&tx, ItemKind::Journal, id, journal.content.as_deref())?;
         tx.commit()?;
         Ok(())
     }
 
-    /// Delete a journal entry by ID
-    pub fn delete_journal(&self, id: i64) -> Result<(), DatabaseError> {
+    fn get_setting(&self, key: &str) -> Result<Option<String>, DatabaseError> {
+        self.conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", rusqlite::params![key], |row| row.get(0))
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+
+    fn set_setting(&self, key: &str, value: &str) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![key, value],
+        )?;
+        Ok(())
+    }
+
+    fn delete_journal(&self, id: i64) -> Result<(), DatabaseError> {
         let tx = self.conn.unchecked_transaction()?;
-        tx.execute("DELETE FROM journals WHERE id = ?1", rusqlite::params![id])?;
+        tx.execute(
+            "UPDATE journals SET deleted_at = ?1 WHERE id = ?2",
+            rusqlite::params![
+                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                id
+            ],
+        )?;
         tx.commit()?;
         Ok(())
     }
 
-    /// Archive a journal entry by ID
-    pub fn archive_journal(&self, id: i64) -> Result<(), DatabaseError> {
+    fn archive_journal(&self, id: i64) -> Result<(), DatabaseError> {
         let tx = self.conn.unchecked_transaction()?;
         tx.execute(
             "UPDATE journals SET archived = 1, updated_at = ?1 WHERE id = ?2",
@@ -665,10 +1303,9 @@ impl Database {
         Ok(())
     }
 
-    /// Get all notebooks ordered by name ASC
-    pub fn get_all_notebooks(&self) -> Result<Vec<Notebook>, DatabaseError> {
+    fn get_all_notebooks(&self) -> Result<Vec<Notebook>, DatabaseError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, created_at, updated_at
+            "SELECT id, name, created_at, updated_at, locked, uuid
              FROM notebooks ORDER BY name ASC"
         )?;
         
@@ -678,6 +1315,8 @@ impl Database {
                 name: row.get(1)?,
                 created_at: row.get(2)?,
                 updated_at: row.get(3)?,
+                locked: row.get::<_, i64>(4)? != 0,
+                uuid: row.get(5)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -685,10 +1324,9 @@ impl Database {
         Ok(notebooks)
     }
 
-    /// Get a single notebook by ID
-    pub fn get_notebook(&self, id: i64) -> Result<Notebook, DatabaseError> {
+    fn get_notebook(&self, id: i64) -> Result<Notebook, DatabaseError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, created_at, updated_at
+            "SELECT id, name, created_at, updated_at, locked, uuid
              FROM notebooks WHERE id = ?1"
         )?;
         
@@ -698,30 +1336,30 @@ impl Database {
                 name: row.get(1)?,
                 created_at: row.get(2)?,
                 updated_at: row.get(3)?,
+                locked: row.get::<_, i64>(4)? != 0,
+                uuid: row.get(5)?,
             })
         })
         .map_err(DatabaseError::from)
     }
 
-    /// Insert a notebook into the database and return its ID
-    pub fn insert_notebook(&self, notebook: &Notebook) -> Result<i64, DatabaseError> {
+    fn insert_notebook(&self, notebook: &Notebook) -> Result<i64, DatabaseError> {
         self.conn.execute(
-            "INSERT INTO notebooks (name, created_at, updated_at)
-             VALUES (?1, ?2, ?3)",
+            "INSERT INTO notebooks (name, created_at, updated_at, locked, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
             rusqlite::params![
                 notebook.name,
                 notebook.created_at,
-                notebook.updated_at
+                notebook.updated_at,
+                if notebook.locked { 1 } else { 0 },
+                uuid_or_new(&notebook.uuid)
             ],
         )?;
         Ok(self.conn.last_insert_rowid())
     }
 
-    /// Update an existing notebook
-    pub fn update_notebook(&self, notebook: &Notebook) -> Result<(), DatabaseError> {
-        let id = notebook.id.ok_or_else(|| DatabaseError::SqliteError(
-            rusqlite::Error::InvalidColumnType(0, "id".to_string(), rusqlite::types::Type::Null)
-        ))?;
+    fn update_notebook(&self, notebook: &Notebook) -> Result<(), DatabaseError> {
+        let id = notebook.id.ok_or(DatabaseError::MissingId)?;
         
         let tx = self.conn.unchecked_transaction()?;
         tx.execute(
@@ -736,9 +1374,7 @@ impl Database {
         Ok(())
     }
 
-    /// Delete a notebook by ID
-    /// Sets notebook_id to NULL for all items (tasks, notes, journals) that belonged to this notebook
-    pub fn delete_notebook(&self, id: i64) -> Result<(), DatabaseError> {
+    fn delete_notebook(&self, id: i64) -> Result<(), DatabaseError> {
         let tx = self.conn.unchecked_transaction()?;
         
         // Set notebook_id to NULL for all tasks that belonged to this notebook
@@ -775,19 +1411,54 @@ impl Database {
         Ok(())
     }
 
-    /// Get the first notebook (for default)
-    pub fn get_default_notebook(&self) -> Result<Option<Notebook>, DatabaseError> {
+    fn get_item_ids_in_notebook(&self, kind: ItemKind, notebook_id: i64) -> Result<Vec<i64>, DatabaseError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, created_at, updated_at
-             FROM notebooks ORDER BY name ASC LIMIT 1"
+            &format!("SELECT id FROM {} WHERE notebook_id = ?1 ORDER BY id", table_name(kind))
+        )?;
+        let ids = stmt.query_map(rusqlite::params![notebook_id], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    fn restore_notebook(&self, notebook: &Notebook, items: &[(ItemKind, i64)]) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO notebooks (id, name, created_at, updated_at, locked, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                notebook.id,
+                notebook.name,
+                notebook.created_at,
+                notebook.updated_at,
+                if notebook.locked { 1 } else { 0 },
+                uuid_or_new(&notebook.uuid)
+            ],
+        )?;
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        for (kind, id) in items {
+            tx.execute(
+                &format!("UPDATE {} SET notebook_id = ?1, updated_at = ?2 WHERE id = ?3", table_name(*kind)),
+                rusqlite::params![notebook.id, now, id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_notebook_by_name(&self, name: &str) -> Result<Option<Notebook>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, created_at, updated_at, locked, uuid
+             FROM notebooks WHERE name = ?1 ORDER BY id ASC LIMIT 1"
         )?;
         
-        let result = stmt.query_row([], |row| {
+        let result = stmt.query_row(rusqlite::params![name], |row| {
             Ok(Notebook {
                 id: Some(row.get(0)?),
                 name: row.get(1)?,
                 created_at: row.get(2)?,
                 updated_at: row.get(3)?,
+                locked: row.get::<_, i64>(4)? != 0,
+                uuid: row.get(5)?,
             })
         });
         
@@ -797,5 +1468,768 @@ impl Database {
             Err(e) => Err(DatabaseError::from(e)),
         }
     }
-}
 
+    fn move_items_to_notebook(&self, kind: ItemKind, ids: &[i64], notebook_id: Option<i64>) -> Result<usize, DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let sql = format!(
+            "UPDATE {} SET notebook_id = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL",
+            table_name(kind)
+        );
+        
+        let mut moved = 0;
+        let mut seen = HashSet::new();
+        for id in ids.iter().filter(|id| seen.insert(**id)) {
+            let changed = tx.execute(&sql, rusqlite::params![notebook_id, now, id])?;
+            if changed == 0 {
+                // Dropping the transaction without committing rolls back earlier moves
+                return Err(DatabaseError::ItemNotFound(format!("{} {}", kind, id)));
+            }
+            moved += changed;
+        }
+        
+        tx.commit()?;
+        Ok(moved)
+    }
+
+    fn archive_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError> {
+        self.set_items_archived(kind, ids, true)
+    }
+
+    fn unarchive_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError> {
+        self.set_items_archived(kind, ids, false)
+    }
+
+    fn delete_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let sql = format!(
+            "UPDATE {} SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            table_name(kind)
+        );
+        
+        let mut deleted = 0;
+        for id in ids {
+            deleted += tx.execute(&sql, rusqlite::params![now, id])?;
+        }
+        
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    fn dump_tasks(&self) -> Result<Vec<Task>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM tasks ORDER BY id"
+        )?;
+        let tasks = stmt.query_map([], Self::row_to_task)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tasks)
+    }
+
+    fn dump_notes(&self) -> Result<Vec<Note>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM notes ORDER BY id"
+        )?;
+        let notes = stmt.query_map([], Self::row_to_note)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notes)
+    }
+
+    fn dump_journals(&self) -> Result<Vec<JournalEntry>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM journals ORDER BY id"
+        )?;
+        let journals = stmt.query_map([], Self::row_to_journal)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(journals)
+    }
+
+    fn set_deleted_at(&self, kind: ItemKind, id: i64, deleted_at: Option<&str>) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            &format!("UPDATE {} SET deleted_at = ?1 WHERE id = ?2", table_name(kind)),
+            rusqlite::params![deleted_at, id],
+        )?;
+        Ok(())
+    }
+
+    fn get_deleted_tasks(&self) -> Result<Vec<Task>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, description, due_date, status, tags, \"order\", archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM tasks WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        )?;
+        let tasks = stmt.query_map([], Self::row_to_task)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tasks)
+    }
+
+    fn get_deleted_notes(&self) -> Result<Vec<Note>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM notes WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        )?;
+        let notes = stmt.query_map([], Self::row_to_note)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notes)
+    }
+
+    fn get_deleted_journals(&self) -> Result<Vec<JournalEntry>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, date, title, content, tags, archived, notebook_id, created_at, updated_at, deleted_at, uuid
+             FROM journals WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        )?;
+        let journals = stmt.query_map([], Self::row_to_journal)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(journals)
+    }
+
+    fn restore_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let table = table_name(kind);
+        let mut restored = 0;
+        for id in ids {
+            let changed = tx.execute(
+                &format!("UPDATE {} SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL", table),
+                rusqlite::params![id],
+            )?;
+            if changed == 0 {
+                continue;
+            }
+            tx.execute(
+                &format!(
+                    "UPDATE {} SET notebook_id = NULL
+                     WHERE id = ?1 AND notebook_id IS NOT NULL AND notebook_id NOT IN (SELECT id FROM notebooks)",
+                    table
+                ),
+                rusqlite::params![id],
+            )?;
+            restored += changed;
+        }
+        tx.commit()?;
+        Ok(restored)
+    }
+
+    fn purge_item(&self, kind: ItemKind, id: i64) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let changed = tx.execute(
+            &format!("DELETE FROM {} WHERE id = ?1 AND deleted_at IS NOT NULL", table_name(kind)),
+            rusqlite::params![id],
+        )?;
+        if changed == 0 {
+            return Err(DatabaseError::ItemNotFound(format!("{} {} in trash", kind, id)));
+        }
+        Self::delete_orphaned_revisions(&tx)?;
+        Self::delete_orphaned_links(&tx)?;
+        Self::delete_orphaned_attachments(&tx)?;
+        tx.commit()?;
+        self.remove_unused_attachment_files()?;
+        Ok(())
+    }
+
+    fn empty_trash(&self) -> Result<usize, DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut purged = 0;
+        for kind in [ItemKind::Task, ItemKind::Note, ItemKind::Journal] {
+            purged += tx.execute(
+                &format!("DELETE FROM {} WHERE deleted_at IS NOT NULL", table_name(kind)),
+                [],
+            )?;
+        }
+        Self::delete_orphaned_revisions(&tx)?;
+        Self::delete_orphaned_links(&tx)?;
+        Self::delete_orphaned_attachments(&tx)?;
+        tx.commit()?;
+        self.remove_unused_attachment_files()?;
+        Ok(purged)
+    }
+
+    fn purge_trash_older_than(&self, days: u32) -> Result<usize, DatabaseError> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(days as i64))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let tx = self.conn.unchecked_transaction()?;
+        let mut purged = 0;
+        for kind in [ItemKind::Task, ItemKind::Note, ItemKind::Journal] {
+            purged += tx.execute(
+                &format!("DELETE FROM {} WHERE deleted_at IS NOT NULL AND deleted_at < ?1", table_name(kind)),
+                rusqlite::params![cutoff],
+            )?;
+        }
+        Self::delete_orphaned_revisions(&tx)?;
+        Self::delete_orphaned_links(&tx)?;
+        Self::delete_orphaned_attachments(&tx)?;
+        tx.commit()?;
+        self.remove_unused_attachment_files()?;
+        Ok(purged)
+    }
+
+    fn set_tasks_status(&self, ids: &[i64], status: &str) -> Result<usize, DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        
+        let mut changed = 0;
+        for id in ids {
+            changed += tx.execute(
+                "UPDATE tasks SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                rusqlite::params![status, now, id],
+            )?;
+        }
+        
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    fn add_tag_to_items(&self, kind: ItemKind, ids: &[i64], tag: &str) -> Result<usize, DatabaseError> {
+        let tag = tag.trim().to_string();
+        self.update_items_tags(kind, ids, |tags| {
+            if tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                false
+            } else {
+                tags.push(tag.clone());
+                true
+            }
+        })
+    }
+
+    fn remove_tag_from_items(&self, kind: ItemKind, ids: &[i64], tag: &str) -> Result<usize, DatabaseError> {
+        let tag = tag.trim().to_string();
+        self.update_items_tags(kind, ids, |tags| {
+            let before = tags.len();
+            tags.retain(|t| !t.eq_ignore_ascii_case(&tag));
+            tags.len() != before
+        })
+    }
+
+    fn get_default_notebook(&self) -> Result<Option<Notebook>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, created_at, updated_at, locked, uuid
+             FROM notebooks ORDER BY name ASC LIMIT 1"
+        )?;
+        
+        let result = stmt.query_row([], |row| {
+            Ok(Notebook {
+                id: Some(row.get(0)?),
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                updated_at: row.get(3)?,
+                locked: row.get::<_, i64>(4)? != 0,
+                uuid: row.get(5)?,
+            })
+        });
+        
+        match result {
+            Ok(notebook) => Ok(Some(notebook)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(DatabaseError::from(e)),
+        }
+    }
+
+    fn get_uuids(&self, kind: SyncKind) -> Result<HashMap<i64, String>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            &format!("SELECT id, uuid FROM {} WHERE uuid IS NOT NULL", kind.table())
+        )?;
+        let uuids = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(uuids)
+    }
+
+    fn find_by_uuid(&self, kind: SyncKind, uuid: &str) -> Result<Option<i64>, DatabaseError> {
+        self.conn
+            .query_row(
+                &format!("SELECT id FROM {} WHERE uuid = ?1", kind.table()),
+                rusqlite::params![uuid],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+
+    fn set_uuid(&self, kind: SyncKind, id: i64, uuid: &str) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            &format!("UPDATE {} SET uuid = ?1 WHERE id = ?2", kind.table()),
+            rusqlite::params![uuid, id],
+        )?;
+        Ok(())
+    }
+
+    fn transaction(&self) -> Result<Transaction<'_>, DatabaseError> {
+        Ok(Transaction::new(self.conn.unchecked_transaction()?))
+    }
+
+    fn delete_all_data(&self) -> Result<(), DatabaseError> {
+        for table in ["tasks", "notes", "journals", "notebooks", "revisions", "import_sources", "links", "attachments", "sync_conflicts"] {
+            self.conn.execute(&format!("DELETE FROM {}", table), [])?;
+        }
+        self.conn.execute("DELETE FROM settings WHERE key = ?1", rusqlite::params![crate::lock::CHECK_SETTING])?;
+        Ok(())
+    }
+
+    fn get_imported_item(&self, source: &str) -> Result<Option<(ItemKind, i64)>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT item_kind, item_id FROM import_sources WHERE source = ?1"
+        )?;
+        let mut rows = stmt.query(rusqlite::params![source])?;
+        let Some(row) = rows.next()? else {
+            return Ok(None);
+        };
+        let kind_name: String = row.get(0)?;
+        let item_id: i64 = row.get(1)?;
+        let Ok(kind) = kind_name.parse::<ItemKind>() else {
+            return Ok(None);
+        };
+
+        let exists: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE id = ?1", table_name(kind)),
+            rusqlite::params![item_id],
+            |row| row.get(0),
+        )?;
+        Ok(if exists > 0 { Some((kind, item_id)) } else { None })
+    }
+
+    fn record_import_source(&self, source: &str, kind: ItemKind, item_id: i64) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO import_sources (source, item_kind, item_id, imported_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![
+                source,
+                kind.to_string(),
+                item_id,
+                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn total_changes(&self) -> u64 {
+        self.conn.total_changes()
+    }
+
+    fn data_version(&self) -> Result<i64, DatabaseError> {
+        Ok(self.conn.query_row("PRAGMA data_version", [], |row| row.get(0))?)
+    }
+}
+
+impl LockStore for Database {
+    fn set_notebook_locked(&self, id: i64, locked: bool) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            "UPDATE notebooks SET locked = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![
+                if locked { 1 } else { 0 },
+                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                id
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn set_journal_content(&self, id: i64, content: Option<&str>, forget_history: bool) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE journals SET content = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![
+                content,
+                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                id
+            ],
+        )?;
+        if forget_history {
+            tx.execute(
+                "DELETE FROM revisions WHERE item_kind = ?1 AND item_id = ?2",
+                rusqlite::params![ItemKind::Journal.to_string(), id],
+            )?;
+        }
+        Self::replace_links(&tx, ItemKind::Journal, id, content)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_notebook_journal_contents(&self, notebook_id: i64) -> Result<Vec<(i64, Option<String>)>, DatabaseError> {
+        let mut stmt = self.conn.prepare("SELECT id, content FROM journals WHERE notebook_id = ?1 ORDER BY id")?;
+        let contents = stmt.query_map(rusqlite::params![notebook_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(contents)
+    }
+
+    fn get_any_locked_content(&self) -> Result<Option<String>, DatabaseError> {
+        self.conn
+            .query_row(
+                "SELECT content FROM journals WHERE substr(content, 1, ?1) = ?2 LIMIT 1",
+                rusqlite::params![crate::lock::LOCKED_PREFIX.len() as i64, crate::lock::LOCKED_PREFIX],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(DatabaseError::from)
+    }
+}
+
+impl HistoryStore for Database {
+    fn get_revisions(&self, kind: ItemKind, item_id: i64) -> Result<Vec<Revision>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, item_kind, item_id, title, content, saved_at
+             FROM revisions WHERE item_kind = ?1 AND item_id = ?2 ORDER BY saved_at DESC, id DESC"
+        )?;
+        let revisions = stmt.query_map(rusqlite::params![kind.to_string(), item_id], Self::row_to_revision)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(revisions)
+    }
+
+    fn get_revision(&self, id: i64) -> Result<Revision, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, item_kind, item_id, title, content, saved_at
+             FROM revisions WHERE id = ?1"
+        )?;
+        stmt.query_row(rusqlite::params![id], Self::row_to_revision)
+            .map_err(DatabaseError::from)
+    }
+
+    fn restore_revision(&self, revision_id: i64) -> Result<Revision, DatabaseError> {
+        let revision = self.get_revision(revision_id)?;
+        let tx = self.conn.unchecked_transaction()?;
+        Self::record_revision(&tx, revision.item_kind, revision.item_id, revision.title.as_deref(), revision.content.as_deref())?;
+        let changed = tx.execute(
+            &format!(
+                "UPDATE {} SET title = ?1, content = ?2, updated_at = ?3 WHERE id = ?4 AND deleted_at IS NULL",
+                table_name(revision.item_kind)
+            ),
+            rusqlite::params![
+                revision.title,
+                revision.content,
+                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                revision.item_id
+            ],
+        )?;
+        if changed == 0 {
+            return Err(DatabaseError::ItemNotFound(format!("{} {}", revision.item_kind, revision.item_id)));
+        }
+        Self::replace_links(&tx, revision.item_kind, revision.item_id, revision.content.as_deref())?;
+        tx.commit()?;
+        Ok(revision)
+    }
+
+    fn dump_revisions(&self) -> Result<Vec<Revision>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, item_kind, item_id, title, content, saved_at FROM revisions ORDER BY id"
+        )?;
+        let revisions = stmt.query_map([], Self::row_to_revision)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(revisions)
+    }
+
+    fn add_revision(&self, revision: &Revision) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO revisions (item_kind, item_id, title, content, saved_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                revision.item_kind.to_string(),
+                revision.item_id,
+                revision.title,
+                revision.content,
+                revision.saved_at
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+}
+
+impl LinkStore for Database {
+    fn resolve_link(&self, target: &LinkTarget) -> Result<Option<(ItemKind, i64)>, DatabaseError> {
+        match target {
+            LinkTarget::Item(kind, id) => {
+                Ok(if self.is_live_item(*kind, *id)? { Some((*kind, *id)) } else { None })
+            }
+            LinkTarget::Date(date) => {
+                let mut stmt = self.conn.prepare(
+                    "SELECT id FROM journals WHERE date = ?1 AND deleted_at IS NULL
+                     ORDER BY archived, created_at, id LIMIT 1"
+                )?;
+                let mut rows = stmt.query(rusqlite::params![date])?;
+                Ok(rows.next()?.map(|row| row.get(0)).transpose()?.map(|id| (ItemKind::Journal, id)))
+            }
+            LinkTarget::Title(title) => {
+                // Compared in Rust, as SQLite's lower() only folds ASCII
+                let wanted = title.to_lowercase();
+                for kind in [ItemKind::Note, ItemKind::Task, ItemKind::Journal] {
+                    let mut stmt = self.conn.prepare(&format!(
+                        "SELECT id, title FROM {} WHERE title IS NOT NULL AND deleted_at IS NULL ORDER BY archived, id",
+                        table_name(kind)
+                    ))?;
+                    let mut rows = stmt.query([])?;
+                    while let Some(row) = rows.next()? {
+                        let item_title: String = row.get(1)?;
+                        if item_title.trim().to_lowercase() == wanted {
+                            return Ok(Some((kind, row.get(0)?)));
+                        }
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn get_backlinks(&self, kind: ItemKind, id: i64) -> Result<Vec<(ItemKind, i64)>, DatabaseError> {
+        let (title, date): (Option<String>, Option<String>) = match kind {
+            ItemKind::Journal => self.conn.query_row(
+                "SELECT title, date FROM journals WHERE id = ?1",
+                rusqlite::params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?,
+            _ => self.conn.query_row(
+                &format!("SELECT title, NULL FROM {} WHERE id = ?1", table_name(kind)),
+                rusqlite::params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?,
+        };
+
+        let mut targets = vec![LinkTarget::Item(kind, id)];
+        if let Some(title) = title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+            targets.push(LinkTarget::Title(title));
+        }
+        if let Some(date) = date {
+            targets.push(LinkTarget::Date(date));
+        }
+
+        let mut backlinks: Vec<(ItemKind, i64)> = Vec::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT source_kind, source_id FROM links WHERE target = ?1"
+        )?;
+        for target in &targets {
+            // A title or date link only counts if it resolves to this item and not to another one
+            if !matches!(target, LinkTarget::Item(..)) && self.resolve_link(target)? != Some((kind, id)) {
+                continue;
+            }
+            let sources = stmt
+                .query_map(rusqlite::params![target.key()], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            for (source_kind, source_id) in sources {
+                let Ok(source_kind) = source_kind.parse::<ItemKind>() else {
+                    continue;
+                };
+                let source = (source_kind, source_id);
+                if source != (kind, id) && !backlinks.contains(&source) && self.is_live_item(source_kind, source_id)? {
+                    backlinks.push(source);
+                }
+            }
+        }
+        backlinks.sort();
+        Ok(backlinks)
+    }
+}
+
+impl AttachmentStore for Database {
+    fn add_attachment(&self, attachment: &Attachment, data: &[u8], storage: AttachmentStorage) -> Result<i64, DatabaseError> {
+        let storage = if self.encrypted { AttachmentStorage::Database } else { storage };
+        let kind = attachment.item_kind;
+        let exists: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE id = ?1", table_name(kind)),
+            rusqlite::params![attachment.item_id],
+            |row| row.get(0),
+        )?;
+        if exists == 0 {
+            return Err(DatabaseError::ItemNotFound(format!("{} {}", kind, attachment.item_id)));
+        }
+
+        self.conn.execute(
+            "INSERT INTO attachments (item_kind, item_id, file_name, size, data, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                kind.to_string(),
+                attachment.item_id,
+                attachment.file_name,
+                data.len() as i64,
+                if storage == AttachmentStorage::Database { Some(data) } else { None },
+                attachment.created_at
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+
+        if storage == AttachmentStorage::Directory {
+            // The ID keeps names unique; only the file name part of `file_name` is used
+            let base_name = Path::new(&attachment.file_name)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "attachment".to_string());
+            let stored_file = format!("{}-{}", id, base_name);
+            let written = fs::create_dir_all(&self.attachments_dir)
+                .and_then(|_| fs::write(self.attachments_dir.join(&stored_file), data));
+            if let Err(e) = written {
+                self.conn.execute("DELETE FROM attachments WHERE id = ?1", rusqlite::params![id])?;
+                return Err(DatabaseError::AttachmentFileError(e.to_string()));
+            }
+            self.conn.execute(
+                "UPDATE attachments SET stored_file = ?1 WHERE id = ?2",
+                rusqlite::params![stored_file, id],
+            )?;
+        }
+        Ok(id)
+    }
+
+    fn get_attachments(&self, kind: ItemKind, item_id: i64) -> Result<Vec<Attachment>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, item_kind, item_id, file_name, size, stored_file, created_at
+             FROM attachments WHERE item_kind = ?1 AND item_id = ?2 ORDER BY id"
+        )?;
+        let attachments = stmt.query_map(rusqlite::params![kind.to_string(), item_id], Self::row_to_attachment)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(attachments)
+    }
+
+    fn dump_attachments(&self) -> Result<Vec<Attachment>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, item_kind, item_id, file_name, size, stored_file, created_at
+             FROM attachments ORDER BY id"
+        )?;
+        let attachments = stmt.query_map([], Self::row_to_attachment)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(attachments)
+    }
+
+    fn get_attachment(&self, id: i64) -> Result<Attachment, DatabaseError> {
+        self.conn.query_row(
+            "SELECT id, item_kind, item_id, file_name, size, stored_file, created_at
+             FROM attachments WHERE id = ?1",
+            rusqlite::params![id],
+            Self::row_to_attachment,
+        )
+        .optional()?
+        .ok_or_else(|| DatabaseError::ItemNotFound(format!("attachment {}", id)))
+    }
+
+    fn read_attachment(&self, attachment: &Attachment) -> Result<Vec<u8>, DatabaseError> {
+        if let Some(ref stored_file) = attachment.stored_file {
+            return fs::read(self.attachments_dir.join(stored_file))
+                .map_err(|e| DatabaseError::AttachmentFileError(format!("{}: {}", stored_file, e)));
+        }
+        let id = attachment.id.ok_or(DatabaseError::MissingId)?;
+        let data: Option<Vec<u8>> = self.conn.query_row(
+            "SELECT data FROM attachments WHERE id = ?1",
+            rusqlite::params![id],
+            |row| row.get(0),
+        )?;
+        Ok(data.unwrap_or_default())
+    }
+
+    fn attachment_path(&self, attachment: &Attachment) -> Result<PathBuf, DatabaseError> {
+        if let Some(ref stored_file) = attachment.stored_file {
+            return Ok(self.attachments_dir.join(stored_file));
+        }
+        temporary_attachment_file(attachment, &self.read_attachment(attachment)?)
+    }
+
+    fn delete_attachment(&self, id: i64) -> Result<Attachment, DatabaseError> {
+        let attachment = self.get_attachment(id)?;
+        self.conn.execute("DELETE FROM attachments WHERE id = ?1", rusqlite::params![id])?;
+        self.remove_unused_attachment_files()?;
+        Ok(attachment)
+    }
+
+    fn remove_unused_attachment_files(&self) -> Result<(), DatabaseError> {
+        let Ok(entries) = fs::read_dir(&self.attachments_dir) else {
+            return Ok(()); // No attachments directory, so nothing to clean up
+        };
+        let mut stmt = self.conn.prepare("SELECT stored_file FROM attachments WHERE stored_file IS NOT NULL")?;
+        let used: HashSet<String> = stmt.query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stored_name = name
+                .split_once('-')
+                .is_some_and(|(id, _)| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()));
+            if stored_name && !used.contains(&name) && entry.path().is_file() {
+                fs::remove_file(entry.path()).map_err(|e| DatabaseError::AttachmentFileError(format!("{}: {}", name, e)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SyncStore for Database {
+    fn delete_item_permanently(&self, kind: ItemKind, id: i64) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(&format!("DELETE FROM {} WHERE id = ?1", table_name(kind)), rusqlite::params![id])?;
+        Self::delete_orphaned_revisions(&tx)?;
+        Self::delete_orphaned_links(&tx)?;
+        Self::delete_orphaned_attachments(&tx)?;
+        tx.commit()?;
+        self.remove_unused_attachment_files()?;
+        Ok(())
+    }
+
+    fn get_sync_states(&self) -> Result<Vec<SyncState>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT uuid, kind, clock, device, versions, hash FROM sync_state"
+        )?;
+        let states = stmt.query_map([], |row| {
+            let kind: String = row.get(1)?;
+            let versions: String = row.get(4)?;
+            Ok(SyncState {
+                uuid: row.get(0)?,
+                kind: kind.parse().map_err(|e: String| {
+                    rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+                })?,
+                clock: row.get(2)?,
+                device: row.get(3)?,
+                versions: serde_json::from_str(&versions).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, e.into())
+                })?,
+                hash: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(states)
+    }
+
+    fn set_sync_state(&self, state: &SyncState) -> Result<(), DatabaseError> {
+        let versions = serde_json::to_string(&state.versions)
+            .map_err(|e| DatabaseError::SqliteError(rusqlite::Error::ToSqlConversionFailure(e.into())))?;
+        self.conn.execute(
+            "INSERT INTO sync_state (uuid, kind, clock, device, versions, hash) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(uuid) DO UPDATE SET kind = excluded.kind, clock = excluded.clock,
+             device = excluded.device, versions = excluded.versions, hash = excluded.hash",
+            rusqlite::params![state.uuid, state.kind.to_string(), state.clock, state.device, versions, state.hash],
+        )?;
+        Ok(())
+    }
+
+    fn add_sync_conflict(&self, conflict: &SyncConflict) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO sync_conflicts (kind, uuid, title, other_version, other_device, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                conflict.kind.to_string(),
+                conflict.uuid,
+                conflict.title,
+                conflict.other_version,
+                conflict.other_device,
+                conflict.created_at
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn get_sync_conflicts(&self) -> Result<Vec<SyncConflict>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, kind, uuid, title, other_version, other_device, created_at
+             FROM sync_conflicts ORDER BY id"
+        )?;
+        let conflicts = stmt.query_map([], Self::row_to_sync_conflict)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(conflicts)
+    }
+
+    fn get_sync_conflict(&self, id: i64) -> Result<SyncConflict, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, kind, uuid, title, other_version, other_device, created_at
+             FROM sync_conflicts WHERE id = ?1"
+        )?;
+        stmt.query_row(rusqlite::params![id], Self::row_to_sync_conflict)
+            .map_err(DatabaseError::from)
+    }
+
+    fn delete_sync_conflict(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn.execute("DELETE FROM sync_conflicts WHERE id = ?1", rusqlite::params![id])?;
+        Ok(())
+    }
+}
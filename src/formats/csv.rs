@@ -0,0 +1,422 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::formats::{join_tags, normalize_timestamp, normalize_uuid, FormatError, ImportSummary, ItemCounts, NotebookResolver};
+use crate::models::{ItemKind, SyncKind, Task};
+use crate::store::Store;
+
+/// Column headers written by `export`, which `import` maps back automatically
+const EXPORT_HEADERS: &[&str] = &[
+    "id", "uuid", "title", "description", "status", "due_date", "tags", "notebook", "archived", "created_at", "updated_at",
+];
+
+/// Date formats tried when no `--date-format` is given (only unambiguous ones)
+const DEFAULT_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y", "%Y%m%d"];
+
+/// A task field a CSV column can be mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CsvField {
+    Uuid,
+    Title,
+    Description,
+    Status,
+    DueDate,
+    Tags,
+    Notebook,
+    Archived,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl FromStr for CsvField {
+    type Err = String;
+
+    /// Accepts field names and common aliases, ignoring case, spaces and dashes
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase().replace([' ', '-'], "_");
+        match normalized.as_str() {
+            "uuid" => Ok(CsvField::Uuid),
+            "title" | "name" | "task" | "summary" => Ok(CsvField::Title),
+            "description" | "notes" | "details" => Ok(CsvField::Description),
+            "status" | "done" | "completed" => Ok(CsvField::Status),
+            "due_date" | "due" => Ok(CsvField::DueDate),
+            "tags" | "labels" => Ok(CsvField::Tags),
+            "notebook" | "project" => Ok(CsvField::Notebook),
+            "archived" => Ok(CsvField::Archived),
+            "created_at" | "created" => Ok(CsvField::CreatedAt),
+            "updated_at" | "updated" | "modified" => Ok(CsvField::UpdatedAt),
+            _ => Err(format!(
+                "Unknown task field '{}' (expected uuid, title, description, status, due_date, tags, notebook, archived, created_at or updated_at)",
+                s.trim()
+            )),
+        }
+    }
+}
+
+/// Options for importing a CSV file
+#[derive(Debug, Clone, Default)]
+pub struct CsvOptions {
+    pub map: Option<String>,         // "Header=field,Header=field"
+    pub date_format: Option<String>, // chrono format such as "%d/%m/%Y"
+    pub dry_run: bool,
+}
+
+/// Parse a `--map` value ("Name=title,Due=due_date") into header -> field pairs
+pub fn parse_mapping(spec: &str) -> Result<Vec<(String, CsvField)>, FormatError> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (header, field) = pair
+                .split_once('=')
+                .ok_or_else(|| FormatError::InvalidInput(format!("Invalid mapping '{}' (expected Header=field)", pair)))?;
+            let field = field.parse::<CsvField>().map_err(FormatError::InvalidInput)?;
+            Ok((header.trim().to_string(), field))
+        })
+        .collect()
+}
+
+/// Decide which field each column is imported into: `--map` entries first, then headers that
+/// name a field; other columns are ignored
+fn map_columns(headers: &csv::StringRecord, mapping: &[(String, CsvField)]) -> Result<BTreeMap<usize, CsvField>, FormatError> {
+    let mut columns = BTreeMap::new();
+    for (header, field) in mapping {
+        let index = headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(header))
+            .ok_or_else(|| FormatError::InvalidInput(format!("Column '{}' from --map not found in the header", header)))?;
+        columns.insert(index, *field);
+    }
+    for (index, header) in headers.iter().enumerate() {
+        let already_mapped = columns.values().copied().collect::<HashSet<_>>();
+        if columns.contains_key(&index) {
+            continue;
+        }
+        if let Ok(field) = header.parse::<CsvField>()
+            && !already_mapped.contains(&field)
+        {
+            columns.insert(index, field);
+        }
+    }
+    if !columns.values().any(|f| *f == CsvField::Title) {
+        return Err(FormatError::InvalidInput(
+            "No title column; name one with --map \"<Header>=title\"".to_string(),
+        ));
+    }
+    Ok(columns)
+}
+
+/// Parse a date with the given format, or the default formats (ISO date-times included)
+fn parse_date(value: &str, date_format: Option<&str>) -> Result<String, String> {
+    let value = value.trim();
+    let formats: Vec<&str> = match date_format {
+        Some(format) => vec![format],
+        None => DEFAULT_DATE_FORMATS.to_vec(),
+    };
+    for format in &formats {
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(value, format) {
+            return Ok(date.format("%Y-%m-%d").to_string());
+        }
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value, format) {
+            return Ok(dt.format("%Y-%m-%d").to_string());
+        }
+    }
+    if date_format.is_none()
+        && let Some(timestamp) = normalize_timestamp(value)
+    {
+        return Ok(timestamp[..10].to_string());
+    }
+    Err(match date_format {
+        Some(format) => format!("date '{}' does not match format '{}'", value, format),
+        None => format!("unrecognized date '{}' (set one with --date-format)", value),
+    })
+}
+
+/// Parse a timestamp column: a date-time, or a date parsed like `parse_date`
+fn parse_timestamp(value: &str, date_format: Option<&str>) -> Result<String, String> {
+    if let Some(timestamp) = normalize_timestamp(value) {
+        return Ok(timestamp);
+    }
+    if let Some(format) = date_format
+        && let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value.trim(), format)
+    {
+        return Ok(dt.format("%Y-%m-%d %H:%M:%S").to_string());
+    }
+    parse_date(value, date_format).map(|date| format!("{} 00:00:00", date))
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "" | "no" | "false" | "0" | "n" => Some(false),
+        "yes" | "true" | "1" | "y" | "x" => Some(true),
+        _ => None,
+    }
+}
+
+fn parse_status(value: &str) -> Option<&'static str> {
+    match value.trim().to_lowercase().as_str() {
+        "" | "todo" | "to do" | "open" | "pending" | "not started" | "in progress" => Some("todo"),
+        "done" | "completed" | "complete" | "closed" | "finished" => Some("done"),
+        other => parse_flag(other).map(|done| if done { "done" } else { "todo" }),
+    }
+}
+
+/// Split a tags cell on commas, semicolons or pipes
+fn split_tags(value: &str) -> Vec<String> {
+    value
+        .split([',', ';', '|'])
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Get the line a record starts on
+/// The reader's position for a record is where the previous record ended, before any blank lines.
+fn record_line(data: &[u8], position: &csv::Position) -> usize {
+    let offset = (position.byte() as usize).min(data.len());
+    let start = offset + data[offset..].iter().take_while(|b| matches!(b, b'\r' | b'\n')).count();
+    data[..start].iter().filter(|b| **b == b'\n').count() + 1
+}
+
+/// Export all tasks (including archived, excluding trashed) to a CSV file
+pub fn export(db: &dyn Store, path: &Path) -> Result<ItemCounts, FormatError> {
+    let notebooks: HashMap<i64, String> = db
+        .get_all_notebooks()?
+        .into_iter()
+        .filter_map(|n| n.id.map(|id| (id, n.name)))
+        .collect();
+    let mut tasks: Vec<Task> = db.dump_tasks()?.into_iter().filter(|t| t.deleted_at.is_none()).collect();
+    tasks.sort_by_key(|t| (t.order, t.id));
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(EXPORT_HEADERS)?;
+    for task in &tasks {
+        let notebook = task.notebook_id.and_then(|id| notebooks.get(&id)).map(String::as_str);
+        writer.write_record([
+            task.id.map(|id| id.to_string()).unwrap_or_default().as_str(),
+            task.uuid.as_deref().unwrap_or(""),
+            &task.title,
+            task.description.as_deref().unwrap_or(""),
+            &task.status,
+            task.due_date.as_deref().unwrap_or(""),
+            task.tags.as_deref().unwrap_or(""),
+            notebook.unwrap_or(""),
+            if task.archived { "true" } else { "false" },
+            &task.created_at,
+            &task.updated_at,
+        ])?;
+    }
+    writer.flush()?;
+
+    Ok(ItemCounts { tasks: tasks.len(), ..Default::default() })
+}
+
+/// Import tasks from a CSV file with a header row
+///
+/// Columns are matched to task fields by header name or through `options.map`; dates are parsed
+/// with `options.date_format` when given. Rows that fail validation are reported with their line
+/// number and skipped, the rest are imported. Rows with the UUID of an existing task, or the same
+/// title and creation time as one (e.g. from re-importing an export), are skipped.
+pub fn import(db: &dyn Store, path: &Path, options: &CsvOptions) -> Result<ImportSummary, FormatError> {
+    let mapping = match options.map {
+        Some(ref spec) => parse_mapping(spec)?,
+        None => Vec::new(),
+    };
+    let data = fs::read(path)?;
+    let mut reader = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(data.as_slice());
+    let headers = reader.headers()?.clone();
+    let columns = map_columns(&headers, &mapping)?;
+    let date_format = options.date_format.as_deref();
+    let dry_run = options.dry_run;
+
+    let mut summary = ImportSummary { dry_run, ..Default::default() };
+    for (index, header) in headers.iter().enumerate() {
+        if !columns.contains_key(&index) && !header.eq_ignore_ascii_case("id") {
+            summary.warnings.push(format!("column '{}' ignored", header));
+        }
+    }
+
+    let mut existing: HashSet<(String, String)> = db
+        .dump_tasks()?
+        .into_iter()
+        .map(|t| (t.title, t.created_at))
+        .collect();
+    let mut notebooks = NotebookResolver::new(dry_run);
+    let mut next_order = db.get_max_task_order().unwrap_or(-1) + 1;
+
+    for result in reader.records() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| record_line(&data, p)).unwrap_or_default();
+                summary.errors.push(format!("line {}: {}", line, e));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| record_line(&data, p)).unwrap_or_default();
+        if record.iter().all(|cell| cell.is_empty()) {
+            continue;
+        }
+
+        let mut task = Task::new(String::new());
+        let mut notebook_name: Option<String> = None;
+        let mut created_given = false;
+        let mut updated_given = false;
+        let mut problems: Vec<String> = Vec::new();
+
+        for (&index, &field) in &columns {
+            let value = record.get(index).unwrap_or("");
+            if value.is_empty() {
+                continue;
+            }
+            match field {
+                CsvField::Uuid => match normalize_uuid(value) {
+                    Some(uuid) => task.uuid = Some(uuid),
+                    None => problems.push(format!("invalid UUID '{}'", value)),
+                },
+                CsvField::Title => task.title = value.to_string(),
+                CsvField::Description => task.description = Some(value.to_string()),
+                CsvField::Status => match parse_status(value) {
+                    Some(status) => task.status = status.to_string(),
+                    None => problems.push(format!("invalid status '{}'", value)),
+                },
+                CsvField::DueDate => match parse_date(value, date_format) {
+                    Ok(date) => task.due_date = Some(date),
+                    Err(e) => problems.push(e),
+                },
+                CsvField::Tags => task.tags = join_tags(&split_tags(value)),
+                CsvField::Notebook => notebook_name = Some(value.to_string()),
+                CsvField::Archived => match parse_flag(value) {
+                    Some(archived) => task.archived = archived,
+                    None => problems.push(format!("invalid archived value '{}'", value)),
+                },
+                CsvField::CreatedAt => match parse_timestamp(value, date_format) {
+                    Ok(ts) => {
+                        task.created_at = ts;
+                        created_given = true;
+                    }
+                    Err(e) => problems.push(e),
+                },
+                CsvField::UpdatedAt => match parse_timestamp(value, date_format) {
+                    Ok(ts) => {
+                        task.updated_at = ts;
+                        updated_given = true;
+                    }
+                    Err(e) => problems.push(e),
+                },
+            }
+        }
+        if task.title.trim().is_empty() {
+            problems.insert(0, "missing title".to_string());
+        }
+        if !problems.is_empty() {
+            summary.errors.push(format!("line {}: {}", line, problems.join("; ")));
+            continue;
+        }
+        if created_given && !updated_given {
+            task.updated_at = task.created_at.clone();
+        }
+
+        if let Some(ref uuid) = task.uuid
+            && db.find_by_uuid(SyncKind::Task, uuid)?.is_some()
+        {
+            summary.skipped += 1;
+            continue;
+        }
+        if created_given && !existing.insert((task.title.clone(), task.created_at.clone())) {
+            summary.skipped += 1;
+            continue;
+        }
+        if let Some(ref name) = notebook_name {
+            task.notebook_id = notebooks.resolve(db, name)?;
+        }
+        task.order = next_order;
+        next_order += 1;
+
+        if !dry_run {
+            db.insert_task(&task)?;
+        }
+        summary.imported.add(ItemKind::Task);
+    }
+
+    summary.notebooks_created = notebooks.created;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    fn header(names: &[&str]) -> csv::StringRecord {
+        csv::StringRecord::from(names.to_vec())
+    }
+
+    #[test]
+    fn columns_are_mapped_by_header_and_by_map() {
+        let columns = map_columns(&header(&["ID", "Name", "Due", "Notes", "Extra"]), &[]).unwrap();
+        assert_eq!(columns.len(), 3);
+        assert_eq!((columns[&1], columns[&2], columns[&3]), (CsvField::Title, CsvField::DueDate, CsvField::Description));
+
+        // A mapped column takes the field from a header that names it
+        let mapping = parse_mapping("Extra=title, notes = tags").unwrap();
+        let columns = map_columns(&header(&["ID", "Name", "Due", "Notes", "Extra"]), &mapping).unwrap();
+        assert_eq!((columns[&4], columns[&3], columns[&2]), (CsvField::Title, CsvField::Tags, CsvField::DueDate));
+        assert!(!columns.contains_key(&1));
+
+        assert!(map_columns(&header(&["Name"]), &parse_mapping("Missing=title").unwrap()).is_err());
+        assert!(map_columns(&header(&["Due"]), &[]).is_err());
+        assert!(parse_mapping("Name=colour").is_err());
+        assert!(parse_mapping("Name").is_err());
+    }
+
+    #[test]
+    fn dates_follow_the_date_format_if_one_is_given() {
+        assert_eq!(parse_date("2024-03-05", None).as_deref(), Ok("2024-03-05"));
+        assert_eq!(parse_date("05.03.2024", None).as_deref(), Ok("2024-03-05"));
+        assert_eq!(parse_date("2024-03-05T10:00:00Z", None).as_deref(), Ok("2024-03-05"));
+        assert!(parse_date("03/05/2024", None).is_err());
+        assert_eq!(parse_date("03/05/2024", Some("%m/%d/%Y")).as_deref(), Ok("2024-03-05"));
+        assert_eq!(parse_date("05/03/2024", Some("%d/%m/%Y")).as_deref(), Ok("2024-03-05"));
+        assert!(parse_date("2024-03-05", Some("%d/%m/%Y")).is_err());
+        assert_eq!(parse_timestamp("05/03/2024 10:30", Some("%d/%m/%Y %H:%M")).as_deref(), Ok("2024-03-05 10:30:00"));
+    }
+
+    #[test]
+    fn failing_rows_are_reported_with_their_line_number() {
+        let db = MemoryStore::new();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plan.csv");
+        fs::write(&path, "title,due,status\nCall Bob,2024-03-05,open\n,2024-03-06,\n\n\"Pay\nrent\",soon,maybe\nShip,,done\n").unwrap();
+
+        let summary = import(&db, &path, &CsvOptions::default()).unwrap();
+        assert_eq!(summary.imported.tasks, 2);
+        assert_eq!(summary.errors, vec![
+            "line 3: missing title".to_string(),
+            "line 5: unrecognized date 'soon' (set one with --date-format); invalid status 'maybe'".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn imported_tasks_keep_their_uuid() {
+        let source = MemoryStore::new();
+        let id = source.insert_task(&Task::new("Call Bob".to_string())).unwrap();
+        let uuid = source.get_task(id).unwrap().uuid;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.csv");
+        export(&source, &path).unwrap();
+
+        let target = MemoryStore::new();
+        let summary = import(&target, &path, &CsvOptions::default()).unwrap();
+        assert_eq!(summary.imported.tasks, 1);
+        assert!(summary.warnings.is_empty());
+        assert_eq!(target.dump_tasks().unwrap()[0].uuid, uuid);
+        let summary = import(&target, &path, &CsvOptions::default()).unwrap();
+        assert_eq!((summary.imported.tasks, summary.skipped), (0, 1));
+    }
+}
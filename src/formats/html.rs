@@ -0,0 +1,634 @@
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use ratatui::style::Color;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use crate::config::Theme;
+use crate::formats::markdown::attachment_file_name;
+use crate::formats::{slugify, write_if_changed, FormatError, ItemCounts};
+use crate::models::{Attachment, ItemKind};
+use crate::store::AttachmentStore;
+use crate::tui::widgets::color::parse_color;
+
+/// Sub-directories of the site; `export` owns them and removes pages it no longer generates
+const NOTES_DIR: &str = "notes";
+const JOURNAL_DIR: &str = "journal";
+const TAGS_DIR: &str = "tags";
+const NOTEBOOKS_DIR: &str = "notebooks";
+const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Longest plain-text excerpt of an item kept in the search index
+const SEARCH_TEXT_LIMIT: usize = 5000;
+
+/// A note or journal entry as it appears on the site
+struct Page {
+    kind: ItemKind,
+    title: String,
+    date: String, // Journal date, or the note's creation date
+    updated_at: String,
+    notebook_id: Option<i64>,
+    tags: Vec<String>,
+    markdown: String,
+    url: String, // Relative to the site root
+    attachments: Vec<Attachment>,
+}
+
+/// Everything pages link to: notebooks, tags and items
+struct Site {
+    title: String,
+    pages: Vec<Page>,
+    notebooks: Vec<(Option<i64>, String, String)>, // ID, name, URL (None: items outside any notebook)
+    tag_urls: BTreeMap<String, String>,
+    link_targets: HashMap<String, String>, // Lowercase note title or journal date -> URL
+}
+
+impl Site {
+    fn notebook(&self, id: Option<i64>) -> Option<&(Option<i64>, String, String)> {
+        self.notebooks.iter().find(|(nb_id, _, _)| *nb_id == id)
+    }
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode a file name for use as a URL path segment
+fn url_encode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// CSS color for a theme color (named terminal colors use the xterm palette)
+fn css_color(value: &str) -> String {
+    let (r, g, b) = match parse_color(value) {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        _ => (255, 255, 255),
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// The site stylesheet, with colors taken from a theme
+fn stylesheet(theme: &Theme) -> String {
+    format!(
+        r#":root {{
+  --fg: {fg};
+  --bg: {bg};
+  --accent: {accent};
+  --accent-fg: {accent_fg};
+  --muted: {muted};
+}}
+* {{ box-sizing: border-box; }}
+body {{ margin: 0; background: var(--bg); color: var(--fg); font: 16px/1.6 system-ui, sans-serif; display: flex; min-height: 100vh; }}
+a {{ color: var(--accent); }}
+nav {{ width: 16rem; flex-shrink: 0; padding: 1rem; border-right: 1px solid var(--muted); }}
+nav h2 {{ font-size: 1rem; margin: 1.5rem 0 0.5rem; }}
+nav ul {{ list-style: none; padding: 0; margin: 0; }}
+nav li.current > a {{ background: var(--accent); color: var(--accent-fg); text-decoration: none; padding: 0 0.25rem; }}
+main {{ flex: 1; max-width: 50rem; padding: 1rem 2rem; }}
+.meta {{ color: var(--muted); font-size: 0.9rem; }}
+.tag {{ display: inline-block; margin-right: 0.5rem; }}
+pre, code {{ font-family: ui-monospace, monospace; }}
+pre {{ padding: 0.75rem; overflow-x: auto; border: 1px solid var(--muted); }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid var(--muted); padding: 0.25rem 0.5rem; }}
+blockquote {{ margin-left: 0; padding-left: 1rem; border-left: 3px solid var(--muted); }}
+#search {{ width: 100%; padding: 0.25rem; background: var(--bg); color: var(--fg); border: 1px solid var(--muted); }}
+#search-results li {{ margin: 0.25rem 0; }}
+"#,
+        fg = css_color(&theme.fg),
+        bg = css_color(&theme.bg),
+        accent = css_color(&theme.highlight_bg),
+        accent_fg = css_color(&theme.highlight_fg),
+        muted = css_color(&theme.tab_bg),
+    )
+}
+
+/// Client-side search over `search-index.js` (a script rather than JSON so it also works from file://)
+const SEARCH_SCRIPT: &str = r#"(function () {
+  var input = document.getElementById("search");
+  var results = document.getElementById("search-results");
+  var root = document.body.getAttribute("data-root");
+  if (!input || !results || !window.TNJ_SEARCH_INDEX) { return; }
+  input.addEventListener("input", function () {
+    var terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+    results.innerHTML = "";
+    if (terms.length === 0) { return; }
+    var matches = window.TNJ_SEARCH_INDEX.filter(function (entry) {
+      var haystack = (entry.title + " " + entry.tags.join(" ") + " " + entry.text).toLowerCase();
+      return terms.every(function (term) { return haystack.indexOf(term) !== -1; });
+    }).slice(0, 20);
+    matches.forEach(function (entry) {
+      var item = document.createElement("li");
+      var link = document.createElement("a");
+      link.href = root + entry.url;
+      link.textContent = entry.title;
+      item.appendChild(link);
+      results.appendChild(item);
+    });
+    if (matches.length === 0) { results.innerHTML = "<li>No results</li>"; }
+  });
+})();
+"#;
+
+/// Turn `[[Target]]` and `[[Target|Label]]` wikilinks to exported notes and journal dates into
+/// Markdown links; other wikilinks are left as they are. Fenced code blocks are not touched.
+fn resolve_wikilinks(markdown: &str, targets: &HashMap<String, String>, root: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut in_code_block = false;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+        }
+        if in_code_block || !line.contains("[[") {
+            out.push_str(line);
+            continue;
+        }
+        let mut rest = line;
+        while let Some(start) = rest.find("[[") {
+            let Some(len) = rest[start + 2..].find("]]") else {
+                break;
+            };
+            let inner = &rest[start + 2..start + 2 + len];
+            let (target, label) = inner.split_once('|').unwrap_or((inner, inner));
+            out.push_str(&rest[..start]);
+            match targets.get(&target.trim().to_lowercase()) {
+                Some(url) => out.push_str(&format!("[{}](<{}{}>)", label.trim(), root, url)),
+                None => out.push_str(&rest[start..start + 4 + len]),
+            }
+            rest = &rest[start + 4 + len..];
+        }
+        out.push_str(rest);
+    }
+    out
+}
+
+/// Check whether a link or image URL is safe to publish: relative, or http(s) or mailto
+/// Browsers ignore whitespace and control characters in the scheme, so those are dropped first
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_control()).collect();
+    let scheme_end = url.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')));
+    match scheme_end.filter(|&end| url[end..].starts_with(':')) {
+        Some(end) => matches!(url[..end].to_lowercase().as_str(), "http" | "https" | "mailto"),
+        None => true,
+    }
+}
+
+/// Replace an unsafe link or image URL with an empty fragment
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    if is_safe_url(&url) {
+        url
+    } else {
+        CowStr::Borrowed("#")
+    }
+}
+
+/// Render Markdown to HTML; raw HTML in the source is shown as text rather than passed through,
+/// and links and images to other schemes than http, https and mailto (e.g. `javascript:`) are dropped
+fn render_markdown(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+            Event::Start(Tag::Link { link_type, dest_url: safe_url(dest_url), title, id })
+        }
+        Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+            Event::Start(Tag::Image { link_type, dest_url: safe_url(dest_url), title, id })
+        }
+        other => other,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+/// Plain text of a Markdown document, for the search index
+fn plain_text(markdown: &str) -> String {
+    let mut text = String::new();
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak | Event::End(_) => text.push(' '),
+            _ => {}
+        }
+        if text.len() > SEARCH_TEXT_LIMIT {
+            break;
+        }
+    }
+    let text: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    text.chars().take(SEARCH_TEXT_LIMIT).collect()
+}
+
+/// Wrap page content in the site layout
+/// `root` is the relative path back to the site root ("" or "../"); `current` is the page's own URL
+fn layout(site: &Site, root: &str, title: &str, notebook: Option<Option<i64>>, current: &str, content: &str) -> String {
+    let link = |url: &str, label: &str| {
+        let class = if url == current { " class=\"current\"" } else { "" };
+        format!("<li{}><a href=\"{}{}\">{}</a></li>\n", class, root, escape_html(url), escape_html(label))
+    };
+
+    let mut nav = String::new();
+    nav.push_str(&format!("<p><a href=\"{}index.html\"><strong>{}</strong></a></p>\n", root, escape_html(&site.title)));
+    nav.push_str("<input id=\"search\" type=\"search\" placeholder=\"Search\" aria-label=\"Search\">\n");
+    nav.push_str("<ul id=\"search-results\"></ul>\n");
+
+    // Pages of the current notebook
+    if let Some(notebook_id) = notebook
+        && let Some((_, name, _)) = site.notebook(notebook_id)
+    {
+        nav.push_str(&format!("<h2>{}</h2>\n<ul>\n", escape_html(name)));
+        for page in site.pages.iter().filter(|p| p.notebook_id == notebook_id) {
+            nav.push_str(&link(&page.url, &page.title));
+        }
+        nav.push_str("</ul>\n");
+    }
+
+    nav.push_str("<h2>Notebooks</h2>\n<ul>\n");
+    for (_, name, url) in &site.notebooks {
+        nav.push_str(&link(url, name));
+    }
+    nav.push_str("</ul>\n");
+    if !site.tag_urls.is_empty() {
+        nav.push_str("<h2>Tags</h2>\n<ul>\n");
+        for (tag, url) in &site.tag_urls {
+            nav.push_str(&link(url, &format!("#{}", tag)));
+        }
+        nav.push_str("</ul>\n");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title} - {site_title}</title>
+<link rel="stylesheet" href="{root}style.css">
+</head>
+<body data-root="{root}">
+<nav>
+{nav}</nav>
+<main>
+{content}</main>
+<script src="{root}search-index.js"></script>
+<script src="{root}search.js"></script>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        site_title = escape_html(&site.title),
+        root = root,
+        nav = nav,
+        content = content,
+    )
+}
+
+/// A list of links to pages
+fn page_list<'a>(pages: impl Iterator<Item = &'a Page>, root: &str) -> String {
+    let mut list = String::from("<ul>\n");
+    for page in pages {
+        list.push_str(&format!(
+            "<li><a href=\"{}{}\">{}</a> <span class=\"meta\">{}</span></li>\n",
+            root,
+            escape_html(&page.url),
+            escape_html(&page.title),
+            page.date
+        ));
+    }
+    list.push_str("</ul>\n");
+    list
+}
+
+/// Notes (by title) and journal entries (newest first) of a set of pages, as headed lists
+fn notes_and_journal(pages: &[&Page], root: &str) -> String {
+    let mut notes: Vec<&&Page> = pages.iter().filter(|p| p.kind == ItemKind::Note).collect();
+    notes.sort_by_key(|p| p.title.to_lowercase());
+    let mut journals: Vec<&&Page> = pages.iter().filter(|p| p.kind == ItemKind::Journal).collect();
+    journals.sort_by(|a, b| b.date.cmp(&a.date));
+
+    let mut content = String::new();
+    if !notes.is_empty() {
+        content.push_str("<h2>Notes</h2>\n");
+        content.push_str(&page_list(notes.into_iter().copied(), root));
+    }
+    if !journals.is_empty() {
+        content.push_str("<h2>Journal</h2>\n");
+        content.push_str(&page_list(journals.into_iter().copied(), root));
+    }
+    content
+}
+
+fn item_page(site: &Site, page: &Page) -> String {
+    let root = "../";
+    let mut meta = vec![page.date.clone()];
+    if let Some((_, name, url)) = site.notebook(page.notebook_id)
+        && page.notebook_id.is_some()
+    {
+        meta.push(format!("<a href=\"{}{}\">{}</a>", root, escape_html(url), escape_html(name)));
+    }
+    for tag in &page.tags {
+        if let Some(url) = site.tag_urls.get(tag) {
+            meta.push(format!("<a class=\"tag\" href=\"{}{}\">#{}</a>", root, escape_html(url), escape_html(tag)));
+        }
+    }
+    let markdown = resolve_wikilinks(&page.markdown, &site.link_targets, root);
+    let mut attachments = String::new();
+    if !page.attachments.is_empty() {
+        attachments.push_str("<h2>Attachments</h2>\n<ul>\n");
+        for attachment in &page.attachments {
+            attachments.push_str(&format!(
+                "<li><a href=\"{}{}/{}\">{}</a> <span class=\"meta\">{}</span></li>\n",
+                root,
+                ATTACHMENTS_DIR,
+                escape_html(&url_encode(&attachment_file_name(attachment))),
+                escape_html(&attachment.file_name),
+                crate::utils::format_file_size(attachment.size)
+            ));
+        }
+        attachments.push_str("</ul>\n");
+    }
+    let content = format!(
+        "<h1>{}</h1>\n<p class=\"meta\">{}</p>\n{}{}<p class=\"meta\">Last updated {}</p>\n",
+        escape_html(&page.title),
+        meta.join(" · "),
+        render_markdown(&markdown),
+        attachments,
+        page.updated_at
+    );
+    layout(site, root, &page.title, Some(page.notebook_id), &page.url, &content)
+}
+
+/// JavaScript defining the search index: title, URL, tags and plain text of every page
+fn search_index(site: &Site) -> Result<String, FormatError> {
+    let entries: Vec<serde_json::Value> = site
+        .pages
+        .iter()
+        .map(|page| {
+            serde_json::json!({
+                "title": page.title,
+                "url": page.url,
+                "tags": page.tags,
+                "text": plain_text(&page.markdown),
+            })
+        })
+        .collect();
+    Ok(format!("window.TNJ_SEARCH_INDEX = {};\n", serde_json::to_string(&entries)?))
+}
+
+/// Remove `.html` files (or copied attachments) in a site sub-directory that this export did not generate
+fn remove_stale_pages(dir: &Path, subdir: &str, written: &BTreeSet<String>) -> Result<(), FormatError> {
+    let Ok(entries) = fs::read_dir(dir.join(subdir)) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let url = format!("{}/{}", subdir, name);
+        let generated = if subdir == ATTACHMENTS_DIR {
+            name.split_once('-').is_some_and(|(id, _)| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        } else {
+            name.ends_with(".html")
+        };
+        if generated && entry.file_type()?.is_file() && !written.contains(&url) {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Export notes and journal entries (excluding archived, trashed and locked ones) as a static HTML site
+///
+/// Generates a page per item with Markdown rendered to HTML, an index, a page per notebook and
+/// per tag, navigation between the items of a notebook, and a client-side search index. Colors
+/// come from `theme`. Attached files are copied to `attachments/` and linked from their item's
+/// page. With `notebook`, only that notebook is exported.
+pub fn export(db: &dyn AttachmentStore, dir: &Path, notebook: Option<&str>, theme: &Theme) -> Result<ItemCounts, FormatError> {
+    let (notebook_filter, site_title) = match notebook {
+        Some(name) => {
+            let notebook = db
+                .get_notebook_by_name(name)?
+                .ok_or_else(|| FormatError::InvalidInput(format!("Notebook not found: {}", name)))?;
+            (notebook.id, notebook.name)
+        }
+        None => (None, "tnj".to_string()),
+    };
+
+    let mut counts = ItemCounts::default();
+    let mut pages: Vec<Page> = Vec::new();
+    let mut attachments: HashMap<(ItemKind, i64), Vec<Attachment>> = HashMap::new();
+    for attachment in db.dump_attachments()? {
+        attachments.entry((attachment.item_kind, attachment.item_id)).or_default().push(attachment);
+    }
+    let split_tags = |tags: Option<&str>| -> Vec<String> {
+        tags.unwrap_or("")
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect()
+    };
+
+    let (notes, journals) = match notebook_filter {
+        Some(id) => (db.get_all_notes(Some(id))?, db.get_all_journals(Some(id))?),
+        None => (
+            db.dump_notes()?.into_iter().filter(|n| !n.archived && n.deleted_at.is_none()).collect(),
+            db.dump_journals()?.into_iter().filter(|j| !j.archived && j.deleted_at.is_none()).collect(),
+        ),
+    };
+
+    for note in notes {
+        let id = note.id.unwrap_or_default();
+        pages.push(Page {
+            kind: ItemKind::Note,
+            url: format!("{}/{}-{}.html", NOTES_DIR, id, slugify(&note.title)),
+            title: note.title,
+            date: note.created_at.get(..10).unwrap_or(&note.created_at).to_string(),
+            updated_at: note.updated_at,
+            notebook_id: note.notebook_id,
+            tags: split_tags(note.tags.as_deref()),
+            markdown: note.content.unwrap_or_default(),
+            attachments: attachments.remove(&(ItemKind::Note, id)).unwrap_or_default(),
+        });
+        counts.add(ItemKind::Note);
+    }
+    // Locked entries are private, so they are left out rather than published encrypted
+    for journal in journals.into_iter().filter(|j| !j.is_locked()) {
+        let id = journal.id.unwrap_or_default();
+        pages.push(Page {
+            kind: ItemKind::Journal,
+            url: format!("{}/{}-{}.html", JOURNAL_DIR, journal.date, id),
+            title: journal.title.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| journal.date.clone()),
+            date: journal.date,
+            updated_at: journal.updated_at,
+            notebook_id: journal.notebook_id,
+            tags: split_tags(journal.tags.as_deref()),
+            markdown: journal.content.unwrap_or_default(),
+            attachments: attachments.remove(&(ItemKind::Journal, id)).unwrap_or_default(),
+        });
+        counts.add(ItemKind::Journal);
+    }
+
+    // Notebooks that have pages, in name order, followed by items outside any notebook
+    let used: BTreeSet<Option<i64>> = pages.iter().map(|p| p.notebook_id).collect();
+    let mut notebooks: Vec<(Option<i64>, String, String)> = db
+        .get_all_notebooks()?
+        .into_iter()
+        .filter(|n| used.contains(&n.id))
+        .map(|n| {
+            let url = format!("{}/{}-{}.html", NOTEBOOKS_DIR, n.id.unwrap_or_default(), slugify(&n.name));
+            (n.id, n.name, url)
+        })
+        .collect();
+    notebooks.sort_by_key(|(_, name, _)| name.to_lowercase());
+    if used.contains(&None) {
+        notebooks.push((None, "No notebook".to_string(), format!("{}/none.html", NOTEBOOKS_DIR)));
+    }
+
+    // Tag pages; tags whose slugs collide get a number appended
+    let all_tags: BTreeSet<String> = pages.iter().flat_map(|p| p.tags.iter().cloned()).collect();
+    let mut tag_urls: BTreeMap<String, String> = BTreeMap::new();
+    let mut taken: BTreeSet<String> = BTreeSet::new();
+    for tag in all_tags {
+        let base = slugify(&tag);
+        let mut slug = base.clone();
+        let mut n = 2;
+        while !taken.insert(slug.clone()) {
+            slug = format!("{}-{}", base, n);
+            n += 1;
+        }
+        tag_urls.insert(tag, format!("{}/{}.html", TAGS_DIR, slug));
+    }
+
+    let mut link_targets: HashMap<String, String> = HashMap::new();
+    for page in &pages {
+        let key = match page.kind {
+            ItemKind::Journal => page.date.clone(),
+            _ => page.title.to_lowercase(),
+        };
+        link_targets.entry(key).or_insert_with(|| page.url.clone());
+    }
+
+    let site = Site {
+        title: site_title,
+        pages,
+        notebooks,
+        tag_urls,
+        link_targets,
+    };
+
+    let mut written: BTreeSet<String> = BTreeSet::new();
+    let mut write = |url: &str, contents: &str| -> Result<(), FormatError> {
+        write_if_changed(&dir.join(url), contents)?;
+        written.insert(url.to_string());
+        Ok(())
+    };
+
+    // Index: notebooks with their item counts, then tags
+    let mut content = format!("<h1>{}</h1>\n<h2>Notebooks</h2>\n<ul>\n", escape_html(&site.title));
+    for (id, name, url) in &site.notebooks {
+        let count = site.pages.iter().filter(|p| p.notebook_id == *id).count();
+        content.push_str(&format!(
+            "<li><a href=\"{}\">{}</a> <span class=\"meta\">{}</span></li>\n",
+            escape_html(url),
+            escape_html(name),
+            count
+        ));
+    }
+    content.push_str("</ul>\n");
+    if !site.tag_urls.is_empty() {
+        content.push_str("<h2>Tags</h2>\n<p>\n");
+        for (tag, url) in &site.tag_urls {
+            content.push_str(&format!("<a class=\"tag\" href=\"{}\">#{}</a>\n", escape_html(url), escape_html(tag)));
+        }
+        content.push_str("</p>\n");
+    }
+    let all: Vec<&Page> = site.pages.iter().collect();
+    content.push_str(&notes_and_journal(&all, ""));
+    write("index.html", &layout(&site, "", &site.title, None, "index.html", &content))?;
+
+    for (id, name, url) in &site.notebooks {
+        let pages: Vec<&Page> = site.pages.iter().filter(|p| p.notebook_id == *id).collect();
+        let content = format!("<h1>{}</h1>\n{}", escape_html(name), notes_and_journal(&pages, "../"));
+        write(url, &layout(&site, "../", name, Some(*id), url, &content))?;
+    }
+
+    for (tag, url) in &site.tag_urls {
+        let pages: Vec<&Page> = site.pages.iter().filter(|p| p.tags.contains(tag)).collect();
+        let content = format!("<h1>#{}</h1>\n{}", escape_html(tag), notes_and_journal(&pages, "../"));
+        write(url, &layout(&site, "../", &format!("#{}", tag), None, url, &content))?;
+    }
+
+    for page in &site.pages {
+        write(&page.url, &item_page(&site, page))?;
+    }
+
+    write("style.css", &stylesheet(theme))?;
+    write("search.js", SEARCH_SCRIPT)?;
+    write("search-index.js", &search_index(&site)?)?;
+
+    for attachment in site.pages.iter().flat_map(|p| &p.attachments) {
+        let url = format!("{}/{}", ATTACHMENTS_DIR, attachment_file_name(attachment));
+        write_if_changed(&dir.join(&url), db.read_attachment(attachment)?)?;
+        written.insert(url);
+    }
+
+    for subdir in [NOTES_DIR, JOURNAL_DIR, TAGS_DIR, NOTEBOOKS_DIR, ATTACHMENTS_DIR] {
+        remove_stale_pages(dir, subdir, &written)?;
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_safe_link_schemes_are_rendered() {
+        let html = render_markdown(
+            "[a](javascript:alert(1)) [b](<java\tscript:x>) ![c](data:text/html,x) <JavaScript:y> \
+             [d](https://example.com) [e](mailto:me@example.com) [f](../notes/g.html#top)",
+        );
+        assert_eq!(html.matches("href=\"#\"").count(), 3, "{}", html);
+        assert!(html.contains("src=\"#\""), "{}", html);
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("href=\"mailto:me@example.com\""));
+        assert!(html.contains("href=\"../notes/g.html#top\""));
+    }
+}
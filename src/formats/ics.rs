@@ -0,0 +1,529 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::formats::{join_tags, normalize_uuid, FormatError, ImportSummary, ItemCounts, NotebookResolver};
+use crate::models::{ItemKind, JournalEntry, SyncKind, Task};
+use crate::store::Store;
+
+/// Longest line (in bytes, without the line break) allowed by RFC 5545
+const MAX_LINE_LENGTH: usize = 75;
+
+/// A content line of an iCalendar file, e.g. `DUE;VALUE=DATE:20240305`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,                   // Upper case
+    pub params: Vec<(String, String)>,  // Upper-case names
+    pub value: String,                  // Raw (still escaped) value
+}
+
+impl Property {
+    /// Get a parameter value by name
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// A VTODO, VEVENT or VJOURNAL component with its own properties (nested components such as
+/// VALARM are left out)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+}
+
+impl Component {
+    /// Get the first property with this name
+    pub fn get(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    /// Get the unescaped text of the first property with this name
+    pub fn text(&self, name: &str) -> Option<String> {
+        self.get(name).map(|p| unescape_text(&p.value)).filter(|t| !t.trim().is_empty())
+    }
+
+    /// Get all values of a list property (such as CATEGORIES), over all of its occurrences
+    pub fn list(&self, name: &str) -> Vec<String> {
+        self.properties
+            .iter()
+            .filter(|p| p.name == name)
+            .flat_map(|p| split_list(&p.value))
+            .collect()
+    }
+}
+
+/// Escape text for a property value
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Undo `escape_text`
+fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// Split a comma-separated list value on unescaped commas and unescape the parts
+fn split_list(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ',' => parts.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    parts.push(current);
+    parts
+        .iter()
+        .map(|p| unescape_text(p).trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Append a content line, folded so that no line is longer than 75 bytes
+fn push_line(out: &mut String, line: &str) {
+    let mut limit = MAX_LINE_LENGTH;
+    let mut rest = line;
+    while rest.len() > limit {
+        let mut split = limit;
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        out.push_str(&rest[..split]);
+        out.push_str("\r\n ");
+        rest = &rest[split..];
+        limit = MAX_LINE_LENGTH - 1; // Continuation lines start with a space
+    }
+    out.push_str(rest);
+    out.push_str("\r\n");
+}
+
+/// Format a database timestamp (UTC, YYYY-MM-DD HH:MM:SS) as an iCalendar UTC date-time
+fn format_timestamp(timestamp: &str) -> String {
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+        .map(|dt| dt.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_else(|_| chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// Format a YYYY-MM-DD date as an iCalendar date
+fn format_date(date: &str) -> String {
+    date.replace('-', "")
+}
+
+/// Parse an iCalendar DATE or DATE-TIME value to a database timestamp
+/// UTC values ("...Z") are kept as they are; floating and TZID values are taken as UTC too
+fn parse_timestamp(value: &str) -> Option<String> {
+    let value = value.trim().trim_end_matches('Z');
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some(dt.format("%Y-%m-%d %H:%M:%S").to_string());
+    }
+    parse_date(value).map(|date| format!("{} 00:00:00", date))
+}
+
+/// Parse the date part of an iCalendar DATE or DATE-TIME value to YYYY-MM-DD
+fn parse_date(value: &str) -> Option<String> {
+    let date_part = value.trim().get(..8)?;
+    chrono::NaiveDate::parse_from_str(date_part, "%Y%m%d")
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// The stable UID of an exported item, built from its UUID (or, for an item without one, from its
+/// ID and creation time so that it does not match a different item in another database)
+pub fn item_uid(kind: ItemKind, id: i64, uuid: Option<&str>, created_at: &str) -> String {
+    if let Some(uuid) = uuid {
+        return format!("{}@tnj", uuid);
+    }
+    let created: String = created_at.chars().filter(|c| c.is_ascii_digit()).collect();
+    format!("tnj-{}-{}-{}@tnj", kind, id, created)
+}
+
+/// Get the UUID of the item a UID was exported from (None for UIDs not made by `item_uid` from a UUID)
+fn exported_uuid(uid: &str) -> Option<String> {
+    uid.strip_suffix("@tnj").and_then(normalize_uuid)
+}
+
+/// Find the task an exported UID refers to, if it is in this database
+fn find_exported_task(db: &dyn Store, uid: &str) -> Result<Option<i64>, FormatError> {
+    if let Some(uuid) = exported_uuid(uid) {
+        return Ok(db.find_by_uuid(SyncKind::Task, &uuid)?);
+    }
+    // UIDs exported before items had UUIDs
+    let Some(rest) = uid.strip_prefix("tnj-task-").and_then(|r| r.strip_suffix("@tnj")) else {
+        return Ok(None);
+    };
+    let Some(id) = rest.split('-').next().and_then(|id| id.parse::<i64>().ok()) else {
+        return Ok(None);
+    };
+    match db.get_task(id) {
+        Ok(task) if item_uid(ItemKind::Task, id, None, &task.created_at) == uid => Ok(Some(id)),
+        _ => Ok(None),
+    }
+}
+
+/// Split an iCalendar file into VTODO, VEVENT and VJOURNAL components
+pub fn parse_components(text: &str) -> Vec<Component> {
+    // Unfold continuation lines (lines starting with a space or tab)
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if let Some(continued) = line.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(continued);
+        } else if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+
+    let mut components = Vec::new();
+    let mut stack: Vec<Component> = Vec::new();
+    for line in &lines {
+        let Some(property) = parse_property(line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Component {
+                name: property.value.trim().to_uppercase(),
+                properties: Vec::new(),
+            }),
+            "END" => {
+                if let Some(component) = stack.pop()
+                    && matches!(component.name.as_str(), "VTODO" | "VEVENT" | "VJOURNAL")
+                {
+                    components.push(component);
+                }
+            }
+            _ => {
+                if let Some(component) = stack.last_mut() {
+                    component.properties.push(property);
+                }
+            }
+        }
+    }
+    components
+}
+
+/// Parse a content line: NAME *(";" PARAM "=" VALUE) ":" VALUE
+fn parse_property(line: &str) -> Option<Property> {
+    // The value starts after the first colon that is not inside a quoted parameter value
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => return Some(i),
+            _ => {}
+        }
+        None
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(n, v)| (n.trim().to_uppercase(), v.trim().trim_matches('"').to_string()))
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn push_task(out: &mut String, task: &Task, id: i64, notebook: Option<&str>, stamp: &str) {
+    push_line(out, "BEGIN:VTODO");
+    push_line(out, &format!("UID:{}", item_uid(ItemKind::Task, id, task.uuid.as_deref(), &task.created_at)));
+    push_line(out, &format!("DTSTAMP:{}", stamp));
+    push_line(out, &format!("CREATED:{}", format_timestamp(&task.created_at)));
+    push_line(out, &format!("LAST-MODIFIED:{}", format_timestamp(&task.updated_at)));
+    push_line(out, &format!("SUMMARY:{}", escape_text(&task.title)));
+    if let Some(ref description) = task.description
+        && !description.trim().is_empty()
+    {
+        push_line(out, &format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    if let Some(ref due) = task.due_date {
+        push_line(out, &format!("DUE;VALUE=DATE:{}", format_date(due)));
+    }
+    if task.status == "done" {
+        push_line(out, "STATUS:COMPLETED");
+        push_line(out, &format!("COMPLETED:{}", format_timestamp(&task.updated_at)));
+    } else {
+        push_line(out, "STATUS:NEEDS-ACTION");
+    }
+    push_categories(out, task.tags.as_deref());
+    if let Some(name) = notebook {
+        push_line(out, &format!("X-TNJ-NOTEBOOK:{}", escape_text(name)));
+    }
+    push_line(out, "END:VTODO");
+}
+
+fn push_journal(out: &mut String, journal: &JournalEntry, id: i64, notebook: Option<&str>, stamp: &str) {
+    push_line(out, "BEGIN:VJOURNAL");
+    push_line(out, &format!("UID:{}", item_uid(ItemKind::Journal, id, journal.uuid.as_deref(), &journal.created_at)));
+    push_line(out, &format!("DTSTAMP:{}", stamp));
+    push_line(out, &format!("CREATED:{}", format_timestamp(&journal.created_at)));
+    push_line(out, &format!("LAST-MODIFIED:{}", format_timestamp(&journal.updated_at)));
+    push_line(out, &format!("DTSTART;VALUE=DATE:{}", format_date(&journal.date)));
+    let title = journal.title.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or(&journal.date);
+    push_line(out, &format!("SUMMARY:{}", escape_text(title)));
+    if let Some(ref content) = journal.content
+        && !content.trim().is_empty()
+    {
+        push_line(out, &format!("DESCRIPTION:{}", escape_text(content)));
+    }
+    push_line(out, "STATUS:FINAL");
+    push_categories(out, journal.tags.as_deref());
+    if let Some(name) = notebook {
+        push_line(out, &format!("X-TNJ-NOTEBOOK:{}", escape_text(name)));
+    }
+    push_line(out, "END:VJOURNAL");
+}
+
+fn push_categories(out: &mut String, tags: Option<&str>) {
+    let tags: Vec<String> = tags
+        .unwrap_or("")
+        .split(',')
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .map(escape_text)
+        .collect();
+    if !tags.is_empty() {
+        push_line(out, &format!("CATEGORIES:{}", tags.join(",")));
+    }
+}
+
+/// Export tasks as VTODO and journal entries as VJOURNAL components to an iCalendar file
+/// Archived and trashed items and locked journal entries are left out.
+pub fn export(db: &dyn Store, path: &Path) -> Result<ItemCounts, FormatError> {
+    let notebooks: HashMap<i64, String> = db
+        .get_all_notebooks()?
+        .into_iter()
+        .filter_map(|n| n.id.map(|id| (id, n.name)))
+        .collect();
+    let notebook_name = |id: Option<i64>| id.and_then(|id| notebooks.get(&id)).map(String::as_str);
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut counts = ItemCounts::default();
+
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, &format!("PRODID:-//tnj//tnj {}//EN", env!("CARGO_PKG_VERSION")));
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+
+    for task in db.dump_tasks()?.into_iter().filter(|t| !t.archived && t.deleted_at.is_none()) {
+        if let Some(id) = task.id {
+            push_task(&mut out, &task, id, notebook_name(task.notebook_id), &stamp);
+            counts.add(ItemKind::Task);
+        }
+    }
+    for journal in db.dump_journals()?.into_iter().filter(|j| !j.archived && j.deleted_at.is_none() && !j.is_locked()) {
+        if let Some(id) = journal.id {
+            push_journal(&mut out, &journal, id, notebook_name(journal.notebook_id), &stamp);
+            counts.add(ItemKind::Journal);
+        }
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, out)?;
+    Ok(counts)
+}
+
+/// Import VTODO and VEVENT components from an iCalendar file as tasks
+///
+/// Components are matched by UID: a component imported before (or exported by tnj from this
+/// database) updates its task instead of creating a new one, unless the task is in the trash. A
+/// new task keeps the UUID of a component exported by tnj. VEVENTs use their start date as the
+/// due date. With `dry_run` nothing is written.
+pub fn import(db: &dyn Store, path: &Path, dry_run: bool) -> Result<ImportSummary, FormatError> {
+    let text = fs::read_to_string(path)?;
+    let mut summary = ImportSummary { dry_run, ..Default::default() };
+    let mut notebooks = NotebookResolver::new(dry_run);
+    let mut next_order = db.get_max_task_order().unwrap_or(-1) + 1;
+    let mut journals_ignored = 0;
+
+    for (index, component) in parse_components(&text).iter().enumerate() {
+        let label = format!("{} #{}", component.name, index + 1);
+        if component.name == "VJOURNAL" {
+            journals_ignored += 1;
+            continue;
+        }
+
+        let Some(title) = component.text("SUMMARY") else {
+            summary.errors.push(format!("{}: no SUMMARY", label));
+            continue;
+        };
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let date_property = if component.name == "VEVENT" { "DTSTART" } else { "DUE" };
+        let due_date = match component.get(date_property) {
+            Some(p) => {
+                let date = parse_date(&p.value);
+                if date.is_none() {
+                    summary.warnings.push(format!("{}: invalid {} '{}' ignored", label, date_property, p.value));
+                }
+                date
+            }
+            None => None,
+        };
+        let status = match component.text("STATUS").map(|s| s.trim().to_uppercase()).as_deref() {
+            Some("COMPLETED") => "done",
+            Some("CANCELLED") => {
+                summary.warnings.push(format!("{}: cancelled, imported as done", label));
+                "done"
+            }
+            _ if component.get("COMPLETED").is_some() => "done",
+            _ => "todo",
+        };
+        let description = component.text("DESCRIPTION");
+        let tags = join_tags(&component.list("CATEGORIES"));
+        let notebook = component.text("X-TNJ-NOTEBOOK");
+
+        // Match the component to a task imported (or exported) before
+        let uid = component.text("UID");
+        let source = match uid {
+            Some(ref uid) => format!("ics:{}", uid.trim()),
+            None => {
+                summary.warnings.push(format!("{}: no UID, matched by summary and date", label));
+                format!("ics:{}|{}", title, due_date.as_deref().unwrap_or(""))
+            }
+        };
+        let existing_id = match db.get_imported_item(&source)? {
+            Some((ItemKind::Task, id)) => Some(id),
+            _ => match uid {
+                Some(ref uid) => find_exported_task(db, uid.trim())?,
+                None => None,
+            },
+        };
+
+        if let Some(id) = existing_id {
+            let mut task = db.get_task(id)?;
+            if task.deleted_at.is_some() {
+                summary.warnings.push(format!(
+                    "{}: task '{}' is in the trash, skipped (restore it to import changes)",
+                    label, task.title
+                ));
+                summary.skipped += 1;
+                continue;
+            }
+            let current_tags: Vec<&str> = task.tags.as_deref().unwrap_or("").split(',').collect();
+            let changed = task.title != title
+                || task.description != description
+                || task.due_date != due_date
+                || task.status != status
+                || join_tags(&current_tags) != tags;
+            if !changed {
+                summary.skipped += 1;
+                continue;
+            }
+            task.title = title;
+            task.description = description;
+            task.due_date = due_date;
+            task.status = status.to_string();
+            task.tags = tags;
+            task.updated_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            if !dry_run {
+                db.update_task(&task)?;
+                db.record_import_source(&source, ItemKind::Task, id)?;
+            }
+            summary.updated += 1;
+            continue;
+        }
+
+        let mut task = Task::new(title);
+        task.uuid = uid.as_deref().and_then(|uid| exported_uuid(uid.trim()));
+        task.description = description;
+        task.due_date = due_date;
+        task.status = status.to_string();
+        task.tags = tags;
+        task.order = next_order;
+        next_order += 1;
+        if let Some(ref name) = notebook {
+            task.notebook_id = notebooks.resolve(db, name)?;
+        }
+        if let Some(created) = component.get("CREATED").and_then(|p| parse_timestamp(&p.value)) {
+            task.created_at = created;
+        }
+        if let Some(modified) = component.get("LAST-MODIFIED").and_then(|p| parse_timestamp(&p.value)) {
+            task.updated_at = modified;
+        }
+
+        if !dry_run {
+            let id = db.insert_task(&task)?;
+            db.record_import_source(&source, ItemKind::Task, id)?;
+        }
+        summary.imported.add(ItemKind::Task);
+    }
+
+    if journals_ignored > 0 {
+        summary.warnings.push(format!(
+            "{} VJOURNAL {} ignored (only VTODO and VEVENT are imported)",
+            journals_ignored,
+            if journals_ignored == 1 { "entry" } else { "entries" }
+        ));
+    }
+    summary.notebooks_created = notebooks.created;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    #[test]
+    fn imported_tasks_keep_the_uuid_of_their_uid() {
+        let source = MemoryStore::new();
+        let id = source.insert_task(&Task::new("Call Bob".to_string())).unwrap();
+        let uuid = source.get_task(id).unwrap().uuid;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.ics");
+        export(&source, &path).unwrap();
+
+        let target = MemoryStore::new();
+        let summary = import(&target, &path, false).unwrap();
+        assert_eq!(summary.imported.tasks, 1);
+        assert_eq!(target.dump_tasks().unwrap()[0].uuid, uuid);
+        let summary = import(&target, &path, false).unwrap();
+        assert_eq!((summary.imported.tasks, summary.skipped), (0, 1));
+    }
+}
@@ -0,0 +1,309 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::formats::{FormatError, ImportSummary, ItemCounts};
+use crate::models::{Attachment, AttachmentStorage, ItemKind, JournalEntry, Note, Notebook, Revision, Task};
+use crate::store::Backend;
+
+/// Identifies tnj backup documents
+pub const BACKUP_FORMAT: &str = "tnj-backup";
+
+/// Version of the backup document layout; bump when the layout changes incompatibly
+pub const BACKUP_VERSION: u32 = 1;
+
+/// A complete copy of the database: all notebooks and items, including archived and trashed ones,
+/// with their revisions and attachments (settings belong to the database and are not included)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    #[serde(default)]
+    pub notebooks: Vec<Notebook>,
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub notes: Vec<Note>,
+    #[serde(default)]
+    pub journals: Vec<JournalEntry>,
+    #[serde(default)]
+    pub revisions: Vec<Revision>, // Item IDs are those in the backed-up database
+    #[serde(default)]
+    pub attachments: Vec<BackupAttachment>,
+}
+
+/// An attached file in a backup, with its contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupAttachment {
+    pub item_kind: ItemKind,
+    pub item_id: i64, // ID of the item in the backed-up database
+    pub file_name: String,
+    pub created_at: String,
+    pub data: String, // Base64
+}
+
+/// How a backup is restored into a database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Delete everything in the database first
+    Replace,
+    /// Keep existing data; add notebooks and items that are not already present
+    Merge,
+}
+
+impl FromStr for RestoreMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "replace" => Ok(RestoreMode::Replace),
+            "merge" => Ok(RestoreMode::Merge),
+            other => Err(format!("Unknown restore mode '{}' (expected replace or merge)", other)),
+        }
+    }
+}
+
+/// Write a JSON backup of the whole database (revisions and attachments included) to `path`
+pub fn backup(db: &dyn Backend, path: &Path) -> Result<ItemCounts, FormatError> {
+    let mut attachments = Vec::new();
+    for attachment in db.dump_attachments()? {
+        let data = db.read_attachment(&attachment)?;
+        attachments.push(BackupAttachment {
+            item_kind: attachment.item_kind,
+            item_id: attachment.item_id,
+            file_name: attachment.file_name,
+            created_at: attachment.created_at,
+            data: BASE64.encode(data),
+        });
+    }
+
+    let backup = Backup {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        notebooks: db.get_all_notebooks()?,
+        tasks: db.dump_tasks()?,
+        notes: db.dump_notes()?,
+        journals: db.dump_journals()?,
+        revisions: db.dump_revisions()?,
+        attachments,
+    };
+    let counts = ItemCounts {
+        tasks: backup.tasks.len(),
+        notes: backup.notes.len(),
+        journals: backup.journals.len(),
+    };
+
+    let json = serde_json::to_string_pretty(&backup)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, json + "\n")?;
+    Ok(counts)
+}
+
+/// Read and validate a backup document
+pub fn read_backup(path: &Path) -> Result<Backup, FormatError> {
+    let contents = fs::read_to_string(path)?;
+    let backup: Backup = serde_json::from_str(&contents)?;
+    if backup.format != BACKUP_FORMAT {
+        return Err(FormatError::InvalidInput(format!(
+            "{} is not a tnj backup (format '{}')",
+            path.display(),
+            backup.format
+        )));
+    }
+    if backup.version > BACKUP_VERSION {
+        return Err(FormatError::InvalidInput(format!(
+            "Backup version {} is newer than this version of tnj supports ({})",
+            backup.version, BACKUP_VERSION
+        )));
+    }
+    Ok(backup)
+}
+
+/// Restore a backup into the database in a single transaction
+///
+/// Items get new IDs but keep their UUIDs; notebook associations are kept by mapping each
+/// backed-up notebook ID to the ID of the restored (or, when merging, the existing notebook with
+/// the same UUID or name) notebook. When merging, items that already exist (same UUID, or same
+/// title or date and same creation time) are skipped, with their revisions and attachments.
+/// Restored attachments are stored as `storage` says.
+pub fn restore(db: &dyn Backend, path: &Path, mode: RestoreMode, storage: AttachmentStorage) -> Result<ImportSummary, FormatError> {
+    let backup = read_backup(path)?;
+    let mut summary = ImportSummary::default();
+
+    let tx = db.transaction()?;
+
+    if mode == RestoreMode::Replace {
+        db.delete_all_data()?;
+    }
+
+    // Map backed-up notebook IDs to IDs in this database
+    let mut existing: HashMap<String, i64> = HashMap::new();
+    let mut existing_uuids: HashMap<String, i64> = HashMap::new();
+    for notebook in db.get_all_notebooks()? {
+        let Some(id) = notebook.id else {
+            continue;
+        };
+        existing.insert(notebook.name, id);
+        if let Some(uuid) = notebook.uuid {
+            existing_uuids.insert(uuid, id);
+        }
+    }
+    let mut notebook_ids: HashMap<i64, i64> = HashMap::new();
+    for notebook in &backup.notebooks {
+        let Some(old_id) = notebook.id else {
+            continue;
+        };
+        let by_uuid = notebook.uuid.as_ref().and_then(|uuid| existing_uuids.get(uuid));
+        let new_id = match by_uuid.or(existing.get(&notebook.name)) {
+            Some(id) => *id,
+            None => {
+                summary.notebooks_created += 1;
+                db.insert_notebook(notebook)?
+            }
+        };
+        notebook_ids.insert(old_id, new_id);
+    }
+    let map_notebook = |id: Option<i64>| id.and_then(|old| notebook_ids.get(&old).copied());
+
+    // Items already in the database, by (title or date, created_at), to skip duplicates when merging
+    let mut existing_tasks: HashSet<(String, String)> = HashSet::new();
+    let mut existing_notes: HashSet<(String, String)> = HashSet::new();
+    let mut existing_journals: HashSet<(String, String)> = HashSet::new();
+    // UUIDs of all items in the database (an item whose UUID is taken is always already present)
+    let mut existing_items: HashSet<String> = HashSet::new();
+    if mode == RestoreMode::Merge {
+        for task in db.dump_tasks()? {
+            existing_items.extend(task.uuid);
+            existing_tasks.insert((task.title, task.created_at));
+        }
+        for note in db.dump_notes()? {
+            existing_items.extend(note.uuid);
+            existing_notes.insert((note.title, note.created_at));
+        }
+        for journal in db.dump_journals()? {
+            existing_items.extend(journal.uuid);
+            existing_journals.insert((journal.date, journal.created_at));
+        }
+    }
+    let already_present = |uuid: &Option<String>| uuid.as_ref().is_some_and(|uuid| existing_items.contains(uuid));
+
+    // Backed-up item IDs to the IDs of the restored items, for revisions and attachments
+    let mut item_ids: HashMap<(ItemKind, i64), i64> = HashMap::new();
+
+    let next_order = db.get_max_task_order().unwrap_or(-1) + 1;
+    for task in &backup.tasks {
+        if already_present(&task.uuid) || existing_tasks.contains(&(task.title.clone(), task.created_at.clone())) {
+            summary.skipped += 1;
+            continue;
+        }
+        let mut task = task.clone();
+        task.notebook_id = map_notebook(task.notebook_id);
+        if mode == RestoreMode::Merge {
+            // Keep merged tasks after the existing ones, in their backed-up order
+            task.order += next_order;
+        }
+        let id = db.insert_task(&task)?;
+        if task.deleted_at.is_some() {
+            db.set_deleted_at(ItemKind::Task, id, task.deleted_at.as_deref())?;
+        }
+        if let Some(old_id) = task.id {
+            item_ids.insert((ItemKind::Task, old_id), id);
+        }
+        summary.imported.add(ItemKind::Task);
+    }
+
+    for note in &backup.notes {
+        if already_present(&note.uuid) || existing_notes.contains(&(note.title.clone(), note.created_at.clone())) {
+            summary.skipped += 1;
+            continue;
+        }
+        let mut note = note.clone();
+        note.notebook_id = map_notebook(note.notebook_id);
+        let id = db.insert_note(&note)?;
+        if note.deleted_at.is_some() {
+            db.set_deleted_at(ItemKind::Note, id, note.deleted_at.as_deref())?;
+        }
+        if let Some(old_id) = note.id {
+            item_ids.insert((ItemKind::Note, old_id), id);
+        }
+        summary.imported.add(ItemKind::Note);
+    }
+
+    for journal in &backup.journals {
+        if already_present(&journal.uuid) || existing_journals.contains(&(journal.date.clone(), journal.created_at.clone())) {
+            summary.skipped += 1;
+            continue;
+        }
+        let mut journal = journal.clone();
+        journal.notebook_id = map_notebook(journal.notebook_id);
+        let id = db.insert_journal(&journal)?;
+        if journal.deleted_at.is_some() {
+            db.set_deleted_at(ItemKind::Journal, id, journal.deleted_at.as_deref())?;
+        }
+        if let Some(old_id) = journal.id {
+            item_ids.insert((ItemKind::Journal, old_id), id);
+        }
+        summary.imported.add(ItemKind::Journal);
+    }
+
+    for revision in &backup.revisions {
+        let Some(&item_id) = item_ids.get(&(revision.item_kind, revision.item_id)) else {
+            continue;
+        };
+        db.add_revision(&Revision { item_id, ..revision.clone() })?;
+    }
+
+    for backed_up in &backup.attachments {
+        let Some(&item_id) = item_ids.get(&(backed_up.item_kind, backed_up.item_id)) else {
+            continue;
+        };
+        let data = BASE64.decode(&backed_up.data).map_err(|e| {
+            FormatError::InvalidInput(format!("attachment {}: invalid data: {}", backed_up.file_name, e))
+        })?;
+        let mut attachment = Attachment::new(backed_up.item_kind, item_id, backed_up.file_name.clone());
+        attachment.created_at = backed_up.created_at.clone();
+        db.add_attachment(&attachment, &data, storage)?;
+    }
+
+    tx.commit()?;
+    // Files of attachments that were replaced
+    db.remove_unused_attachment_files()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::store::{HistoryStore, MemoryStore, Store};
+
+    #[test]
+    fn revisions_are_backed_up_and_restored() {
+        let source = MemoryStore::new();
+        source.insert_note(&Note::new("Filler".to_string())).unwrap();
+        let id = source.insert_note(&Note::new("Ideas".to_string())).unwrap();
+        let mut note = source.get_note(id).unwrap();
+        note.content = Some("First draft".to_string());
+        source.update_note(&note).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.json");
+        backup(&source, &path).unwrap();
+
+        let target = Database::new(":memory:").unwrap();
+        target.insert_note(&Note::new("Already here".to_string())).unwrap();
+        restore(&target, &path, RestoreMode::Replace, AttachmentStorage::Database).unwrap();
+        let restored = target.dump_notes().unwrap().into_iter().find(|n| n.title == "Ideas").unwrap();
+        let revisions = target.get_revisions(ItemKind::Note, restored.id.unwrap()).unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].content, None);
+        assert_eq!(revisions[0].saved_at, note.created_at);
+    }
+}
@@ -0,0 +1,873 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::formats::{
+    join_tags, normalize_date, normalize_timestamp, normalize_uuid, notebook_dir_names, notebook_dir_names_with, slugify,
+    write_if_changed, FormatError, ImportSummary, ItemCounts, NotebookResolver,
+};
+use crate::lock::Unlocker;
+use crate::models::{Attachment, ItemKind, JournalEntry, Note, Task};
+use crate::store::{AttachmentStore, Store};
+
+/// Manifest of the files written by the last export, used to remove files of deleted items
+pub const MANIFEST_FILE: &str = ".tnj-export";
+
+/// Sub-directories used for each item kind (inside a notebook directory or the export root)
+pub const TASKS_DIR: &str = "tasks";
+pub const NOTES_DIR: &str = "notes";
+pub const JOURNAL_DIR: &str = "journal";
+
+/// Directory (in the export root) that attached files are copied to
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// What a Markdown export is written for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportMode {
+    /// A copy to read or keep in git: files named by ID, attachments copied, trashed items left out
+    Copy,
+    /// The files of git storage: named by UUID alone and without local IDs, so they are the same
+    /// in every clone and keep their name when the title changes; trashed items are kept (with
+    /// their `deleted` time), attachments are not
+    Storage,
+}
+
+/// Quote a string as a YAML double-quoted scalar
+pub fn yaml_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Split a comma-separated tag string into trimmed, non-empty tags
+fn split_tags(tags: Option<&str>) -> Vec<String> {
+    tags.map(|t| {
+        t.split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Builds the YAML front matter block of an exported file, one `key: value` line at a time
+struct FrontMatter {
+    lines: Vec<String>,
+}
+
+impl FrontMatter {
+    fn new(id: Option<i64>, uuid: Option<&str>, kind: &str) -> Self {
+        let mut lines = Vec::new();
+        if let Some(id) = id {
+            lines.push(format!("id: {}", id));
+        }
+        if let Some(uuid) = uuid {
+            lines.push(format!("uuid: {}", uuid));
+        }
+        lines.push(format!("type: {}", kind));
+        Self { lines }
+    }
+
+    fn string(&mut self, key: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.lines.push(format!("{}: {}", key, yaml_quote(value)));
+        }
+    }
+
+    fn plain(&mut self, key: &str, value: impl std::fmt::Display) {
+        self.lines.push(format!("{}: {}", key, value));
+    }
+
+    fn list(&mut self, key: &str, values: &[String]) {
+        if !values.is_empty() {
+            let quoted: Vec<String> = values.iter().map(|v| yaml_quote(v)).collect();
+            self.lines.push(format!("{}: [{}]", key, quoted.join(", ")));
+        }
+    }
+
+    fn tags(&mut self, tags: Option<&str>) {
+        let tags = split_tags(tags);
+        if !tags.is_empty() {
+            let quoted: Vec<String> = tags.iter().map(|t| yaml_quote(t)).collect();
+            self.lines.push(format!("tags: [{}]", quoted.join(", ")));
+        }
+    }
+
+    /// Render the front matter followed by the item body
+    fn render(self, body: Option<&str>) -> String {
+        let mut out = String::from("---\n");
+        for line in self.lines {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out.push_str("---\n");
+        if let Some(body) = body.map(str::trim_end).filter(|b| !b.is_empty()) {
+            out.push('\n');
+            out.push_str(body);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn task_to_markdown(task: &Task, notebook: Option<&str>, attachments: &[String], mode: ExportMode) -> String {
+    let id = if mode == ExportMode::Storage { None } else { task.id };
+    let mut fm = FrontMatter::new(id, task.uuid.as_deref(), "task");
+    fm.string("title", Some(&task.title));
+    fm.plain("status", &task.status);
+    fm.string("due", task.due_date.as_deref());
+    fm.string("notebook", notebook);
+    fm.tags(task.tags.as_deref());
+    fm.plain("order", task.order);
+    fm.plain("archived", task.archived);
+    fm.list("attachments", attachments);
+    fm.string("created", Some(&task.created_at));
+    fm.string("updated", Some(&task.updated_at));
+    fm.string("deleted", task.deleted_at.as_deref());
+    fm.render(task.description.as_deref())
+}
+
+fn note_to_markdown(note: &Note, notebook: Option<&str>, attachments: &[String], mode: ExportMode) -> String {
+    let id = if mode == ExportMode::Storage { None } else { note.id };
+    let mut fm = FrontMatter::new(id, note.uuid.as_deref(), "note");
+    fm.string("title", Some(&note.title));
+    fm.string("notebook", notebook);
+    fm.tags(note.tags.as_deref());
+    fm.plain("archived", note.archived);
+    fm.list("attachments", attachments);
+    fm.string("created", Some(&note.created_at));
+    fm.string("updated", Some(&note.updated_at));
+    fm.string("deleted", note.deleted_at.as_deref());
+    fm.render(note.content.as_deref())
+}
+
+fn journal_to_markdown(journal: &JournalEntry, notebook: Option<&str>, attachments: &[String], mode: ExportMode) -> String {
+    let id = if mode == ExportMode::Storage { None } else { journal.id };
+    let mut fm = FrontMatter::new(id, journal.uuid.as_deref(), "journal");
+    fm.string("date", Some(&journal.date));
+    fm.string("title", journal.title.as_deref());
+    fm.string("notebook", notebook);
+    fm.tags(journal.tags.as_deref());
+    fm.plain("archived", journal.archived);
+    fm.list("attachments", attachments);
+    fm.string("created", Some(&journal.created_at));
+    fm.string("updated", Some(&journal.updated_at));
+    fm.string("deleted", journal.deleted_at.as_deref());
+    fm.render(journal.content.as_deref())
+}
+
+/// File name of an exported attachment: "<attachment id>-<file name>", unique across items
+pub fn attachment_file_name(attachment: &Attachment) -> String {
+    let name = Path::new(&attachment.file_name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+    format!("{}-{}", attachment.id.unwrap_or_default(), name)
+}
+
+/// Read the list of files written by a previous export
+fn read_manifest(dir: &Path) -> BTreeSet<String> {
+    fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map(|contents| {
+            contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Remove a file left over from a previous export, and any directories it leaves empty
+fn remove_stale_file(dir: &Path, relative: &str) -> Result<(), FormatError> {
+    // Only ever touch Markdown files and copied attachments inside the export directory,
+    // whatever the manifest says
+    let relative_path = Path::new(relative);
+    let inside = relative_path.components().all(|c| matches!(c, Component::Normal(_)));
+    let markdown = relative_path.extension().and_then(|e| e.to_str()) == Some("md");
+    if !inside || !(markdown || relative_path.starts_with(ATTACHMENTS_DIR)) {
+        return Ok(());
+    }
+
+    let path = dir.join(relative_path);
+    if path.is_file() {
+        fs::remove_file(&path)?;
+    }
+    let mut parent = path.parent().map(Path::to_path_buf);
+    while let Some(current) = parent {
+        if current == dir || fs::remove_dir(&current).is_err() {
+            break; // Not empty (or already gone): stop climbing
+        }
+        parent = current.parent().map(Path::to_path_buf);
+    }
+    Ok(())
+}
+
+/// Export every task, note and journal entry (including archived, excluding trashed and locked
+/// entries) as Markdown files
+///
+/// Layout: `<dir>/<notebook>/{tasks,notes,journal}/<file>.md`, with items outside any notebook
+/// directly under `<dir>/{tasks,notes,journal}`. File names include the item ID, so they stay
+/// stable across exports; unchanged files are not rewritten and files of deleted items are removed.
+/// Attached files are copied to `<dir>/attachments/` and listed in the item's `attachments` key.
+pub fn export(db: &dyn AttachmentStore, dir: &Path) -> Result<ItemCounts, FormatError> {
+    export_with(db, dir, ExportMode::Copy)
+}
+
+/// Export as `export` does, or in the layout of git storage (see `ExportMode`)
+pub fn export_with(db: &dyn AttachmentStore, dir: &Path, mode: ExportMode) -> Result<ItemCounts, FormatError> {
+    fs::create_dir_all(dir)?;
+
+    let notebooks = db.get_all_notebooks()?;
+    let reserved = [TASKS_DIR, NOTES_DIR, JOURNAL_DIR, ATTACHMENTS_DIR];
+    let dir_names = match mode {
+        ExportMode::Copy => notebook_dir_names(&notebooks, &reserved),
+        // IDs differ between clones, the start of the UUID does not
+        ExportMode::Storage => notebook_dir_names_with(&notebooks, &reserved, |id, notebook| {
+            notebook.uuid.as_deref().and_then(|uuid| uuid.get(..8)).map_or_else(|| id.to_string(), String::from)
+        }),
+    };
+    let previous = read_manifest(dir);
+
+    let mut attachments: HashMap<(ItemKind, i64), Vec<Attachment>> = HashMap::new();
+    if mode == ExportMode::Copy {
+        for attachment in db.dump_attachments()? {
+            attachments.entry((attachment.item_kind, attachment.item_id)).or_default().push(attachment);
+        }
+    }
+
+    // Storage keeps trashed items, which the per-notebook queries leave out, so it reads every item up front
+    let (all_tasks, all_notes, all_journals) = match mode {
+        ExportMode::Copy => (Vec::new(), Vec::new(), Vec::new()),
+        ExportMode::Storage => (db.dump_tasks()?, db.dump_notes()?, db.dump_journals()?),
+    };
+    // Files are named by ID and title in a copy, by UUID in storage
+    let file_name = |id: Option<i64>, uuid: &Option<String>, title: &str| match mode {
+        ExportMode::Copy => format!("{}-{}.md", id.unwrap_or_default(), slugify(title)),
+        ExportMode::Storage => format!("{}.md", uuid.as_deref().unwrap_or_default()),
+    };
+    let mut exported_attachments: Vec<&Attachment> = Vec::new();
+
+    let mut written: BTreeSet<String> = BTreeSet::new();
+    let mut summary = ItemCounts::default();
+
+    let mut targets: Vec<(Option<i64>, Option<&str>)> = notebooks
+        .iter()
+        .map(|n| (n.id, Some(n.name.as_str())))
+        .collect();
+    targets.push((None, None));
+
+    for (notebook_id, notebook_name) in targets {
+        let base = match notebook_id {
+            Some(id) => match dir_names.get(&id) {
+                Some(name) => PathBuf::from(name),
+                None => continue,
+            },
+            None => PathBuf::new(),
+        };
+
+        let mut files: Vec<(PathBuf, String)> = Vec::new();
+
+        // Paths of an item's attachments as seen from its Markdown file (`depth` folders down)
+        let depth = base.components().count() + 1;
+        let mut attachment_links = |kind: ItemKind, id: Option<i64>| -> Vec<String> {
+            let Some(item_attachments) = id.and_then(|id| attachments.get(&(kind, id))) else {
+                return Vec::new();
+            };
+            item_attachments
+                .iter()
+                .map(|attachment| {
+                    exported_attachments.push(attachment);
+                    format!("{}{}/{}", "../".repeat(depth), ATTACHMENTS_DIR, attachment_file_name(attachment))
+                })
+                .collect()
+        };
+
+        let tasks = match mode {
+            ExportMode::Copy => db.get_all_tasks_including_archived(notebook_id)?,
+            ExportMode::Storage => all_tasks.iter().filter(|t| t.notebook_id == notebook_id).cloned().collect(),
+        };
+        for task in tasks {
+            let file = file_name(task.id, &task.uuid, &task.title);
+            let links = attachment_links(ItemKind::Task, task.id);
+            files.push((base.join(TASKS_DIR).join(file), task_to_markdown(&task, notebook_name, &links, mode)));
+            summary.tasks += 1;
+        }
+
+        let notes = match mode {
+            ExportMode::Copy => db.get_all_notes_including_archived(notebook_id)?,
+            ExportMode::Storage => all_notes.iter().filter(|n| n.notebook_id == notebook_id).cloned().collect(),
+        };
+        for note in notes {
+            let file = file_name(note.id, &note.uuid, &note.title);
+            let links = attachment_links(ItemKind::Note, note.id);
+            files.push((base.join(NOTES_DIR).join(file), note_to_markdown(&note, notebook_name, &links, mode)));
+            summary.notes += 1;
+        }
+
+        let journals: Vec<JournalEntry> = match mode {
+            // Locked entries would only show ciphertext; storage keeps them to be read back
+            ExportMode::Copy => db.get_all_journals_including_archived(notebook_id)?
+                .into_iter()
+                .filter(|j| !j.is_locked())
+                .collect(),
+            ExportMode::Storage => all_journals.iter().filter(|j| j.notebook_id == notebook_id).cloned().collect(),
+        };
+        for journal in journals {
+            let key = match mode {
+                ExportMode::Copy => journal.id.unwrap_or_default().to_string(),
+                ExportMode::Storage => journal.uuid.clone().unwrap_or_default(),
+            };
+            let file = format!("{}-{}.md", journal.date, key);
+            let links = attachment_links(ItemKind::Journal, journal.id);
+            files.push((base.join(JOURNAL_DIR).join(file), journal_to_markdown(&journal, notebook_name, &links, mode)));
+            summary.journals += 1;
+        }
+
+        for (relative, contents) in files {
+            write_if_changed(&dir.join(&relative), &contents)?;
+            // Manifest entries always use '/' so the manifest is the same on every platform
+            let key = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            written.insert(key);
+        }
+    }
+
+    for attachment in exported_attachments {
+        let file_name = attachment_file_name(attachment);
+        write_if_changed(&dir.join(ATTACHMENTS_DIR).join(&file_name), db.read_attachment(attachment)?)?;
+        written.insert(format!("{}/{}", ATTACHMENTS_DIR, file_name));
+    }
+
+    for stale in previous.difference(&written) {
+        remove_stale_file(dir, stale)?;
+    }
+
+    let mut manifest: String = written.iter().map(|path| format!("{}\n", path)).collect();
+    if manifest.is_empty() {
+        manifest.push('\n');
+    }
+    write_if_changed(&dir.join(MANIFEST_FILE), &manifest)?;
+
+    Ok(summary)
+}
+
+/// A front matter value: a single scalar or a list of scalars
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrontMatterValue {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+/// Front matter keys and values, in file order
+#[derive(Debug, Clone, Default)]
+pub struct FrontMatterMap {
+    entries: Vec<(String, FrontMatterValue)>,
+}
+
+impl FrontMatterMap {
+    fn get(&self, key: &str) -> Option<&FrontMatterValue> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Check whether a key is present
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get a non-empty scalar value (the first element for lists)
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            FrontMatterValue::Scalar(s) => Some(s.as_str()),
+            FrontMatterValue::List(items) => items.first().map(String::as_str),
+        }
+        .filter(|s| !s.is_empty())
+    }
+
+    /// Get a list value; comma- or space-separated scalars are split into a list
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        match self.get(key) {
+            Some(FrontMatterValue::List(items)) => items.clone(),
+            Some(FrontMatterValue::Scalar(s)) => s
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get a boolean value ("true"/"yes"; anything else is false)
+    pub fn get_bool(&self, key: &str) -> bool {
+        self.get_str(key)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true") || v.eq_ignore_ascii_case("yes"))
+    }
+}
+
+/// Parse a YAML scalar: double-quoted (with escapes), single-quoted or plain
+fn parse_scalar(raw: &str) -> String {
+    let raw = raw.trim();
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        let mut out = String::new();
+        let mut chars = raw[1..raw.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+        return out;
+    }
+    if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
+        return raw[1..raw.len() - 1].replace("''", "'");
+    }
+    // Plain scalars may end with a comment
+    match raw.find(" #") {
+        Some(pos) => raw[..pos].trim_end().to_string(),
+        None => raw.to_string(),
+    }
+}
+
+/// Parse a YAML flow sequence such as `[a, "b, c", 'd']`
+fn parse_flow_list(raw: &str) -> Vec<String> {
+    let inner = raw.trim().trim_start_matches('[').trim_end_matches(']');
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in inner.chars() {
+        match quote {
+            Some(q) => {
+                current.push(c);
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                current.push(c);
+            }
+            None if c == ',' => {
+                items.push(parse_scalar(&current));
+                current.clear();
+            }
+            None => current.push(c),
+        }
+    }
+    items.push(parse_scalar(&current));
+    items.into_iter().filter(|item| !item.is_empty()).collect()
+}
+
+/// Split a Markdown document into its YAML front matter and body
+/// Supports the subset of YAML used in note front matter: `key: value` pairs with plain or quoted
+/// scalars, flow lists (`[a, b]`) and block lists (`- a` lines). Documents without front matter
+/// return an empty map and the whole text as body.
+pub fn parse_front_matter(text: &str) -> (FrontMatterMap, &str) {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return (FrontMatterMap::default(), text);
+    };
+
+    let mut map = FrontMatterMap::default();
+    let mut offset = 0;
+    let mut body_start = None;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            body_start = Some(offset);
+            break;
+        }
+        if trimmed.trim().is_empty() || trimmed.trim_start().starts_with('#') {
+            continue;
+        }
+
+        // Block list item belonging to the previous key
+        if let Some(item) = trimmed.trim_start().strip_prefix("- ").or_else(|| {
+            (trimmed.trim() == "-").then_some("")
+        }) {
+            if let Some((_, value)) = map.entries.last_mut() {
+                let item = parse_scalar(item);
+                match value {
+                    FrontMatterValue::List(items) => items.push(item),
+                    FrontMatterValue::Scalar(s) if s.is_empty() => *value = FrontMatterValue::List(vec![item]),
+                    FrontMatterValue::Scalar(_) => {}
+                }
+            }
+            continue;
+        }
+
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = if value.starts_with('[') {
+            FrontMatterValue::List(parse_flow_list(value))
+        } else {
+            FrontMatterValue::Scalar(parse_scalar(value))
+        };
+        map.entries.push((key.trim().to_string(), value));
+    }
+
+    match body_start {
+        Some(start) => (map, &rest[start..]),
+        // No closing delimiter: not front matter after all
+        None => (FrontMatterMap::default(), text),
+    }
+}
+
+/// Take a leading `# Heading` line off the body, returning the heading text and the remaining body
+fn take_heading(body: &str) -> (Option<String>, &str) {
+    let trimmed = body.trim_start_matches(['\n', '\r']);
+    if let Some(rest) = trimmed.strip_prefix("# ") {
+        let (heading, remaining) = rest.split_once('\n').unwrap_or((rest, ""));
+        return (Some(heading.trim().to_string()), remaining);
+    }
+    (None, body)
+}
+
+/// Collect all Markdown files below `dir`, sorted, skipping hidden files and directories
+pub fn collect_markdown_files(dir: &Path) -> Result<Vec<PathBuf>, FormatError> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("md")) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Notebook name for a file: its folder path relative to the import root, without item kind folders
+fn notebook_from_folders(relative: &Path) -> String {
+    relative
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .filter_map(|c| match c {
+                    Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                    _ => None,
+                })
+                .filter(|name| {
+                    ![TASKS_DIR, NOTES_DIR, JOURNAL_DIR].iter().any(|kind_dir| name.eq_ignore_ascii_case(kind_dir))
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_default()
+}
+
+/// Decide which kind of item a file becomes
+/// Uses the `type` key, then the kind folder it sits in, then the keys that are present
+fn detect_kind(front_matter: &FrontMatterMap, relative: &Path) -> Result<ItemKind, String> {
+    if let Some(kind) = front_matter.get_str("type") {
+        return kind.parse::<ItemKind>();
+    }
+    let folder = relative
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if folder == TASKS_DIR {
+        return Ok(ItemKind::Task);
+    }
+    if folder == JOURNAL_DIR {
+        return Ok(ItemKind::Journal);
+    }
+    if folder == NOTES_DIR {
+        return Ok(ItemKind::Note);
+    }
+    if front_matter.contains("status") || front_matter.contains("due") {
+        return Ok(ItemKind::Task);
+    }
+    if front_matter.contains("date") && !front_matter.contains("title") {
+        return Ok(ItemKind::Journal);
+    }
+    Ok(ItemKind::Note)
+}
+
+/// Optional body text: None when empty
+pub fn body_text(body: &str) -> Option<String> {
+    let body = body.trim();
+    if body.is_empty() {
+        None
+    } else {
+        Some(body.to_string())
+    }
+}
+
+/// Import a directory tree of Markdown files
+///
+/// Front matter keys (`type`, `uuid`, `title`, `tags`, `date`, `created`, `updated`, `status`,
+/// `due`, `archived`, `notebook`) are mapped onto tasks, notes and journal entries; without a
+/// `notebook` key, the folder a file sits in becomes its notebook. Files imported before, and
+/// files whose `uuid` is already taken by an item of their kind, are skipped.
+/// With `dry_run`, nothing is written and the summary describes what would be imported. Journal
+/// entries going into a locked notebook are locked with `unlocker`, or not imported without one.
+pub fn import(db: &dyn Store, dir: &Path, dry_run: bool, unlocker: Option<Unlocker>) -> Result<ImportSummary, FormatError> {
+    let root = dir.canonicalize()?;
+    let files = collect_markdown_files(&root)?;
+    let mut summary = ImportSummary { dry_run, ..Default::default() };
+    let mut notebooks = NotebookResolver::new(dry_run).with_unlocker(unlocker);
+    let mut next_order = db.get_max_task_order().unwrap_or(-1) + 1;
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    for path in files {
+        let relative = path.strip_prefix(&root).unwrap_or(&path).to_path_buf();
+        let display = relative.display().to_string();
+        let source = format!("markdown:{}", path.display());
+
+        if db.get_imported_item(&source)?.is_some() {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                summary.errors.push(format!("{}: {}", display, e));
+                continue;
+            }
+        };
+        let (front_matter, body) = parse_front_matter(&text);
+        let kind = match detect_kind(&front_matter, &relative) {
+            Ok(kind) => kind,
+            Err(e) => {
+                summary.errors.push(format!("{}: {}", display, e));
+                continue;
+            }
+        };
+
+        // A file exported from tnj keeps its item's UUID, unless that item is already here
+        let uuid = match front_matter.get_str("uuid") {
+            Some(value) => {
+                let uuid = normalize_uuid(value);
+                if uuid.is_none() {
+                    summary.warnings.push(format!("{}: invalid uuid '{}' ignored", display, value));
+                }
+                uuid
+            }
+            None => None,
+        };
+        if let Some(ref uuid) = uuid
+            && let Some(id) = db.find_by_uuid(kind.into(), uuid)?
+        {
+            if !dry_run {
+                db.record_import_source(&source, kind, id)?;
+            }
+            summary.skipped += 1;
+            continue;
+        }
+
+        let notebook_name = front_matter
+            .get_str("notebook")
+            .map(String::from)
+            .unwrap_or_else(|| notebook_from_folders(&relative));
+        let notebook_id = notebooks.resolve(db, &notebook_name)?;
+
+        let tags = join_tags(&front_matter.get_list("tags"));
+        let archived = front_matter.get_bool("archived");
+        let created_at = front_matter
+            .get_str("created")
+            .or_else(|| front_matter.get_str("created_at"))
+            .and_then(normalize_timestamp)
+            .unwrap_or_else(|| now.clone());
+        let updated_at = front_matter
+            .get_str("updated")
+            .or_else(|| front_matter.get_str("updated_at"))
+            .and_then(normalize_timestamp)
+            .unwrap_or_else(|| created_at.clone());
+
+        let (heading, body_without_heading) = take_heading(body);
+        let (title, body) = match front_matter.get_str("title") {
+            Some(title) => (Some(title.to_string()), body),
+            None => (heading, body_without_heading),
+        };
+        let file_stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let item_id = match kind {
+            ItemKind::Task => {
+                let mut task = Task::new(title.unwrap_or(file_stem));
+                task.uuid = uuid;
+                task.description = body_text(body);
+                task.status = match front_matter.get_str("status").map(str::to_lowercase).as_deref() {
+                    Some("done") | Some("completed") | Some("x") => "done".to_string(),
+                    _ => "todo".to_string(),
+                };
+                if let Some(due) = front_matter.get_str("due").or_else(|| front_matter.get_str("due_date")) {
+                    match normalize_date(due) {
+                        Some(date) => task.due_date = Some(date),
+                        None => summary.warnings.push(format!("{}: invalid due date '{}' ignored", display, due)),
+                    }
+                }
+                task.tags = tags;
+                task.archived = archived;
+                task.notebook_id = notebook_id;
+                task.order = next_order;
+                task.created_at = created_at;
+                task.updated_at = updated_at;
+                next_order += 1;
+                if dry_run { None } else { Some(db.insert_task(&task)?) }
+            }
+            ItemKind::Note => {
+                let mut note = Note::new(title.unwrap_or(file_stem));
+                note.uuid = uuid;
+                note.content = body_text(body);
+                note.tags = tags;
+                note.archived = archived;
+                note.notebook_id = notebook_id;
+                note.created_at = created_at;
+                note.updated_at = updated_at;
+                if dry_run { None } else { Some(db.insert_note(&note)?) }
+            }
+            ItemKind::Journal => {
+                // Date from front matter, then the created date, then a date at the start of the file name
+                let date = front_matter
+                    .get_str("date")
+                    .or_else(|| front_matter.get_str("created"))
+                    .and_then(normalize_date)
+                    .or_else(|| normalize_date(&file_stem))
+                    .unwrap_or_else(crate::utils::get_current_date_string);
+                let mut journal = JournalEntry::new(date);
+                journal.uuid = uuid;
+                journal.title = title;
+                journal.content = body_text(body);
+                journal.tags = tags;
+                journal.archived = archived;
+                journal.notebook_id = notebook_id;
+                journal.created_at = created_at;
+                journal.updated_at = updated_at;
+                if let Err(e) = notebooks.lock_journal(&mut journal) {
+                    summary.errors.push(format!("{}: {}", display, e));
+                    continue;
+                }
+                if dry_run { None } else { Some(db.insert_journal(&journal)?) }
+            }
+        };
+
+        if let Some(id) = item_id {
+            db.record_import_source(&source, kind, id)?;
+        }
+        summary.imported.add(kind);
+    }
+
+    summary.notebooks_created = notebooks.created;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Notebook;
+    use crate::store::{LockStore, MemoryStore};
+
+    #[test]
+    fn front_matter_values_and_lists_are_parsed() {
+        let text = "---\r\ntitle: \"Say \\\"hi\\\"\\n\"\r\ntype: note # kind\r\nnotebook: 'It''s mine'\r\ntags: [a, \"b, c\", 'd']\r\naliases:\r\n  - one\r\n  - two\r\narchived: yes\r\n---\r\nBody\r\n";
+        let (front_matter, body) = parse_front_matter(text);
+        assert_eq!(front_matter.get_str("Title"), Some("Say \"hi\"\n"));
+        assert_eq!(front_matter.get_str("type"), Some("note"));
+        assert_eq!(front_matter.get_str("notebook"), Some("It's mine"));
+        assert_eq!(front_matter.get_list("tags"), vec!["a", "b, c", "d"]);
+        assert_eq!(front_matter.get_list("aliases"), vec!["one", "two"]);
+        assert!(front_matter.get_bool("archived"));
+        assert_eq!(body, "Body\r\n");
+
+        // Space- or comma-separated scalars are lists too
+        let (front_matter, _) = parse_front_matter("---\ntags: work, home urgent\n---\n");
+        assert_eq!(front_matter.get_list("tags"), vec!["work", "home", "urgent"]);
+    }
+
+    #[test]
+    fn text_without_closed_front_matter_is_all_body() {
+        for text in ["# Title\n\nBody", "---\ntitle: Open\nBody", "Intro\n---\ntitle: x\n---\n"] {
+            let (front_matter, body) = parse_front_matter(text);
+            assert!(!front_matter.contains("title"));
+            assert_eq!(body, text);
+        }
+    }
+
+    #[test]
+    fn imported_items_keep_their_uuid() {
+        let source = MemoryStore::new();
+        let id = source.insert_note(&Note::new("Ideas".to_string())).unwrap();
+        let uuid = source.get_note(id).unwrap().uuid;
+        let dir = tempfile::tempdir().unwrap();
+        export(&source, dir.path()).unwrap();
+
+        let target = MemoryStore::new();
+        let summary = import(&target, dir.path(), false, None).unwrap();
+        assert_eq!(summary.imported.notes, 1);
+        assert_eq!(target.dump_notes().unwrap()[0].uuid, uuid);
+
+        // The same item exported again from elsewhere is recognized by its UUID
+        let summary = import(&source, dir.path(), false, None).unwrap();
+        assert_eq!((summary.imported.notes, summary.skipped), (0, 1));
+    }
+
+    #[test]
+    fn journal_entries_imported_into_a_locked_notebook_are_locked() {
+        let db = MemoryStore::new();
+        let notebook = db.insert_notebook(&Notebook::new("Private".to_string())).unwrap();
+        db.set_notebook_locked(notebook, true).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("Private")).unwrap();
+        fs::write(dir.path().join("Private").join("2024-03-05.md"), "---\ntype: journal\n---\nDear diary\n").unwrap();
+
+        let summary = import(&db, dir.path(), false, None).unwrap();
+        assert_eq!(summary.errors.len(), 1);
+        assert!(db.dump_journals().unwrap().is_empty());
+
+        let unlocker = Unlocker::new("secret").unwrap();
+        let summary = import(&db, dir.path(), false, Some(unlocker)).unwrap();
+        assert_eq!(summary.imported.journals, 1);
+        let journal = db.dump_journals().unwrap().remove(0);
+        assert_eq!(journal.notebook_id, Some(notebook));
+        assert!(journal.is_locked());
+        let mut unlocker = Unlocker::new("secret").unwrap();
+        assert_eq!(unlocker.unlock(journal.content.as_deref().unwrap()).unwrap(), "Dear diary");
+    }
+}
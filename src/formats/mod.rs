@@ -0,0 +1,315 @@
+pub mod csv;
+pub mod html;
+pub mod ics;
+pub mod json;
+pub mod markdown;
+pub mod obsidian;
+pub mod org;
+pub mod taskwarrior;
+pub mod todotxt;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::database::DatabaseError;
+use crate::lock::{LockError, Unlocker};
+use crate::models::{ItemKind, JournalEntry, Notebook};
+use crate::store::Store;
+
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] DatabaseError),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("CSV error: {0}")]
+    CsvError(#[from] ::csv::Error),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Notebook '{0}' is locked; give the passphrase of locked entries to import journal entries into it")]
+    NotebookLocked(String),
+    #[error("Failed to lock journal entry: {0}")]
+    LockError(#[from] LockError),
+}
+
+/// Number of tasks, notes and journal entries handled by an import or export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemCounts {
+    pub tasks: usize,
+    pub notes: usize,
+    pub journals: usize,
+}
+
+impl ItemCounts {
+    /// Count one more item of the given kind
+    pub fn add(&mut self, kind: ItemKind) {
+        match kind {
+            ItemKind::Task => self.tasks += 1,
+            ItemKind::Note => self.notes += 1,
+            ItemKind::Journal => self.journals += 1,
+        }
+    }
+}
+
+impl fmt::Display for ItemCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}, {} {}, {} {}",
+            self.tasks,
+            ItemKind::Task.label(self.tasks),
+            self.notes,
+            ItemKind::Note.label(self.notes),
+            self.journals,
+            ItemKind::Journal.label(self.journals)
+        )
+    }
+}
+
+/// Result of an import (or of a dry run, where nothing is written)
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    pub dry_run: bool,
+    pub imported: ItemCounts,
+    pub notebooks_created: usize,
+    pub updated: usize,         // Items imported before whose contents changed
+    pub skipped: usize,         // Items that were already in the database (imported before)
+    pub warnings: Vec<String>,  // Imported, but something was ignored
+    pub errors: Vec<String>,    // Not imported
+}
+
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.dry_run { "Would import" } else { "Imported" };
+        write!(f, "{} {}", verb, self.imported)?;
+        if self.notebooks_created > 0 {
+            let verb = if self.dry_run { "would create" } else { "created" };
+            write!(f, "; {} {} notebooks", verb, self.notebooks_created)?;
+        }
+        if self.updated > 0 {
+            let verb = if self.dry_run { "would update" } else { "updated" };
+            write!(f, "; {} {}", verb, self.updated)?;
+        }
+        if self.skipped > 0 {
+            write!(f, "; skipped {} already in the database", self.skipped)?;
+        }
+        if !self.errors.is_empty() {
+            write!(f, "; {} failed", self.errors.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Looks up notebooks by name during an import, creating missing ones (unless in a dry run)
+pub struct NotebookResolver {
+    dry_run: bool,
+    cache: HashMap<String, Option<i64>>, // Name -> ID (None for notebooks a dry run would create)
+    locked: HashMap<i64, String>,        // ID -> name of the locked notebooks resolved so far
+    unlocker: Option<Unlocker>,
+    pub created: usize,
+}
+
+impl NotebookResolver {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            cache: HashMap::new(),
+            locked: HashMap::new(),
+            unlocker: None,
+            created: 0,
+        }
+    }
+
+    /// Lock the journal entries imported into locked notebooks with this unlocker
+    pub fn with_unlocker(mut self, unlocker: Option<Unlocker>) -> Self {
+        self.unlocker = unlocker;
+        self
+    }
+
+    /// Lock the content of a journal entry going into a locked notebook (in a dry run it is left as is)
+    /// Fails if the notebook is locked and there is no unlocker
+    pub fn lock_journal(&mut self, journal: &mut JournalEntry) -> Result<(), FormatError> {
+        let Some(name) = journal.notebook_id.and_then(|id| self.locked.get(&id)) else {
+            return Ok(());
+        };
+        if self.dry_run || journal.is_locked() {
+            return Ok(());
+        }
+        let unlocker = self.unlocker.as_mut().ok_or_else(|| FormatError::NotebookLocked(name.clone()))?;
+        journal.content = Some(unlocker.lock(journal.content.as_deref().unwrap_or_default())?);
+        Ok(())
+    }
+
+    /// Get the ID of the notebook with this name, creating it if needed
+    /// An empty name means "no notebook"
+    pub fn resolve(&mut self, db: &dyn Store, name: &str) -> Result<Option<i64>, FormatError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Ok(None);
+        }
+        if let Some(id) = self.cache.get(name) {
+            return Ok(*id);
+        }
+
+        let id = match db.get_notebook_by_name(name)? {
+            Some(notebook) => {
+                if notebook.locked
+                    && let Some(id) = notebook.id
+                {
+                    self.locked.insert(id, notebook.name);
+                }
+                notebook.id
+            }
+            None => {
+                self.created += 1;
+                if self.dry_run {
+                    None
+                } else {
+                    Some(db.insert_notebook(&Notebook::new(name.to_string()))?)
+                }
+            }
+        };
+        self.cache.insert(name.to_string(), id);
+        Ok(id)
+    }
+}
+
+/// Normalize a date or date-time string to the database timestamp format (YYYY-MM-DD HH:MM:SS)
+/// Accepts RFC 3339, "YYYY-MM-DD HH:MM[:SS]", "YYYY-MM-DDTHH:MM[:SS]" and plain dates
+pub fn normalize_timestamp(value: &str) -> Option<String> {
+    const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+    let value = value.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&chrono::Utc).format(FORMAT).to_string());
+    }
+    for pattern in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"] {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value, pattern) {
+            return Some(dt.format(FORMAT).to_string());
+        }
+    }
+    normalize_date(value).map(|date| format!("{} 00:00:00", date))
+}
+
+/// Normalize an imported UUID to the lower-case hyphenated form tnj stores (None if it is not a UUID)
+pub fn normalize_uuid(value: &str) -> Option<String> {
+    uuid::Uuid::parse_str(value.trim()).ok().map(|uuid| uuid.to_string())
+}
+
+/// Normalize the date part of a date or date-time string to YYYY-MM-DD
+pub fn normalize_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    let date_part = value.get(..10).unwrap_or(value);
+    crate::utils::parse_date(date_part)
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// Join tags into the comma-separated form stored in the database (None if there are none)
+pub fn join_tags<S: AsRef<str>>(tags: &[S]) -> Option<String> {
+    let tags: Vec<&str> = tags
+        .iter()
+        .map(|tag| tag.as_ref().trim().trim_start_matches('#'))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if tags.is_empty() {
+        None
+    } else {
+        Some(tags.join(", "))
+    }
+}
+
+/// Turn a title into a lowercase, dash-separated file name fragment
+/// Returns "untitled" when nothing usable is left
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(|c| c.to_lowercase()) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= 50 {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Make a notebook name safe to use as a directory name
+/// Path separators and characters that are invalid on common file systems are replaced with '-'
+pub fn sanitize_dir_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    cleaned.trim().trim_matches('.').trim().to_string()
+}
+
+/// Map each notebook to a unique directory name
+/// Notebooks whose names collide with each other or with a reserved name (or are empty after
+/// sanitizing) get their ID appended
+pub fn notebook_dir_names(notebooks: &[Notebook], reserved: &[&str]) -> HashMap<i64, String> {
+    notebook_dir_names_with(notebooks, reserved, |id, _| id.to_string())
+}
+
+/// Map each notebook to a unique directory name as `notebook_dir_names` does, with `suffix`
+/// (given the ID and notebook) deciding what is appended to names that are not unique
+pub fn notebook_dir_names_with(
+    notebooks: &[Notebook],
+    reserved: &[&str],
+    suffix: impl Fn(i64, &Notebook) -> String,
+) -> HashMap<i64, String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for name in reserved {
+        counts.insert(name.to_lowercase(), 1);
+    }
+    for notebook in notebooks {
+        *counts.entry(sanitize_dir_name(&notebook.name).to_lowercase()).or_default() += 1;
+    }
+
+    notebooks
+        .iter()
+        .filter_map(|notebook| {
+            let id = notebook.id?;
+            let name = sanitize_dir_name(&notebook.name);
+            let unique = !name.is_empty() && counts.get(&name.to_lowercase()) == Some(&1);
+            let dir = if unique {
+                name
+            } else if name.is_empty() {
+                format!("notebook-{}", suffix(id, notebook))
+            } else {
+                format!("{}-{}", name, suffix(id, notebook))
+            };
+            Some((id, dir))
+        })
+        .collect()
+}
+
+/// Write a file only if its contents changed, so repeated exports leave unchanged files untouched
+pub fn write_if_changed(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), FormatError> {
+    let contents = contents.as_ref();
+    if let Ok(existing) = fs::read(path)
+        && existing == contents
+    {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
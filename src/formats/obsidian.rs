@@ -0,0 +1,343 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::formats::markdown::{body_text, collect_markdown_files, parse_front_matter};
+use crate::formats::{join_tags, normalize_date, normalize_timestamp, FormatError, ImportSummary, NotebookResolver};
+use crate::lock::Unlocker;
+use crate::models::{ItemKind, JournalEntry, Note, Task};
+use crate::store::Store;
+
+/// Folders that hold daily notes when the vault does not configure one
+/// ("journals" is where Logseq keeps them)
+const DAILY_FOLDERS: &[&str] = &["Daily Notes", "Daily", "journals"];
+
+/// Folder Logseq keeps regular pages in; it does not become a notebook
+const LOGSEQ_PAGES_DIR: &str = "pages";
+
+/// Options for importing a vault
+#[derive(Debug, Clone, Default)]
+pub struct ObsidianOptions {
+    pub daily_folder: Option<PathBuf>, // Relative to the vault; detected when None
+    pub extract_tasks: bool,           // Also create a task for every `- [ ]` checklist line
+    pub dry_run: bool,
+}
+
+/// A `- [ ]` or `- [x]` checklist line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub text: String,
+    pub done: bool,
+    pub due: Option<String>,       // From an Obsidian Tasks "📅 YYYY-MM-DD" marker
+    pub completed: Option<String>, // From a "✅ YYYY-MM-DD" marker
+}
+
+/// Find the daily notes folder: the Daily Notes plugin setting, else the first common folder name
+fn detect_daily_folder(vault: &Path) -> Option<PathBuf> {
+    let settings = vault.join(".obsidian").join("daily-notes.json");
+    if let Ok(text) = fs::read_to_string(settings)
+        && let Ok(value) = serde_json::from_str::<serde_json::Value>(&text)
+        && let Some(folder) = value.get("folder").and_then(|f| f.as_str())
+        && !folder.trim().is_empty()
+    {
+        return Some(PathBuf::from(folder.trim().trim_matches('/')));
+    }
+    DAILY_FOLDERS
+        .iter()
+        .map(PathBuf::from)
+        .find(|folder| vault.join(folder).is_dir())
+}
+
+/// The date of a daily note from its file name (YYYY-MM-DD, or Logseq's YYYY_MM_DD)
+fn daily_note_date(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_string_lossy().replace(['_', '.'], "-");
+    if stem.len() != 10 {
+        return None;
+    }
+    normalize_date(&stem)
+}
+
+/// Notebook name for a file: its folder path inside the vault (Logseq's `pages` folder left out)
+fn notebook_from_folders(relative: &Path) -> String {
+    let folders: Vec<String> = relative
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .filter_map(|c| match c {
+                    Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    match folders.split_first() {
+        Some((first, rest)) if first == LOGSEQ_PAGES_DIR => rest.join("/"),
+        _ => folders.join("/"),
+    }
+}
+
+/// Call `f` with every line outside fenced code blocks
+fn for_each_text_line<'a>(body: &'a str, mut f: impl FnMut(&'a str)) {
+    let mut in_code_block = false;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if !in_code_block {
+            f(line);
+        }
+    }
+}
+
+/// Collect the inline `#tags` of a line (not inside `code` spans, not headings, not pure numbers)
+fn line_tags(line: &str, tags: &mut Vec<String>) {
+    let mut in_code = false;
+    let mut previous = ' ';
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '`' {
+            in_code = !in_code;
+        } else if c == '#' && !in_code && (previous.is_whitespace() || previous == '(') {
+            let tag: String = line[i + 1..]
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'))
+                .collect();
+            if !tag.is_empty() && !tag.chars().all(|c| c.is_ascii_digit()) && !tags.contains(&tag) {
+                tags.push(tag.clone());
+            }
+            for _ in 0..tag.chars().count() {
+                chars.next();
+            }
+        }
+        previous = c;
+    }
+}
+
+/// Collect the inline `#tags` of a note body, in order of first appearance
+pub fn inline_tags(body: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for_each_text_line(body, |line| line_tags(line, &mut tags));
+    tags
+}
+
+/// Take a "<marker> YYYY-MM-DD" date off a checklist item's text
+fn take_marker_date(text: &mut String, marker: &str) -> Option<String> {
+    let start = text.find(marker)?;
+    let after = text[start + marker.len()..].trim_start();
+    let date = after.get(..10).and_then(normalize_date)?;
+    let end = text.len() - after.len() + 10;
+    text.replace_range(start..end, "");
+    *text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some(date)
+}
+
+/// Collect the checklist lines of a note body
+pub fn checklist_items(body: &str) -> Vec<ChecklistItem> {
+    let mut items = Vec::new();
+    for_each_text_line(body, |line| {
+        let trimmed = line.trim_start();
+        let Some(rest) = trimmed.strip_prefix("- [").or_else(|| trimmed.strip_prefix("* [")) else {
+            return;
+        };
+        let done = match rest.chars().next() {
+            Some(' ') => false,
+            Some('x') | Some('X') => true,
+            _ => return,
+        };
+        let Some(text) = rest[1..].strip_prefix(']') else {
+            return;
+        };
+        let mut text = text.trim().to_string();
+        let due = take_marker_date(&mut text, "📅");
+        let completed = take_marker_date(&mut text, "✅");
+        if !text.is_empty() {
+            items.push(ChecklistItem { text, done, due, completed });
+        }
+    });
+    items
+}
+
+/// Import an Obsidian or Logseq vault
+///
+/// Every Markdown file becomes a note titled after its file name (so `[[wikilinks]]`, which are
+/// kept as they are, keep pointing at the right note), except for files in the daily notes folder
+/// named after a date, which become journal entries. Folders become notebooks, and front matter
+/// `tags` plus inline `#tags` become tags. With `extract_tasks`, every checklist line also becomes
+/// a task that links back to its note. Files and checklist items imported before are skipped.
+/// Journal entries going into a locked notebook are locked with `unlocker`, or not imported without one.
+pub fn import(db: &dyn Store, vault: &Path, options: &ObsidianOptions, unlocker: Option<Unlocker>) -> Result<ImportSummary, FormatError> {
+    let root = vault.canonicalize()?;
+    let files = collect_markdown_files(&root)?;
+    let daily_folder = options.daily_folder.clone().or_else(|| detect_daily_folder(&root));
+    let dry_run = options.dry_run;
+    let mut summary = ImportSummary { dry_run, ..Default::default() };
+    let mut notebooks = NotebookResolver::new(dry_run).with_unlocker(unlocker);
+    let mut next_order = db.get_max_task_order().unwrap_or(-1) + 1;
+
+    for path in files {
+        let relative = path.strip_prefix(&root).unwrap_or(&path).to_path_buf();
+        let display = relative.display().to_string();
+        let source = format!("obsidian:{}", path.display());
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                summary.errors.push(format!("{}: {}", display, e));
+                continue;
+            }
+        };
+        let (front_matter, body) = parse_front_matter(&text);
+        let file_stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut tags: Vec<String> = front_matter
+            .get_list("tags")
+            .into_iter()
+            .map(|tag| tag.trim_start_matches('#').to_string())
+            .collect();
+        for tag in inline_tags(body) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        let tags = join_tags(&tags);
+
+        // File times stand in for missing front matter dates
+        let modified = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|time| chrono::DateTime::<chrono::Utc>::from(time).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        let created_at = front_matter
+            .get_str("created")
+            .and_then(normalize_timestamp)
+            .unwrap_or_else(|| modified.clone());
+        let updated_at = front_matter
+            .get_str("updated")
+            .or_else(|| front_matter.get_str("modified"))
+            .and_then(normalize_timestamp)
+            .unwrap_or(modified);
+
+        let journal_date = daily_folder
+            .as_ref()
+            .filter(|folder| relative.parent() == Some(folder.as_path()))
+            .and_then(|_| daily_note_date(&relative));
+        let already_imported = db.get_imported_item(&source)?.is_some();
+        let notebook_id = match front_matter.get_str("notebook") {
+            _ if already_imported && !options.extract_tasks => None,
+            Some(name) => notebooks.resolve(db, name)?,
+            None if journal_date.is_some() => None,
+            None => notebooks.resolve(db, &notebook_from_folders(&relative))?,
+        };
+
+        if already_imported {
+            summary.skipped += 1;
+        } else {
+            let kind = if journal_date.is_some() { ItemKind::Journal } else { ItemKind::Note };
+            let item_id = match journal_date {
+                Some(ref date) => {
+                    let mut journal = JournalEntry::new(date.clone());
+                    journal.title = front_matter.get_str("title").map(String::from);
+                    journal.content = body_text(body);
+                    journal.tags = tags.clone();
+                    journal.notebook_id = notebook_id;
+                    journal.created_at = created_at.clone();
+                    journal.updated_at = updated_at.clone();
+                    if let Err(e) = notebooks.lock_journal(&mut journal) {
+                        summary.errors.push(format!("{}: {}", display, e));
+                        continue;
+                    }
+                    if dry_run { None } else { Some(db.insert_journal(&journal)?) }
+                }
+                None => {
+                    let title = front_matter.get_str("title").map(String::from).unwrap_or(file_stem.clone());
+                    let mut note = Note::new(title);
+                    note.content = body_text(body);
+                    note.tags = tags.clone();
+                    note.notebook_id = notebook_id;
+                    note.created_at = created_at.clone();
+                    note.updated_at = updated_at.clone();
+                    if dry_run { None } else { Some(db.insert_note(&note)?) }
+                }
+            };
+            if let Some(id) = item_id {
+                db.record_import_source(&source, kind, id)?;
+            }
+            summary.imported.add(kind);
+        }
+
+        if !options.extract_tasks {
+            continue;
+        }
+        for item in checklist_items(body) {
+            let task_source = format!("{}#{}", source, item.text);
+            if db.get_imported_item(&task_source)?.is_some() {
+                summary.skipped += 1;
+                continue;
+            }
+            let mut task_tags = Vec::new();
+            line_tags(&item.text, &mut task_tags);
+
+            let mut task = Task::new(item.text.clone());
+            task.description = Some(format!("From [[{}]]", file_stem));
+            task.status = if item.done { "done" } else { "todo" }.to_string();
+            task.due_date = item.due.clone();
+            task.tags = join_tags(&task_tags);
+            task.notebook_id = notebook_id;
+            task.order = next_order;
+            task.created_at = created_at.clone();
+            task.updated_at = item
+                .completed
+                .as_ref()
+                .map(|date| format!("{} 00:00:00", date))
+                .unwrap_or_else(|| updated_at.clone());
+            next_order += 1;
+
+            if !dry_run {
+                let id = db.insert_task(&task)?;
+                db.record_import_source(&task_source, ItemKind::Task, id)?;
+            }
+            summary.imported.add(ItemKind::Task);
+        }
+    }
+
+    summary.notebooks_created = notebooks.created;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checklist_items_take_their_due_and_done_dates() {
+        let body = "- [ ] Call Bob 📅 2024-03-05 #phone\n* [x] Pay rent ✅ 2024-03-01\n- [?] Maybe\n```\n- [ ] In code\n```\n- [ ] Bad date 📅 soon";
+        let items = checklist_items(body);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], ChecklistItem {
+            text: "Call Bob #phone".to_string(),
+            done: false,
+            due: Some("2024-03-05".to_string()),
+            completed: None,
+        });
+        assert_eq!((items[1].text.as_str(), items[1].done), ("Pay rent", true));
+        assert_eq!(items[1].completed.as_deref(), Some("2024-03-01"));
+        assert_eq!((items[2].text.as_str(), items[2].due.as_deref()), ("Bad date 📅 soon", None));
+
+        let mut text = "Ship ✅2024-03-01 it".to_string();
+        assert_eq!(take_marker_date(&mut text, "✅").as_deref(), Some("2024-03-01"));
+        assert_eq!(text, "Ship it");
+    }
+
+    #[test]
+    fn inline_tags_skip_code_headings_and_numbers() {
+        let mut tags = Vec::new();
+        line_tags("Plan #work/q1 (#home) `#code` issue#1 #2024 #work/q1", &mut tags);
+        assert_eq!(tags, vec!["work/q1", "home"]);
+        assert_eq!(inline_tags("# Heading\n#idea and #draft-2\n```\n#hidden\n```"), vec!["idea", "draft-2"]);
+    }
+}
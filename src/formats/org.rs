@@ -0,0 +1,246 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::formats::{notebook_dir_names, write_if_changed, FormatError, ItemCounts};
+use crate::models::{JournalEntry, Note, Task};
+use crate::store::Store;
+
+/// First line of every generated file; only files starting with it are removed as stale
+const GENERATED_MARKER: &str = "# Generated by tnj export org; changes are overwritten by the next export";
+
+/// File name (without extension) for items outside any notebook
+const UNFILED_NAME: &str = "unfiled";
+
+/// Turn a tag into a valid org tag (letters, digits, `_`, `@`, `#` and `%`)
+fn org_tag(tag: &str) -> String {
+    tag.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%') { c } else { '_' })
+        .collect()
+}
+
+/// A headline with optional tags, e.g. `** TODO Title :work:urgent:`
+fn headline(level: usize, keyword: Option<&str>, title: &str, tags: Option<&str>, archived: bool) -> String {
+    let mut line = "*".repeat(level);
+    if let Some(keyword) = keyword {
+        line.push(' ');
+        line.push_str(keyword);
+    }
+    line.push(' ');
+    line.push_str(&title.split_whitespace().collect::<Vec<_>>().join(" "));
+
+    let mut tags: Vec<String> = tags
+        .unwrap_or("")
+        .split(',')
+        .map(org_tag)
+        .filter(|t| !t.is_empty())
+        .collect();
+    if archived {
+        tags.push("ARCHIVE".to_string());
+    }
+    if !tags.is_empty() {
+        line.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    line.push('\n');
+    line
+}
+
+/// An org timestamp for a YYYY-MM-DD date (active: `<...>`, inactive: `[...]`)
+fn org_date(date: &str, active: bool) -> Option<String> {
+    let date = crate::utils::parse_date(date.get(..10)?).ok()?;
+    let text = date.format("%Y-%m-%d %a").to_string();
+    Some(if active { format!("<{}>", text) } else { format!("[{}]", text) })
+}
+
+/// An inactive org timestamp with time for a database timestamp
+fn org_timestamp(timestamp: &str) -> Option<String> {
+    let dt = chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    Some(dt.format("[%Y-%m-%d %a %H:%M]").to_string())
+}
+
+/// A property drawer with the item's ID, UUID (as org-id's `ID` property) and creation time
+fn properties(id: Option<i64>, uuid: Option<&str>, created_at: &str) -> String {
+    let mut drawer = String::from(":PROPERTIES:\n");
+    if let Some(uuid) = uuid {
+        drawer.push_str(&format!(":ID: {}\n", uuid));
+    }
+    if let Some(id) = id {
+        drawer.push_str(&format!(":TNJ_ID: {}\n", id));
+    }
+    if let Some(created) = org_timestamp(created_at) {
+        drawer.push_str(&format!(":CREATED: {}\n", created));
+    }
+    drawer.push_str(":END:\n");
+    drawer
+}
+
+/// Body text, with lines that org would read as headlines (starting with `*`) indented by a space
+fn body(text: Option<&str>) -> String {
+    let Some(text) = text.map(str::trim_end).filter(|t| !t.trim().is_empty()) else {
+        return String::new();
+    };
+    let mut out = String::new();
+    for line in text.lines() {
+        if line.starts_with('*') {
+            out.push(' ');
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+fn task_entry(task: &Task) -> String {
+    let keyword = if task.status == "done" { "DONE" } else { "TODO" };
+    let mut entry = headline(2, Some(keyword), &task.title, task.tags.as_deref(), task.archived);
+
+    let mut planning: Vec<String> = Vec::new();
+    if task.status == "done"
+        && let Some(closed) = org_timestamp(&task.updated_at)
+    {
+        planning.push(format!("CLOSED: {}", closed));
+    }
+    if let Some(deadline) = task.due_date.as_deref().and_then(|d| org_date(d, true)) {
+        planning.push(format!("DEADLINE: {}", deadline));
+    }
+    if !planning.is_empty() {
+        entry.push_str(&planning.join(" "));
+        entry.push('\n');
+    }
+    entry.push_str(&properties(task.id, task.uuid.as_deref(), &task.created_at));
+    entry.push_str(&body(task.description.as_deref()));
+    entry
+}
+
+fn note_entry(note: &Note) -> String {
+    let mut entry = headline(2, None, &note.title, note.tags.as_deref(), note.archived);
+    entry.push_str(&properties(note.id, note.uuid.as_deref(), &note.created_at));
+    entry.push_str(&body(note.content.as_deref()));
+    entry
+}
+
+/// Journal entries as a datetree: year, month and day headlines, with the entries under their day
+fn journal_tree(journals: &mut [JournalEntry]) -> String {
+    journals.sort_by(|a, b| (&a.date, &a.created_at).cmp(&(&b.date, &b.created_at)));
+    let mut tree = String::new();
+    let (mut year, mut month, mut day) = (String::new(), String::new(), String::new());
+    for journal in journals.iter() {
+        let Ok(date) = crate::utils::parse_date(&journal.date) else {
+            continue;
+        };
+        let this_year = date.format("%Y").to_string();
+        let this_month = date.format("%Y-%m %B").to_string();
+        let this_day = date.format("%Y-%m-%d %A").to_string();
+        if this_year != year {
+            tree.push_str(&format!("** {}\n", this_year));
+            year = this_year;
+            month.clear();
+        }
+        if this_month != month {
+            tree.push_str(&format!("*** {}\n", this_month));
+            month = this_month;
+            day.clear();
+        }
+        if this_day != day {
+            tree.push_str(&format!("**** {}\n", this_day));
+            day = this_day;
+        }
+
+        let created_time = journal.created_at.get(11..16).unwrap_or("");
+        let title = journal
+            .title
+            .as_deref()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or(created_time);
+        let title = if title.is_empty() { "Entry" } else { title };
+        tree.push_str(&headline(5, None, title, journal.tags.as_deref(), journal.archived));
+        tree.push_str(&properties(journal.id, journal.uuid.as_deref(), &journal.created_at));
+        tree.push_str(&body(journal.content.as_deref()));
+    }
+    tree
+}
+
+/// Render one notebook's org file
+fn notebook_file(title: &str, tasks: &[Task], notes: &[Note], journals: &mut [JournalEntry]) -> String {
+    let mut out = format!("{}\n#+TITLE: {}\n#+STARTUP: overview\n\n", GENERATED_MARKER, title);
+    if !tasks.is_empty() {
+        out.push_str("* Tasks\n");
+        for task in tasks {
+            out.push_str(&task_entry(task));
+        }
+    }
+    if !notes.is_empty() {
+        out.push_str("* Notes\n");
+        for note in notes {
+            out.push_str(&note_entry(note));
+        }
+    }
+    if !journals.is_empty() {
+        out.push_str("* Journal\n");
+        out.push_str(&journal_tree(journals));
+    }
+    out
+}
+
+/// Export tasks, notes and journal entries (including archived, excluding trashed and locked
+/// entries) as one org file per notebook, plus `unfiled.org` for items outside any notebook
+///
+/// Tasks are TODO/DONE headlines with DEADLINE from the due date, notes are headlines with their
+/// content as body and journal entries form a datetree. Archived items get the ARCHIVE tag, so
+/// org-agenda skips them. Generated files of notebooks that no longer have items are removed.
+pub fn export(db: &dyn Store, dir: &Path) -> Result<ItemCounts, FormatError> {
+    fs::create_dir_all(dir)?;
+    let notebooks = db.get_all_notebooks()?;
+    let file_names = notebook_dir_names(&notebooks, &[UNFILED_NAME]);
+    let mut counts = ItemCounts::default();
+    let mut written: BTreeSet<String> = BTreeSet::new();
+
+    let mut targets: Vec<(Option<i64>, String, String)> = notebooks
+        .iter()
+        .filter_map(|n| {
+            let id = n.id?;
+            Some((Some(id), n.name.clone(), file_names.get(&id)?.clone()))
+        })
+        .collect();
+    targets.push((None, "Unfiled".to_string(), UNFILED_NAME.to_string()));
+
+    for (notebook_id, title, file_name) in targets {
+        let mut tasks = db.get_all_tasks_including_archived(notebook_id)?;
+        tasks.sort_by_key(|t| (t.order, t.id));
+        let mut notes = db.get_all_notes_including_archived(notebook_id)?;
+        notes.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        let mut journals: Vec<JournalEntry> = db
+            .get_all_journals_including_archived(notebook_id)?
+            .into_iter()
+            .filter(|j| !j.is_locked())
+            .collect();
+        if tasks.is_empty() && notes.is_empty() && journals.is_empty() {
+            continue;
+        }
+        counts.tasks += tasks.len();
+        counts.notes += notes.len();
+        counts.journals += journals.len();
+
+        let file = format!("{}.org", file_name);
+        write_if_changed(&dir.join(&file), notebook_file(&title, &tasks, &notes, &mut journals))?;
+        written.insert(file);
+    }
+
+    // Remove files this export generated before that are no longer needed
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.ends_with(".org") || written.contains(&name) || !entry.file_type()?.is_file() {
+            continue;
+        }
+        let generated = fs::read_to_string(entry.path())
+            .map(|text| text.starts_with(GENERATED_MARKER))
+            .unwrap_or(false);
+        if generated {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    Ok(counts)
+}
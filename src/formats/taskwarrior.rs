@@ -0,0 +1,212 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::formats::{join_tags, FormatError, ImportSummary, NotebookResolver};
+use crate::models::{ItemKind, Task};
+use crate::store::Store;
+
+/// Taskwarrior fields that are imported, or that only matter inside Taskwarrior
+const HANDLED_FIELDS: &[&str] = &[
+    "description", "status", "due", "tags", "project", "priority", "annotations",
+    "entry", "modified", "end", "uuid", "id", "urgency",
+];
+
+/// Read the tasks of a `task export` file
+/// Accepts both a JSON array and one JSON object per line (older Taskwarrior versions)
+fn read_tasks(text: &str) -> Result<Vec<Map<String, Value>>, FormatError> {
+    let values: Vec<Value> = if text.trim_start().starts_with('[') {
+        serde_json::from_str(text)?
+    } else {
+        text.lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?
+    };
+
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::Object(map) => Ok(map),
+            other => Err(FormatError::InvalidInput(format!("expected a task object, found {}", other))),
+        })
+        .collect()
+}
+
+/// Parse a Taskwarrior date (20240305T120000Z) to a database timestamp
+fn parse_timestamp(value: &str) -> Option<String> {
+    chrono::NaiveDateTime::parse_from_str(value.trim().trim_end_matches('Z'), "%Y%m%dT%H%M%S")
+        .ok()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn get_str<'a>(task: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+    task.get(key).and_then(Value::as_str).map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Turn annotations into description lines ("YYYY-MM-DD text")
+fn annotations_to_text(task: &Map<String, Value>) -> Option<String> {
+    let lines: Vec<String> = task
+        .get("annotations")?
+        .as_array()?
+        .iter()
+        .filter_map(|annotation| {
+            let text = annotation.get("description")?.as_str()?.trim();
+            let date = annotation
+                .get("entry")
+                .and_then(Value::as_str)
+                .and_then(parse_timestamp)
+                .map(|ts| ts[..10].to_string());
+            Some(match date {
+                Some(date) => format!("{} {}", date, text),
+                None => text.to_string(),
+            })
+        })
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Import tasks from a Taskwarrior `task export` JSON file
+///
+/// Pending and waiting tasks become open tasks, completed tasks become done and deleted tasks go
+/// to the trash. Projects become notebooks, priorities become `pri:H`/`pri:M`/`pri:L` tags and
+/// annotations are appended to the description. Recurring task templates are skipped. Fields tnj
+/// has no place for (e.g. `scheduled`, `depends`, user-defined attributes) are listed as warnings.
+/// Tasks imported before (by UUID) are skipped; with `dry_run` nothing is written.
+pub fn import(db: &dyn Store, path: &Path, dry_run: bool) -> Result<ImportSummary, FormatError> {
+    let text = fs::read_to_string(path)?;
+    let tasks = read_tasks(&text)?;
+    let mut summary = ImportSummary { dry_run, ..Default::default() };
+    let mut notebooks = NotebookResolver::new(dry_run);
+    let mut next_order = db.get_max_task_order().unwrap_or(-1) + 1;
+    let mut skipped_fields: BTreeMap<String, usize> = BTreeMap::new();
+    let mut recurring = 0;
+
+    for (index, tw_task) in tasks.iter().enumerate() {
+        let label = match get_str(tw_task, "uuid") {
+            Some(uuid) => format!("task {}", uuid),
+            None => format!("task #{}", index + 1),
+        };
+        let status = get_str(tw_task, "status").unwrap_or("pending");
+        if status == "recurring" {
+            recurring += 1;
+            continue;
+        }
+        let Some(title) = get_str(tw_task, "description") else {
+            summary.errors.push(format!("{}: no description", label));
+            continue;
+        };
+
+        let source = get_str(tw_task, "uuid").map(|uuid| format!("taskwarrior:{}", uuid));
+        if let Some(ref source) = source
+            && db.get_imported_item(source)?.is_some()
+        {
+            summary.skipped += 1;
+            continue;
+        }
+
+        for key in tw_task.keys() {
+            if !HANDLED_FIELDS.contains(&key.as_str()) {
+                *skipped_fields.entry(key.clone()).or_default() += 1;
+            }
+        }
+
+        let mut task = Task::new(title.to_string());
+        task.status = if status == "completed" { "done" } else { "todo" }.to_string();
+        task.description = annotations_to_text(tw_task);
+        task.order = next_order;
+        next_order += 1;
+
+        if let Some(due) = get_str(tw_task, "due") {
+            match parse_timestamp(due) {
+                Some(ts) => task.due_date = Some(ts[..10].to_string()),
+                None => summary.warnings.push(format!("{}: invalid due date '{}' ignored", label, due)),
+            }
+        }
+
+        let mut tags: Vec<String> = tw_task
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().filter_map(Value::as_str).map(str::to_string).collect())
+            .unwrap_or_default();
+        if let Some(priority) = get_str(tw_task, "priority") {
+            tags.insert(0, format!("pri:{}", priority));
+        }
+        task.tags = join_tags(&tags);
+
+        if let Some(project) = get_str(tw_task, "project") {
+            task.notebook_id = notebooks.resolve(db, project)?;
+        }
+        if let Some(entry) = get_str(tw_task, "entry").and_then(parse_timestamp) {
+            task.created_at = entry.clone();
+            task.updated_at = entry;
+        }
+        if let Some(modified) = get_str(tw_task, "modified").and_then(parse_timestamp) {
+            task.updated_at = modified;
+        }
+
+        if !dry_run {
+            let id = db.insert_task(&task)?;
+            if status == "deleted" {
+                // Trashed as of now, so the trash retention period starts with the import
+                let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                db.set_deleted_at(ItemKind::Task, id, Some(&now))?;
+            }
+            if let Some(ref source) = source {
+                db.record_import_source(source, ItemKind::Task, id)?;
+            }
+        }
+        summary.imported.add(ItemKind::Task);
+    }
+
+    if recurring > 0 {
+        summary.warnings.push(format!(
+            "skipped {} recurring task {} (their generated instances are imported)",
+            recurring,
+            if recurring == 1 { "template" } else { "templates" }
+        ));
+    }
+    if !skipped_fields.is_empty() {
+        let fields: Vec<String> = skipped_fields
+            .iter()
+            .map(|(field, count)| format!("{} ({})", field, count))
+            .collect();
+        summary.warnings.push(format!("fields not imported: {}", fields.join(", ")));
+    }
+    summary.notebooks_created = notebooks.created;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tasks_are_read_from_an_array_or_one_object_per_line() {
+        let array = r#"[{"description": "Call Bob", "uuid": "a"}, {"description": "Pay rent", "uuid": "b"}]"#;
+        let lines = "{\"description\": \"Call Bob\", \"uuid\": \"a\"},\n\n{\"description\": \"Pay rent\", \"uuid\": \"b\"}\n";
+        for text in [array, lines] {
+            let tasks = read_tasks(text).unwrap();
+            let descriptions: Vec<_> = tasks.iter().map(|task| get_str(task, "description")).collect();
+            assert_eq!(descriptions, vec![Some("Call Bob"), Some("Pay rent")]);
+        }
+        assert!(read_tasks("[1, 2]").is_err());
+        assert!(read_tasks("{\"description\": \"Call Bob\"\n").is_err());
+    }
+
+    #[test]
+    fn timestamps_and_annotations_are_converted() {
+        assert_eq!(parse_timestamp("20240305T120000Z").as_deref(), Some("2024-03-05 12:00:00"));
+        assert_eq!(parse_timestamp("2024-03-05"), None);
+        let task = read_tasks(r#"{"annotations": [{"entry": "20240305T120000Z", "description": "Left a message"}, {"description": "Try again"}]}"#)
+            .unwrap()
+            .remove(0);
+        assert_eq!(annotations_to_text(&task).as_deref(), Some("2024-03-05 Left a message\nTry again"));
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::formats::{join_tags, normalize_date, FormatError, ImportSummary, ItemCounts, NotebookResolver};
+use crate::models::{ItemKind, Task};
+use crate::store::Store;
+
+/// A task parsed from one todo.txt line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TodoTxtTask {
+    pub done: bool,
+    pub priority: Option<char>,
+    pub completion_date: Option<String>,
+    pub creation_date: Option<String>,
+    pub title: String,
+    pub projects: Vec<String>,
+    pub tags: Vec<String>, // Contexts (without '@') and custom key:value pairs
+    pub due: Option<String>,
+    pub archived: bool,
+    pub description: Option<String>,
+}
+
+/// Keys tnj reads into task fields, so tags with these keys are written as `@context`s
+const FIELD_KEYS: [&str; 3] = ["due", "archived", "desc"];
+
+/// Check whether a token is a YYYY-MM-DD date
+fn is_date(token: &str) -> bool {
+    token.len() == 10 && crate::utils::parse_date(token).is_ok()
+}
+
+/// Check whether a token is a todo.txt `key:value` pair (URLs like "https://..." are not)
+fn split_key_value(token: &str) -> Option<(&str, &str)> {
+    let (key, value) = token.split_once(':')?;
+    let valid = !key.is_empty()
+        && !value.is_empty()
+        && !value.starts_with("//")
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    valid.then_some((key, value))
+}
+
+/// Encode a notebook name, tag or description so it stays a single token: `%`, whitespace and
+/// other control characters are written as `%XX`
+fn encode_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '%' || c.is_whitespace() || c.is_control() {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            encoded.push(c);
+        }
+    }
+    encoded
+}
+
+/// Undo `encode_value`; `%` not followed by two hex digits is kept as it is
+fn decode_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match hex.filter(|_| bytes[i] == b'%').and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Check whether a title word would be read back as a `+project`, `@context` or `key:value`
+/// (or starts with the `\` used to escape those)
+fn needs_escape(word: &str) -> bool {
+    word.starts_with('\\')
+        || (word.len() > 1 && (word.starts_with('+') || word.starts_with('@')))
+        || split_key_value(word).is_some()
+}
+
+/// Parse one line of a todo.txt file
+/// Returns None for blank lines
+pub fn parse_line(line: &str) -> Option<TodoTxtTask> {
+    let mut tokens = line.split_whitespace().peekable();
+    tokens.peek()?;
+
+    let mut task = TodoTxtTask::default();
+
+    if tokens.peek() == Some(&"x") {
+        tokens.next();
+        task.done = true;
+        if let Some(date) = tokens.next_if(|t| is_date(t)) {
+            task.completion_date = Some(date.to_string());
+        }
+    } else if let Some(priority) = tokens.next_if(|t| {
+        t.len() == 3 && t.starts_with('(') && t.ends_with(')') && t.as_bytes()[1].is_ascii_uppercase()
+    }) {
+        task.priority = priority.chars().nth(1);
+    }
+    if let Some(date) = tokens.next_if(|t| is_date(t)) {
+        task.creation_date = Some(date.to_string());
+    }
+
+    let mut words: Vec<&str> = Vec::new();
+    for token in tokens {
+        if let Some(word) = token.strip_prefix('\\') {
+            words.push(word); // Escaped title word
+        } else if let Some(project) = token.strip_prefix('+').filter(|p| !p.is_empty()) {
+            task.projects.push(decode_value(project));
+        } else if let Some(context) = token.strip_prefix('@').filter(|c| !c.is_empty()) {
+            task.tags.push(decode_value(context));
+        } else if let Some((key, value)) = split_key_value(token) {
+            match key.to_lowercase().as_str() {
+                "due" => task.due = Some(value.to_string()),
+                "archived" => task.archived = value.eq_ignore_ascii_case("true"),
+                "pri" if task.priority.is_none() => task.priority = value.chars().next(),
+                "desc" => task.description = Some(decode_value(value)),
+                _ => task.tags.push(decode_value(token)),
+            }
+        } else {
+            words.push(token);
+        }
+    }
+    task.title = words.join(" ");
+    Some(task)
+}
+
+/// Format a task as a todo.txt line
+/// Tags become `@context`s (or stay `key:value` pairs), the notebook becomes a `+project`,
+/// a `pri:X` tag becomes the `(X)` priority of an open task and the description a `desc:` pair.
+/// Title words that would read back as projects, contexts or pairs are escaped with `\`.
+pub fn format_task(task: &Task, notebook: Option<&str>) -> String {
+    let tags: Vec<String> = task
+        .tags
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    let priority = tags
+        .iter()
+        .find_map(|t| t.strip_prefix("pri:"))
+        .and_then(|p| p.chars().next())
+        .filter(|p| p.is_ascii_uppercase());
+    let done = task.status == "done";
+    let created = task.created_at.get(..10).unwrap_or(&task.created_at);
+
+    let mut parts: Vec<String> = Vec::new();
+    if done {
+        parts.push("x".to_string());
+        parts.push(task.updated_at.get(..10).unwrap_or(&task.updated_at).to_string());
+    } else if let Some(p) = priority {
+        parts.push(format!("({})", p));
+    }
+    parts.push(created.to_string());
+    parts.extend(task.title.split_whitespace().map(|word| {
+        if needs_escape(word) {
+            format!("\\{}", word)
+        } else {
+            word.to_string()
+        }
+    }));
+
+    if let Some(name) = notebook {
+        parts.push(format!("+{}", encode_value(name)));
+    }
+    for tag in &tags {
+        let tag = encode_value(tag);
+        if tag.starts_with("pri:") && !done {
+            continue; // Written as the (X) priority
+        }
+        let field_key = split_key_value(&tag).is_some_and(|(key, _)| FIELD_KEYS.contains(&key.to_lowercase().as_str()));
+        if !field_key && (tag.starts_with('+') || split_key_value(&tag).is_some()) {
+            parts.push(tag); // Extra projects and key:value pairs are written as they are
+        } else {
+            parts.push(format!("@{}", tag));
+        }
+    }
+    if let Some(ref due) = task.due_date {
+        parts.push(format!("due:{}", due));
+    }
+    if task.archived {
+        parts.push("archived:true".to_string());
+    }
+    if let Some(description) = task.description.as_deref().filter(|d| !d.is_empty()) {
+        parts.push(format!("desc:{}", encode_value(description)));
+    }
+    parts.join(" ")
+}
+
+/// Export all tasks (including archived, excluding trashed) to a todo.txt file
+pub fn export(db: &dyn Store, path: &Path) -> Result<ItemCounts, FormatError> {
+    let notebooks: HashMap<i64, String> = db
+        .get_all_notebooks()?
+        .into_iter()
+        .filter_map(|n| n.id.map(|id| (id, n.name)))
+        .collect();
+
+    let mut tasks: Vec<Task> = db.dump_tasks()?.into_iter().filter(|t| t.deleted_at.is_none()).collect();
+    tasks.sort_by_key(|t| (t.order, t.id));
+
+    let mut contents = String::new();
+    for task in &tasks {
+        let notebook = task.notebook_id.and_then(|id| notebooks.get(&id)).map(String::as_str);
+        contents.push_str(&format_task(task, notebook));
+        contents.push('\n');
+    }
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+
+    Ok(ItemCounts { tasks: tasks.len(), ..Default::default() })
+}
+
+/// Import tasks from a todo.txt file
+/// The first `+project` of a line becomes the task's notebook, further projects become tags.
+/// Lines imported before are skipped; with `dry_run` nothing is written.
+pub fn import(db: &dyn Store, path: &Path, dry_run: bool) -> Result<ImportSummary, FormatError> {
+    let contents = fs::read_to_string(path)?;
+    let mut summary = ImportSummary { dry_run, ..Default::default() };
+    let mut notebooks = NotebookResolver::new(dry_run);
+    let mut next_order = db.get_max_task_order().unwrap_or(-1) + 1;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let Some(parsed) = parse_line(line) else {
+            continue;
+        };
+        if parsed.title.is_empty() {
+            summary.errors.push(format!("line {}: task has no text", line_number));
+            continue;
+        }
+
+        let source = format!("todotxt:{}", line.split_whitespace().collect::<Vec<_>>().join(" "));
+        if db.get_imported_item(&source)?.is_some() {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let mut task = Task::new(parsed.title.clone());
+        task.description = parsed.description.clone();
+        task.status = if parsed.done { "done" } else { "todo" }.to_string();
+        task.archived = parsed.archived;
+        task.order = next_order;
+        next_order += 1;
+
+        let mut tags = parsed.tags.clone();
+        if let Some(p) = parsed.priority {
+            tags.insert(0, format!("pri:{}", p));
+        }
+        let mut projects = parsed.projects.iter();
+        if let Some(project) = projects.next() {
+            task.notebook_id = notebooks.resolve(db, project)?;
+        }
+        tags.extend(projects.map(|p| format!("+{}", p)));
+        task.tags = join_tags(&tags);
+
+        if let Some(ref due) = parsed.due {
+            match normalize_date(due) {
+                Some(date) => task.due_date = Some(date),
+                None => summary.warnings.push(format!("line {}: invalid due date '{}' ignored", line_number, due)),
+            }
+        }
+        if let Some(ref created) = parsed.creation_date {
+            task.created_at = format!("{} 00:00:00", created);
+            task.updated_at = task.created_at.clone();
+        }
+        if let Some(ref completed) = parsed.completion_date {
+            task.updated_at = format!("{} 00:00:00", completed);
+        }
+
+        if !dry_run {
+            let id = db.insert_task(&task)?;
+            db.record_import_source(&source, ItemKind::Task, id)?;
+        }
+        summary.imported.add(ItemKind::Task);
+    }
+
+    summary.notebooks_created = notebooks.created;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Notebook;
+    use crate::store::MemoryStore;
+
+    #[test]
+    fn tasks_round_trip_through_a_file() {
+        let source = MemoryStore::new();
+        let notebook = source.insert_notebook(&Notebook::new("Side projects".to_string())).unwrap();
+        let mut task = Task::new("Meet at 10:30 email @bob +1 the PR \\o/".to_string());
+        task.description = Some("Agenda:\n- 100% of the budget\tfirst".to_string());
+        task.tags = Some("home office, due:soon, pri:B, +extra, note:a b".to_string());
+        task.due_date = Some("2026-11-02".to_string());
+        task.notebook_id = Some(notebook);
+        source.insert_task(&task).unwrap();
+        let mut other = Task::new("Review".to_string());
+        other.notebook_id = Some(source.insert_notebook(&Notebook::new("my_project".to_string())).unwrap());
+        source.insert_task(&other).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("todo.txt");
+        export(&source, &path).unwrap();
+        let target = MemoryStore::new();
+        let summary = import(&target, &path, false).unwrap();
+        assert!(summary.errors.is_empty() && summary.warnings.is_empty());
+
+        let mut imported = target.dump_tasks().unwrap();
+        let other = imported.pop().unwrap();
+        let imported = imported.pop().unwrap();
+        assert_eq!(imported.title, task.title);
+        assert_eq!(imported.description, task.description);
+        assert_eq!(imported.due_date, task.due_date);
+        let mut tags: Vec<&str> = imported.tags.as_deref().unwrap().split(", ").collect();
+        tags.sort();
+        assert_eq!(tags, ["+extra", "due:soon", "home office", "note:a b", "pri:B"]);
+        let name = target.get_notebook(imported.notebook_id.unwrap()).unwrap().name;
+        assert_eq!(name, "Side projects");
+        let name = target.get_notebook(other.notebook_id.unwrap()).unwrap().name;
+        assert_eq!(name, "my_project");
+    }
+}
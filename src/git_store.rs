@@ -0,0 +1,495 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::database::DatabaseError;
+use crate::formats::markdown::{self, ExportMode};
+use crate::formats::{FormatError, NotebookResolver, join_tags, normalize_date, normalize_timestamp, write_if_changed};
+use crate::models::{ItemKind, JournalEntry, Note, Notebook, SyncKind, Task};
+use crate::store::{Backend, Store};
+use crate::sync::{self, SyncError};
+
+/// File (in the repository root) listing the notebooks, so empty and locked notebooks are kept as well
+const NOTEBOOKS_FILE: &str = "notebooks.json";
+/// Setting holding the commit the database was last written to or read from
+const HEAD_SETTING: &str = "git_head";
+/// Setting holding the files written by hand (paths relative to the repository) that the next
+/// save replaces with files under the usual names, with the UUID each was given, as JSON
+const REPLACED_SETTING: &str = "git_replaced_files";
+/// Identity used for commits when git has no user configured
+const FALLBACK_IDENTITY: [&str; 4] = ["-c", "user.name=tnj", "-c", "user.email=tnj@localhost"];
+
+#[derive(Debug, Error)]
+pub enum GitError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] DatabaseError),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("{0}")]
+    FormatError(#[from] FormatError),
+    #[error("{0}")]
+    SyncError(#[from] SyncError),
+    #[error("git failed: {0}")]
+    CommandFailed(String),
+    #[error("{} is not a git repository (set it up with `tnj git init` or `tnj git clone`)", .0.display())]
+    NotARepository(PathBuf),
+    #[error("{} already exists", .0.display())]
+    AlreadyExists(PathBuf),
+    #[error("Unresolved merge in {}: fix the conflicting files, commit them and run `tnj git pull` again", .0.display())]
+    MergeInProgress(PathBuf),
+}
+
+/// Where the canonical copy of the data lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Storage {
+    #[default]
+    Sqlite, // The database only
+    Git,    // Markdown files in a git repository, with the database as their index
+}
+
+impl FromStr for Storage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sqlite" | "database" => Ok(Storage::Sqlite),
+            "git" => Ok(Storage::Git),
+            other => Err(format!("Unknown storage '{}' (expected sqlite or git)", other)),
+        }
+    }
+}
+
+impl Storage {
+    /// Value stored in the config file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Storage::Sqlite => "sqlite",
+            Storage::Git => "git",
+        }
+    }
+}
+
+/// A notebook as listed in `notebooks.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredNotebook {
+    uuid: String,
+    name: String,
+    #[serde(default)]
+    locked: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+/// What reading the files into the database changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexSummary {
+    pub changed: usize, // Notebooks and items added or updated
+    pub removed: usize, // Notebooks and items whose files are gone
+}
+
+impl fmt::Display for IndexSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changed == 0 && self.removed == 0 {
+            return write!(f, "Already up to date");
+        }
+        write!(
+            f,
+            "{} added or updated, {} removed",
+            self.changed, self.removed
+        )
+    }
+}
+
+/// Run git in `dir` and return what it printed
+fn git(dir: &Path, args: &[&str]) -> Result<String, GitError> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let message = if stderr.is_empty() {
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        } else {
+            stderr
+        };
+        return Err(GitError::CommandFailed(message));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Run a git command that creates commits, with a fallback identity when git has none configured
+fn git_committing(dir: &Path, args: &[&str]) -> Result<String, GitError> {
+    let mut full_args = Vec::new();
+    if git(dir, &["config", "user.email"]).is_err() {
+        full_args.extend(FALLBACK_IDENTITY);
+    }
+    full_args.extend(args);
+    git(dir, &full_args)
+}
+
+fn check_repository(dir: &Path) -> Result<(), GitError> {
+    if !dir.join(".git").exists() {
+        return Err(GitError::NotARepository(dir.to_path_buf()));
+    }
+    if dir.join(".git").join("MERGE_HEAD").exists() {
+        return Err(GitError::MergeInProgress(dir.to_path_buf()));
+    }
+    Ok(())
+}
+
+/// Get the current commit (None in a repository without commits)
+fn head(dir: &Path) -> Option<String> {
+    git(dir, &["rev-parse", "--verify", "--quiet", "HEAD"]).ok()
+}
+
+fn record_head(db: &dyn Store, dir: &Path) -> Result<(), GitError> {
+    if let Some(head) = head(dir) {
+        db.set_setting(HEAD_SETTING, &head)?;
+    }
+    Ok(())
+}
+
+/// Get the files written by hand that are waiting to be replaced, with the UUID each was given
+fn replaced_files(db: &dyn Store) -> Result<BTreeMap<String, String>, GitError> {
+    match db.get_setting(REPLACED_SETTING)? {
+        Some(json) if !json.is_empty() => Ok(serde_json::from_str(&json)?),
+        _ => Ok(BTreeMap::new()),
+    }
+}
+
+/// Write every notebook and item to the files of the repository
+fn write_files(db: &dyn Backend, dir: &Path) -> Result<(), GitError> {
+    markdown::export_with(db, dir, ExportMode::Storage)?;
+
+    let notebooks: Vec<StoredNotebook> = db
+        .get_all_notebooks()?
+        .into_iter()
+        .filter_map(|notebook| {
+            Some(StoredNotebook {
+                uuid: notebook.uuid?,
+                name: notebook.name,
+                locked: notebook.locked,
+                created_at: notebook.created_at,
+                updated_at: notebook.updated_at,
+            })
+        })
+        .collect();
+    let mut json = serde_json::to_string_pretty(&notebooks)?;
+    json.push('\n');
+    write_if_changed(&dir.join(NOTEBOOKS_FILE), json)?;
+    // The export manifest only describes this clone
+    write_if_changed(&dir.join(".gitignore"), format!("{}\n", markdown::MANIFEST_FILE))?;
+    Ok(())
+}
+
+/// Describe the staged changes (`git status --porcelain`) for a commit message, e.g. "Add 1 file, update 2 files"
+fn commit_message(status: &str) -> String {
+    let (mut added, mut updated, mut removed) = (0, 0, 0);
+    for line in status.lines() {
+        match line.chars().next() {
+            Some('A') => added += 1,
+            Some('D') => removed += 1,
+            Some(_) => updated += 1,
+            None => {}
+        }
+    }
+    let parts: Vec<String> = [("Add", added), ("Update", updated), ("Remove", removed)]
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .enumerate()
+        .map(|(index, (verb, count))| {
+            let verb = if index == 0 { verb.to_string() } else { verb.to_lowercase() };
+            format!("{} {} {}", verb, count, if *count == 1 { "file" } else { "files" })
+        })
+        .collect();
+    parts.join(", ")
+}
+
+/// Write the database to the files of git storage and commit whatever changed
+/// Returns whether a commit was made.
+pub fn save(db: &dyn Backend, dir: &Path) -> Result<bool, GitError> {
+    check_repository(dir)?;
+    write_files(db, dir)?;
+    // Files written by hand are only removed once the files replacing them are written
+    let replaced = replaced_files(db)?;
+    for relative in replaced.keys() {
+        let path = dir.join(relative);
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+    }
+    if !replaced.is_empty() {
+        db.set_setting(REPLACED_SETTING, "")?;
+    }
+    git(dir, &["add", "--all"])?;
+    let status = git(dir, &["status", "--porcelain"])?;
+    if status.is_empty() {
+        return Ok(false);
+    }
+    git_committing(dir, &["commit", "--quiet", "-m", &commit_message(&status)])?;
+    record_head(db, dir)?;
+    Ok(true)
+}
+
+/// Read the repository into the database if its commit changed since tnj last wrote or read it
+/// (e.g. after a `git pull` or `git revert` by hand)
+pub fn refresh(db: &dyn Backend, dir: &Path) -> Result<Option<IndexSummary>, GitError> {
+    check_repository(dir)?;
+    let Some(current) = head(dir) else {
+        return Ok(None);
+    };
+    let previous = db.get_setting(HEAD_SETTING)?;
+    if previous.as_deref() == Some(current.as_str()) {
+        return Ok(None);
+    }
+    let committed = match previous {
+        Some(previous) => Some(committed_uuids(dir, &previous)?),
+        None => None,
+    };
+    let summary = reindex(db, dir, committed.as_ref())?;
+    db.set_setting(HEAD_SETTING, &current)?;
+    Ok(Some(summary))
+}
+
+/// Create the repository in `dir`, write the database to it and make the first commit
+pub fn init(db: &dyn Backend, dir: &Path, remote: Option<&str>) -> Result<(), GitError> {
+    if !dir.join(".git").exists() {
+        fs::create_dir_all(dir)?;
+        git(dir, &["init", "--quiet"])?;
+    }
+    if let Some(remote) = remote {
+        if git(dir, &["remote", "get-url", "origin"]).is_ok() {
+            git(dir, &["remote", "set-url", "origin", remote])?;
+        } else {
+            git(dir, &["remote", "add", "origin", remote])?;
+        }
+    }
+    save(db, dir)?;
+    Ok(())
+}
+
+/// Clone a repository into `dir` and add its notebooks and items to the database
+/// Nothing is deleted: what the database has that the repository lacks is committed on top.
+pub fn clone(db: &dyn Backend, url: &str, dir: &Path) -> Result<IndexSummary, GitError> {
+    if dir.exists() && fs::read_dir(dir)?.next().is_some() {
+        return Err(GitError::AlreadyExists(dir.to_path_buf()));
+    }
+    if let Some(parent) = dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    git(Path::new("."), &["clone", "--quiet", url, &dir.to_string_lossy()])?;
+    let summary = reindex(db, dir, None)?;
+    save(db, dir)?;
+    record_head(db, dir)?;
+    Ok(summary)
+}
+
+/// Commit local changes, merge the remote's and read the result into the database
+pub fn pull(db: &dyn Backend, dir: &Path) -> Result<IndexSummary, GitError> {
+    let mut summary = refresh(db, dir)?.unwrap_or_default();
+    save(db, dir)?;
+    let previous = head(dir);
+    match git_committing(dir, &["pull", "--quiet", "--no-rebase", "--no-edit"]) {
+        Err(_) if dir.join(".git").join("MERGE_HEAD").exists() => {
+            return Err(GitError::MergeInProgress(dir.to_path_buf()));
+        }
+        result => result?,
+    };
+    let committed = match previous {
+        Some(previous) => committed_uuids(dir, &previous)?,
+        None => HashSet::new(),
+    };
+    let merged = reindex(db, dir, Some(&committed))?;
+    summary.changed += merged.changed;
+    summary.removed += merged.removed;
+    // Files that were written by hand are renamed and rewritten in the usual form
+    save(db, dir)?;
+    record_head(db, dir)?;
+    Ok(summary)
+}
+
+/// Commit local changes and push them to the remote
+pub fn push(db: &dyn Backend, dir: &Path) -> Result<(), GitError> {
+    refresh(db, dir)?;
+    save(db, dir)?;
+    git(dir, &["push", "--quiet", "--set-upstream", "origin", "HEAD"])?;
+    Ok(())
+}
+
+/// Make the database match a version of a notebook or item, unless it already does
+fn apply_if_changed(db: &dyn Backend, kind: SyncKind, uuid: &str, item: &Value) -> Result<bool, GitError> {
+    if sync::current_version(db, kind, uuid)?.as_ref() == Some(item) {
+        return Ok(false);
+    }
+    sync::apply_version(db, kind, uuid, Some(item))?;
+    Ok(true)
+}
+
+/// Get the UUIDs of the notebooks and items in a commit
+fn committed_uuids(dir: &Path, commit: &str) -> Result<HashSet<String>, GitError> {
+    let mut uuids = HashSet::new();
+    for path in git(dir, &["ls-tree", "-r", "--name-only", commit])?.lines() {
+        // Item files end with their UUID: "<uuid>.md" or "<date>-<uuid>.md"
+        if let Some(stem) = path.strip_suffix(".md")
+            && let Some(uuid) = stem.get(stem.len().saturating_sub(36)..)
+        {
+            uuids.insert(uuid.to_string());
+        }
+    }
+    if let Ok(json) = git(dir, &["show", &format!("{}:{}", commit, NOTEBOOKS_FILE)]) {
+        let notebooks: Vec<StoredNotebook> = serde_json::from_str(&json)?;
+        uuids.extend(notebooks.into_iter().map(|notebook| notebook.uuid));
+    }
+    Ok(uuids)
+}
+
+/// Read the notebooks and item files of the repository into the database
+/// Notebooks and items in `committed` (the commit the database was read from or written to last)
+/// that have no file any more are deleted; others were added since and are kept.
+fn reindex(db: &dyn Backend, dir: &Path, committed: Option<&HashSet<String>>) -> Result<IndexSummary, GitError> {
+    let mut summary = IndexSummary::default();
+    let mut present: HashSet<(SyncKind, String)> = HashSet::new();
+
+    let stored: Vec<StoredNotebook> = match fs::read_to_string(dir.join(NOTEBOOKS_FILE)) {
+        Ok(json) => serde_json::from_str(&json)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    for stored in stored {
+        // Notebook names are unique: a local notebook with the same name is the same notebook
+        if db.find_by_uuid(SyncKind::Notebook, &stored.uuid)?.is_none()
+            && let Some(local) = db.get_notebook_by_name(&stored.name)?
+            && let Some(id) = local.id
+        {
+            db.set_uuid(SyncKind::Notebook, id, &stored.uuid)?;
+        }
+        let notebook = Notebook {
+            id: None,
+            uuid: None,
+            name: stored.name,
+            created_at: stored.created_at,
+            updated_at: stored.updated_at,
+            locked: stored.locked,
+        };
+        let (_, item) = sync::to_synced(&notebook, &HashMap::new())?;
+        if apply_if_changed(db, SyncKind::Notebook, &stored.uuid, &item)? {
+            summary.changed += 1;
+        }
+        present.insert((SyncKind::Notebook, stored.uuid));
+    }
+
+    let mut notebooks = NotebookResolver::new(false);
+    let mut notebook_uuids = db.get_uuids(SyncKind::Notebook)?;
+    let mut replaced = replaced_files(db)?;
+    let replaced_before = replaced.len();
+    for path in markdown::collect_markdown_files(dir)? {
+        let relative = path.strip_prefix(dir).unwrap_or(&path).display().to_string();
+        let text = fs::read_to_string(&path)?;
+        let (front_matter, body) = markdown::parse_front_matter(&text);
+        let kind = front_matter
+            .get_str("type")
+            .unwrap_or("note")
+            .parse::<ItemKind>()
+            .map_err(|e| FormatError::InvalidInput(format!("{}: {}", relative, e)))?;
+
+        let notebook_id = notebooks.resolve(db, front_matter.get_str("notebook").unwrap_or_default())?;
+        if notebook_id.is_some_and(|id| !notebook_uuids.contains_key(&id)) {
+            notebook_uuids = db.get_uuids(SyncKind::Notebook)?; // Created for this file
+        }
+
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let created_at = front_matter.get_str("created").and_then(normalize_timestamp).unwrap_or(now);
+        let updated_at = front_matter
+            .get_str("updated")
+            .and_then(normalize_timestamp)
+            .unwrap_or_else(|| created_at.clone());
+        let deleted_at = front_matter.get_str("deleted").and_then(normalize_timestamp);
+        let tags = join_tags(&front_matter.get_list("tags"));
+        let archived = front_matter.get_bool("archived");
+        let title = front_matter.get_str("title").map(String::from);
+        let body = markdown::body_text(body);
+
+        let (_, item) = match kind {
+            ItemKind::Task => {
+                let mut task = Task::new(title.unwrap_or_default());
+                task.description = body;
+                task.status = front_matter.get_str("status").unwrap_or("todo").to_string();
+                task.due_date = front_matter.get_str("due").and_then(normalize_date);
+                task.order = front_matter.get_str("order").and_then(|o| o.parse().ok()).unwrap_or_default();
+                task.tags = tags;
+                task.archived = archived;
+                task.notebook_id = notebook_id;
+                task.created_at = created_at;
+                task.updated_at = updated_at;
+                task.deleted_at = deleted_at;
+                sync::to_synced(&task, &notebook_uuids)?
+            }
+            ItemKind::Note => {
+                let mut note = Note::new(title.unwrap_or_default());
+                note.content = body;
+                note.tags = tags;
+                note.archived = archived;
+                note.notebook_id = notebook_id;
+                note.created_at = created_at;
+                note.updated_at = updated_at;
+                note.deleted_at = deleted_at;
+                sync::to_synced(&note, &notebook_uuids)?
+            }
+            ItemKind::Journal => {
+                let date = front_matter
+                    .get_str("date")
+                    .and_then(normalize_date)
+                    .unwrap_or_else(crate::utils::get_current_date_string);
+                let mut journal = JournalEntry::new(date);
+                journal.title = title;
+                journal.content = body;
+                journal.tags = tags;
+                journal.archived = archived;
+                journal.notebook_id = notebook_id;
+                journal.created_at = created_at;
+                journal.updated_at = updated_at;
+                journal.deleted_at = deleted_at;
+                sync::to_synced(&journal, &notebook_uuids)?
+            }
+        };
+
+        // A file written by hand gets a UUID; the next save writes it under the usual name instead
+        // and removes it (until then, reading it again keeps the same UUID)
+        let uuid = match front_matter.get_str("uuid") {
+            Some(uuid) => uuid.to_lowercase(),
+            None => replaced
+                .entry(relative)
+                .or_insert_with(|| uuid::Uuid::new_v4().to_string())
+                .clone(),
+        };
+        if apply_if_changed(db, kind.into(), &uuid, &item)? {
+            summary.changed += 1;
+        }
+        present.insert((kind.into(), uuid));
+    }
+
+    if replaced.len() != replaced_before {
+        db.set_setting(REPLACED_SETTING, &serde_json::to_string(&replaced)?)?;
+    }
+
+    if let Some(committed) = committed {
+        // Items first, so notebooks are only deleted once nothing refers to them
+        for kind in SyncKind::ALL.iter().rev() {
+            for uuid in db.get_uuids(*kind)?.into_values() {
+                if committed.contains(&uuid) && !present.contains(&(*kind, uuid.clone())) {
+                    sync::apply_version(db, *kind, &uuid, None)?;
+                    summary.removed += 1;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
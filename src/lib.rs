@@ -3,10 +3,19 @@ pub mod database;
 pub mod models;
 pub mod utils;
 pub mod cli;
+pub mod formats;
+pub mod links;
+pub mod lock;
+pub mod backups;
+pub mod sync;
+pub mod git_store;
+pub mod server;
+pub mod store;
 pub mod tui;
 
 pub use config::Config;
 pub use database::Database;
+pub use store::{Store, LockStore, HistoryStore, LinkStore, AttachmentStore, SyncStore, Backend, MemoryStore};
 pub use models::{Task, Note, JournalEntry};
 pub use utils::Profile;
 
@@ -0,0 +1,104 @@
+use std::fmt;
+
+use crate::models::ItemKind;
+
+/// What a `[[...]]` link points at
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LinkTarget {
+    Item(ItemKind, i64), // [[task:42]], [[note:7]], [[journal:3]]
+    Date(String),        // [[2024-03-05]]: the journal entry of that day
+    Title(String),       // [[Note Title]]: the note (else task, else journal entry) with that title
+}
+
+impl LinkTarget {
+    /// Parse the part of a link before the `|`
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        if let Some((kind, id)) = text.split_once(':')
+            && let Ok(kind) = kind.parse::<ItemKind>()
+            && let Ok(id) = id.trim().parse::<i64>()
+        {
+            return Some(LinkTarget::Item(kind, id));
+        }
+        if text.len() == 10 && crate::utils::parse_date(text).is_ok() {
+            return Some(LinkTarget::Date(text.to_string()));
+        }
+        Some(LinkTarget::Title(text.to_string()))
+    }
+
+    /// Normalized form stored in the links table ("task:42", "date:2024-03-05", "title:<lowercase>")
+    pub fn key(&self) -> String {
+        match self {
+            LinkTarget::Item(kind, id) => format!("{}:{}", kind, id),
+            LinkTarget::Date(date) => format!("date:{}", date),
+            LinkTarget::Title(title) => format!("title:{}", title.to_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for LinkTarget {
+    /// The target as written inside `[[...]]`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkTarget::Item(kind, id) => write!(f, "{}:{}", kind, id),
+            LinkTarget::Date(date) => write!(f, "{}", date),
+            LinkTarget::Title(title) => write!(f, "{}", title),
+        }
+    }
+}
+
+/// A `[[Target]]` or `[[Target|Label]]` link found in an item's text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    pub target: LinkTarget,
+    pub label: Option<String>, // The text after `|`, if any
+}
+
+/// Find the links in a text, in order of first appearance (each target once)
+/// Links inside fenced code blocks and `code` spans are ignored.
+pub fn parse_links(text: &str) -> Vec<WikiLink> {
+    let mut links: Vec<WikiLink> = Vec::new();
+    let mut in_code_block = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || !line.contains("[[") {
+            continue;
+        }
+
+        let mut in_code = false;
+        let mut rest = line;
+        while let Some(start) = rest.find(['`', '[']) {
+            let after = &rest[start..];
+            if let Some(code_rest) = after.strip_prefix('`') {
+                in_code = !in_code;
+                rest = code_rest;
+                continue;
+            }
+            let Some((inner, link_rest)) = after.strip_prefix("[[").and_then(|inner| inner.split_once("]]")) else {
+                rest = &after[1..];
+                continue;
+            };
+            rest = link_rest;
+            if in_code {
+                continue;
+            }
+            let (target, label) = match inner.split_once('|') {
+                Some((target, label)) => (target, Some(label.trim().to_string()).filter(|l| !l.is_empty())),
+                None => (inner, None),
+            };
+            if let Some(target) = LinkTarget::parse(target)
+                && !links.iter().any(|link| link.target == target)
+            {
+                links.push(WikiLink { target, label });
+            }
+        }
+    }
+    links
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use thiserror::Error;
+
+use crate::database::DatabaseError;
+use crate::store::LockStore;
+
+/// Start of the content of a locked item: `tnj-locked:v1:<salt>:<nonce and ciphertext>` (base64)
+pub const LOCKED_PREFIX: &str = "tnj-locked:v1:";
+
+/// Settings key of a short text locked with the passphrase of locked entries, to check passphrases against
+pub const CHECK_SETTING: &str = "lock_check";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error("Locked content is damaged")]
+    Malformed,
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+    #[error("Key derivation failed: {0}")]
+    KeyDerivation(String),
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+}
+
+/// Check whether a text is locked (encrypted) content
+pub fn is_locked(text: &str) -> bool {
+    text.starts_with(LOCKED_PREFIX)
+}
+
+/// Check whether a passphrase for locked entries was chosen (once chosen, passphrases are checked against it)
+pub fn has_passphrase(db: &dyn LockStore) -> Result<bool, DatabaseError> {
+    Ok(db.get_setting(CHECK_SETTING)?.is_some() || db.get_any_locked_content()?.is_some())
+}
+
+/// Check a passphrase and get an unlocker for it
+///
+/// The passphrase is checked against the stored check text, or else against any locked entry. If
+/// there is no check text yet, one is stored, so that the first passphrase given is the one kept.
+pub fn open(db: &dyn LockStore, passphrase: &str) -> Result<Unlocker, LockError> {
+    let check = db.get_setting(CHECK_SETTING)?;
+    let sample = match check {
+        Some(ref check) => Some(check.clone()),
+        None => db.get_any_locked_content()?,
+    };
+    let mut unlocker = Unlocker::new(passphrase)?;
+    if let Some(ref sample) = sample {
+        unlocker.unlock(sample)?;
+    }
+    if check.is_none()
+        && let Ok(check) = unlocker.lock("tnj")
+    {
+        db.set_setting(CHECK_SETTING, &check)?;
+    }
+    Ok(unlocker)
+}
+
+/// Split locked content into its salt and the nonce followed by the ciphertext
+fn parse(text: &str) -> Result<([u8; SALT_LEN], Vec<u8>), LockError> {
+    let (salt, sealed) = text
+        .strip_prefix(LOCKED_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or(LockError::Malformed)?;
+    let salt: [u8; SALT_LEN] = BASE64
+        .decode(salt)
+        .ok()
+        .and_then(|salt| salt.try_into().ok())
+        .ok_or(LockError::Malformed)?;
+    let sealed = BASE64.decode(sealed).map_err(|_| LockError::Malformed)?;
+    if sealed.len() < NONCE_LEN {
+        return Err(LockError::Malformed);
+    }
+    Ok((salt, sealed))
+}
+
+/// Derive the key for a salt from the passphrase (Argon2id with default parameters)
+fn derive_key(passphrase: &str, salt: &[u8; SALT_LEN]) -> Result<Key, LockError> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| LockError::KeyDerivation(e.to_string()))?;
+    Ok(key)
+}
+
+/// Locks and unlocks content with a passphrase, keeping the keys it derived
+///
+/// Every locked text carries the salt its key was derived with, so texts locked in earlier
+/// sessions (or restored from a backup) open with the same passphrase. New texts are locked
+/// with the key of this session's salt.
+pub struct Unlocker {
+    passphrase: String,
+    salt: [u8; SALT_LEN],
+    keys: HashMap<[u8; SALT_LEN], Key>,
+}
+
+impl std::fmt::Debug for Unlocker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unlocker { .. }")
+    }
+}
+
+impl Unlocker {
+    pub fn new(passphrase: &str) -> Result<Self, LockError> {
+        let mut salt = [0u8; SALT_LEN];
+        salt.copy_from_slice(&XChaCha20Poly1305::generate_nonce(&mut OsRng)[..SALT_LEN]);
+        let key = derive_key(passphrase, &salt)?;
+        Ok(Self {
+            passphrase: passphrase.to_string(),
+            salt,
+            keys: HashMap::from([(salt, key)]),
+        })
+    }
+
+    fn key(&mut self, salt: &[u8; SALT_LEN]) -> Result<Key, LockError> {
+        if let Some(key) = self.keys.get(salt) {
+            return Ok(*key);
+        }
+        let key = derive_key(&self.passphrase, salt)?;
+        self.keys.insert(*salt, key);
+        Ok(key)
+    }
+
+    /// Encrypt a text into locked content
+    pub fn lock(&mut self, plaintext: &str) -> Result<String, LockError> {
+        let salt = self.salt;
+        let cipher = XChaCha20Poly1305::new(&self.key(&salt)?);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher.encrypt(&nonce, plaintext.as_bytes()).map_err(|_| LockError::Malformed)?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}:{}", LOCKED_PREFIX, BASE64.encode(salt), BASE64.encode(sealed)))
+    }
+
+    /// Decrypt locked content; fails with `WrongPassphrase` if it was locked with another passphrase
+    pub fn unlock(&mut self, text: &str) -> Result<String, LockError> {
+        let (salt, sealed) = parse(text)?;
+        let cipher = XChaCha20Poly1305::new(&self.key(&salt)?);
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| LockError::WrongPassphrase)?;
+        String::from_utf8(plaintext).map_err(|_| LockError::Malformed)
+    }
+}
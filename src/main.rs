@@ -1,6 +1,6 @@
 use color_eyre::Result;
 use clap::Parser;
-use tnj_tui::{Config, Database, Profile, cli::{Cli, Commands}};
+use tnj_tui::{Config, Database, Profile, Store, cli::{Cli, Commands}, git_store::Storage};
 
 fn main() -> Result<()> {
     // Set up error reporting with color-eyre
@@ -19,21 +19,66 @@ fn main() -> Result<()> {
     // Load configuration with the determined profile
     // Note: --config option is parsed but not yet used to override config path
     // This can be enhanced in the future if needed
-    let config = Config::load_with_profile(profile)?;
+    let mut config = Config::load_with_profile(profile)?;
 
-    // Initialize database
+    // Default to Tui if no command is provided
+    let command = cli.command.unwrap_or(Commands::Tui);
+
+    // Initialize database, asking for the passphrase if it is encrypted
     let db_path = config.get_database_path();
-    let db = Database::new(
-        db_path.to_str()
-            .ok_or_else(|| color_eyre::eyre::eyre!("Database path contains invalid UTF-8"))?
-    )?;
+    let db_path_str = db_path.to_str()
+        .ok_or_else(|| color_eyre::eyre::eyre!("Database path contains invalid UTF-8"))?;
+    #[cfg(feature = "encryption")]
+    let db = if Database::is_encrypted_file(&db_path) {
+        match command {
+            Commands::Encrypt => return Err(color_eyre::eyre::eyre!("The database is already encrypted")),
+            Commands::Tui if std::env::var(tnj_tui::cli::PASSPHRASE_ENV).is_err() => {
+                match tnj_tui::tui::unlock_database(db_path_str, &config)? {
+                    Some(db) => db,
+                    None => return Ok(()),
+                }
+            }
+            _ => Database::open(db_path_str, Some(&tnj_tui::cli::read_passphrase()?))?,
+        }
+    } else {
+        Database::new(db_path_str)?
+    };
+    #[cfg(not(feature = "encryption"))]
+    let db = Database::new(db_path_str)?;
+
+    // Snapshot the database on startup or once a day (before anything is purged); a failed
+    // backup must not keep tnj from starting. Encrypting or decrypting is not snapshotted, so no
+    // unencrypted copy is taken just before the database is encrypted.
+    let converting = matches!(command, Commands::Encrypt | Commands::Decrypt);
+    if !matches!(command, Commands::Backups { .. })
+        && !converting
+        && let Err(e) = tnj_tui::backups::auto_backup(&db, config.get_auto_backup(), config.backup_count)
+    {
+        eprintln!("Warning: automatic backup failed: {}", e);
+    }
+
+    // In git storage the files are canonical: pick up commits made outside tnj (e.g. a plain `git pull`)
+    // before anything is written over them
+    let git_storage = config.get_storage() == Storage::Git;
+    let git_command = matches!(command, Commands::Git { .. });
+    if git_storage
+        && !git_command
+        && let Err(e) = tnj_tui::git_store::refresh(&db, &config.get_git_dir())
+    {
+        eprintln!("Warning: could not read the git repository: {}", e);
+    }
+
+    // Purge items that have been in the trash longer than the retention period
+    if config.trash_retention_days > 0 {
+        db.purge_trash_older_than(config.trash_retention_days)?;
+    }
 
     // Dispatch to appropriate command handler
-    // Default to Tui if no command is provided
-    match cli.command.unwrap_or(Commands::Tui) {
+    match command {
         Commands::Tui => {
-            let app = tnj_tui::tui::App::new(config, db)?;
+            let app = tnj_tui::tui::App::new(config, Box::new(db))?;
             tnj_tui::tui::run_event_loop(app)?;
+            return Ok(());
         }
         Commands::AddTask { title, due, tags } => {
             tnj_tui::cli::handle_add_task(title, due, tags, &db)?;
@@ -44,6 +89,59 @@ fn main() -> Result<()> {
         Commands::AddJournal { content, title, tags } => {
             tnj_tui::cli::handle_add_journal(content, title, tags, &db)?;
         }
+        Commands::Move { kind, ids, notebook, no_notebook: _ } => {
+            tnj_tui::cli::handle_move(kind, ids, notebook, &db)?;
+        }
+        Commands::Export { format } => {
+            tnj_tui::cli::handle_export(format, &db, &config)?;
+        }
+        Commands::Import { format } => {
+            tnj_tui::cli::handle_import(format, &db)?;
+        }
+        Commands::Attach { kind, id, file } => {
+            tnj_tui::cli::handle_attach(kind, id, file, &db, &config)?;
+        }
+        Commands::Detach { attachment_id } => {
+            tnj_tui::cli::handle_detach(attachment_id, &db)?;
+        }
+        Commands::Attachments { kind, id } => {
+            tnj_tui::cli::handle_attachments(kind, id, &db)?;
+        }
+        Commands::OpenAttachment { attachment_id, output } => {
+            tnj_tui::cli::handle_open_attachment(attachment_id, output, &db)?;
+        }
+        Commands::Backup { json } => {
+            tnj_tui::cli::handle_backup(json, &db)?;
+        }
+        Commands::Restore { file, mode } => {
+            tnj_tui::cli::handle_restore(file, mode, &db, &config)?;
+        }
+        Commands::Encrypt => {
+            tnj_tui::cli::handle_encrypt(db)?;
+            return Ok(());
+        }
+        Commands::Decrypt => {
+            tnj_tui::cli::handle_decrypt(db)?;
+            return Ok(());
+        }
+        Commands::Backups { action } => {
+            tnj_tui::cli::handle_backups(action, db, &config)?;
+            return Ok(());
+        }
+        Commands::Sync { dir } => {
+            tnj_tui::cli::handle_sync(dir, &db, &config)?;
+        }
+        Commands::Serve { port, token } => {
+            tnj_tui::cli::handle_serve(port, token, &db, &config)?;
+        }
+        Commands::Git { action } => {
+            tnj_tui::cli::handle_git(action, &db, &mut config, profile)?;
+        }
+    }
+
+    // Commit what the command changed (the TUI and the git commands commit themselves)
+    if git_storage && !git_command && db.total_changes() > 0 {
+        tnj_tui::git_store::save(&db, &config.get_git_dir())?;
     }
 
     Ok(())
@@ -1,8 +1,60 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The kind of item stored in the database (tasks, notes or journal entries)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Task,
+    Note,
+    Journal,
+}
+
+impl ItemKind {
+    /// Human-readable label used in status and CLI messages
+    pub fn label(&self, count: usize) -> &'static str {
+        match (self, count == 1) {
+            (ItemKind::Task, true) => "task",
+            (ItemKind::Task, false) => "tasks",
+            (ItemKind::Note, true) => "note",
+            (ItemKind::Note, false) => "notes",
+            (ItemKind::Journal, true) => "journal entry",
+            (ItemKind::Journal, false) => "journal entries",
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ItemKind::Task => "task",
+            ItemKind::Note => "note",
+            ItemKind::Journal => "journal",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ItemKind {
+    type Err = String;
+
+    /// Parse an item kind, accepting singular and plural forms (e.g. "task" or "tasks")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "task" | "tasks" => Ok(ItemKind::Task),
+            "note" | "notes" => Ok(ItemKind::Note),
+            "journal" | "journals" => Ok(ItemKind::Journal),
+            other => Err(format!("Unknown item kind '{}' (expected task, note or journal)", other)),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: Option<i64>,
+    #[serde(default)]
+    pub uuid: Option<String>, // Globally unique ID, assigned when the row is inserted
     pub title: String,
     pub description: Option<String>,
     pub due_date: Option<String>, // ISO 8601: YYYY-MM-DD
@@ -13,11 +65,15 @@ pub struct Task {
     pub notebook_id: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub deleted_at: Option<String>, // Set when the item is in the trash
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Note {
     pub id: Option<i64>,
+    #[serde(default)]
+    pub uuid: Option<String>, // Globally unique ID, assigned when the row is inserted
     pub title: String,
     pub content: Option<String>,
     pub tags: Option<String>,
@@ -25,11 +81,15 @@ pub struct Note {
     pub notebook_id: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub deleted_at: Option<String>, // Set when the item is in the trash
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JournalEntry {
     pub id: Option<i64>,
+    #[serde(default)]
+    pub uuid: Option<String>, // Globally unique ID, assigned when the row is inserted
     pub date: String, // YYYY-MM-DD
     pub title: Option<String>,
     pub content: Option<String>,
@@ -38,6 +98,8 @@ pub struct JournalEntry {
     pub notebook_id: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub deleted_at: Option<String>, // Set when the item is in the trash
 }
 
 impl Task {
@@ -45,6 +107,7 @@ impl Task {
         let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
         Self {
             id: None,
+            uuid: None,
             title,
             description: None,
             due_date: None,
@@ -55,8 +118,17 @@ impl Task {
             notebook_id: None,
             created_at: now.clone(),
             updated_at: now,
+            deleted_at: None,
         }
     }
+
+    /// Check whether the title, description or tags contain the query (case-insensitive)
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [Some(self.title.as_str()), self.description.as_deref(), self.tags.as_deref()]
+            .iter()
+            .any(|field| contains_lowercase(*field, &query))
+    }
 }
 
 impl Note {
@@ -64,6 +136,7 @@ impl Note {
         let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
         Self {
             id: None,
+            uuid: None,
             title,
             content: None,
             tags: None,
@@ -71,8 +144,17 @@ impl Note {
             notebook_id: None,
             created_at: now.clone(),
             updated_at: now,
+            deleted_at: None,
         }
     }
+
+    /// Check whether the title, content or tags contain the query (case-insensitive)
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [Some(self.title.as_str()), self.content.as_deref(), self.tags.as_deref()]
+            .iter()
+            .any(|field| contains_lowercase(*field, &query))
+    }
 }
 
 impl JournalEntry {
@@ -80,6 +162,7 @@ impl JournalEntry {
         let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
         Self {
             id: None,
+            uuid: None,
             date,
             title: None,
             content: None,
@@ -88,16 +171,39 @@ impl JournalEntry {
             notebook_id: None,
             created_at: now.clone(),
             updated_at: now,
+            deleted_at: None,
         }
     }
+
+    /// Check whether the entry's content is locked (encrypted with the passphrase of locked entries)
+    pub fn is_locked(&self) -> bool {
+        self.content.as_deref().is_some_and(crate::lock::is_locked)
+    }
+
+    /// Check whether the date, title, content or tags contain the query (case-insensitive)
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [Some(self.date.as_str()), self.title.as_deref(), self.content.as_deref(), self.tags.as_deref()]
+            .iter()
+            .any(|field| contains_lowercase(*field, &query))
+    }
+}
+
+/// Check whether an optional text field contains an already lowercased query
+fn contains_lowercase(field: Option<&str>, query: &str) -> bool {
+    field.is_some_and(|text| text.to_lowercase().contains(query))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notebook {
     pub id: Option<i64>,
+    #[serde(default)]
+    pub uuid: Option<String>, // Globally unique ID, assigned when the row is inserted
     pub name: String,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub locked: bool, // Journal entries in a locked notebook are locked
 }
 
 impl Notebook {
@@ -105,10 +211,163 @@ impl Notebook {
         let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
         Self {
             id: None,
+            uuid: None,
             name,
             created_at: now.clone(),
             updated_at: now,
+            locked: false,
+        }
+    }
+}
+
+/// What a UUID identifies (and a sync record describes): a notebook or one of the three kinds of items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncKind {
+    Notebook,
+    Task,
+    Note,
+    Journal,
+}
+
+impl SyncKind {
+    /// Notebooks first, since items refer to them
+    pub const ALL: [SyncKind; 4] = [SyncKind::Notebook, SyncKind::Task, SyncKind::Note, SyncKind::Journal];
+
+    /// Get the table rows of this kind are stored in
+    pub fn table(&self) -> &'static str {
+        match self {
+            SyncKind::Notebook => "notebooks",
+            SyncKind::Task => "tasks",
+            SyncKind::Note => "notes",
+            SyncKind::Journal => "journals",
+        }
+    }
+
+    /// Get the item kind (None for notebooks)
+    pub fn item_kind(&self) -> Option<ItemKind> {
+        match self {
+            SyncKind::Notebook => None,
+            SyncKind::Task => Some(ItemKind::Task),
+            SyncKind::Note => Some(ItemKind::Note),
+            SyncKind::Journal => Some(ItemKind::Journal),
+        }
+    }
+}
+
+impl From<ItemKind> for SyncKind {
+    fn from(kind: ItemKind) -> Self {
+        match kind {
+            ItemKind::Task => SyncKind::Task,
+            ItemKind::Note => SyncKind::Note,
+            ItemKind::Journal => SyncKind::Journal,
+        }
+    }
+}
+
+impl fmt::Display for SyncKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.item_kind() {
+            Some(kind) => write!(f, "{}", kind),
+            None => write!(f, "notebook"),
+        }
+    }
+}
+
+impl FromStr for SyncKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "notebook" | "notebooks" => Ok(SyncKind::Notebook),
+            other => match other.parse::<ItemKind>() {
+                Ok(ItemKind::Task) => Ok(SyncKind::Task),
+                Ok(ItemKind::Note) => Ok(SyncKind::Note),
+                Ok(ItemKind::Journal) => Ok(SyncKind::Journal),
+                Err(_) => Err(format!("Unknown sync kind '{}' (expected notebook, task, note or journal)", other)),
+            },
         }
     }
 }
 
+/// The synced version of a notebook or item
+/// `versions` counts the changes seen from each device (a version vector); `clock` and `device`
+/// name the change the item is at. A `hash` of None means the item was deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncState {
+    pub uuid: String,
+    pub kind: SyncKind,
+    pub clock: i64,
+    pub device: String,
+    pub versions: std::collections::BTreeMap<String, i64>,
+    pub hash: Option<String>,
+}
+
+/// A version of an item that lost to a concurrent change from another device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub id: Option<i64>,
+    pub kind: SyncKind,
+    pub uuid: String,
+    pub title: String,                 // Title (or notebook name) when the conflict was found
+    pub other_version: Option<String>, // The version that was not kept, as synced JSON; None if it was a deletion
+    pub other_device: String,          // Device whose version was kept instead
+    pub created_at: String,
+}
+
+/// A saved earlier version of a note or journal entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Revision {
+    pub id: Option<i64>,
+    pub item_kind: ItemKind, // note or journal
+    pub item_id: i64,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub saved_at: String, // When this version was saved (the item's updated_at at the time)
+}
+
+/// Where the contents of attached files are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AttachmentStorage {
+    #[default]
+    Database,  // As blobs inside the SQLite database
+    Directory, // As files in an `attachments` directory next to the database
+}
+
+impl FromStr for AttachmentStorage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "database" | "db" => Ok(AttachmentStorage::Database),
+            "directory" | "dir" => Ok(AttachmentStorage::Directory),
+            other => Err(format!("Unknown attachment storage '{}' (expected database or directory)", other)),
+        }
+    }
+}
+
+/// A file attached to a task, note or journal entry (the file contents are read separately)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: Option<i64>,
+    pub item_kind: ItemKind,
+    pub item_id: i64,
+    pub file_name: String,
+    pub size: i64,                   // In bytes
+    pub stored_file: Option<String>, // File in the attachments directory; None when stored in the database
+    pub created_at: String,
+}
+
+impl Attachment {
+    pub fn new(item_kind: ItemKind, item_id: i64, file_name: String) -> Self {
+        Self {
+            id: None,
+            item_kind,
+            item_id,
+            file_name,
+            size: 0,
+            stored_file: None,
+            created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
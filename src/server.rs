@@ -0,0 +1,717 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use thiserror::Error;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::database::DatabaseError;
+use crate::models::{ItemKind, JournalEntry, Note, Notebook, SyncKind, Task};
+use crate::store::{Backend, Store};
+
+/// Largest request body accepted, in bytes
+const MAX_BODY_SIZE: u64 = 1024 * 1024;
+/// Fields of each kind of item that requests may set
+const TASK_FIELDS: &[&str] = &["title", "description", "due_date", "status", "tags", "order", "archived", "notebook_id"];
+const NOTE_FIELDS: &[&str] = &["title", "content", "tags", "archived", "notebook_id"];
+const JOURNAL_FIELDS: &[&str] = &["date", "title", "content", "tags", "archived", "notebook_id"];
+
+#[derive(Debug, Error)]
+pub enum ServerError {
+    #[error("Failed to listen on 127.0.0.1:{0}: {1}")]
+    BindError(u16, String),
+}
+
+/// How `serve` listens and what it does after changes
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    pub port: u16,
+    pub token: Option<String>,    // Required as `Authorization: Bearer <token>` when set
+    pub git_dir: Option<PathBuf>, // Repository to commit changes to, in git storage
+}
+
+/// An error answered to the client, as `{"error": message}` with the HTTP status
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(400, message)
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self::new(404, message)
+    }
+
+    fn conflict(message: impl Into<String>) -> Self {
+        Self::new(409, message)
+    }
+}
+
+impl From<DatabaseError> for ApiError {
+    fn from(e: DatabaseError) -> Self {
+        match e {
+            DatabaseError::ItemNotFound(what) => Self::not_found(format!("Not found: {}", what)),
+            DatabaseError::SqliteError(rusqlite::Error::QueryReturnedNoRows) => Self::not_found("Not found"),
+            e => Self::new(500, e.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::bad_request(format!("Invalid JSON: {}", e))
+    }
+}
+
+/// A successful answer: the HTTP status and the JSON body
+type ApiResult = Result<(u16, Value), ApiError>;
+
+/// Serve the JSON API on 127.0.0.1 until the process is stopped
+///
+/// Requests are handled one at a time. In git storage each change is committed, like the TUI does.
+pub fn serve(db: &dyn Backend, options: &ServerOptions) -> Result<(), ServerError> {
+    let server = Server::http(("127.0.0.1", options.port))
+        .map_err(|e| ServerError::BindError(options.port, e.to_string()))?;
+    let mut committed_changes = db.total_changes();
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match handle(db, options, &mut request) {
+            Ok(answer) => answer,
+            Err(e) => (e.status, json!({ "error": e.message })),
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(json_header());
+        if let Err(e) = request.respond(response) {
+            eprintln!("Warning: failed to answer a request: {}", e);
+        }
+
+        if let Some(ref dir) = options.git_dir
+            && db.total_changes() != committed_changes
+        {
+            if let Err(e) = crate::git_store::save(db, dir) {
+                eprintln!("Warning: failed to commit to git: {}", e);
+            }
+            // Saving records the new commit in the database, which is not a change to commit
+            committed_changes = db.total_changes();
+        }
+    }
+
+    Ok(())
+}
+
+fn json_header() -> Header {
+    Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("valid header")
+}
+
+/// Get the value of a request header
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request.headers().iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+/// Check that the request was addressed to this machine by name or loopback address
+/// (a web page that rebinds its own host name to 127.0.0.1 sends its own host name)
+fn is_local_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    matches!(name.to_ascii_lowercase().as_str(), "localhost" | "127.0.0.1" | "::1")
+}
+
+/// Check the request, then route it
+fn handle(db: &dyn Store, options: &ServerOptions, request: &mut Request) -> ApiResult {
+    if !header(request, "Host").is_some_and(is_local_host) {
+        return Err(ApiError::new(403, "Requests must be addressed to localhost"));
+    }
+    if let Some(ref token) = options.token
+        && header(request, "Authorization") != Some(format!("Bearer {}", token).as_str())
+    {
+        return Err(ApiError::new(401, "Missing or wrong API token"));
+    }
+
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+    let query = parse_query(query);
+    let segments: Vec<String> = path.split('/').filter(|s| !s.is_empty()).map(percent_decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    let body = if matches!(method, Method::Post | Method::Put | Method::Patch) {
+        read_body(request)?
+    } else {
+        Map::new()
+    };
+
+    route(db, &method, &segments, &query, body)
+}
+
+/// Read the JSON object sent with the request
+/// Bodies must be sent as `application/json`, which browsers do not send to other sites without asking
+fn read_body(request: &mut Request) -> Result<Map<String, Value>, ApiError> {
+    if !header(request, "Content-Type").is_some_and(|value| value.trim().starts_with("application/json")) {
+        return Err(ApiError::new(415, "The request body must be JSON (Content-Type: application/json)"));
+    }
+    let mut text = String::new();
+    request.as_reader().take(MAX_BODY_SIZE + 1).read_to_string(&mut text)
+        .map_err(|e| ApiError::bad_request(format!("Failed to read the request body: {}", e)))?;
+    if text.len() as u64 > MAX_BODY_SIZE {
+        return Err(ApiError::new(413, "The request body is too large"));
+    }
+    match serde_json::from_str(&text)? {
+        Value::Object(body) => Ok(body),
+        _ => Err(ApiError::bad_request("The request body must be a JSON object")),
+    }
+}
+
+fn route(db: &dyn Store, method: &Method, segments: &[&str], query: &HashMap<String, String>, body: Map<String, Value>) -> ApiResult {
+    let kind = segments.first().and_then(|segment| match *segment {
+        "tasks" => Some(ItemKind::Task),
+        "notes" => Some(ItemKind::Note),
+        "journals" => Some(ItemKind::Journal),
+        _ => None,
+    });
+
+    if let Some(kind) = kind {
+        return match (method, segments) {
+            (Method::Get, [_]) => list_items(db, kind, query),
+            (Method::Post, [_]) => create_item(db, kind, body),
+            (Method::Get, [_, id]) => get_item(db, kind, resolve_item_id(db, kind, id)?),
+            (Method::Put | Method::Patch, [_, id]) => update_item(db, kind, resolve_item_id(db, kind, id)?, body),
+            (Method::Delete, [_, id]) => delete_item(db, kind, resolve_item_id(db, kind, id)?),
+            (_, [_] | [_, _]) => Err(method_not_allowed()),
+            _ => Err(ApiError::not_found("No such endpoint")),
+        };
+    }
+
+    match (method, segments) {
+        (Method::Get, []) => Ok((200, json!({ "name": "tnj", "version": env!("CARGO_PKG_VERSION") }))),
+        (Method::Get, ["search"]) => search(db, query),
+        (Method::Get, ["notebooks"]) => Ok((200, to_json(&db.get_all_notebooks()?)?)),
+        (Method::Post, ["notebooks"]) => create_notebook(db, body),
+        (Method::Get, ["notebooks", id]) => Ok((200, to_json(&db.get_notebook(resolve_notebook_id(db, id)?)?)?)),
+        (Method::Put | Method::Patch, ["notebooks", id]) => update_notebook(db, resolve_notebook_id(db, id)?, body),
+        (Method::Delete, ["notebooks", id]) => delete_notebook(db, resolve_notebook_id(db, id)?),
+        (_, [] | ["search" | "notebooks"] | ["notebooks", _]) => Err(method_not_allowed()),
+        _ => Err(ApiError::not_found("No such endpoint")),
+    }
+}
+
+fn method_not_allowed() -> ApiError {
+    ApiError::new(405, "Method not allowed")
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Value, ApiError> {
+    serde_json::to_value(value).map_err(|e| ApiError::new(500, e.to_string()))
+}
+
+/// Get the JSON of a journal entry; the content of a locked entry is left out, since it is encrypted
+fn journal_json(journal: &JournalEntry) -> Result<Value, ApiError> {
+    let mut value = to_json(journal)?;
+    value["locked"] = Value::Bool(journal.is_locked());
+    if journal.is_locked() {
+        value["content"] = Value::Null;
+    }
+    Ok(value)
+}
+
+/// Split a query string into its decoded parameters (`+` is a space in query strings only)
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(&key.replace('+', " ")), percent_decode(&value.replace('+', " ")))
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes in a URL component
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Get the ID of an item given by its numeric ID or its UUID
+fn resolve_item_id(db: &dyn Store, kind: ItemKind, id: &str) -> Result<i64, ApiError> {
+    if let Ok(id) = id.parse::<i64>() {
+        return Ok(id);
+    }
+    db.find_by_uuid(kind.into(), &id.to_lowercase())?
+        .ok_or_else(|| ApiError::not_found(format!("No {} with UUID {}", kind, id)))
+}
+
+/// Get the ID of a notebook given by its numeric ID or its UUID
+fn resolve_notebook_id(db: &dyn Store, id: &str) -> Result<i64, ApiError> {
+    if let Ok(id) = id.parse::<i64>() {
+        return Ok(id);
+    }
+    db.find_by_uuid(SyncKind::Notebook, &id.to_lowercase())?
+        .ok_or_else(|| ApiError::not_found(format!("No notebook with UUID {}", id)))
+}
+
+/// Parse a true/false query parameter
+fn parse_bool(name: &str, value: &str) -> Result<bool, ApiError> {
+    match value {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(ApiError::bad_request(format!("'{}' must be true or false", name))),
+    }
+}
+
+/// Filters of a list request, from its query parameters
+struct ListFilter {
+    notebook_id: Option<Option<i64>>, // None lists every notebook; Some(None) the items without one
+    archived: Option<bool>,           // None lists archived and active items
+    tag: Option<String>,
+    query: Option<String>,
+    status: Option<String>,
+}
+
+impl ListFilter {
+    fn from_query(db: &dyn Store, query: &HashMap<String, String>) -> Result<Self, ApiError> {
+        let notebook_id = match query.get("notebook").map(|name| name.trim()) {
+            None => None,
+            Some("") => Some(None),
+            Some(name) => {
+                let notebook = match db.get_notebook_by_name(name)? {
+                    Some(notebook) => notebook,
+                    None => name.parse::<i64>().ok()
+                        .and_then(|id| db.get_notebook(id).ok())
+                        .ok_or_else(|| ApiError::not_found(format!("Notebook not found: {}", name)))?,
+                };
+                Some(notebook.id)
+            }
+        };
+        let archived = match query.get("archived").map(String::as_str) {
+            None => Some(false),
+            Some("all") => None,
+            Some(value) => Some(parse_bool("archived", value)?),
+        };
+        Ok(Self {
+            notebook_id,
+            archived,
+            tag: query.get("tag").map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()),
+            query: query.get("q").cloned().filter(|q| !q.is_empty()),
+            status: query.get("status").cloned(),
+        })
+    }
+
+    /// Check the fields every kind of item has
+    fn matches(&self, notebook_id: Option<i64>, archived: bool, tags: Option<&str>) -> bool {
+        self.notebook_id.is_none_or(|id| id == notebook_id)
+            && self.archived.is_none_or(|wanted| wanted == archived)
+            && self.tag.as_ref().is_none_or(|wanted| {
+                tags.unwrap_or_default().split(',').any(|tag| tag.trim().eq_ignore_ascii_case(wanted))
+            })
+    }
+}
+
+/// List the items of one kind that are not in the trash, in the order the TUI shows them
+fn list_items(db: &dyn Store, kind: ItemKind, query: &HashMap<String, String>) -> ApiResult {
+    let filter = ListFilter::from_query(db, query)?;
+    let text = filter.query.as_deref();
+    let items = match kind {
+        ItemKind::Task => {
+            let mut tasks: Vec<Task> = db.dump_tasks()?.into_iter()
+                .filter(|task| task.deleted_at.is_none()
+                    && filter.matches(task.notebook_id, task.archived, task.tags.as_deref())
+                    && filter.status.as_ref().is_none_or(|status| *status == task.status)
+                    && text.is_none_or(|text| task.matches_search(text)))
+                .collect();
+            tasks.sort_by_key(|task| task.order);
+            to_json(&tasks)?
+        }
+        ItemKind::Note => {
+            let mut notes: Vec<Note> = db.dump_notes()?.into_iter()
+                .filter(|note| note.deleted_at.is_none()
+                    && filter.matches(note.notebook_id, note.archived, note.tags.as_deref())
+                    && text.is_none_or(|text| note.matches_search(text)))
+                .collect();
+            notes.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            to_json(&notes)?
+        }
+        ItemKind::Journal => {
+            let mut journals: Vec<JournalEntry> = db.dump_journals()?.into_iter()
+                .filter(|journal| journal.deleted_at.is_none()
+                    && filter.matches(journal.notebook_id, journal.archived, journal.tags.as_deref())
+                    && text.is_none_or(|text| journal.matches_search(text)))
+                .collect();
+            journals.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| b.created_at.cmp(&a.created_at)));
+            Value::Array(journals.iter().map(journal_json).collect::<Result<_, _>>()?)
+        }
+    };
+    Ok((200, items))
+}
+
+/// Search all items that are not in the trash
+fn search(db: &dyn Store, query: &HashMap<String, String>) -> ApiResult {
+    let text = query.get("q").filter(|q| !q.trim().is_empty())
+        .ok_or_else(|| ApiError::bad_request("Missing search query 'q'"))?;
+    let results = db.search(text)?;
+    Ok((200, json!({
+        "tasks": to_json(&results.tasks)?,
+        "notes": to_json(&results.notes)?,
+        "journals": results.journals.iter().map(journal_json).collect::<Result<Vec<_>, _>>()?,
+    })))
+}
+
+/// Get an item that is not in the trash
+fn get_item(db: &dyn Store, kind: ItemKind, id: i64) -> ApiResult {
+    let (value, deleted) = match kind {
+        ItemKind::Task => {
+            let task = db.get_task(id)?;
+            (to_json(&task)?, task.deleted_at.is_some())
+        }
+        ItemKind::Note => {
+            let note = db.get_note(id)?;
+            (to_json(&note)?, note.deleted_at.is_some())
+        }
+        ItemKind::Journal => {
+            let journal = db.get_journal(id)?;
+            (journal_json(&journal)?, journal.deleted_at.is_some())
+        }
+    };
+    if deleted {
+        return Err(ApiError::not_found(format!("{} {} is in the trash", kind, id)));
+    }
+    Ok((200, value))
+}
+
+/// Set the fields given in the request on an item, checking their names and types
+fn apply_fields<T: Serialize + DeserializeOwned>(item: &T, body: Map<String, Value>, fields: &[&str]) -> Result<T, ApiError> {
+    let mut value = to_json(item)?;
+    for (key, field) in body {
+        if !fields.contains(&key.as_str()) {
+            return Err(ApiError::bad_request(format!("Unknown or read-only field '{}'", key)));
+        }
+        value[key] = field;
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Check fields whose values are restricted, and that the notebook exists
+fn validate(db: &dyn Store, kind: ItemKind, title: Option<&str>, dates: &[Option<&str>], notebook_id: Option<i64>) -> Result<(), ApiError> {
+    if title.is_some_and(|title| title.trim().is_empty()) {
+        return Err(ApiError::bad_request("'title' must not be empty"));
+    }
+    for date in dates.iter().flatten() {
+        crate::utils::parse_date(date)
+            .map_err(|_| ApiError::bad_request(format!("Invalid date '{}' (expected YYYY-MM-DD)", date)))?;
+    }
+    if let Some(id) = notebook_id {
+        let notebook = db.get_notebook(id)
+            .map_err(|_| ApiError::bad_request(format!("Notebook not found: {}", id)))?;
+        if kind == ItemKind::Journal && notebook.locked {
+            return Err(ApiError::conflict("Journal entries of locked notebooks cannot be written through the API"));
+        }
+    }
+    Ok(())
+}
+
+/// Check the status of a task
+fn validate_status(status: &str) -> Result<(), ApiError> {
+    if matches!(status, "todo" | "done") {
+        Ok(())
+    } else {
+        Err(ApiError::bad_request("'status' must be todo or done"))
+    }
+}
+
+/// Create an item from the fields in the request
+fn create_item(db: &dyn Store, kind: ItemKind, body: Map<String, Value>) -> ApiResult {
+    let id = match kind {
+        ItemKind::Task => {
+            let has_order = body.contains_key("order");
+            let mut task = apply_fields(&Task::new(String::new()), body, TASK_FIELDS)?;
+            validate(db, kind, Some(&task.title), &[task.due_date.as_deref()], task.notebook_id)?;
+            validate_status(&task.status)?;
+            if !has_order {
+                task.order = db.get_max_task_order()? + 1;
+            }
+            db.insert_task(&task)?
+        }
+        ItemKind::Note => {
+            let note = apply_fields(&Note::new(String::new()), body, NOTE_FIELDS)?;
+            validate(db, kind, Some(&note.title), &[], note.notebook_id)?;
+            db.insert_note(&note)?
+        }
+        ItemKind::Journal => {
+            let journal = apply_fields(&JournalEntry::new(crate::utils::get_current_date_string()), body, JOURNAL_FIELDS)?;
+            validate(db, kind, None, &[Some(&journal.date)], journal.notebook_id)?;
+            if journal.content.as_deref().is_some_and(crate::lock::is_locked) {
+                return Err(ApiError::bad_request("'content' must not be locked text"));
+            }
+            db.insert_journal(&journal)?
+        }
+    };
+    let (_, item) = get_item(db, kind, id)?;
+    Ok((201, item))
+}
+
+/// Change the fields given in the request of an item that is not in the trash
+fn update_item(db: &dyn Store, kind: ItemKind, id: i64, body: Map<String, Value>) -> ApiResult {
+    get_item(db, kind, id)?;
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    match kind {
+        ItemKind::Task => {
+            let mut task = apply_fields(&db.get_task(id)?, body, TASK_FIELDS)?;
+            validate(db, kind, Some(&task.title), &[task.due_date.as_deref()], task.notebook_id)?;
+            validate_status(&task.status)?;
+            task.updated_at = now;
+            db.update_task(&task)?;
+        }
+        ItemKind::Note => {
+            let mut note = apply_fields(&db.get_note(id)?, body, NOTE_FIELDS)?;
+            validate(db, kind, Some(&note.title), &[], note.notebook_id)?;
+            note.updated_at = now;
+            db.update_note(&note)?;
+        }
+        ItemKind::Journal => {
+            let journal = db.get_journal(id)?;
+            if journal.is_locked() {
+                return Err(ApiError::conflict("Locked journal entries cannot be changed through the API"));
+            }
+            let mut journal = apply_fields(&journal, body, JOURNAL_FIELDS)?;
+            validate(db, kind, None, &[Some(&journal.date)], journal.notebook_id)?;
+            if journal.content.as_deref().is_some_and(crate::lock::is_locked) {
+                return Err(ApiError::bad_request("'content' must not be locked text"));
+            }
+            journal.updated_at = now;
+            db.update_journal(&journal)?;
+        }
+    }
+    get_item(db, kind, id)
+}
+
+/// Move an item to the trash
+fn delete_item(db: &dyn Store, kind: ItemKind, id: i64) -> ApiResult {
+    if db.delete_items(kind, &[id])? == 0 {
+        return Err(ApiError::not_found(format!("{} {} does not exist or is already in the trash", kind, id)));
+    }
+    Ok((200, json!({ "deleted": id })))
+}
+
+/// Get the trimmed, non-empty name of a notebook from a request, checking no other notebook has it
+fn notebook_name(db: &dyn Store, body: &Map<String, Value>, id: Option<i64>) -> Result<String, ApiError> {
+    if let Some(key) = body.keys().find(|key| *key != "name") {
+        return Err(ApiError::bad_request(format!("Unknown or read-only field '{}'", key)));
+    }
+    let name = body.get("name").and_then(Value::as_str).map(str::trim).unwrap_or_default();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("'name' must be a non-empty string"));
+    }
+    if db.get_notebook_by_name(name)?.is_some_and(|notebook| notebook.id != id) {
+        return Err(ApiError::conflict(format!("A notebook named '{}' already exists", name)));
+    }
+    Ok(name.to_string())
+}
+
+fn create_notebook(db: &dyn Store, body: Map<String, Value>) -> ApiResult {
+    let name = notebook_name(db, &body, None)?;
+    let id = db.insert_notebook(&Notebook::new(name))?;
+    Ok((201, to_json(&db.get_notebook(id)?)?))
+}
+
+fn update_notebook(db: &dyn Store, id: i64, body: Map<String, Value>) -> ApiResult {
+    let mut notebook = db.get_notebook(id)?;
+    notebook.name = notebook_name(db, &body, Some(id))?;
+    notebook.updated_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    db.update_notebook(&notebook)?;
+    Ok((200, to_json(&db.get_notebook(id)?)?))
+}
+
+/// Delete a notebook; its items are kept without a notebook
+fn delete_notebook(db: &dyn Store, id: i64) -> ApiResult {
+    let notebook = db.get_notebook(id)?;
+    if notebook.locked {
+        return Err(ApiError::conflict("Locked notebooks cannot be deleted through the API"));
+    }
+    db.delete_notebook(id)?;
+    Ok((200, json!({ "deleted": id })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::store::MemoryStore;
+    use tiny_http::TestRequest;
+
+    /// Run a check against both backends
+    fn on_each_store(check: impl Fn(&dyn Store)) {
+        check(&MemoryStore::new());
+        check(&Database::new(":memory:").unwrap());
+    }
+
+    fn options(token: Option<&str>) -> ServerOptions {
+        ServerOptions { port: 0, token: token.map(String::from), git_dir: None }
+    }
+
+    /// Send a request to `handle` with the given headers, returning the status and the JSON answered
+    fn send(db: &dyn Store, options: &ServerOptions, method: Method, path: &str, headers: &[(&str, &str)], body: &'static str) -> (u16, Value) {
+        let mut request = TestRequest::new().with_method(method).with_path(path).with_body(body);
+        for (name, value) in headers {
+            request = request.with_header(Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap());
+        }
+        match handle(db, options, &mut request.into()) {
+            Ok(answer) => answer,
+            Err(e) => (e.status, json!({ "error": e.message })),
+        }
+    }
+
+    /// Send a request from localhost without a token; bodies are sent as JSON
+    fn call(db: &dyn Store, method: Method, path: &str, body: &'static str) -> (u16, Value) {
+        let headers = [("Host", "localhost:8080"), ("Content-Type", "application/json")];
+        send(db, &options(None), method, path, &headers, body)
+    }
+
+    #[test]
+    fn requests_must_be_local_and_carry_the_token() {
+        on_each_store(|db| {
+            let open = options(None);
+            assert_eq!(send(db, &open, Method::Get, "/", &[], "").0, 403);
+            assert_eq!(send(db, &open, Method::Get, "/", &[("Host", "evil.example:8080")], "").0, 403);
+            assert_eq!(send(db, &open, Method::Get, "/", &[("Host", "localhost.evil.example")], "").0, 403);
+            assert_eq!(send(db, &open, Method::Get, "/", &[("Host", "127.0.0.1:8080")], "").0, 200);
+            assert_eq!(send(db, &open, Method::Get, "/", &[("Host", "[::1]:8080")], "").0, 200);
+
+            let locked = options(Some("secret"));
+            let host = ("Host", "localhost");
+            assert_eq!(send(db, &locked, Method::Get, "/tasks", &[host], "").0, 401);
+            assert_eq!(send(db, &locked, Method::Get, "/tasks", &[host, ("Authorization", "Bearer wrong")], "").0, 401);
+            assert_eq!(send(db, &locked, Method::Get, "/tasks", &[host, ("Authorization", "Bearer secret")], "").0, 200);
+        });
+    }
+
+    #[test]
+    fn bodies_must_be_small_json_objects_with_known_fields() {
+        on_each_store(|db| {
+            let host = ("Host", "localhost");
+            let body = r#"{"title": "Write report"}"#;
+            assert_eq!(send(db, &options(None), Method::Post, "/tasks", &[host], body).0, 415);
+            assert_eq!(send(db, &options(None), Method::Post, "/tasks", &[host, ("Content-Type", "text/plain")], body).0, 415);
+            let large: &'static str = Box::leak(format!(r#"{{"title": "{}"}}"#, "x".repeat(MAX_BODY_SIZE as usize)).into_boxed_str());
+            assert_eq!(call(db, Method::Post, "/tasks", large).0, 413);
+            assert_eq!(call(db, Method::Post, "/tasks", "[]").0, 400);
+            assert_eq!(call(db, Method::Post, "/tasks", "{").0, 400);
+            assert_eq!(call(db, Method::Post, "/tasks", r#"{"title": "x", "id": 7}"#).0, 400);
+            assert_eq!(call(db, Method::Post, "/tasks", r#"{"title": " "}"#).0, 400);
+            assert_eq!(call(db, Method::Post, "/tasks", r#"{"title": "x", "status": "later"}"#).0, 400);
+            assert_eq!(call(db, Method::Post, "/tasks", r#"{"title": "x", "due_date": "tomorrow"}"#).0, 400);
+
+            let (status, task) = call(db, Method::Post, "/tasks", body);
+            assert_eq!((status, task["title"].as_str()), (201, Some("Write report")));
+            let path = format!("/tasks/{}", task["uuid"].as_str().unwrap());
+            assert_eq!(send(db, &options(None), Method::Get, &path, &[host], "").0, 200);
+        });
+    }
+
+    #[test]
+    fn lists_are_filtered_by_notebook_tag_status_and_text() {
+        on_each_store(|db| {
+            let work = db.insert_notebook(&Notebook::new("My Work".to_string())).unwrap();
+            db.insert_notebook(&Notebook::new("C++".to_string())).unwrap();
+            let mut task = Task::new("Write report".to_string());
+            task.notebook_id = Some(work);
+            task.tags = Some("urgent, office".to_string());
+            db.insert_task(&task).unwrap();
+            let mut done = Task::new("Call the bank".to_string());
+            done.status = "done".to_string();
+            db.insert_task(&done).unwrap();
+            let mut archived = Task::new("Old report".to_string());
+            archived.archived = true;
+            db.insert_task(&archived).unwrap();
+            let trashed = db.insert_task(&Task::new("Trashed report".to_string())).unwrap();
+            db.delete_task(trashed).unwrap();
+
+            let titles = |path: &str| -> Vec<String> {
+                let (status, items) = call(db, Method::Get, path, "");
+                assert_eq!(status, 200, "{}", path);
+                items.as_array().unwrap().iter().map(|t| t["title"].as_str().unwrap().to_string()).collect()
+            };
+            assert_eq!(titles("/tasks"), ["Write report", "Call the bank"]);
+            assert_eq!(titles("/tasks?notebook=My+Work"), ["Write report"]);
+            assert_eq!(titles("/tasks?notebook=My%20Work&tag=URGENT"), ["Write report"]);
+            assert_eq!(titles("/tasks?notebook="), ["Call the bank"]);
+            assert!(titles("/tasks?notebook=C%2B%2B").is_empty());
+            assert_eq!(titles("/tasks?status=done"), ["Call the bank"]);
+            assert_eq!(titles("/tasks?q=report&archived=all"), ["Write report", "Old report"]);
+            assert_eq!(titles("/tasks?archived=true"), ["Old report"]);
+            assert_eq!(call(db, Method::Get, "/tasks?archived=maybe", "").0, 400);
+            assert_eq!(call(db, Method::Get, "/tasks?notebook=Nowhere", "").0, 404);
+            assert_eq!(call(db, Method::Get, &format!("/tasks/{}", trashed), "").0, 404);
+        });
+    }
+
+    #[test]
+    fn locked_journal_entries_are_not_read_or_written() {
+        on_each_store(|db| {
+            let mut notebook = Notebook::new("Diary".to_string());
+            notebook.locked = true;
+            let diary = db.insert_notebook(&notebook).unwrap();
+            let mut journal = JournalEntry::new("2024-03-05".to_string());
+            journal.content = Some(format!("{}c2FsdA==:c2VhbGVk", crate::lock::LOCKED_PREFIX));
+            journal.notebook_id = Some(diary);
+            let id = db.insert_journal(&journal).unwrap();
+
+            let (status, entry) = call(db, Method::Get, &format!("/journals/{}", id), "");
+            assert_eq!((status, entry["locked"].as_bool()), (200, Some(true)));
+            assert!(entry["content"].is_null());
+            assert_eq!(call(db, Method::Put, &format!("/journals/{}", id), r#"{"title": "Day"}"#).0, 409);
+            let body = Box::leak(format!(r#"{{"date": "2024-03-06", "notebook_id": {}}}"#, diary).into_boxed_str());
+            assert_eq!(call(db, Method::Post, "/journals", body).0, 409);
+            assert_eq!(call(db, Method::Post, "/journals", r#"{"content": "tnj-locked:v1:x:y"}"#).0, 400);
+            assert_eq!(call(db, Method::Delete, &format!("/notebooks/{}", diary), "").0, 409);
+        });
+    }
+
+    #[test]
+    fn unknown_paths_and_methods_are_refused() {
+        on_each_store(|db| {
+            assert_eq!(call(db, Method::Get, "/nothing", "").0, 404);
+            assert_eq!(call(db, Method::Get, "/tasks/1/extra", "").0, 404);
+            assert_eq!(call(db, Method::Delete, "/tasks", "").0, 405);
+            assert_eq!(call(db, Method::Post, "/search", "{}").0, 405);
+            assert_eq!(call(db, Method::Put, "/", "{}").0, 405);
+            assert_eq!(call(db, Method::Get, "/search", "").0, 400);
+            assert_eq!(call(db, Method::Get, "/tasks/42", "").0, 404);
+            // `+` in a path is not a space
+            let (status, error) = call(db, Method::Get, "/notebooks/C++", "");
+            assert_eq!(status, 404);
+            assert!(error["error"].as_str().unwrap().contains("C++"));
+        });
+    }
+}
@@ -0,0 +1,995 @@
+use std::cell::{Cell, RefCell, RefMut};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::database::DatabaseError;
+use crate::links::{parse_links, LinkTarget};
+use crate::models::{Attachment, AttachmentStorage, ItemKind, JournalEntry, Note, Notebook, Revision, SyncConflict, SyncKind, SyncState, Task};
+use crate::store::{AttachmentStore, Commit, HistoryStore, LinkStore, LockStore, Store, SyncStore, Transaction, temporary_attachment_file, uuid_or_new};
+
+/// A store that keeps everything in memory, for tests and tools that do not need a database file
+///
+/// It follows the same rules as the SQLite store (IDs, ordering, trash and archive behaviour,
+/// revisions, links and sync state). Attachments are kept in memory whatever storage is asked for.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    data: RefCell<Data>,
+    changes: Cell<u64>, // Number of changing calls, for `total_changes`
+}
+
+#[derive(Debug, Default, Clone)]
+struct Data {
+    tasks: BTreeMap<i64, Task>,
+    notes: BTreeMap<i64, Note>,
+    journals: BTreeMap<i64, JournalEntry>,
+    notebooks: BTreeMap<i64, Notebook>,
+    settings: HashMap<String, String>,
+    revisions: BTreeMap<i64, Revision>,
+    import_sources: HashMap<String, (ItemKind, i64)>,
+    attachments: BTreeMap<i64, (Attachment, Vec<u8>)>,
+    sync_states: BTreeMap<String, SyncState>,
+    sync_conflicts: BTreeMap<i64, SyncConflict>,
+    last_ids: HashMap<&'static str, i64>, // Highest ID each table ever had
+}
+
+/// The title, date and linkable text of an item, to resolve links with
+struct Linkable {
+    id: i64,
+    title: Option<String>,
+    date: Option<String>,
+    text: Option<String>,
+    archived: bool,
+    created_at: String,
+    trashed: bool,
+}
+
+/// A transaction on a `MemoryStore`: a copy of the data to put back unless it is committed
+struct MemoryTransaction<'a> {
+    data: &'a RefCell<Data>,
+    saved: Option<Data>,
+}
+
+impl Commit for MemoryTransaction<'_> {
+    fn commit(mut self: Box<Self>) -> Result<(), DatabaseError> {
+        self.saved = None;
+        Ok(())
+    }
+}
+
+impl Drop for MemoryTransaction<'_> {
+    fn drop(&mut self) {
+        if let Some(saved) = self.saved.take() {
+            *self.data.borrow_mut() = saved;
+        }
+    }
+}
+
+/// Fields shared by tasks, notes and journal entries, so operations on any kind of item are written once
+trait Row {
+    fn notebook_id(&mut self) -> &mut Option<i64>;
+    fn archived(&mut self) -> &mut bool;
+    fn deleted_at(&mut self) -> &mut Option<String>;
+    fn updated_at(&mut self) -> &mut String;
+    fn tags(&mut self) -> &mut Option<String>;
+}
+
+macro_rules! impl_row {
+    ($($item:ty),*) => {$(
+        impl Row for $item {
+            fn notebook_id(&mut self) -> &mut Option<i64> { &mut self.notebook_id }
+            fn archived(&mut self) -> &mut bool { &mut self.archived }
+            fn deleted_at(&mut self) -> &mut Option<String> { &mut self.deleted_at }
+            fn updated_at(&mut self) -> &mut String { &mut self.updated_at }
+            fn tags(&mut self) -> &mut Option<String> { &mut self.tags }
+        }
+    )*};
+}
+
+impl_row!(Task, Note, JournalEntry);
+
+/// Get the current time in the format stored in created_at, updated_at and deleted_at
+fn now() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Get the rows of one table that are not in the trash, in the given notebook, optionally leaving out archived ones
+fn listed<T: Row + Clone>(rows: &BTreeMap<i64, T>, notebook_id: Option<i64>, include_archived: bool) -> Vec<T> {
+    rows.values()
+        .cloned()
+        .filter_map(|mut row| {
+            let listed = row.deleted_at().is_none()
+                && *row.notebook_id() == notebook_id
+                && (include_archived || !*row.archived());
+            listed.then_some(row)
+        })
+        .collect()
+}
+
+/// Get the rows of one table that are in the trash, most recently deleted first
+fn trashed<T: Row + Clone>(rows: &BTreeMap<i64, T>) -> Vec<T> {
+    let mut rows: Vec<(String, T)> = rows.values()
+        .cloned()
+        .filter_map(|mut row| row.deleted_at().clone().map(|deleted_at| (deleted_at, row)))
+        .collect();
+    rows.sort_by(|a, b| b.0.cmp(&a.0));
+    rows.into_iter().map(|(_, row)| row).collect()
+}
+
+impl MemoryStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Borrow the data to change it, counting the change
+    fn write(&self) -> RefMut<'_, Data> {
+        self.changes.set(self.changes.get() + 1);
+        self.data.borrow_mut()
+    }
+}
+
+impl Data {
+    /// Get the ID for a new row of a table: one more than the highest ID the table ever had, so
+    /// IDs of deleted rows are not reused (like SQLite's AUTOINCREMENT)
+    fn new_id(&mut self, table: &'static str) -> i64 {
+        let last = self.last_ids.entry(table).or_default();
+        *last += 1;
+        *last
+    }
+
+    /// Note that a row was stored with the given ID, so new rows get higher ones
+    fn claim_id(&mut self, table: &'static str, id: i64) {
+        let last = self.last_ids.entry(table).or_default();
+        *last = (*last).max(id);
+    }
+
+    /// Get an item of any kind to change it
+    fn row_mut(&mut self, kind: ItemKind, id: i64) -> Option<&mut dyn Row> {
+        match kind {
+            ItemKind::Task => self.tasks.get_mut(&id).map(|row| row as &mut dyn Row),
+            ItemKind::Note => self.notes.get_mut(&id).map(|row| row as &mut dyn Row),
+            ItemKind::Journal => self.journals.get_mut(&id).map(|row| row as &mut dyn Row),
+        }
+    }
+
+    /// Get every item of one kind to change it, by ID
+    fn rows_mut(&mut self, kind: ItemKind) -> Vec<(i64, &mut dyn Row)> {
+        match kind {
+            ItemKind::Task => self.tasks.iter_mut().map(|(id, row)| (*id, row as &mut dyn Row)).collect(),
+            ItemKind::Note => self.notes.iter_mut().map(|(id, row)| (*id, row as &mut dyn Row)).collect(),
+            ItemKind::Journal => self.journals.iter_mut().map(|(id, row)| (*id, row as &mut dyn Row)).collect(),
+        }
+    }
+
+    /// Permanently delete the items of one kind the predicate selects, returning how many were deleted
+    fn remove_where(&mut self, kind: ItemKind, mut remove: impl FnMut(&mut dyn Row) -> bool) -> usize {
+        let ids: Vec<i64> = self.rows_mut(kind).into_iter()
+            .filter_map(|(id, row)| remove(row).then_some(id))
+            .collect();
+        for id in &ids {
+            self.remove(kind, *id);
+        }
+        ids.len()
+    }
+
+    /// Permanently delete an item, with its revisions and attachments
+    fn remove(&mut self, kind: ItemKind, id: i64) {
+        match kind {
+            ItemKind::Task => self.tasks.remove(&id).map(|_| ()),
+            ItemKind::Note => self.notes.remove(&id).map(|_| ()),
+            ItemKind::Journal => self.journals.remove(&id).map(|_| ()),
+        };
+        self.revisions.retain(|_, revision| (revision.item_kind, revision.item_id) != (kind, id));
+        self.attachments.retain(|_, (attachment, _)| (attachment.item_kind, attachment.item_id) != (kind, id));
+    }
+
+    /// Check whether an item exists, in the trash or not
+    fn exists(&self, kind: ItemKind, id: i64) -> bool {
+        match kind {
+            ItemKind::Task => self.tasks.contains_key(&id),
+            ItemKind::Note => self.notes.contains_key(&id),
+            ItemKind::Journal => self.journals.contains_key(&id),
+        }
+    }
+
+    /// Get what links can match of every item of one kind, by ID
+    fn linkables(&self, kind: ItemKind) -> Vec<Linkable> {
+        match kind {
+            ItemKind::Task => self.tasks.values().map(|task| Linkable {
+                id: task.id.unwrap_or_default(),
+                title: Some(task.title.clone()),
+                date: None,
+                text: task.description.clone(),
+                archived: task.archived,
+                created_at: task.created_at.clone(),
+                trashed: task.deleted_at.is_some(),
+            }).collect(),
+            ItemKind::Note => self.notes.values().map(|note| Linkable {
+                id: note.id.unwrap_or_default(),
+                title: Some(note.title.clone()),
+                date: None,
+                text: note.content.clone(),
+                archived: note.archived,
+                created_at: note.created_at.clone(),
+                trashed: note.deleted_at.is_some(),
+            }).collect(),
+            ItemKind::Journal => self.journals.values().map(|journal| Linkable {
+                id: journal.id.unwrap_or_default(),
+                title: journal.title.clone(),
+                date: Some(journal.date.clone()),
+                text: journal.content.clone(),
+                archived: journal.archived,
+                created_at: journal.created_at.clone(),
+                trashed: journal.deleted_at.is_some(),
+            }).collect(),
+        }
+    }
+
+    /// Check whether an item exists and is not in the trash
+    fn is_live_item(&self, kind: ItemKind, id: i64) -> bool {
+        self.linkables(kind).iter().any(|item| item.id == id && !item.trashed)
+    }
+
+    /// Find the item a link points at, ignoring items in the trash (see `Store::resolve_link`)
+    fn resolve_link(&self, target: &LinkTarget) -> Option<(ItemKind, i64)> {
+        match target {
+            LinkTarget::Item(kind, id) => self.is_live_item(*kind, *id).then_some((*kind, *id)),
+            LinkTarget::Date(date) => {
+                let mut journals: Vec<Linkable> = self.linkables(ItemKind::Journal).into_iter()
+                    .filter(|journal| !journal.trashed && journal.date.as_ref() == Some(date))
+                    .collect();
+                journals.sort_by(|a, b| (a.archived, &a.created_at, a.id).cmp(&(b.archived, &b.created_at, b.id)));
+                journals.first().map(|journal| (ItemKind::Journal, journal.id))
+            }
+            LinkTarget::Title(title) => {
+                let wanted = title.to_lowercase();
+                [ItemKind::Note, ItemKind::Task, ItemKind::Journal].into_iter().find_map(|kind| {
+                    let mut items = self.linkables(kind);
+                    items.sort_by_key(|item| (item.archived, item.id));
+                    items.into_iter()
+                        .find(|item| !item.trashed && item.title.as_ref().is_some_and(|t| t.trim().to_lowercase() == wanted))
+                        .map(|item| (kind, item.id))
+                })
+            }
+        }
+    }
+
+    /// Keep the current title and content of a note or journal entry as a revision, if the new ones differ
+    fn record_revision(&mut self, kind: ItemKind, id: i64, title: Option<&str>, content: Option<&str>) {
+        let current = match kind {
+            ItemKind::Note => self.notes.get(&id).map(|note| (Some(note.title.clone()), note.content.clone(), note.updated_at.clone())),
+            ItemKind::Journal => self.journals.get(&id).map(|journal| (journal.title.clone(), journal.content.clone(), journal.updated_at.clone())),
+            ItemKind::Task => None,
+        };
+        let Some((old_title, old_content, saved_at)) = current else {
+            return;
+        };
+        if old_title.as_deref() == title && old_content.as_deref() == content {
+            return;
+        }
+        let revision_id = self.new_id("revisions");
+        self.revisions.insert(revision_id, Revision {
+            id: Some(revision_id),
+            item_kind: kind,
+            item_id: id,
+            title: old_title,
+            content: old_content,
+            saved_at,
+        });
+    }
+
+    /// Get the UUID of every notebook or item of one kind, by ID
+    fn uuids(&self, kind: SyncKind) -> Vec<(i64, Option<&String>)> {
+        match kind {
+            SyncKind::Notebook => self.notebooks.iter().map(|(id, row)| (*id, row.uuid.as_ref())).collect(),
+            SyncKind::Task => self.tasks.iter().map(|(id, row)| (*id, row.uuid.as_ref())).collect(),
+            SyncKind::Note => self.notes.iter().map(|(id, row)| (*id, row.uuid.as_ref())).collect(),
+            SyncKind::Journal => self.journals.iter().map(|(id, row)| (*id, row.uuid.as_ref())).collect(),
+        }
+    }
+
+    /// Rewrite the comma-separated tags of several items
+    /// The closure edits the parsed tag list and returns whether it changed anything
+    fn update_items_tags<F>(&mut self, kind: ItemKind, ids: &[i64], mut update: F) -> usize
+    where
+        F: FnMut(&mut Vec<String>) -> bool,
+    {
+        let now = now();
+        let mut changed = 0;
+        for id in ids {
+            let Some(row) = self.row_mut(kind, *id) else {
+                continue;
+            };
+            let mut tag_list: Vec<String> = row.tags().clone().unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            if update(&mut tag_list) {
+                *row.tags() = if tag_list.is_empty() { None } else { Some(tag_list.join(", ")) };
+                *row.updated_at() = now.clone();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Archive or unarchive items of one kind
+    fn set_items_archived(&mut self, kind: ItemKind, ids: &[i64], archived: bool) -> usize {
+        let now = now();
+        let mut changed = 0;
+        for id in ids {
+            if let Some(row) = self.row_mut(kind, *id) {
+                *row.archived() = archived;
+                *row.updated_at() = now.clone();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Move an item to the trash
+    fn trash(&mut self, kind: ItemKind, id: i64) {
+        if let Some(row) = self.row_mut(kind, id) {
+            *row.deleted_at() = Some(now());
+        }
+    }
+
+    /// Archive an item
+    fn archive(&mut self, kind: ItemKind, id: i64) {
+        self.set_items_archived(kind, &[id], true);
+    }
+}
+
+impl Store for MemoryStore {
+    fn insert_task(&self, task: &Task) -> Result<i64, DatabaseError> {
+        let mut data = self.write();
+        let id = data.new_id("tasks");
+        let task = Task { id: Some(id), uuid: Some(uuid_or_new(&task.uuid)), deleted_at: None, ..task.clone() };
+        data.tasks.insert(id, task);
+        Ok(id)
+    }
+
+    fn insert_note(&self, note: &Note) -> Result<i64, DatabaseError> {
+        let mut data = self.write();
+        let id = data.new_id("notes");
+        let note = Note { id: Some(id), uuid: Some(uuid_or_new(&note.uuid)), deleted_at: None, ..note.clone() };
+        data.notes.insert(id, note);
+        Ok(id)
+    }
+
+    fn insert_journal(&self, journal: &JournalEntry) -> Result<i64, DatabaseError> {
+        let mut data = self.write();
+        let id = data.new_id("journals");
+        let journal = JournalEntry { id: Some(id), uuid: Some(uuid_or_new(&journal.uuid)), deleted_at: None, ..journal.clone() };
+        data.journals.insert(id, journal);
+        Ok(id)
+    }
+
+    fn get_all_tasks(&self, notebook_id: Option<i64>) -> Result<Vec<Task>, DatabaseError> {
+        let mut tasks = listed(&self.data.borrow().tasks, notebook_id, false);
+        tasks.sort_by_key(|task| task.order);
+        Ok(tasks)
+    }
+
+    fn get_all_tasks_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<Task>, DatabaseError> {
+        let mut tasks = listed(&self.data.borrow().tasks, notebook_id, true);
+        tasks.sort_by_key(|task| task.order);
+        Ok(tasks)
+    }
+
+    fn get_task(&self, id: i64) -> Result<Task, DatabaseError> {
+        self.data.borrow().tasks.get(&id).cloned()
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("{} {}", ItemKind::Task, id)))
+    }
+
+    fn update_task(&self, task: &Task) -> Result<(), DatabaseError> {
+        let id = task.id.ok_or(DatabaseError::MissingId)?;
+        if let Some(stored) = self.write().tasks.get_mut(&id) {
+            *stored = Task { id: Some(id), uuid: stored.uuid.clone(), created_at: stored.created_at.clone(), deleted_at: stored.deleted_at.clone(), ..task.clone() };
+        }
+        Ok(())
+    }
+
+    fn get_max_task_order(&self) -> Result<i64, DatabaseError> {
+        Ok(self.data.borrow().tasks.values().map(|task| task.order).max().unwrap_or(-1))
+    }
+
+    fn update_task_order(&self, task_id: i64, new_order: i64) -> Result<(), DatabaseError> {
+        if let Some(task) = self.write().tasks.get_mut(&task_id) {
+            task.order = new_order;
+            task.updated_at = now();
+        }
+        Ok(())
+    }
+
+    fn delete_task(&self, id: i64) -> Result<(), DatabaseError> {
+        self.write().trash(ItemKind::Task, id);
+        Ok(())
+    }
+
+    fn archive_task(&self, id: i64) -> Result<(), DatabaseError> {
+        self.write().archive(ItemKind::Task, id);
+        Ok(())
+    }
+
+    fn set_tasks_status(&self, ids: &[i64], status: &str) -> Result<usize, DatabaseError> {
+        let mut data = self.write();
+        let now = now();
+        let mut changed = 0;
+        for id in ids {
+            if let Some(task) = data.tasks.get_mut(id) {
+                task.status = status.to_string();
+                task.updated_at = now.clone();
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn get_all_notes(&self, notebook_id: Option<i64>) -> Result<Vec<Note>, DatabaseError> {
+        let mut notes = listed(&self.data.borrow().notes, notebook_id, false);
+        notes.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(notes)
+    }
+
+    fn get_all_notes_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<Note>, DatabaseError> {
+        let mut notes = listed(&self.data.borrow().notes, notebook_id, true);
+        notes.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(notes)
+    }
+
+    fn get_note(&self, id: i64) -> Result<Note, DatabaseError> {
+        self.data.borrow().notes.get(&id).cloned()
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("{} {}", ItemKind::Note, id)))
+    }
+
+    fn update_note(&self, note: &Note) -> Result<(), DatabaseError> {
+        let id = note.id.ok_or(DatabaseError::MissingId)?;
+        let mut data = self.write();
+        data.record_revision(ItemKind::Note, id, Some(&note.title), note.content.as_deref());
+        if let Some(stored) = data.notes.get_mut(&id) {
+            *stored = Note { id: Some(id), uuid: stored.uuid.clone(), created_at: stored.created_at.clone(), deleted_at: stored.deleted_at.clone(), ..note.clone() };
+        }
+        Ok(())
+    }
+
+    fn delete_note(&self, id: i64) -> Result<(), DatabaseError> {
+        self.write().trash(ItemKind::Note, id);
+        Ok(())
+    }
+
+    fn archive_note(&self, id: i64) -> Result<(), DatabaseError> {
+        self.write().archive(ItemKind::Note, id);
+        Ok(())
+    }
+
+    fn get_all_journals(&self, notebook_id: Option<i64>) -> Result<Vec<JournalEntry>, DatabaseError> {
+        let mut journals = listed(&self.data.borrow().journals, notebook_id, false);
+        journals.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| b.created_at.cmp(&a.created_at)));
+        Ok(journals)
+    }
+
+    fn get_all_journals_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<JournalEntry>, DatabaseError> {
+        let mut journals = listed(&self.data.borrow().journals, notebook_id, true);
+        journals.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| b.created_at.cmp(&a.created_at)));
+        Ok(journals)
+    }
+
+    fn get_journal(&self, id: i64) -> Result<JournalEntry, DatabaseError> {
+        self.data.borrow().journals.get(&id).cloned()
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("{} {}", ItemKind::Journal, id)))
+    }
+
+    fn update_journal(&self, journal: &JournalEntry) -> Result<(), DatabaseError> {
+        let id = journal.id.ok_or(DatabaseError::MissingId)?;
+        let mut data = self.write();
+        data.record_revision(ItemKind::Journal, id, journal.title.as_deref(), journal.content.as_deref());
+        if let Some(stored) = data.journals.get_mut(&id) {
+            *stored = JournalEntry { id: Some(id), uuid: stored.uuid.clone(), created_at: stored.created_at.clone(), deleted_at: stored.deleted_at.clone(), ..journal.clone() };
+        }
+        Ok(())
+    }
+
+    fn delete_journal(&self, id: i64) -> Result<(), DatabaseError> {
+        self.write().trash(ItemKind::Journal, id);
+        Ok(())
+    }
+
+    fn archive_journal(&self, id: i64) -> Result<(), DatabaseError> {
+        self.write().archive(ItemKind::Journal, id);
+        Ok(())
+    }
+
+    fn dump_tasks(&self) -> Result<Vec<Task>, DatabaseError> {
+        Ok(self.data.borrow().tasks.values().cloned().collect())
+    }
+
+    fn dump_notes(&self) -> Result<Vec<Note>, DatabaseError> {
+        Ok(self.data.borrow().notes.values().cloned().collect())
+    }
+
+    fn dump_journals(&self) -> Result<Vec<JournalEntry>, DatabaseError> {
+        Ok(self.data.borrow().journals.values().cloned().collect())
+    }
+
+    fn get_all_notebooks(&self) -> Result<Vec<Notebook>, DatabaseError> {
+        let mut notebooks: Vec<Notebook> = self.data.borrow().notebooks.values().cloned().collect();
+        notebooks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(notebooks)
+    }
+
+    fn get_notebook(&self, id: i64) -> Result<Notebook, DatabaseError> {
+        self.data.borrow().notebooks.get(&id).cloned()
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("notebook {}", id)))
+    }
+
+    fn get_notebook_by_name(&self, name: &str) -> Result<Option<Notebook>, DatabaseError> {
+        Ok(self.data.borrow().notebooks.values().find(|notebook| notebook.name == name).cloned())
+    }
+
+    fn get_default_notebook(&self) -> Result<Option<Notebook>, DatabaseError> {
+        Ok(self.get_all_notebooks()?.into_iter().next())
+    }
+
+    fn insert_notebook(&self, notebook: &Notebook) -> Result<i64, DatabaseError> {
+        let mut data = self.write();
+        let id = data.new_id("notebooks");
+        let notebook = Notebook { id: Some(id), uuid: Some(uuid_or_new(&notebook.uuid)), ..notebook.clone() };
+        data.notebooks.insert(id, notebook);
+        Ok(id)
+    }
+
+    fn update_notebook(&self, notebook: &Notebook) -> Result<(), DatabaseError> {
+        let id = notebook.id.ok_or(DatabaseError::MissingId)?;
+        if let Some(stored) = self.write().notebooks.get_mut(&id) {
+            stored.name = notebook.name.clone();
+            stored.updated_at = notebook.updated_at.clone();
+        }
+        Ok(())
+    }
+
+    fn delete_notebook(&self, id: i64) -> Result<(), DatabaseError> {
+        let mut data = self.write();
+        let now = now();
+        for kind in [ItemKind::Task, ItemKind::Note, ItemKind::Journal] {
+            for (_, row) in data.rows_mut(kind) {
+                if *row.notebook_id() == Some(id) {
+                    *row.notebook_id() = None;
+                    *row.updated_at() = now.clone();
+                }
+            }
+        }
+        data.notebooks.remove(&id);
+        Ok(())
+    }
+
+    fn get_item_ids_in_notebook(&self, kind: ItemKind, notebook_id: i64) -> Result<Vec<i64>, DatabaseError> {
+        let mut data = self.data.borrow_mut();
+        Ok(data.rows_mut(kind).into_iter()
+            .filter_map(|(id, row)| (*row.notebook_id() == Some(notebook_id)).then_some(id))
+            .collect())
+    }
+
+    fn restore_notebook(&self, notebook: &Notebook, items: &[(ItemKind, i64)]) -> Result<(), DatabaseError> {
+        let id = notebook.id.ok_or(DatabaseError::MissingId)?;
+        let mut data = self.write();
+        data.claim_id("notebooks", id);
+        data.notebooks.insert(id, Notebook { uuid: Some(uuid_or_new(&notebook.uuid)), ..notebook.clone() });
+        let now = now();
+        for (kind, item_id) in items {
+            if let Some(row) = data.row_mut(*kind, *item_id) {
+                *row.notebook_id() = Some(id);
+                *row.updated_at() = now.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn move_items_to_notebook(&self, kind: ItemKind, ids: &[i64], notebook_id: Option<i64>) -> Result<usize, DatabaseError> {
+        let mut data = self.write();
+        if let Some(id) = ids.iter().find(|id| data.row_mut(kind, **id).is_none_or(|row| row.deleted_at().is_some())) {
+            return Err(DatabaseError::ItemNotFound(format!("{} {}", kind, id)));
+        }
+        let now = now();
+        let mut seen = HashSet::new();
+        for id in ids.iter().filter(|id| seen.insert(**id)) {
+            if let Some(row) = data.row_mut(kind, *id) {
+                *row.notebook_id() = notebook_id;
+                *row.updated_at() = now.clone();
+            }
+        }
+        Ok(seen.len())
+    }
+
+    fn archive_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError> {
+        Ok(self.write().set_items_archived(kind, ids, true))
+    }
+
+    fn unarchive_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError> {
+        Ok(self.write().set_items_archived(kind, ids, false))
+    }
+
+    fn delete_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError> {
+        let mut data = self.write();
+        let now = now();
+        let mut deleted = 0;
+        for id in ids {
+            if let Some(row) = data.row_mut(kind, *id)
+                && row.deleted_at().is_none()
+            {
+                *row.deleted_at() = Some(now.clone());
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    fn add_tag_to_items(&self, kind: ItemKind, ids: &[i64], tag: &str) -> Result<usize, DatabaseError> {
+        let tag = tag.trim().to_string();
+        Ok(self.write().update_items_tags(kind, ids, |tags| {
+            if tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                false
+            } else {
+                tags.push(tag.clone());
+                true
+            }
+        }))
+    }
+
+    fn remove_tag_from_items(&self, kind: ItemKind, ids: &[i64], tag: &str) -> Result<usize, DatabaseError> {
+        let tag = tag.trim().to_string();
+        Ok(self.write().update_items_tags(kind, ids, |tags| {
+            let before = tags.len();
+            tags.retain(|t| !t.eq_ignore_ascii_case(&tag));
+            tags.len() != before
+        }))
+    }
+
+    fn get_deleted_tasks(&self) -> Result<Vec<Task>, DatabaseError> {
+        Ok(trashed(&self.data.borrow().tasks))
+    }
+
+    fn get_deleted_notes(&self) -> Result<Vec<Note>, DatabaseError> {
+        Ok(trashed(&self.data.borrow().notes))
+    }
+
+    fn get_deleted_journals(&self) -> Result<Vec<JournalEntry>, DatabaseError> {
+        Ok(trashed(&self.data.borrow().journals))
+    }
+
+    fn set_deleted_at(&self, kind: ItemKind, id: i64, deleted_at: Option<&str>) -> Result<(), DatabaseError> {
+        if let Some(row) = self.write().row_mut(kind, id) {
+            *row.deleted_at() = deleted_at.map(str::to_string);
+        }
+        Ok(())
+    }
+
+    fn restore_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError> {
+        let mut data = self.write();
+        let notebook_ids: Vec<i64> = data.notebooks.keys().copied().collect();
+        let mut restored = 0;
+        for id in ids {
+            let Some(row) = data.row_mut(kind, *id) else {
+                continue;
+            };
+            if row.deleted_at().is_none() {
+                continue;
+            }
+            *row.deleted_at() = None;
+            if row.notebook_id().is_some_and(|notebook_id| !notebook_ids.contains(&notebook_id)) {
+                *row.notebook_id() = None;
+            }
+            restored += 1;
+        }
+        Ok(restored)
+    }
+
+    fn purge_item(&self, kind: ItemKind, id: i64) -> Result<(), DatabaseError> {
+        let mut data = self.write();
+        if !data.row_mut(kind, id).is_some_and(|row| row.deleted_at().is_some()) {
+            return Err(DatabaseError::ItemNotFound(format!("{} {} in trash", kind, id)));
+        }
+        data.remove(kind, id);
+        Ok(())
+    }
+
+    fn empty_trash(&self) -> Result<usize, DatabaseError> {
+        let mut data = self.write();
+        Ok([ItemKind::Task, ItemKind::Note, ItemKind::Journal].into_iter()
+            .map(|kind| data.remove_where(kind, |row| row.deleted_at().is_some()))
+            .sum())
+    }
+
+    fn purge_trash_older_than(&self, days: u32) -> Result<usize, DatabaseError> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(days as i64))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let mut data = self.write();
+        Ok([ItemKind::Task, ItemKind::Note, ItemKind::Journal].into_iter()
+            .map(|kind| data.remove_where(kind, |row| row.deleted_at().as_ref().is_some_and(|deleted_at| *deleted_at < cutoff)))
+            .sum())
+    }
+
+    fn get_setting(&self, key: &str) -> Result<Option<String>, DatabaseError> {
+        Ok(self.data.borrow().settings.get(key).cloned())
+    }
+
+    fn set_setting(&self, key: &str, value: &str) -> Result<(), DatabaseError> {
+        self.write().settings.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn get_uuids(&self, kind: SyncKind) -> Result<HashMap<i64, String>, DatabaseError> {
+        Ok(self.data.borrow().uuids(kind).into_iter()
+            .filter_map(|(id, uuid)| uuid.map(|uuid| (id, uuid.clone())))
+            .collect())
+    }
+
+    fn find_by_uuid(&self, kind: SyncKind, uuid: &str) -> Result<Option<i64>, DatabaseError> {
+        Ok(self.data.borrow().uuids(kind).into_iter()
+            .find(|(_, row_uuid)| row_uuid.is_some_and(|row_uuid| row_uuid == uuid))
+            .map(|(id, _)| id))
+    }
+
+    fn set_uuid(&self, kind: SyncKind, id: i64, uuid: &str) -> Result<(), DatabaseError> {
+        let mut data = self.write();
+        let uuid = Some(uuid.to_string());
+        match kind {
+            SyncKind::Notebook => data.notebooks.get_mut(&id).map(|row| row.uuid = uuid),
+            SyncKind::Task => data.tasks.get_mut(&id).map(|row| row.uuid = uuid),
+            SyncKind::Note => data.notes.get_mut(&id).map(|row| row.uuid = uuid),
+            SyncKind::Journal => data.journals.get_mut(&id).map(|row| row.uuid = uuid),
+        };
+        Ok(())
+    }
+
+    fn transaction(&self) -> Result<Transaction<'_>, DatabaseError> {
+        Ok(Transaction::new(MemoryTransaction { data: &self.data, saved: Some(self.data.borrow().clone()) }))
+    }
+
+    fn total_changes(&self) -> u64 {
+        self.changes.get()
+    }
+
+    fn data_version(&self) -> Result<i64, DatabaseError> {
+        Ok(0) // Nothing else can change the data
+    }
+
+    fn delete_all_data(&self) -> Result<(), DatabaseError> {
+        let mut data = self.write();
+        data.tasks.clear();
+        data.notes.clear();
+        data.journals.clear();
+        data.notebooks.clear();
+        data.revisions.clear();
+        data.import_sources.clear();
+        data.attachments.clear();
+        data.settings.remove(crate::lock::CHECK_SETTING);
+        data.sync_conflicts.clear();
+        Ok(())
+    }
+
+    fn get_imported_item(&self, source: &str) -> Result<Option<(ItemKind, i64)>, DatabaseError> {
+        let data = self.data.borrow();
+        Ok(data.import_sources.get(source).copied().filter(|(kind, id)| data.exists(*kind, *id)))
+    }
+
+    fn record_import_source(&self, source: &str, kind: ItemKind, item_id: i64) -> Result<(), DatabaseError> {
+        self.write().import_sources.insert(source.to_string(), (kind, item_id));
+        Ok(())
+    }
+}
+
+impl LockStore for MemoryStore {
+    fn set_notebook_locked(&self, id: i64, locked: bool) -> Result<(), DatabaseError> {
+        if let Some(notebook) = self.write().notebooks.get_mut(&id) {
+            notebook.locked = locked;
+            notebook.updated_at = now();
+        }
+        Ok(())
+    }
+
+    fn set_journal_content(&self, id: i64, content: Option<&str>, forget_history: bool) -> Result<(), DatabaseError> {
+        let mut data = self.write();
+        if let Some(journal) = data.journals.get_mut(&id) {
+            journal.content = content.map(str::to_string);
+            journal.updated_at = now();
+        }
+        if forget_history {
+            data.revisions.retain(|_, revision| (revision.item_kind, revision.item_id) != (ItemKind::Journal, id));
+        }
+        Ok(())
+    }
+
+    fn get_notebook_journal_contents(&self, notebook_id: i64) -> Result<Vec<(i64, Option<String>)>, DatabaseError> {
+        Ok(self.data.borrow().journals.iter()
+            .filter(|(_, journal)| journal.notebook_id == Some(notebook_id))
+            .map(|(id, journal)| (*id, journal.content.clone()))
+            .collect())
+    }
+
+    fn get_any_locked_content(&self) -> Result<Option<String>, DatabaseError> {
+        Ok(self.data.borrow().journals.values()
+            .filter_map(|journal| journal.content.clone())
+            .find(|content| content.starts_with(crate::lock::LOCKED_PREFIX)))
+    }
+}
+
+impl HistoryStore for MemoryStore {
+    fn get_revisions(&self, kind: ItemKind, item_id: i64) -> Result<Vec<Revision>, DatabaseError> {
+        let mut revisions: Vec<Revision> = self.data.borrow().revisions.values()
+            .filter(|revision| (revision.item_kind, revision.item_id) == (kind, item_id))
+            .cloned()
+            .collect();
+        revisions.sort_by(|a, b| (&b.saved_at, b.id).cmp(&(&a.saved_at, a.id)));
+        Ok(revisions)
+    }
+
+    fn get_revision(&self, id: i64) -> Result<Revision, DatabaseError> {
+        self.data.borrow().revisions.get(&id).cloned()
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("revision {}", id)))
+    }
+
+    fn restore_revision(&self, revision_id: i64) -> Result<Revision, DatabaseError> {
+        let revision = self.get_revision(revision_id)?;
+        let (kind, id) = (revision.item_kind, revision.item_id);
+        let mut data = self.write();
+        if !data.is_live_item(kind, id) {
+            return Err(DatabaseError::ItemNotFound(format!("{} {}", kind, id)));
+        }
+        data.record_revision(kind, id, revision.title.as_deref(), revision.content.as_deref());
+        let now = now();
+        if let Some(note) = data.notes.get_mut(&id).filter(|_| kind == ItemKind::Note) {
+            note.title = revision.title.clone().unwrap_or_default();
+            note.content = revision.content.clone();
+            note.updated_at = now;
+        } else if let Some(journal) = data.journals.get_mut(&id).filter(|_| kind == ItemKind::Journal) {
+            journal.title = revision.title.clone();
+            journal.content = revision.content.clone();
+            journal.updated_at = now;
+        }
+        Ok(revision)
+    }
+
+    fn dump_revisions(&self) -> Result<Vec<Revision>, DatabaseError> {
+        Ok(self.data.borrow().revisions.values().cloned().collect())
+    }
+
+    fn add_revision(&self, revision: &Revision) -> Result<i64, DatabaseError> {
+        let mut data = self.write();
+        let id = data.new_id("revisions");
+        data.revisions.insert(id, Revision { id: Some(id), ..revision.clone() });
+        Ok(id)
+    }
+}
+
+impl LinkStore for MemoryStore {
+    fn resolve_link(&self, target: &LinkTarget) -> Result<Option<(ItemKind, i64)>, DatabaseError> {
+        Ok(self.data.borrow().resolve_link(target))
+    }
+
+    fn get_backlinks(&self, kind: ItemKind, id: i64) -> Result<Vec<(ItemKind, i64)>, DatabaseError> {
+        let data = self.data.borrow();
+        let item = data.linkables(kind).into_iter()
+            .find(|item| item.id == id)
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("{} {}", kind, id)))?;
+
+        // A title or date link only counts if it resolves to this item and not to another one
+        let mut targets = vec![LinkTarget::Item(kind, id)];
+        if let Some(title) = item.title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+            targets.push(LinkTarget::Title(title));
+        }
+        if let Some(date) = item.date {
+            targets.push(LinkTarget::Date(date));
+        }
+        let keys: Vec<String> = targets.iter()
+            .filter(|target| matches!(target, LinkTarget::Item(..)) || data.resolve_link(target) == Some((kind, id)))
+            .map(LinkTarget::key)
+            .collect();
+
+        let mut backlinks = Vec::new();
+        for source_kind in [ItemKind::Task, ItemKind::Note, ItemKind::Journal] {
+            for source in data.linkables(source_kind) {
+                let links = parse_links(source.text.as_deref().unwrap_or(""));
+                if !source.trashed
+                    && (source_kind, source.id) != (kind, id)
+                    && links.iter().any(|link| keys.contains(&link.target.key()))
+                {
+                    backlinks.push((source_kind, source.id));
+                }
+            }
+        }
+        backlinks.sort();
+        Ok(backlinks)
+    }
+}
+
+impl AttachmentStore for MemoryStore {
+    fn add_attachment(&self, attachment: &Attachment, data: &[u8], _storage: AttachmentStorage) -> Result<i64, DatabaseError> {
+        let mut store = self.write();
+        let (kind, item_id) = (attachment.item_kind, attachment.item_id);
+        if !store.exists(kind, item_id) {
+            return Err(DatabaseError::ItemNotFound(format!("{} {}", kind, item_id)));
+        }
+        let id = store.new_id("attachments");
+        let attachment = Attachment { id: Some(id), size: data.len() as i64, stored_file: None, ..attachment.clone() };
+        store.attachments.insert(id, (attachment, data.to_vec()));
+        Ok(id)
+    }
+
+    fn get_attachments(&self, kind: ItemKind, item_id: i64) -> Result<Vec<Attachment>, DatabaseError> {
+        Ok(self.data.borrow().attachments.values()
+            .filter(|(attachment, _)| (attachment.item_kind, attachment.item_id) == (kind, item_id))
+            .map(|(attachment, _)| attachment.clone())
+            .collect())
+    }
+
+    fn dump_attachments(&self) -> Result<Vec<Attachment>, DatabaseError> {
+        Ok(self.data.borrow().attachments.values().map(|(attachment, _)| attachment.clone()).collect())
+    }
+
+    fn get_attachment(&self, id: i64) -> Result<Attachment, DatabaseError> {
+        self.data.borrow().attachments.get(&id)
+            .map(|(attachment, _)| attachment.clone())
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("attachment {}", id)))
+    }
+
+    fn read_attachment(&self, attachment: &Attachment) -> Result<Vec<u8>, DatabaseError> {
+        let id = attachment.id.ok_or(DatabaseError::MissingId)?;
+        self.data.borrow().attachments.get(&id)
+            .map(|(_, data)| data.clone())
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("attachment {}", id)))
+    }
+
+    fn attachment_path(&self, attachment: &Attachment) -> Result<PathBuf, DatabaseError> {
+        temporary_attachment_file(attachment, &self.read_attachment(attachment)?)
+    }
+
+    fn delete_attachment(&self, id: i64) -> Result<Attachment, DatabaseError> {
+        self.write().attachments.remove(&id)
+            .map(|(attachment, _)| attachment)
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("attachment {}", id)))
+    }
+
+    fn remove_unused_attachment_files(&self) -> Result<(), DatabaseError> {
+        Ok(()) // Attachments have no files of their own
+    }
+}
+
+impl SyncStore for MemoryStore {
+    fn delete_item_permanently(&self, kind: ItemKind, id: i64) -> Result<(), DatabaseError> {
+        self.write().remove(kind, id);
+        Ok(())
+    }
+
+    fn get_sync_states(&self) -> Result<Vec<SyncState>, DatabaseError> {
+        Ok(self.data.borrow().sync_states.values().cloned().collect())
+    }
+
+    fn set_sync_state(&self, state: &SyncState) -> Result<(), DatabaseError> {
+        self.write().sync_states.insert(state.uuid.clone(), state.clone());
+        Ok(())
+    }
+
+    fn add_sync_conflict(&self, conflict: &SyncConflict) -> Result<i64, DatabaseError> {
+        let mut data = self.write();
+        let id = data.new_id("sync_conflicts");
+        data.sync_conflicts.insert(id, SyncConflict { id: Some(id), ..conflict.clone() });
+        Ok(id)
+    }
+
+    fn get_sync_conflicts(&self) -> Result<Vec<SyncConflict>, DatabaseError> {
+        Ok(self.data.borrow().sync_conflicts.values().cloned().collect())
+    }
+
+    fn get_sync_conflict(&self, id: i64) -> Result<SyncConflict, DatabaseError> {
+        self.data.borrow().sync_conflicts.get(&id).cloned()
+            .ok_or_else(|| DatabaseError::ItemNotFound(format!("sync conflict {}", id)))
+    }
+
+    fn delete_sync_conflict(&self, id: i64) -> Result<(), DatabaseError> {
+        self.write().sync_conflicts.remove(&id);
+        Ok(())
+    }
+}
@@ -0,0 +1,479 @@
+pub mod memory;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::database::DatabaseError;
+use crate::links::LinkTarget;
+use crate::models::{Attachment, AttachmentStorage, ItemKind, JournalEntry, Note, Notebook, Revision, SyncConflict, SyncKind, SyncState, Task};
+
+pub use memory::MemoryStore;
+
+/// Items matching a search, grouped by kind
+#[derive(Debug, Default, Clone)]
+pub struct SearchResults {
+    pub tasks: Vec<Task>,
+    pub notes: Vec<Note>,
+    pub journals: Vec<JournalEntry>,
+}
+
+impl SearchResults {
+    /// Total number of matching items
+    pub fn len(&self) -> usize {
+        self.tasks.len() + self.notes.len() + self.journals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// An open transaction on a store, rolled back when dropped without being committed
+pub struct Transaction<'a>(Box<dyn Commit + 'a>);
+
+impl<'a> Transaction<'a> {
+    pub(crate) fn new(commit: impl Commit + 'a) -> Self {
+        Transaction(Box::new(commit))
+    }
+
+    /// Commit the changes made since the transaction started
+    pub fn commit(self) -> Result<(), DatabaseError> {
+        self.0.commit()
+    }
+}
+
+/// The backend side of a `Transaction`; dropping it without committing rolls the changes back
+pub(crate) trait Commit {
+    fn commit(self: Box<Self>) -> Result<(), DatabaseError>;
+}
+
+/// Storage of notebooks, items and settings
+///
+/// `Database` keeps everything in SQLite; `MemoryStore` keeps it in memory (for tests and tools
+/// that embed tnj). Listing functions take the notebook to list (`None` lists the items without a
+/// notebook) and leave out trashed items. Locking, revisions, links, attachments and sync have
+/// traits of their own, so a store for a tool that needs none of them only implements this one.
+pub trait Store {
+    /// Insert a task and return its ID
+    fn insert_task(&self, task: &Task) -> Result<i64, DatabaseError>;
+    /// Insert a note and return its ID
+    fn insert_note(&self, note: &Note) -> Result<i64, DatabaseError>;
+    /// Insert a journal entry and return its ID
+    fn insert_journal(&self, journal: &JournalEntry) -> Result<i64, DatabaseError>;
+
+    /// Get all tasks ordered by order ASC, optionally filtered by notebook_id
+    fn get_all_tasks(&self, notebook_id: Option<i64>) -> Result<Vec<Task>, DatabaseError>;
+    /// Get all tasks including archived, ordered by order ASC, optionally filtered by notebook_id
+    fn get_all_tasks_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<Task>, DatabaseError>;
+    /// Get a single task by ID
+    fn get_task(&self, id: i64) -> Result<Task, DatabaseError>;
+    /// Update an existing task
+    fn update_task(&self, task: &Task) -> Result<(), DatabaseError>;
+    /// Get the maximum order value from all tasks (-1 when there are none)
+    fn get_max_task_order(&self) -> Result<i64, DatabaseError>;
+    /// Update a task's order value
+    fn update_task_order(&self, task_id: i64, new_order: i64) -> Result<(), DatabaseError>;
+    /// Delete a task by ID (moves it to the trash)
+    fn delete_task(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Archive a task by ID
+    fn archive_task(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Set the status (todo, done) of several tasks at once
+    fn set_tasks_status(&self, ids: &[i64], status: &str) -> Result<usize, DatabaseError>;
+
+    /// Get all notes ordered by created_at DESC, optionally filtered by notebook_id
+    fn get_all_notes(&self, notebook_id: Option<i64>) -> Result<Vec<Note>, DatabaseError>;
+    /// Get all notes including archived, ordered by created_at DESC, optionally filtered by notebook_id
+    fn get_all_notes_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<Note>, DatabaseError>;
+    /// Get a single note by ID
+    fn get_note(&self, id: i64) -> Result<Note, DatabaseError>;
+    /// Update an existing note
+    fn update_note(&self, note: &Note) -> Result<(), DatabaseError>;
+    /// Delete a note by ID (moves it to the trash)
+    fn delete_note(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Archive a note by ID
+    fn archive_note(&self, id: i64) -> Result<(), DatabaseError>;
+
+    /// Get all journal entries ordered by date DESC (newest first), optionally filtered by notebook_id
+    fn get_all_journals(&self, notebook_id: Option<i64>) -> Result<Vec<JournalEntry>, DatabaseError>;
+    /// Get all journal entries including archived, ordered by date DESC, created_at DESC, optionally filtered by notebook_id
+    fn get_all_journals_including_archived(&self, notebook_id: Option<i64>) -> Result<Vec<JournalEntry>, DatabaseError>;
+    /// Get a single journal entry by ID
+    fn get_journal(&self, id: i64) -> Result<JournalEntry, DatabaseError>;
+    /// Update an existing journal entry
+    fn update_journal(&self, journal: &JournalEntry) -> Result<(), DatabaseError>;
+    /// Delete a journal entry by ID (moves it to the trash)
+    fn delete_journal(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Archive a journal entry by ID
+    fn archive_journal(&self, id: i64) -> Result<(), DatabaseError>;
+
+    /// Get every task in any notebook, including archived and trashed ones (used for backups)
+    fn dump_tasks(&self) -> Result<Vec<Task>, DatabaseError>;
+    /// Get every note in any notebook, including archived and trashed ones (used for backups)
+    fn dump_notes(&self) -> Result<Vec<Note>, DatabaseError>;
+    /// Get every journal entry in any notebook, including archived and trashed ones (used for backups)
+    fn dump_journals(&self) -> Result<Vec<JournalEntry>, DatabaseError>;
+
+    /// Find the tasks, notes and journal entries (in any notebook, archived or not) whose text or
+    /// tags contain the query, ignoring case; trashed items are left out
+    fn search(&self, query: &str) -> Result<SearchResults, DatabaseError> {
+        Ok(SearchResults {
+            tasks: self.dump_tasks()?.into_iter()
+                .filter(|task| task.deleted_at.is_none() && task.matches_search(query))
+                .collect(),
+            notes: self.dump_notes()?.into_iter()
+                .filter(|note| note.deleted_at.is_none() && note.matches_search(query))
+                .collect(),
+            journals: self.dump_journals()?.into_iter()
+                .filter(|journal| journal.deleted_at.is_none() && journal.matches_search(query))
+                .collect(),
+        })
+    }
+
+    /// Get all notebooks ordered by name ASC
+    fn get_all_notebooks(&self) -> Result<Vec<Notebook>, DatabaseError>;
+    /// Get a single notebook by ID
+    fn get_notebook(&self, id: i64) -> Result<Notebook, DatabaseError>;
+    /// Get a single notebook by name (exact match)
+    fn get_notebook_by_name(&self, name: &str) -> Result<Option<Notebook>, DatabaseError>;
+    /// Get the first notebook (for default)
+    fn get_default_notebook(&self) -> Result<Option<Notebook>, DatabaseError>;
+    /// Insert a notebook and return its ID
+    fn insert_notebook(&self, notebook: &Notebook) -> Result<i64, DatabaseError>;
+    /// Update an existing notebook
+    fn update_notebook(&self, notebook: &Notebook) -> Result<(), DatabaseError>;
+    /// Delete a notebook by ID
+    /// Sets notebook_id to NULL for all items (tasks, notes, journals) that belonged to this notebook
+    fn delete_notebook(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Get the IDs of all items of one kind in a notebook, including archived and trashed items
+    fn get_item_ids_in_notebook(&self, kind: ItemKind, notebook_id: i64) -> Result<Vec<i64>, DatabaseError>;
+    /// Re-create a deleted notebook with its original ID and move its former items back into it
+    fn restore_notebook(&self, notebook: &Notebook, items: &[(ItemKind, i64)]) -> Result<(), DatabaseError>;
+
+    /// Move items of one kind to a notebook (None moves them to "[None]")
+    /// If any ID does not exist or is in the trash, nothing is moved; repeated IDs are moved once
+    fn move_items_to_notebook(&self, kind: ItemKind, ids: &[i64], notebook_id: Option<i64>) -> Result<usize, DatabaseError>;
+    /// Archive several items of one kind at once
+    fn archive_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError>;
+    /// Unarchive several items of one kind at once
+    fn unarchive_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError>;
+    /// Delete several items of one kind at once (moves them to the trash)
+    fn delete_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError>;
+    /// Add a tag to several items of one kind at once
+    /// Items that already have the tag (case-insensitive) are left untouched
+    fn add_tag_to_items(&self, kind: ItemKind, ids: &[i64], tag: &str) -> Result<usize, DatabaseError>;
+    /// Remove a tag (case-insensitive) from several items of one kind at once
+    fn remove_tag_from_items(&self, kind: ItemKind, ids: &[i64], tag: &str) -> Result<usize, DatabaseError>;
+
+    /// Get all tasks in the trash, most recently deleted first
+    fn get_deleted_tasks(&self) -> Result<Vec<Task>, DatabaseError>;
+    /// Get all notes in the trash, most recently deleted first
+    fn get_deleted_notes(&self) -> Result<Vec<Note>, DatabaseError>;
+    /// Get all journal entries in the trash, most recently deleted first
+    fn get_deleted_journals(&self) -> Result<Vec<JournalEntry>, DatabaseError>;
+    /// Mark an item as trashed at the given time (used when restoring backups of trashed items)
+    fn set_deleted_at(&self, kind: ItemKind, id: i64, deleted_at: Option<&str>) -> Result<(), DatabaseError>;
+    /// Restore an item from the trash
+    /// Items whose notebook was deleted in the meantime are restored to "[None]"
+    fn restore_item(&self, kind: ItemKind, id: i64) -> Result<(), DatabaseError> {
+        if self.restore_items(kind, &[id])? == 0 {
+            return Err(DatabaseError::ItemNotFound(format!("{} {} in trash", kind, id)));
+        }
+        Ok(())
+    }
+    /// Restore several items of one kind from the trash at once
+    /// Returns the number of restored items (IDs that are not in the trash are skipped)
+    fn restore_items(&self, kind: ItemKind, ids: &[i64]) -> Result<usize, DatabaseError>;
+    /// Permanently delete an item that is in the trash
+    fn purge_item(&self, kind: ItemKind, id: i64) -> Result<(), DatabaseError>;
+    /// Permanently delete everything in the trash, returning the number of purged items
+    fn empty_trash(&self) -> Result<usize, DatabaseError>;
+    /// Permanently delete items that have been in the trash for more than `days` days
+    /// Returns the number of purged items
+    fn purge_trash_older_than(&self, days: u32) -> Result<usize, DatabaseError>;
+    /// Get a stored setting
+    fn get_setting(&self, key: &str) -> Result<Option<String>, DatabaseError>;
+    /// Store a setting
+    fn set_setting(&self, key: &str, value: &str) -> Result<(), DatabaseError>;
+
+    /// Get the UUIDs of all notebooks or items of one kind, by ID (rows without one are left out)
+    fn get_uuids(&self, kind: SyncKind) -> Result<HashMap<i64, String>, DatabaseError>;
+    /// Find the ID of the notebook or item with the given UUID
+    fn find_by_uuid(&self, kind: SyncKind, uuid: &str) -> Result<Option<i64>, DatabaseError>;
+    /// Set the UUID of a notebook or item (used when a notebook in git storage has the name of a local one)
+    fn set_uuid(&self, kind: SyncKind, id: i64, uuid: &str) -> Result<(), DatabaseError>;
+
+    /// Start a transaction: changes made through the store until `Transaction::commit` are
+    /// rolled back if the transaction is dropped without committing
+    fn transaction(&self) -> Result<Transaction<'_>, DatabaseError>;
+    /// Get the number of rows inserted, updated or deleted since the store was opened
+    fn total_changes(&self) -> u64;
+    /// Get a number that changes whenever another connection (e.g. `tnj serve`) commits a change
+    fn data_version(&self) -> Result<i64, DatabaseError>;
+    /// Delete all notebooks and items, with their revisions, import records, links, attachments and sync
+    /// conflicts, and the passphrase check of their locked entries (other settings and the sync state are
+    /// kept, so the deletions reach other devices)
+    /// Does not open a transaction of its own, so it can be combined with inserts in one (e.g. a restore);
+    /// attachment files are left for `remove_unused_attachment_files` to clean up after the commit
+    fn delete_all_data(&self) -> Result<(), DatabaseError>;
+
+    /// Find the item created by an earlier import of `source` (e.g. "markdown:/path/to/file.md")
+    /// Returns None if the source was never imported or its item has since been purged
+    fn get_imported_item(&self, source: &str) -> Result<Option<(ItemKind, i64)>, DatabaseError>;
+    /// Remember which item was created from an import source
+    fn record_import_source(&self, source: &str, kind: ItemKind, item_id: i64) -> Result<(), DatabaseError>;
+}
+
+/// Locking of journal entries in locked notebooks (see `crate::lock`)
+pub trait LockStore: Store {
+    /// Mark a notebook as locked or unlocked (its journal entries are locked separately)
+    fn set_notebook_locked(&self, id: i64, locked: bool) -> Result<(), DatabaseError>;
+    /// Replace the content of a journal entry when locking or unlocking it
+    /// Locking also deletes the entry's earlier versions, which would keep the content readable.
+    fn set_journal_content(&self, id: i64, content: Option<&str>, forget_history: bool) -> Result<(), DatabaseError>;
+    /// Get the IDs and contents of all journal entries of a notebook, including archived and trashed ones
+    fn get_notebook_journal_contents(&self, notebook_id: i64) -> Result<Vec<(i64, Option<String>)>, DatabaseError>;
+    /// Get the content of some locked journal entry, to check a passphrase against
+    fn get_any_locked_content(&self) -> Result<Option<String>, DatabaseError>;
+}
+
+/// Earlier versions of notes and journal entries, kept when they are edited
+pub trait HistoryStore: Store {
+    /// Get all revisions of a note or journal entry, newest first
+    fn get_revisions(&self, kind: ItemKind, item_id: i64) -> Result<Vec<Revision>, DatabaseError>;
+    /// Get a single revision by ID
+    fn get_revision(&self, id: i64) -> Result<Revision, DatabaseError>;
+    /// Restore the title and content of a note or journal entry from a revision
+    /// The version being replaced is itself kept as a revision, so a restore can be undone
+    fn restore_revision(&self, revision_id: i64) -> Result<Revision, DatabaseError>;
+    /// Get every revision of every note and journal entry, oldest first (used for backups)
+    fn dump_revisions(&self) -> Result<Vec<Revision>, DatabaseError>;
+    /// Add a revision as it is and return its ID (used for restores)
+    /// Does not open a transaction of its own, so it can be used inside one.
+    fn add_revision(&self, revision: &Revision) -> Result<i64, DatabaseError>;
+}
+
+/// Links between items (see `crate::links`), kept up to date as items are edited
+pub trait LinkStore: Store {
+    /// Find the item a link points at, ignoring items in the trash
+    /// A title matches notes first, then tasks, then journal entries (case-insensitively, active
+    /// items before archived ones); a date matches the first journal entry of that day.
+    fn resolve_link(&self, target: &LinkTarget) -> Result<Option<(ItemKind, i64)>, DatabaseError>;
+    /// Get the items (not in the trash) whose text links to the given item, ordered by kind and ID
+    fn get_backlinks(&self, kind: ItemKind, id: i64) -> Result<Vec<(ItemKind, i64)>, DatabaseError>;
+}
+
+/// Files attached to items
+pub trait AttachmentStore: Store {
+    /// Attach a file with the given contents to the attachment's item and return the attachment's ID
+    /// Does not open a transaction of its own, so it can be used inside one (e.g. a restore).
+    fn add_attachment(&self, attachment: &Attachment, data: &[u8], storage: AttachmentStorage) -> Result<i64, DatabaseError>;
+    /// Get the attachments of an item, oldest first
+    fn get_attachments(&self, kind: ItemKind, item_id: i64) -> Result<Vec<Attachment>, DatabaseError>;
+    /// Get every attachment of every item (used for backups and exports)
+    fn dump_attachments(&self) -> Result<Vec<Attachment>, DatabaseError>;
+    /// Get a single attachment by ID
+    fn get_attachment(&self, id: i64) -> Result<Attachment, DatabaseError>;
+    /// Read the contents of an attachment
+    fn read_attachment(&self, attachment: &Attachment) -> Result<Vec<u8>, DatabaseError>;
+    /// Get a file with the contents of an attachment, to open it in another application
+    fn attachment_path(&self, attachment: &Attachment) -> Result<PathBuf, DatabaseError>;
+    /// Remove an attachment (and its file, if it has one)
+    fn delete_attachment(&self, id: i64) -> Result<Attachment, DatabaseError>;
+    /// Delete attachment files that no attachment refers to any more
+    fn remove_unused_attachment_files(&self) -> Result<(), DatabaseError>;
+}
+
+/// What file-based sync (see `crate::sync`) remembers between runs
+pub trait SyncStore: Store {
+    /// Permanently delete an item, whether or not it is in the trash (used when another device purged it)
+    fn delete_item_permanently(&self, kind: ItemKind, id: i64) -> Result<(), DatabaseError>;
+    /// Get the sync state of every synced notebook and item
+    fn get_sync_states(&self) -> Result<Vec<SyncState>, DatabaseError>;
+    /// Store the sync state of a notebook or item
+    fn set_sync_state(&self, state: &SyncState) -> Result<(), DatabaseError>;
+    /// Record a version that lost to a concurrent change and return its ID
+    fn add_sync_conflict(&self, conflict: &SyncConflict) -> Result<i64, DatabaseError>;
+    /// Get all unresolved sync conflicts, oldest first
+    fn get_sync_conflicts(&self) -> Result<Vec<SyncConflict>, DatabaseError>;
+    /// Get a single sync conflict by ID
+    fn get_sync_conflict(&self, id: i64) -> Result<SyncConflict, DatabaseError>;
+    /// Delete a sync conflict once it has been resolved
+    fn delete_sync_conflict(&self, id: i64) -> Result<(), DatabaseError>;
+}
+
+/// A store with everything the TUI, the CLI and sync need
+pub trait Backend: LockStore + HistoryStore + LinkStore + AttachmentStore + SyncStore {}
+
+impl<T: LockStore + HistoryStore + LinkStore + AttachmentStore + SyncStore + ?Sized> Backend for T {}
+
+/// Write the contents of an attachment to a temporary file named after it (for attachments that have no file of their own)
+pub(crate) fn temporary_attachment_file(attachment: &Attachment, data: &[u8]) -> Result<PathBuf, DatabaseError> {
+    let dir = std::env::temp_dir().join("tnj-attachments");
+    let file_name = std::path::Path::new(&attachment.file_name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+    let path = dir.join(format!("{}-{}", attachment.id.unwrap_or_default(), file_name));
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&path, data))
+        .map_err(|e| DatabaseError::AttachmentFileError(e.to_string()))?;
+    Ok(path)
+}
+
+/// Get the UUID to store for a new row: the given one (e.g. from a backup or another device) or a new random one
+pub(crate) fn uuid_or_new(uuid: &Option<String>) -> String {
+    uuid.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    /// Run a check against both backends
+    fn on_each_store(check: impl Fn(&dyn Backend)) {
+        check(&MemoryStore::new());
+        check(&Database::new(":memory:").unwrap());
+    }
+
+    #[test]
+    fn items_are_inserted_updated_and_listed() {
+        on_each_store(|store| {
+            let id = store.insert_task(&Task::new("Write report".to_string())).unwrap();
+            let mut task = store.get_task(id).unwrap();
+            assert!(task.uuid.is_some());
+            task.title = "Send report".to_string();
+            store.update_task(&task).unwrap();
+            assert_eq!(store.get_task(id).unwrap().title, "Send report");
+            assert_eq!(store.get_all_tasks(None).unwrap().len(), 1);
+
+            task.id = None;
+            assert!(matches!(store.update_task(&task), Err(DatabaseError::MissingId)));
+            assert!(store.get_note(id).is_err());
+        });
+    }
+
+    #[test]
+    fn ids_of_deleted_rows_are_not_reused() {
+        on_each_store(|store| {
+            store.insert_note(&Note::new("First".to_string())).unwrap();
+            let newest = store.insert_note(&Note::new("Second".to_string())).unwrap();
+            store.delete_note(newest).unwrap();
+            store.purge_item(ItemKind::Note, newest).unwrap();
+            assert!(store.insert_note(&Note::new("Third".to_string())).unwrap() > newest);
+        });
+    }
+
+    #[test]
+    fn trashed_items_are_restored_or_purged() {
+        on_each_store(|store| {
+            let id = store.insert_note(&Note::new("Draft".to_string())).unwrap();
+            store.delete_note(id).unwrap();
+            assert!(store.get_all_notes(None).unwrap().is_empty());
+            assert_eq!(store.get_deleted_notes().unwrap().len(), 1);
+
+            store.restore_item(ItemKind::Note, id).unwrap();
+            assert_eq!(store.get_all_notes(None).unwrap().len(), 1);
+            assert!(store.purge_item(ItemKind::Note, id).is_err());
+
+            store.delete_note(id).unwrap();
+            assert_eq!(store.empty_trash().unwrap(), 1);
+            assert!(store.get_note(id).is_err());
+        });
+    }
+
+    #[test]
+    fn search_matches_text_and_tags_but_not_the_trash() {
+        on_each_store(|store| {
+            let mut note = Note::new("Groceries".to_string());
+            note.content = Some("Buy MILK".to_string());
+            store.insert_note(&note).unwrap();
+            let mut task = Task::new("Call the bank".to_string());
+            task.tags = Some("milk-run".to_string());
+            store.insert_task(&task).unwrap();
+            let trashed = store.insert_journal(&JournalEntry::new("2024-03-05".to_string())).unwrap();
+            let mut journal = store.get_journal(trashed).unwrap();
+            journal.content = Some("milk".to_string());
+            store.update_journal(&journal).unwrap();
+            store.delete_journal(trashed).unwrap();
+
+            let results = store.search("milk").unwrap();
+            assert_eq!((results.notes.len(), results.tasks.len(), results.journals.len()), (1, 1, 0));
+        });
+    }
+
+    #[test]
+    fn deleting_a_notebook_keeps_its_items() {
+        on_each_store(|store| {
+            let work = store.insert_notebook(&Notebook::new("Work".to_string())).unwrap();
+            assert_eq!(store.get_notebook_by_name("Work").unwrap().and_then(|n| n.id), Some(work));
+            let id = store.insert_task(&Task::new("Plan".to_string())).unwrap();
+            store.move_items_to_notebook(ItemKind::Task, &[id], Some(work)).unwrap();
+            assert_eq!(store.get_all_tasks(Some(work)).unwrap().len(), 1);
+
+            store.delete_notebook(work).unwrap();
+            assert!(store.get_all_notebooks().unwrap().is_empty());
+            assert_eq!(store.get_task(id).unwrap().notebook_id, None);
+        });
+    }
+
+    #[test]
+    fn moving_refuses_trashed_items_and_counts_each_item_once() {
+        on_each_store(|store| {
+            let work = store.insert_notebook(&Notebook::new("Work".to_string())).unwrap();
+            let id = store.insert_task(&Task::new("Plan".to_string())).unwrap();
+            assert_eq!(store.move_items_to_notebook(ItemKind::Task, &[id, id], Some(work)).unwrap(), 1);
+
+            let trashed = store.insert_task(&Task::new("Old plan".to_string())).unwrap();
+            store.delete_task(trashed).unwrap();
+            let result = store.move_items_to_notebook(ItemKind::Task, &[id, trashed], None);
+            assert!(matches!(result, Err(DatabaseError::ItemNotFound(_))));
+            assert_eq!(store.get_task(id).unwrap().notebook_id, Some(work));
+            assert_eq!(store.get_task(trashed).unwrap().notebook_id, None);
+        });
+    }
+
+    #[test]
+    fn rows_are_found_by_uuid() {
+        on_each_store(|store| {
+            let id = store.insert_note(&Note::new("Ideas".to_string())).unwrap();
+            let uuid = store.get_uuids(SyncKind::Note).unwrap()[&id].clone();
+            assert_eq!(store.find_by_uuid(SyncKind::Note, &uuid).unwrap(), Some(id));
+            assert_eq!(store.find_by_uuid(SyncKind::Task, &uuid).unwrap(), None);
+
+            store.set_uuid(SyncKind::Note, id, "shared-uuid").unwrap();
+            assert_eq!(store.find_by_uuid(SyncKind::Note, "shared-uuid").unwrap(), Some(id));
+        });
+    }
+
+    #[test]
+    fn deleting_all_data_keeps_settings_but_the_lock_check() {
+        on_each_store(|store| {
+            store.insert_task(&Task::new("Call Bob".to_string())).unwrap();
+            store.set_setting("sync_device", "laptop").unwrap();
+            store.set_setting(crate::lock::CHECK_SETTING, "tnj-locked:v1:check").unwrap();
+            store.delete_all_data().unwrap();
+            assert!(store.dump_tasks().unwrap().is_empty());
+            assert_eq!(store.get_setting("sync_device").unwrap().as_deref(), Some("laptop"));
+            assert_eq!(store.get_setting(crate::lock::CHECK_SETTING).unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn edits_keep_revisions_and_links() {
+        on_each_store(|store| {
+            let target = store.insert_note(&Note::new("Ideas".to_string())).unwrap();
+            let source = store.insert_note(&Note::new("Plans".to_string())).unwrap();
+            let mut note = store.get_note(source).unwrap();
+            note.content = Some("See [[ideas]]".to_string());
+            store.update_note(&note).unwrap();
+            assert_eq!(store.get_backlinks(ItemKind::Note, target).unwrap(), vec![(ItemKind::Note, source)]);
+
+            let revisions = store.get_revisions(ItemKind::Note, source).unwrap();
+            assert_eq!(revisions.len(), 1);
+            store.restore_revision(revisions[0].id.unwrap()).unwrap();
+            assert_eq!(store.get_note(source).unwrap().content, None);
+            assert!(store.get_backlinks(ItemKind::Note, target).unwrap().is_empty());
+        });
+    }
+}
@@ -0,0 +1,614 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use blake2::{Blake2s256, Digest};
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::database::DatabaseError;
+use crate::models::{JournalEntry, Note, Notebook, SyncConflict, SyncKind, SyncState, Task};
+use crate::store::{Backend, Store};
+
+/// Setting holding the random ID of this device (also the name of its log file)
+const DEVICE_SETTING: &str = "sync_device";
+/// Setting holding this device's Lamport clock
+const CLOCK_SETTING: &str = "sync_clock";
+/// Prefix of the settings holding how many lines of another device's log have been merged
+const READ_SETTING_PREFIX: &str = "sync_read:";
+
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] DatabaseError),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("No sync directory is set (set sync_dir in config.toml to a folder shared between devices)")]
+    NoSyncDir,
+}
+
+/// One line of a device's change log: a new version of a notebook or item, or its deletion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub device: String,
+    pub clock: i64, // Lamport timestamp of the change
+    pub kind: SyncKind,
+    pub uuid: String,
+    pub versions: BTreeMap<String, i64>, // Version vector of the item after the change
+    pub item: Option<Value>,             // None when the item was deleted
+}
+
+/// What one sync did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub sent: usize,
+    pub received: usize,
+    pub conflicts: usize,
+}
+
+impl fmt::Display for SyncSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Sent {} change{}, merged {} from other devices",
+            self.sent,
+            if self.sent == 1 { "" } else { "s" },
+            self.received
+        )?;
+        if self.conflicts > 0 {
+            write!(f, ", {} new conflict{}", self.conflicts, if self.conflicts == 1 { "" } else { "s" })?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the ID of this device, creating it on first use
+pub fn device_id(db: &dyn Store) -> Result<String, SyncError> {
+    if let Some(device) = db.get_setting(DEVICE_SETTING)? {
+        return Ok(device);
+    }
+    let device = uuid::Uuid::new_v4().simple().to_string();
+    db.set_setting(DEVICE_SETTING, &device)?;
+    Ok(device)
+}
+
+/// Write this device's changes to its log in `dir` and merge the logs of the other devices
+///
+/// Every device only appends to its own `<device>.jsonl`, so the folder can be shared with any
+/// file sync tool. Each change carries the item's version vector: a change that already
+/// includes the local version replaces it, and of two concurrent changes the one with the
+/// higher (clock, device) wins on every device. The device whose version lost keeps it as a
+/// conflict when the two differ in more than their modification time.
+pub fn sync(db: &dyn Backend, dir: &Path) -> Result<SyncSummary, SyncError> {
+    fs::create_dir_all(dir)?;
+    let device = device_id(db)?;
+    let own_log = dir.join(format!("{}.jsonl", device));
+
+    // A restored database may be behind its own log; never reuse a clock value
+    let stored_clock = db.get_setting(CLOCK_SETTING)?.and_then(|clock| clock.parse().ok()).unwrap_or(0);
+    let mut clock = read_log(&own_log, 0)?.0.iter().map(|change| change.clock).fold(stored_clock, i64::max);
+
+    let mut states: HashMap<String, SyncState> = db.get_sync_states()?
+        .into_iter()
+        .map(|state| (state.uuid.clone(), state))
+        .collect();
+    let mut summary = SyncSummary::default();
+
+    // Log every local change since the last sync
+    let mut changes = Vec::new();
+    let mut present = HashSet::new();
+    for (kind, uuid, item) in local_versions(db)? {
+        present.insert(uuid.clone());
+        let state = states.get(&uuid);
+        if state.is_some_and(|state| state.hash.as_deref() == Some(hash_version(&item).as_str())) {
+            continue;
+        }
+        clock += 1;
+        let mut versions = state.map(|state| state.versions.clone()).unwrap_or_default();
+        versions.insert(device.clone(), clock);
+        changes.push(Change { device: device.clone(), clock, kind, uuid, versions, item: Some(item) });
+    }
+    let mut deleted: Vec<&SyncState> = states.values()
+        .filter(|state| state.hash.is_some() && !present.contains(&state.uuid))
+        .collect();
+    deleted.sort_by_key(|state| state.kind);
+    for state in deleted {
+        clock += 1;
+        let mut versions = state.versions.clone();
+        versions.insert(device.clone(), clock);
+        changes.push(Change { device: device.clone(), clock, kind: state.kind, uuid: state.uuid.clone(), versions, item: None });
+    }
+
+    append_changes(&own_log, &changes)?;
+    for change in &changes {
+        let state = SyncState {
+            uuid: change.uuid.clone(),
+            kind: change.kind,
+            clock: change.clock,
+            device: change.device.clone(),
+            versions: change.versions.clone(),
+            hash: change.item.as_ref().map(hash_version),
+        };
+        db.set_sync_state(&state)?;
+        states.insert(state.uuid.clone(), state);
+    }
+    summary.sent = changes.len();
+    db.set_setting(CLOCK_SETTING, &clock.to_string())?;
+
+    // Merge the changes of the other devices that have not been merged yet, oldest first
+    let mut incoming = Vec::new();
+    let mut read_lines = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(other) = path.file_stem().map(|stem| stem.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if path.extension().is_none_or(|extension| extension != "jsonl") || other == device {
+            continue;
+        }
+        let setting = format!("{}{}", READ_SETTING_PREFIX, other);
+        let already_read = db.get_setting(&setting)?.and_then(|lines| lines.parse().ok()).unwrap_or(0);
+        let (other_changes, lines) = read_log(&path, already_read)?;
+        incoming.extend(other_changes);
+        read_lines.push((setting, lines));
+    }
+    incoming.sort_by(|a, b| (a.clock, &a.device).cmp(&(b.clock, &b.device)));
+
+    for change in incoming {
+        clock = clock.max(change.clock);
+        match merge_change(db, &mut states, change)? {
+            Merge::Skipped => {}
+            Merge::Applied => summary.received += 1,
+            Merge::Conflict => {
+                summary.received += 1;
+                summary.conflicts += 1;
+            }
+        }
+    }
+    for (setting, lines) in read_lines {
+        db.set_setting(&setting, &lines.to_string())?;
+    }
+    db.set_setting(CLOCK_SETTING, &clock.to_string())?;
+
+    Ok(summary)
+}
+
+/// Outcome of merging one change from another device
+enum Merge {
+    Skipped,  // The local version already includes it
+    Applied,  // It replaced the local version (or lost to it)
+    Conflict, // It was concurrent with the local version, replaced it, and the two differ
+}
+
+/// Merge one change from another device into the database
+fn merge_change(db: &dyn Backend, states: &mut HashMap<String, SyncState>, change: Change) -> Result<Merge, SyncError> {
+    let Some(state) = states.get(&change.uuid) else {
+        apply_version(db, change.kind, &change.uuid, change.item.as_ref())?;
+        record_state(db, states, &change, change.versions.clone())?;
+        return Ok(Merge::Applied);
+    };
+    if includes(&state.versions, &change.versions) {
+        return Ok(Merge::Skipped);
+    }
+    if includes(&change.versions, &state.versions) {
+        apply_version(db, change.kind, &change.uuid, change.item.as_ref())?;
+        record_state(db, states, &change, change.versions.clone())?;
+        return Ok(Merge::Applied);
+    }
+
+    // Concurrent changes: the same version wins on every device
+    let mut versions = state.versions.clone();
+    for (device, &count) in &change.versions {
+        let entry = versions.entry(device.clone()).or_insert(0);
+        *entry = (*entry).max(count);
+    }
+    let incoming_wins = (change.clock, &change.device) > (state.clock, &state.device);
+    let local = current_version(db, change.kind, &change.uuid)?;
+    if incoming_wins {
+        apply_version(db, change.kind, &change.uuid, change.item.as_ref())?;
+    }
+
+    let winner = if incoming_wins { change.clone() } else {
+        Change { device: state.device.clone(), clock: state.clock, ..change.clone() }
+    };
+    record_state(db, states, &winner, versions)?;
+
+    // Only the device whose version lost keeps it, so each conflict is reviewed once: the other
+    // device records it when this device's version reaches it
+    if !incoming_wins || without_timestamp(change.item.as_ref()) == without_timestamp(local.as_ref()) {
+        return Ok(Merge::Applied);
+    }
+    let title = change.item.as_ref().or(local.as_ref()).map(version_title).unwrap_or_default();
+    db.add_sync_conflict(&SyncConflict {
+        id: None,
+        kind: change.kind,
+        uuid: change.uuid,
+        title,
+        other_version: local.map(|local| local.to_string()),
+        other_device: change.device,
+        created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    })?;
+    Ok(Merge::Conflict)
+}
+
+/// Store that an item is at the version of `change`, with the hash of what it now looks like locally
+fn record_state(
+    db: &dyn Backend,
+    states: &mut HashMap<String, SyncState>,
+    change: &Change,
+    versions: BTreeMap<String, i64>,
+) -> Result<(), SyncError> {
+    let state = SyncState {
+        uuid: change.uuid.clone(),
+        kind: change.kind,
+        clock: change.clock,
+        device: change.device.clone(),
+        versions,
+        hash: current_version(db, change.kind, &change.uuid)?.as_ref().map(hash_version),
+    };
+    db.set_sync_state(&state)?;
+    states.insert(state.uuid.clone(), state);
+    Ok(())
+}
+
+/// Check whether version vector `a` includes every change counted in `b`
+fn includes(a: &BTreeMap<String, i64>, b: &BTreeMap<String, i64>) -> bool {
+    b.iter().all(|(device, &count)| a.get(device).is_some_and(|&seen| seen >= count))
+}
+
+/// Hash a synced version, to tell whether an item changed since it was last logged
+fn hash_version(item: &Value) -> String {
+    Blake2s256::digest(item.to_string().as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// A version without its modification time (conflicts are only kept for other differences)
+fn without_timestamp(item: Option<&Value>) -> Option<Value> {
+    let mut item = item?.clone();
+    if let Some(fields) = item.as_object_mut() {
+        fields.remove("updated_at");
+    }
+    Some(item)
+}
+
+/// Append changes to a log file, one JSON object per line
+fn append_changes(path: &Path, changes: &[Change]) -> Result<(), SyncError> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut lines = String::new();
+    for change in changes {
+        lines.push_str(&serde_json::to_string(change)?);
+        lines.push('\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(lines.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Read the changes in a log after its first `skip` lines
+/// Returns the changes and the number of complete lines in the file; a last line without a
+/// newline is still being written (or synced) and is left for the next sync.
+fn read_log(path: &Path, skip: usize) -> Result<(Vec<Change>, usize), SyncError> {
+    if !path.exists() {
+        return Ok((Vec::new(), 0));
+    }
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut changes = Vec::new();
+    let mut lines = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || !line.ends_with('\n') {
+            break;
+        }
+        lines += 1;
+        // Damaged lines are skipped rather than stopping the sync for good
+        if lines > skip && let Ok(change) = serde_json::from_str::<Change>(&line) {
+            changes.push(change);
+        }
+    }
+    Ok((changes, lines))
+}
+
+/// Turn a notebook or item into its synced form: its fields without the local ID and the UUID
+/// (which changes refer to it by), with the notebook referred to by UUID. Returns the local ID as well.
+pub fn to_synced<T: Serialize>(item: &T, notebook_uuids: &HashMap<i64, String>) -> Result<(i64, Value), SyncError> {
+    let mut value = serde_json::to_value(item)?;
+    let fields = value.as_object_mut().map(std::mem::take).unwrap_or_default();
+    let mut synced = Map::new();
+    let mut id = 0;
+    for (key, field) in fields {
+        match key.as_str() {
+            "id" => id = field.as_i64().unwrap_or_default(),
+            "uuid" => {}
+            "notebook_id" => {
+                let notebook = field.as_i64().and_then(|id| notebook_uuids.get(&id)).cloned();
+                synced.insert("notebook".to_string(), notebook.map(Value::String).unwrap_or(Value::Null));
+            }
+            _ => {
+                synced.insert(key, field);
+            }
+        }
+    }
+    Ok((id, Value::Object(synced)))
+}
+
+/// Get the synced form of every notebook and item in the database
+fn local_versions(db: &dyn Store) -> Result<Vec<(SyncKind, String, Value)>, SyncError> {
+    let notebook_uuids = db.get_uuids(SyncKind::Notebook)?;
+
+    let mut versions = Vec::new();
+    let mut add = |kind: SyncKind, items: Vec<Value>| -> Result<(), SyncError> {
+        let uuids = db.get_uuids(kind)?;
+        for item in items {
+            let (id, item) = to_synced(&item, &notebook_uuids)?;
+            if let Some(uuid) = uuids.get(&id) {
+                versions.push((kind, uuid.clone(), item));
+            }
+        }
+        Ok(())
+    };
+    add(SyncKind::Notebook, to_values(db.get_all_notebooks()?)?)?;
+    add(SyncKind::Task, to_values(db.dump_tasks()?)?)?;
+    add(SyncKind::Note, to_values(db.dump_notes()?)?)?;
+    add(SyncKind::Journal, to_values(db.dump_journals()?)?)?;
+    Ok(versions)
+}
+
+fn to_values<T: Serialize>(items: Vec<T>) -> Result<Vec<Value>, SyncError> {
+    items.iter().map(|item| serde_json::to_value(item).map_err(SyncError::from)).collect()
+}
+
+/// Get the synced form of a notebook or item as it is now (None if it does not exist)
+pub fn current_version(db: &dyn Store, kind: SyncKind, uuid: &str) -> Result<Option<Value>, SyncError> {
+    let Some(id) = db.find_by_uuid(kind, uuid)? else {
+        return Ok(None);
+    };
+    let notebook_uuids = db.get_uuids(SyncKind::Notebook)?;
+    let item = match kind {
+        SyncKind::Notebook => to_synced(&db.get_notebook(id)?, &notebook_uuids)?,
+        SyncKind::Task => to_synced(&db.get_task(id)?, &notebook_uuids)?,
+        SyncKind::Note => to_synced(&db.get_note(id)?, &notebook_uuids)?,
+        SyncKind::Journal => to_synced(&db.get_journal(id)?, &notebook_uuids)?,
+    };
+    Ok(Some(item.1))
+}
+
+/// Turn a synced version back into a model with local IDs
+fn from_synced<T: DeserializeOwned>(db: &dyn Store, item: &Value, id: Option<i64>, uuid: &str) -> Result<T, SyncError> {
+    let mut fields = item.as_object().cloned().unwrap_or_default();
+    if let Some(notebook) = fields.remove("notebook") {
+        let notebook_id = match notebook.as_str() {
+            Some(uuid) => db.find_by_uuid(SyncKind::Notebook, uuid)?,
+            None => None,
+        };
+        fields.insert("notebook_id".to_string(), notebook_id.into());
+    }
+    fields.insert("id".to_string(), id.into());
+    fields.insert("uuid".to_string(), uuid.into());
+    Ok(serde_json::from_value(Value::Object(fields))?)
+}
+
+/// Make the local copy of a notebook or item match a synced version (None deletes it)
+pub fn apply_version(db: &dyn Backend, kind: SyncKind, uuid: &str, item: Option<&Value>) -> Result<(), SyncError> {
+    let id = db.find_by_uuid(kind, uuid)?;
+    let Some(item) = item else {
+        if let Some(id) = id {
+            match kind.item_kind() {
+                Some(item_kind) => db.delete_item_permanently(item_kind, id)?,
+                None => db.delete_notebook(id)?,
+            }
+        }
+        return Ok(());
+    };
+
+    let (new_id, deleted_at) = match kind {
+        SyncKind::Notebook => {
+            let notebook: Notebook = from_synced(db, item, id, uuid)?;
+            let new_id = match id {
+                Some(id) => {
+                    // Locking sets the update time, so the version's time is written after it
+                    db.set_notebook_locked(id, notebook.locked)?;
+                    db.update_notebook(&notebook)?;
+                    id
+                }
+                None => db.insert_notebook(&notebook)?,
+            };
+            (new_id, None)
+        }
+        SyncKind::Task => {
+            let task: Task = from_synced(db, item, id, uuid)?;
+            let new_id = match id {
+                Some(id) => {
+                    db.update_task(&task)?;
+                    id
+                }
+                None => db.insert_task(&task)?,
+            };
+            (new_id, Some(task.deleted_at))
+        }
+        SyncKind::Note => {
+            let note: Note = from_synced(db, item, id, uuid)?;
+            let new_id = match id {
+                Some(id) => {
+                    db.update_note(&note)?;
+                    id
+                }
+                None => db.insert_note(&note)?,
+            };
+            (new_id, Some(note.deleted_at))
+        }
+        SyncKind::Journal => {
+            let journal: JournalEntry = from_synced(db, item, id, uuid)?;
+            let new_id = match id {
+                Some(id) => {
+                    db.update_journal(&journal)?;
+                    id
+                }
+                None => db.insert_journal(&journal)?,
+            };
+            (new_id, Some(journal.deleted_at))
+        }
+    };
+    if let (Some(item_kind), Some(deleted_at)) = (kind.item_kind(), deleted_at) {
+        db.set_deleted_at(item_kind, new_id, deleted_at.as_deref())?;
+    }
+    Ok(())
+}
+
+/// Get the title of a synced version (a notebook's name, a journal entry's date if it has no title)
+pub fn version_title(item: &Value) -> String {
+    ["title", "name", "date"]
+        .iter()
+        .find_map(|key| item.get(*key).and_then(Value::as_str).filter(|title| !title.is_empty()))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Get the names of the notebooks by UUID, for showing which notebook a synced version is in
+pub fn notebook_names(db: &dyn Store) -> Result<HashMap<String, String>, SyncError> {
+    let uuids = db.get_uuids(SyncKind::Notebook)?;
+    Ok(db.get_all_notebooks()?
+        .into_iter()
+        .filter_map(|notebook| Some((uuids.get(&notebook.id?)?.clone(), notebook.name)))
+        .collect())
+}
+
+/// Show a synced version as text for comparing two versions: one line per field, then the
+/// content or description (locked content is not shown)
+pub fn version_text(item: Option<&Value>, notebook_names: &HashMap<String, String>) -> String {
+    let Some(fields) = item.and_then(Value::as_object) else {
+        return "(deleted)".to_string();
+    };
+    let mut text = String::new();
+    for (key, value) in fields {
+        if matches!(key.as_str(), "content" | "description" | "created_at" | "updated_at" | "order") || value.is_null() {
+            continue;
+        }
+        let value = match value {
+            Value::String(uuid) if key == "notebook" => notebook_names.get(uuid).unwrap_or(uuid).clone(),
+            Value::String(value) => value.clone(),
+            other => other.to_string(),
+        };
+        text.push_str(&format!("{}: {}\n", key, value));
+    }
+    if let Some(body) = fields.get("content").or(fields.get("description")).and_then(Value::as_str) {
+        text.push('\n');
+        if crate::lock::is_locked(body) {
+            text.push_str("(locked content)\n");
+        } else {
+            text.push_str(body);
+        }
+    }
+    text
+}
+
+/// Resolve a conflict by replacing the current version with the version that was not kept
+/// The replacement is a new local change, so the next sync sends it to the other devices.
+pub fn use_other_version(db: &dyn Backend, conflict: &SyncConflict) -> Result<(), SyncError> {
+    let mut other: Option<Value> = conflict.other_version.as_deref().map(serde_json::from_str).transpose()?;
+    if let Some(fields) = other.as_mut().and_then(Value::as_object_mut) {
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        fields.insert("updated_at".to_string(), Value::String(now));
+    }
+    apply_version(db, conflict.kind, &conflict.uuid, other.as_ref())?;
+    if let Some(id) = conflict.id {
+        db.delete_sync_conflict(id)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::models::ItemKind;
+    use crate::store::SyncStore;
+
+    fn device() -> Database {
+        Database::new(":memory:").unwrap()
+    }
+
+    /// Everything a device holds, in synced form, to compare devices by
+    fn contents(db: &dyn Store) -> Vec<(SyncKind, String, Value)> {
+        let mut versions = local_versions(db).unwrap();
+        versions.sort_by(|a, b| a.1.cmp(&b.1));
+        versions
+    }
+
+    #[test]
+    fn concurrent_edits_converge_with_one_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (device(), device());
+        let id = a.insert_task(&Task::new("Original".to_string())).unwrap();
+        sync(&a, dir.path()).unwrap();
+        sync(&b, dir.path()).unwrap();
+        let b_id = b.dump_tasks().unwrap()[0].id.unwrap();
+
+        let mut task = a.get_task(id).unwrap();
+        task.title = "Edited on A".to_string();
+        a.update_task(&task).unwrap();
+        let mut task = b.get_task(b_id).unwrap();
+        task.title = "Edited on B".to_string();
+        b.update_task(&task).unwrap();
+
+        sync(&a, dir.path()).unwrap();
+        sync(&b, dir.path()).unwrap();
+        sync(&a, dir.path()).unwrap();
+
+        assert_eq!(contents(&a), contents(&b));
+        let conflicts = a.get_sync_conflicts().unwrap().len() + b.get_sync_conflicts().unwrap().len();
+        assert_eq!(conflicts, 1);
+    }
+
+    #[test]
+    fn delete_racing_an_edit_converges() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (device(), device());
+        let id = a.insert_note(&Note::new("Shared".to_string())).unwrap();
+        sync(&a, dir.path()).unwrap();
+        sync(&b, dir.path()).unwrap();
+        let b_id = b.dump_notes().unwrap()[0].id.unwrap();
+
+        a.delete_item_permanently(ItemKind::Note, id).unwrap();
+        let mut note = b.get_note(b_id).unwrap();
+        note.content = Some("Edited on B".to_string());
+        b.update_note(&note).unwrap();
+
+        sync(&a, dir.path()).unwrap();
+        sync(&b, dir.path()).unwrap();
+        sync(&a, dir.path()).unwrap();
+
+        assert_eq!(contents(&a), contents(&b));
+        assert_eq!(a.dump_notes().unwrap().len(), b.dump_notes().unwrap().len());
+    }
+
+    #[test]
+    fn notebook_created_and_used_in_one_sync_arrives_together() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (device(), device());
+        let notebook_id = a.insert_notebook(&Notebook::new("Work".to_string())).unwrap();
+        let mut task = Task::new("In Work".to_string());
+        task.notebook_id = Some(notebook_id);
+        a.insert_task(&task).unwrap();
+
+        sync(&a, dir.path()).unwrap();
+        sync(&b, dir.path()).unwrap();
+
+        let notebook = b.get_notebook_by_name("Work").unwrap().unwrap();
+        let tasks = b.get_all_tasks(notebook.id).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "In Work");
+        assert_eq!(contents(&a), contents(&b));
+    }
+}
@@ -1,9 +1,15 @@
-use crate::{Config, Database, models::{Task, Note, JournalEntry, Notebook}};
+use crate::{Config, models::{Task, Note, JournalEntry, Notebook, ItemKind, Revision, Attachment, SyncConflict}};
 use crate::config::{ConfigError, Theme};
+use crate::backups::BackupFrequency;
 use crate::database::DatabaseError;
+use crate::store::Backend;
+use crate::links::parse_links;
+use crate::lock::{self, LockError, Unlocker};
+use crate::sync::SyncError;
 use crate::tui::widgets::editor::Editor;
 use ratatui::widgets::ListState;
 use std::cmp;
+use std::collections::{BTreeSet, HashMap};
 use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -84,7 +90,7 @@ pub enum NotebookModalField {
 pub struct NotebookModalState {
     pub mode: NotebookModalMode,
     pub selected_index: usize, // 0 = "[None]", 1+ = actual notebooks
-    pub actions_selected_index: usize, // 0 = Add, 1 = Rename, 2 = Delete, 3 = Switch
+    pub actions_selected_index: usize, // 0 = Add, 1 = Rename, 2 = Delete, 3 = Switch, 4 = Lock/Unlock
     pub name_editor: Editor,
     pub list_state: ListState,
     pub current_field: NotebookModalField,
@@ -100,6 +106,12 @@ pub enum Mode {
     MarkdownHelp,
     Filter,
     NotebookModal,
+    MoveToNotebook,
+    BulkActions,
+    Trash,
+    History,
+    Attachments,
+    SyncConflicts,
 }
 
 #[derive(Debug, Clone)]
@@ -180,6 +192,80 @@ pub enum SelectedItem {
     Journal(JournalEntry),
 }
 
+impl SelectedItem {
+    /// Get the kind of the selected item
+    pub fn kind(&self) -> ItemKind {
+        match self {
+            SelectedItem::Task(_) => ItemKind::Task,
+            SelectedItem::Note(_) => ItemKind::Note,
+            SelectedItem::Journal(_) => ItemKind::Journal,
+        }
+    }
+
+    /// Get the database ID of the selected item
+    pub fn id(&self) -> Option<i64> {
+        match self {
+            SelectedItem::Task(task) => task.id,
+            SelectedItem::Note(note) => note.id,
+            SelectedItem::Journal(journal) => journal.id,
+        }
+    }
+
+    /// Get the notebook the selected item belongs to
+    pub fn notebook_id(&self) -> Option<i64> {
+        match self {
+            SelectedItem::Task(task) => task.notebook_id,
+            SelectedItem::Note(note) => note.notebook_id,
+            SelectedItem::Journal(journal) => journal.notebook_id,
+        }
+    }
+
+    /// Get the title shown for the item (journal entries without a title use their date)
+    pub fn display_title(&self) -> &str {
+        match self {
+            SelectedItem::Task(task) => &task.title,
+            SelectedItem::Note(note) => &note.title,
+            SelectedItem::Journal(journal) => journal.title.as_deref().unwrap_or(&journal.date),
+        }
+    }
+
+    /// Check whether the item is archived
+    pub fn is_archived(&self) -> bool {
+        match self {
+            SelectedItem::Task(task) => task.archived,
+            SelectedItem::Note(note) => note.archived,
+            SelectedItem::Journal(journal) => journal.archived,
+        }
+    }
+
+    /// Get when the item was last saved
+    pub fn updated_at(&self) -> &str {
+        match self {
+            SelectedItem::Task(task) => &task.updated_at,
+            SelectedItem::Note(note) => &note.updated_at,
+            SelectedItem::Journal(journal) => &journal.updated_at,
+        }
+    }
+
+    /// Get the text that may contain `[[...]]` links (task description, note or journal content)
+    pub fn link_text(&self) -> Option<&str> {
+        match self {
+            SelectedItem::Task(task) => task.description.as_deref(),
+            SelectedItem::Note(note) => note.content.as_deref(),
+            SelectedItem::Journal(journal) => journal.content.as_deref(),
+        }
+    }
+
+    /// Get when the item was moved to the trash (None if it is not in the trash)
+    pub fn deleted_at(&self) -> Option<&str> {
+        match self {
+            SelectedItem::Task(task) => task.deleted_at.as_deref(),
+            SelectedItem::Note(note) => note.deleted_at.as_deref(),
+            SelectedItem::Journal(journal) => journal.deleted_at.as_deref(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UiState {
     pub current_tab: Tab,
@@ -258,6 +344,8 @@ pub struct SettingsState {
     pub theme_list_state: ListState,
     pub sidebar_width_index: usize,
     pub display_mode_index: usize,
+    // Backup settings: frequency options first, then the number of backups to keep
+    pub backup_setting_index: usize,
     // Color editor state
     pub color_field_index: usize,
     pub color_cycle_indices: [usize; 5],
@@ -280,6 +368,7 @@ impl Default for SettingsState {
             theme_list_state: ListState::default(),
             sidebar_width_index: 0,
             display_mode_index: 0,
+            backup_setting_index: 0,
             color_field_index: 0,
             color_cycle_indices: [0; 5],
             color_input_mode: false,
@@ -292,10 +381,259 @@ impl Default for SettingsState {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct MoveNotebookState {
+    pub selected_index: usize, // 0 = "[None]", 1+ = actual notebooks
+    pub list_state: ListState,
+}
+
+/// Actions that can be applied to all items in a multi-selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkAction {
+    Archive,
+    Unarchive,
+    Delete,
+    MarkDone,
+    AddTag,
+    RemoveTag,
+    MoveToNotebook,
+}
+
+impl BulkAction {
+    /// Get the actions available for items of the given kind, in menu order
+    pub fn available_for(kind: ItemKind) -> Vec<BulkAction> {
+        let mut actions = vec![BulkAction::Archive, BulkAction::Unarchive, BulkAction::Delete];
+        if kind == ItemKind::Task {
+            actions.push(BulkAction::MarkDone);
+        }
+        actions.extend([BulkAction::AddTag, BulkAction::RemoveTag, BulkAction::MoveToNotebook]);
+        actions
+    }
+
+    /// Label shown in the bulk actions menu
+    pub fn label(&self) -> &'static str {
+        match self {
+            BulkAction::Archive => "Archive",
+            BulkAction::Unarchive => "Unarchive",
+            BulkAction::Delete => "Delete",
+            BulkAction::MarkDone => "Mark done",
+            BulkAction::AddTag => "Add tag",
+            BulkAction::RemoveTag => "Remove tag",
+            BulkAction::MoveToNotebook => "Move to notebook",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BulkActionStep {
+    Choose,
+    TagInput { add: bool, editor: Editor },
+    ConfirmDelete { selection: usize }, // 0 = Delete, 1 = Cancel
+}
+
+#[derive(Debug, Clone)]
+pub struct BulkActionsState {
+    pub selected_index: usize,
+    pub list_state: ListState,
+    pub step: BulkActionStep,
+}
+
+/// Pending confirmation in the trash view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrashConfirm {
+    Purge,
+    Empty,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrashState {
+    pub items: Vec<SelectedItem>, // Deleted items of all types, most recently deleted first
+    pub selected_index: usize,
+    pub list_state: ListState,
+    pub confirm: Option<TrashConfirm>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryState {
+    pub item: SelectedItem,         // The note or journal entry whose history is shown (current version)
+    pub revisions: Vec<Revision>,   // Earlier versions, newest first
+    pub selected_index: usize,
+    pub list_state: ListState,
+    pub diff_scroll: u16,
+    pub confirm_restore: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncConflictsState {
+    pub conflicts: Vec<SyncConflict>,                  // Unresolved conflicts, oldest first
+    pub current_versions: Vec<Option<serde_json::Value>>, // Version kept for each conflict (None if deleted)
+    pub notebook_names: HashMap<String, String>,       // Notebook names by UUID
+    pub selected_index: usize,
+    pub list_state: ListState,
+    pub diff_scroll: u16,
+    pub confirm_use_other: bool,
+}
+
+/// What the attachments view is doing: listing, asking for a file to attach, or confirming a detach
+#[derive(Debug, Clone)]
+pub enum AttachmentsStep {
+    List,
+    AddPath(Editor),
+    ConfirmDetach,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttachmentsState {
+    pub item: SelectedItem,
+    pub attachments: Vec<Attachment>,
+    pub selected_index: usize,
+    pub list_state: ListState,
+    pub step: AttachmentsStep,
+}
+
+/// A link shown below the item view: a `[[...]]` link in the item's text or a backlink
+#[derive(Debug, Clone)]
+pub struct ItemLink {
+    pub label: String,
+    pub target: Option<(ItemKind, i64)>, // None when no item matches the link
+    pub backlink: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LinkState {
+    pub loaded_for: Option<(ItemKind, i64, String)>, // Item (and its updated_at) the links were loaded for
+    pub links: Vec<ItemLink>,
+    pub attachments: Vec<Attachment>, // Files attached to the item, listed above the links
+    pub selected: Option<usize>,
+    pub reveal_selected: bool, // Scroll the item view to the selected link on the next render
+}
+
+/// What to do once the passphrase of locked journal entries has been entered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockAction {
+    View,                 // Only open the locked entries
+    ToggleEntry(i64),     // Lock or unlock a journal entry
+    ToggleNotebook(i64),  // Lock or unlock a notebook with its journal entries
+    Edit,                 // Edit the selected (locked) entry
+    SaveForm,             // Save the open form (a locked entry, or one in a locked notebook)
+}
+
+/// Passphrase prompt shown over the current view
+#[derive(Debug, Clone)]
+pub struct UnlockPrompt {
+    pub passphrase: String,
+    pub first_entry: Option<String>, // A new passphrase as typed the first time, until it is confirmed
+    pub new_passphrase: bool,         // Nothing is locked yet, so the passphrase is chosen (and typed twice)
+    pub error: Option<String>,
+    pub action: LockAction,
+}
+
+/// Passphrase session for locked journal entries and notebooks
+#[derive(Debug)]
+pub struct LockSession {
+    pub unlocker: Option<Unlocker>,   // Set while locked entries are unlocked
+    pub last_used: Instant,           // Last key press while unlocked, for the auto-relock timeout
+    pub prompt: Option<UnlockPrompt>,
+    pub decrypted: Option<(String, String)>, // (locked content, plaintext) of the entry in the item view
+}
+
+impl Default for LockSession {
+    fn default() -> Self {
+        Self {
+            unlocker: None,
+            last_used: Instant::now(),
+            prompt: None,
+            decrypted: None,
+        }
+    }
+}
+
+impl LockSession {
+    /// Get the decrypted content of a locked journal entry if it is unlocked (as decrypted for the item view)
+    pub fn unlocked_content(&self, item: &SelectedItem) -> Option<&str> {
+        let SelectedItem::Journal(journal) = item else {
+            return None;
+        };
+        match self.decrypted {
+            Some((ref locked, ref plaintext)) if self.unlocker.is_some() && journal.content.as_ref() == Some(locked) => Some(plaintext),
+            _ => None,
+        }
+    }
+}
+
+/// Maximum number of list actions kept for undo
+const UNDO_LIMIT: usize = 100;
+
+/// A reversible list-level action recorded in the action journal
+#[derive(Debug, Clone)]
+pub enum UndoAction {
+    Delete { kind: ItemKind, ids: Vec<i64> },
+    Archive { kind: ItemKind, ids: Vec<i64>, archived: bool }, // archived = state after the action
+    TaskStatus { changes: Vec<(i64, String)>, status: String }, // (task id, previous status), new status
+    Reorder { changes: Vec<(i64, i64, i64)> },                  // (task id, previous order, new order)
+    DeleteNotebook { notebook: Notebook, items: Vec<(ItemKind, i64)> },
+    MoveToNotebook { kind: ItemKind, changes: Vec<(i64, Option<i64>)>, notebook_id: Option<i64> }, // (item id, previous notebook), target
+}
+
+impl UndoAction {
+    /// Describe the action for status bar messages (e.g. "delete of 2 notes")
+    pub fn description(&self) -> String {
+        match self {
+            UndoAction::Delete { kind, ids } => format!("delete of {} {}", ids.len(), kind.label(ids.len())),
+            UndoAction::Archive { kind, ids, archived } => format!(
+                "{} of {} {}",
+                if *archived { "archive" } else { "unarchive" },
+                ids.len(),
+                kind.label(ids.len())
+            ),
+            UndoAction::TaskStatus { changes, status } => format!(
+                "marking {} {} as {}",
+                changes.len(),
+                ItemKind::Task.label(changes.len()),
+                status
+            ),
+            UndoAction::Reorder { .. } => "task reorder".to_string(),
+            UndoAction::DeleteNotebook { notebook, .. } => format!("delete of notebook '{}'", notebook.name),
+            UndoAction::MoveToNotebook { kind, changes, .. } => format!("move of {} {}", changes.len(), kind.label(changes.len())),
+        }
+    }
+
+    /// Check whether the action changed nothing (e.g. archiving items that were already archived)
+    fn is_noop(&self) -> bool {
+        match self {
+            UndoAction::Delete { ids, .. } | UndoAction::Archive { ids, .. } => ids.is_empty(),
+            UndoAction::TaskStatus { changes, .. } => changes.is_empty(),
+            UndoAction::Reorder { changes } => changes.is_empty(),
+            UndoAction::MoveToNotebook { changes, .. } => changes.is_empty(),
+            UndoAction::DeleteNotebook { .. } => false,
+        }
+    }
+}
+
+/// Undo and redo stacks for list-level actions
+#[derive(Debug, Clone, Default)]
+pub struct ActionJournal {
+    pub undo: Vec<UndoAction>,
+    pub redo: Vec<UndoAction>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SelectionState {
+    pub active: bool,
+    pub ids: BTreeSet<i64>,
+    pub anchor: Option<usize>, // Display index where a range selection starts
+}
+
 #[derive(Debug, Clone)]
 pub struct ModalState {
     pub delete_confirmation: Option<SelectedItem>,
     pub delete_modal_selection: usize,
+    pub move_notebook: Option<MoveNotebookState>,
+    pub bulk_actions: Option<BulkActionsState>,
+    pub trash: Option<TrashState>,
+    pub history: Option<HistoryState>,
+    pub attachments: Option<AttachmentsState>,
+    pub sync_conflicts: Option<SyncConflictsState>,
 }
 
 impl Default for ModalState {
@@ -303,6 +641,12 @@ impl Default for ModalState {
         Self {
             delete_confirmation: None,
             delete_modal_selection: 0,
+            move_notebook: None,
+            bulk_actions: None,
+            trash: None,
+            history: None,
+            attachments: None,
+            sync_conflicts: None,
         }
     }
 }
@@ -368,7 +712,7 @@ impl Default for FormState {
 pub struct App {
     // Core infrastructure
     pub config: Config,
-    pub database: Database,
+    pub database: Box<dyn Backend>,
     
     // Data collections (frequently accessed, keep at top level)
     pub tasks: Vec<Task>,
@@ -384,10 +728,16 @@ pub struct App {
     pub status: StatusState,
     pub search: SearchState,
     pub form: FormState,
+    pub selection: SelectionState,
+    pub action_journal: ActionJournal,
+    pub links: LinkState,
+    pub lock: LockSession,
+    pub committed_changes: u64, // Database changes (see Store::total_changes) already committed in git storage
+    pub data_version: i64,      // Store::data_version when the data was last loaded
 }
 
 impl App {
-    pub fn new(mut config: Config, database: Database) -> Result<Self, DatabaseError> {
+    pub fn new(mut config: Config, database: Box<dyn Backend>) -> Result<Self, DatabaseError> {
         // Load list_view_mode from config before moving config
         let list_view_mode = match config.list_view_mode.as_str() {
             "Simple" => ListViewMode::Simple,
@@ -458,6 +808,7 @@ impl App {
                 theme_list_state: ListState::default(),
                 sidebar_width_index: 0,
                 display_mode_index: 0,
+                backup_setting_index: 0,
                 color_field_index: 0,
                 color_cycle_indices: [0; 5],
                 color_input_mode: false,
@@ -470,6 +821,12 @@ impl App {
             modals: ModalState {
                 delete_confirmation: None,
                 delete_modal_selection: 0,
+                move_notebook: None,
+                bulk_actions: None,
+                trash: None,
+                history: None,
+                attachments: None,
+                sync_conflicts: None,
             },
             notebooks: NotebookState {
                 current_notebook_id: saved_notebook_id, // Use saved notebook ID if valid, otherwise None
@@ -486,8 +843,15 @@ impl App {
             form: FormState {
                 create_form: None,
             },
+            selection: SelectionState::default(),
+            action_journal: ActionJournal::default(),
+            links: LinkState::default(),
+            lock: LockSession::default(),
+            committed_changes: 0,
+            data_version: 0,
         };
         
+        app.data_version = app.database.data_version()?;
         app.load_data()?;
         app.sync_list_state();
         // Auto-select the first item if available
@@ -496,6 +860,11 @@ impl App {
     }
 
     pub fn load_data(&mut self) -> Result<(), DatabaseError> {
+        // Backlinks may have changed with the data; reload the links (keeping the selected one)
+        if let Some((_, _, ref mut updated_at)) = self.links.loaded_for {
+            updated_at.clear();
+        }
+
         // Load archived items if filter requires them
         let need_archived = matches!(self.filter.archived, Some(FilterArchivedStatus::Archived) | Some(FilterArchivedStatus::All));
         
@@ -979,6 +1348,8 @@ impl App {
     pub fn switch_tab(&mut self, new_tab: Tab) {
         self.ui.current_tab = new_tab;
         self.ui.selected_index = 0;
+        // A selection only ever holds items from one tab
+        self.clear_selection();
         self.adjust_selected_index();
         
         // Auto-select the first item if available
@@ -1478,6 +1849,51 @@ impl App {
         Ok(())
     }
 
+    /// Get the automatic backup options (config value, label)
+    pub fn get_backup_frequency_options(&self) -> Vec<(BackupFrequency, &'static str)> {
+        vec![
+            (BackupFrequency::Off, "Off"),
+            (BackupFrequency::Startup, "On startup"),
+            (BackupFrequency::Daily, "Daily"),
+        ]
+    }
+
+    /// Get the options for the number of backups to keep
+    pub fn get_backup_count_options(&self) -> Vec<u32> {
+        vec![3, 5, 7, 14, 30]
+    }
+
+    /// Move backup setting selection up (from the counts into the frequencies)
+    pub fn move_settings_backup_up(&mut self) {
+        if self.settings.backup_setting_index > 0 {
+            self.settings.backup_setting_index -= 1;
+        }
+    }
+
+    /// Move backup setting selection down (from the frequencies into the counts)
+    pub fn move_settings_backup_down(&mut self) {
+        let options = self.get_backup_frequency_options().len() + self.get_backup_count_options().len();
+        if self.settings.backup_setting_index < options.saturating_sub(1) {
+            self.settings.backup_setting_index += 1;
+        }
+    }
+
+    /// Apply selected backup frequency or number of backups to keep
+    pub fn apply_backup_setting(&mut self) -> Result<(), crate::config::ConfigError> {
+        let frequencies = self.get_backup_frequency_options();
+        let index = self.settings.backup_setting_index;
+        if let Some(&(frequency, label)) = frequencies.get(index) {
+            self.config.auto_backup = frequency.as_str().to_string();
+            self.save_config()?;
+            self.set_status_message(format!("Automatic backups: {}", label));
+        } else if let Some(&count) = self.get_backup_count_options().get(index - frequencies.len()) {
+            self.config.backup_count = count;
+            self.save_config()?;
+            self.set_status_message(format!("Keeping the last {} backups", count));
+        }
+        Ok(())
+    }
+
     pub fn exit_settings_mode(&mut self) {
         self.ui.mode = Mode::View;
     }
@@ -1495,6 +1911,26 @@ impl App {
     }
 
     pub fn enter_edit_mode(&mut self) {
+        // A locked entry is edited as plaintext (and locked again when saved)
+        let unlocked_content = match self.ui.selected_item {
+            Some(SelectedItem::Journal(ref journal)) if journal.is_locked() => {
+                let Some(ref mut unlocker) = self.lock.unlocker else {
+                    if let Err(e) = self.request_passphrase(LockAction::Edit) {
+                        self.set_status_message(format!("Failed to unlock entry: {}", e));
+                    }
+                    return;
+                };
+                match unlocker.unlock(journal.content.as_deref().unwrap_or_default()) {
+                    Ok(plaintext) => Some(plaintext),
+                    Err(e) => {
+                        self.set_status_message(format!("Failed to unlock entry: {}", e));
+                        return;
+                    }
+                }
+            }
+            _ => None,
+        };
+
         // Use form-based editing instead of single-field editing
         // Populate form with existing item data
         if let Some(ref item) = self.ui.selected_item {
@@ -1533,7 +1969,7 @@ impl App {
                         current_field: JournalField::Date,
                         date: Editor::from_string(journal.date.clone()),
                         title: Editor::from_string(journal.title.clone().unwrap_or_default()),
-                        content: Editor::from_string(journal.content.clone().unwrap_or_default()),
+                        content: Editor::from_string(unlocked_content.clone().unwrap_or_else(|| journal.content.clone().unwrap_or_default())),
                         tags: Editor::from_string(journal.tags.clone().unwrap_or_default()),
                         notebook_id,
                         notebook_selected_index,
@@ -1923,15 +2359,36 @@ impl App {
                     let title = journal_form.title.to_string().trim().to_string();
                     let content = journal_form.content.to_string().trim().to_string();
                     let tags = journal_form.tags.to_string().trim().to_string();
+                    let notebook_id = journal_form.notebook_id;
+                    let editing_item_id = journal_form.editing_item_id;
+
+                    // A locked entry, or one in a locked notebook, is saved locked
+                    let was_locked = editing_item_id
+                        .and_then(|id| self.journals.iter().find(|j| j.id == Some(id)))
+                        .is_some_and(|journal| journal.is_locked());
+                    let content = if was_locked || notebook_id.is_some_and(|id| self.is_notebook_locked(id)) {
+                        let Some(ref mut unlocker) = self.lock.unlocker else {
+                            return self.request_passphrase(LockAction::SaveForm);
+                        };
+                        match unlocker.lock(&content) {
+                            Ok(content) => content,
+                            Err(e) => {
+                                self.set_status_message(format!("Failed to lock journal entry: {}", e));
+                                return Ok(());
+                            }
+                        }
+                    } else {
+                        content
+                    };
 
-                    if let Some(item_id) = journal_form.editing_item_id {
+                    if let Some(item_id) = editing_item_id {
                         // Update existing journal entry
                         if let Some(ref mut journal) = self.journals.iter_mut().find(|j| j.id == Some(item_id)) {
                             journal.date = date;
                             journal.title = if title.is_empty() { None } else { Some(title) };
                             journal.content = if content.is_empty() { None } else { Some(content) };
                             journal.tags = if tags.is_empty() { None } else { Some(tags) };
-                            journal.notebook_id = journal_form.notebook_id;
+                            journal.notebook_id = notebook_id;
                             journal.updated_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
                             
                             // Update with error handling
@@ -1960,7 +2417,7 @@ impl App {
                         journal.title = if title.is_empty() { None } else { Some(title) };
                         journal.content = if content.is_empty() { None } else { Some(content) };
                         journal.tags = if tags.is_empty() { None } else { Some(tags) };
-                        journal.notebook_id = journal_form.notebook_id;
+                        journal.notebook_id = notebook_id;
 
                         // Insert into database with error handling
                         if let Err(e) = self.database.insert_journal(&journal) {
@@ -1986,7 +2443,7 @@ impl App {
     /// This is a simplified version that doesn't account for wrapping - actual wrapping
     /// happens in render. This is just for approximate scroll calculations.
     fn get_item_view_total_lines(&self) -> usize {
-        if let Some(ref item) = self.ui.selected_item {
+        let content_lines = if let Some(ref item) = self.ui.selected_item {
             match item {
                 SelectedItem::Task(task) => {
                     let mut count = 2; // Title, Status
@@ -2030,7 +2487,8 @@ impl App {
             }
         } else {
             0
-        }
+        };
+        content_lines + crate::tui::widgets::item_view::links_section_height(&self.links)
     }
 
     /// Scroll item view content up by one line
@@ -2141,7 +2599,7 @@ impl App {
 
     /// Get settings categories
     pub fn get_settings_categories(&self) -> Vec<String> {
-        vec!["Theme Settings".to_string(), "Appearance Settings".to_string(), "Display Settings".to_string(), "System Settings".to_string()]
+        vec!["Theme Settings".to_string(), "Appearance Settings".to_string(), "Display Settings".to_string(), "Backup Settings".to_string(), "System Settings".to_string()]
     }
     
     /// Get config file path
@@ -2230,6 +2688,7 @@ impl App {
                 if let Some(ref mut task) = self.tasks.iter_mut().find(|t| t.id == Some(task_id)) {
                     // Get current status before toggling
                     let was_done = task.status == "done";
+                    let previous_status = task.status.clone();
                     
                     // Toggle status
                     task.status = if was_done {
@@ -2241,6 +2700,11 @@ impl App {
                     
                     // Update in database
                     self.database.update_task(task)?;
+                    let action = UndoAction::TaskStatus {
+                        changes: vec![(task_id, previous_status)],
+                        status: task.status.clone(),
+                    };
+                    self.record_action(action);
                     
                     // Reload data
                     self.load_data()?;
@@ -2301,6 +2765,12 @@ impl App {
                             // Update both tasks in database
                             self.database.update_task_order(selected_task_id, above_order)?;
                             self.database.update_task_order(above_task_id, selected_order)?;
+                            self.record_action(UndoAction::Reorder {
+                                changes: vec![
+                                    (selected_task_id, selected_order, above_order),
+                                    (above_task_id, above_order, selected_order),
+                                ],
+                            });
 
                             // Reload data
                             self.load_data()?;
@@ -2364,6 +2834,12 @@ impl App {
                             // Update both tasks in database
                             self.database.update_task_order(selected_task_id, below_order)?;
                             self.database.update_task_order(below_task_id, selected_order)?;
+                            self.record_action(UndoAction::Reorder {
+                                changes: vec![
+                                    (selected_task_id, selected_order, below_order),
+                                    (below_task_id, below_order, selected_order),
+                                ],
+                            });
 
                             // Reload data
                             self.load_data()?;
@@ -2435,6 +2911,13 @@ impl App {
         } else {
             self.settings.display_mode_index = 0;
         }
+
+        // Initialize backup setting index to the current frequency
+        let current_frequency = self.config.get_auto_backup();
+        self.settings.backup_setting_index = self.get_backup_frequency_options()
+            .iter()
+            .position(|&(frequency, _)| frequency == current_frequency)
+            .unwrap_or(0);
     }
 
     /// Get display name for a notebook (returns "[None]" if None)
@@ -2503,6 +2986,7 @@ impl App {
         }
         // Reload data to filter by new notebook
         self.load_data()?;
+        self.clear_selection();
         self.set_status_message(format!("Switched to notebook: {}", self.get_notebook_display_name(id)));
         Ok(())
     }
@@ -2564,8 +3048,16 @@ impl App {
     /// Delete a notebook
     /// Items that belonged to this notebook will be moved to "[None]"
     pub fn delete_notebook(&mut self, id: i64) -> Result<(), DatabaseError> {
+        // Remember the notebook and its items so the delete can be undone
+        let notebook = self.database.get_notebook(id)?;
+        let mut items = Vec::new();
+        for kind in [ItemKind::Task, ItemKind::Note, ItemKind::Journal] {
+            items.extend(self.database.get_item_ids_in_notebook(kind, id)?.into_iter().map(|item_id| (kind, item_id)));
+        }
+
         // Delete from database first to ensure operation succeeds before modifying state
         self.database.delete_notebook(id)?;
+        self.record_action(UndoAction::DeleteNotebook { notebook, items });
         
         // Check if this was the current notebook and update state only after successful deletion
         if self.notebooks.current_notebook_id == Some(id) {
@@ -2592,80 +3084,1501 @@ impl App {
         Ok(())
     }
 
-    /// Navigate notebook modal fields
-    pub fn navigate_notebook_modal(&mut self) {
-        if let Some(ref mut state) = self.notebooks.modal_state {
-            // Tab only switches between NotebookList and ActionsList
-            state.current_field = match state.current_field {
-                NotebookModalField::NotebookList => NotebookModalField::ActionsList,
-                NotebookModalField::ActionsList => NotebookModalField::NotebookList,
-            };
+    /// Get the items a "move to notebook" action applies to
+    /// Uses the multi-selection when there is one, otherwise the selected item
+    fn get_move_targets(&self) -> Option<(ItemKind, Vec<i64>)> {
+        let selected_ids = self.get_selected_ids();
+        if !selected_ids.is_empty() {
+            return Some((self.current_kind(), selected_ids));
         }
+        let item = self.ui.selected_item.as_ref()?;
+        item.id().map(|id| (item.kind(), vec![id]))
     }
 
-    /// Move notebook selection up
-    pub fn move_notebook_selection_up(&mut self) {
-        if let Some(ref mut state) = self.notebooks.modal_state {
-            if state.selected_index > 0 {
-                state.selected_index -= 1;
-                state.list_state.select(Some(state.selected_index));
-            }
+    /// Enter move-to-notebook mode for the selected item
+    pub fn enter_move_notebook_mode(&mut self) {
+        if self.get_move_targets().is_none() {
+            self.set_status_message("No item selected".to_string());
+            return;
         }
+
+        // Preselect the notebook the item currently belongs to
+        let current_notebook_id = self.ui.selected_item.as_ref().and_then(|item| item.notebook_id());
+        let selected_index = self.get_notebook_index_for_id(current_notebook_id);
+        let mut list_state = ListState::default();
+        list_state.select(Some(selected_index));
+
+        self.modals.move_notebook = Some(MoveNotebookState {
+            selected_index,
+            list_state,
+        });
+        self.ui.mode = Mode::MoveToNotebook;
     }
 
-    /// Move notebook selection down
-    pub fn move_notebook_selection_down(&mut self) {
-        if let Some(ref mut state) = self.notebooks.modal_state {
+    /// Exit move-to-notebook mode without moving anything
+    pub fn exit_move_notebook_mode(&mut self) {
+        self.modals.move_notebook = None;
+        self.ui.mode = Mode::View;
+    }
+
+    /// Move the destination selection up in the move-to-notebook modal
+    pub fn move_notebook_target_up(&mut self) {
+        if let Some(ref mut state) = self.modals.move_notebook
+            && state.selected_index > 0
+        {
+            state.selected_index -= 1;
+            state.list_state.select(Some(state.selected_index));
+        }
+    }
+
+    /// Move the destination selection down in the move-to-notebook modal
+    pub fn move_notebook_target_down(&mut self) {
+        if let Some(ref mut state) = self.modals.move_notebook {
             let max_index = self.notebooks.notebooks.len(); // "[None]" + notebooks
-            // Allow incrementing up to and including max_index (the last notebook)
-            // Since index 0 is "[None]" and indices 1+ are notebooks, max_index is the last valid index
-            if state.selected_index <= max_index {
-                state.selected_index = (state.selected_index + 1).min(max_index);
+            if state.selected_index < max_index {
+                state.selected_index += 1;
                 state.list_state.select(Some(state.selected_index));
             }
         }
     }
 
-    /// Move actions selection up
-    pub fn move_actions_selection_up(&mut self) {
-        if let Some(ref mut state) = self.notebooks.modal_state {
-            if state.actions_selected_index > 0 {
-                state.actions_selected_index -= 1;
+    /// Move the target items to the notebook selected in the move-to-notebook modal
+    pub fn confirm_move_to_notebook(&mut self) -> Result<(), DatabaseError> {
+        let selected_index = match self.modals.move_notebook {
+            Some(ref state) => state.selected_index,
+            None => return Ok(()),
+        };
+        let notebook_id = if selected_index == 0 {
+            None // "[None]"
+        } else {
+            self.notebooks.notebooks.get(selected_index - 1).and_then(|n| n.id)
+        };
+
+        self.exit_move_notebook_mode();
+
+        let Some((kind, ids)) = self.get_move_targets() else {
+            self.set_status_message("No item selected".to_string());
+            return Ok(());
+        };
+
+        // Journal entries moved into a locked notebook are locked
+        let mut to_lock = Vec::new();
+        if kind == ItemKind::Journal && notebook_id.is_some_and(|id| self.is_notebook_locked(id)) {
+            let Some(ref mut unlocker) = self.lock.unlocker else {
+                self.set_status_message(format!(
+                    "Unlock locked entries ({}) to move journal entries into a locked notebook",
+                    self.config.key_bindings.unlock
+                ));
+                return Ok(());
+            };
+            for &id in &ids {
+                let journal = self.database.get_journal(id)?;
+                if journal.is_locked() {
+                    continue;
+                }
+                match unlocker.lock(journal.content.as_deref().unwrap_or_default()) {
+                    Ok(content) => to_lock.push((id, content)),
+                    Err(e) => {
+                        self.set_status_message(format!("Failed to lock journal entry: {}", e));
+                        return Ok(());
+                    }
+                }
             }
         }
+
+        let changes: Vec<(i64, Option<i64>)> = ids.iter()
+            .filter_map(|&id| self.find_loaded_item(kind, id).map(|item| (id, item.notebook_id())))
+            .collect();
+        let moved = self.database.move_items_to_notebook(kind, &ids, notebook_id)?;
+        for (id, content) in &to_lock {
+            self.database.set_journal_content(*id, Some(content), true)?;
+        }
+        self.record_action(UndoAction::MoveToNotebook { kind, changes, notebook_id });
+
+        // Items moved out of the current notebook disappear from the list
+        self.load_data()?;
+        self.clear_selection();
+        self.adjust_selected_index();
+        self.select_current_item();
+
+        self.set_status_message(format!(
+            "Moved {} {} to {}",
+            moved,
+            kind.label(moved),
+            self.get_notebook_display_name(notebook_id)
+        ));
+        Ok(())
     }
 
-    /// Move actions selection down
-    pub fn move_actions_selection_down(&mut self) {
-        if let Some(ref mut state) = self.notebooks.modal_state {
-            // Actions: Add (0), Rename (1), Delete (2), Switch (3)
-            let max_index = 3;
-            if state.actions_selected_index < max_index {
-                state.actions_selected_index += 1;
-            }
+    /// Get the kind of items shown on the current tab
+    pub fn current_kind(&self) -> ItemKind {
+        match self.ui.current_tab {
+            Tab::Tasks => ItemKind::Task,
+            Tab::Notes => ItemKind::Note,
+            Tab::Journal => ItemKind::Journal,
         }
     }
 
-    /// Get current notebook modal editor (for add/rename)
-    pub fn get_notebook_modal_editor(&mut self) -> Option<&mut Editor> {
-        if let Some(ref mut state) = self.notebooks.modal_state {
-            if matches!(state.mode, NotebookModalMode::Add | NotebookModalMode::Rename) {
-                Some(&mut state.name_editor)
-            } else {
-                None
-            }
+    /// Get the item ID at a display index (None for headings or out of range)
+    fn get_item_id_at_display_index(&self, display_index: usize) -> Option<i64> {
+        let items = self.get_current_items();
+        let (_, item_indices) = self.get_display_index_mapping();
+        let item_idx = (*item_indices.get(display_index)?)?;
+        items.get(item_idx).and_then(|item| item.id())
+    }
+
+    /// Get the IDs of selected items that are visible with the current search and filters
+    pub fn get_selected_ids(&self) -> Vec<i64> {
+        if !self.selection.active || self.selection.ids.is_empty() {
+            return Vec::new();
+        }
+        self.get_current_items()
+            .iter()
+            .filter_map(|item| item.id())
+            .filter(|id| self.selection.ids.contains(id))
+            .collect::<BTreeSet<i64>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Enter or leave multi-select mode
+    pub fn toggle_multi_select_mode(&mut self) {
+        if self.selection.active {
+            self.clear_selection();
+            self.set_status_message("Multi-select off".to_string());
         } else {
-            None
+            self.selection.active = true;
+            self.selection.anchor = Some(self.ui.selected_index);
+            self.set_status_message("Multi-select on".to_string());
         }
     }
 
-    // Color editor navigation methods
-    pub fn move_color_field_up(&mut self) {
-        if self.settings.color_field_index > 0 {
-            self.settings.color_field_index -= 1;
-        } else {
-            // Wrap to last field
-            self.settings.color_field_index = 4;
+    /// Leave multi-select mode and forget the selection
+    pub fn clear_selection(&mut self) {
+        self.selection = SelectionState::default();
+    }
+
+    /// Toggle whether the item under the cursor is part of the selection
+    pub fn toggle_item_selection(&mut self) {
+        if let Some(id) = self.get_item_id_at_display_index(self.ui.selected_index) {
+            if !self.selection.ids.remove(&id) {
+                self.selection.ids.insert(id);
+            }
+            self.selection.anchor = Some(self.ui.selected_index);
+        }
+    }
+
+    /// Move the cursor up or down and select every item between the anchor and the cursor
+    pub fn extend_selection(&mut self, down: bool) {
+        let anchor = self.selection.anchor.unwrap_or(self.ui.selected_index);
+        if down {
+            self.move_selection_down();
+        } else {
+            self.move_selection_up();
+        }
+
+        let (start, end) = if anchor <= self.ui.selected_index {
+            (anchor, self.ui.selected_index)
+        } else {
+            (self.ui.selected_index, anchor)
+        };
+        for display_index in start..=end {
+            if let Some(id) = self.get_item_id_at_display_index(display_index) {
+                self.selection.ids.insert(id);
+            }
+        }
+        self.selection.anchor = Some(anchor);
+    }
+
+    /// Select every item matching the current search and filters (or clear if all are selected)
+    pub fn select_all_items(&mut self) {
+        let visible_ids: BTreeSet<i64> = self.get_current_items()
+            .iter()
+            .filter_map(|item| item.id())
+            .collect();
+        if !visible_ids.is_empty() && visible_ids.is_subset(&self.selection.ids) {
+            self.selection.ids.retain(|id| !visible_ids.contains(id));
+        } else {
+            self.selection.ids.extend(visible_ids);
+        }
+    }
+
+    /// Open the bulk actions menu for the current selection
+    pub fn enter_bulk_actions_mode(&mut self) {
+        if self.get_selected_ids().is_empty() {
+            self.set_status_message("No items selected".to_string());
+            return;
+        }
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        self.modals.bulk_actions = Some(BulkActionsState {
+            selected_index: 0,
+            list_state,
+            step: BulkActionStep::Choose,
+        });
+        self.ui.mode = Mode::BulkActions;
+    }
+
+    /// Open the bulk delete confirmation directly
+    pub fn enter_bulk_delete_confirmation(&mut self) {
+        self.enter_bulk_actions_mode();
+        if let Some(ref mut state) = self.modals.bulk_actions {
+            state.step = BulkActionStep::ConfirmDelete { selection: 0 };
+        }
+    }
+
+    /// Close the bulk actions menu, keeping the selection
+    pub fn exit_bulk_actions_mode(&mut self) {
+        self.modals.bulk_actions = None;
+        self.ui.mode = Mode::View;
+    }
+
+    /// Move the highlighted action up in the bulk actions menu
+    pub fn move_bulk_action_up(&mut self) {
+        if let Some(ref mut state) = self.modals.bulk_actions
+            && state.selected_index > 0
+        {
+            state.selected_index -= 1;
+            state.list_state.select(Some(state.selected_index));
+        }
+    }
+
+    /// Move the highlighted action down in the bulk actions menu
+    pub fn move_bulk_action_down(&mut self) {
+        let action_count = BulkAction::available_for(self.current_kind()).len();
+        if let Some(ref mut state) = self.modals.bulk_actions
+            && state.selected_index + 1 < action_count
+        {
+            state.selected_index += 1;
+            state.list_state.select(Some(state.selected_index));
+        }
+    }
+
+    /// Run the highlighted bulk action, or move on to its tag prompt / confirmation step
+    pub fn choose_bulk_action(&mut self) -> Result<(), DatabaseError> {
+        let actions = BulkAction::available_for(self.current_kind());
+        let Some(ref mut state) = self.modals.bulk_actions else {
+            return Ok(());
+        };
+        let Some(action) = actions.get(state.selected_index).copied() else {
+            return Ok(());
+        };
+
+        match action {
+            BulkAction::Delete => {
+                state.step = BulkActionStep::ConfirmDelete { selection: 0 };
+                Ok(())
+            }
+            BulkAction::AddTag | BulkAction::RemoveTag => {
+                state.step = BulkActionStep::TagInput {
+                    add: action == BulkAction::AddTag,
+                    editor: Editor::new(),
+                };
+                Ok(())
+            }
+            BulkAction::MoveToNotebook => {
+                self.exit_bulk_actions_mode();
+                self.enter_move_notebook_mode();
+                Ok(())
+            }
+            _ => self.apply_bulk_action(action, None),
+        }
+    }
+
+    /// Get the tag editor when the bulk actions menu is prompting for a tag
+    pub fn get_bulk_tag_editor(&mut self) -> Option<&mut Editor> {
+        match self.modals.bulk_actions {
+            Some(BulkActionsState { step: BulkActionStep::TagInput { ref mut editor, .. }, .. }) => Some(editor),
+            _ => None,
+        }
+    }
+
+    /// Apply the tag typed into the bulk tag prompt
+    pub fn confirm_bulk_tag_input(&mut self) -> Result<(), DatabaseError> {
+        let (add, tag) = match self.modals.bulk_actions {
+            Some(BulkActionsState { step: BulkActionStep::TagInput { add, ref editor }, .. }) => {
+                (add, editor.lines.first().cloned().unwrap_or_default())
+            }
+            _ => return Ok(()),
+        };
+        if tag.trim().is_empty() || tag.contains(',') {
+            self.set_status_message("Enter a single tag".to_string());
+            return Ok(());
+        }
+        let action = if add { BulkAction::AddTag } else { BulkAction::RemoveTag };
+        self.apply_bulk_action(action, Some(tag.trim().to_string()))
+    }
+
+    /// Apply a bulk action to every selected item in one transaction
+    pub fn apply_bulk_action(&mut self, action: BulkAction, tag: Option<String>) -> Result<(), DatabaseError> {
+        self.exit_bulk_actions_mode();
+
+        let ids = self.get_selected_ids();
+        if ids.is_empty() {
+            self.set_status_message("No items selected".to_string());
+            return Ok(());
+        }
+        let kind = self.current_kind();
+        let tag = tag.unwrap_or_default();
+
+        let message = match action {
+            BulkAction::Archive => {
+                let changed: Vec<i64> = ids.iter().copied()
+                    .filter(|&id| self.find_loaded_item(kind, id).is_some_and(|item| !item.is_archived()))
+                    .collect();
+                let count = self.database.archive_items(kind, &ids)?;
+                self.record_action(UndoAction::Archive { kind, ids: changed, archived: true });
+                format!("Archived {} {}", count, kind.label(count))
+            }
+            BulkAction::Unarchive => {
+                let changed: Vec<i64> = ids.iter().copied()
+                    .filter(|&id| self.find_loaded_item(kind, id).is_some_and(|item| item.is_archived()))
+                    .collect();
+                let count = self.database.unarchive_items(kind, &ids)?;
+                self.record_action(UndoAction::Archive { kind, ids: changed, archived: false });
+                format!("Unarchived {} {}", count, kind.label(count))
+            }
+            BulkAction::Delete => {
+                let count = self.database.delete_items(kind, &ids)?;
+                self.record_action(UndoAction::Delete { kind, ids: ids.clone() });
+                format!("Moved {} {} to trash", count, kind.label(count))
+            }
+            BulkAction::MarkDone => {
+                let changes: Vec<(i64, String)> = self.tasks.iter()
+                    .filter(|t| t.status != "done")
+                    .filter_map(|t| t.id.filter(|id| ids.contains(id)).map(|id| (id, t.status.clone())))
+                    .collect();
+                let count = self.database.set_tasks_status(&ids, "done")?;
+                self.record_action(UndoAction::TaskStatus { changes, status: "done".to_string() });
+                format!("Marked {} {} done", count, kind.label(count))
+            }
+            BulkAction::AddTag => {
+                let count = self.database.add_tag_to_items(kind, &ids, &tag)?;
+                format!("Added tag '{}' to {} {}", tag, count, kind.label(count))
+            }
+            BulkAction::RemoveTag => {
+                let count = self.database.remove_tag_from_items(kind, &ids, &tag)?;
+                format!("Removed tag '{}' from {} {}", tag, count, kind.label(count))
+            }
+            BulkAction::MoveToNotebook => return Ok(()), // Handled by the move-to-notebook modal
+        };
+
+        self.load_data()?;
+        self.clear_selection();
+        self.adjust_selected_index();
+        self.select_current_item();
+
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// Load deleted items of all types, most recently deleted first
+    fn load_trash_items(&self) -> Result<Vec<SelectedItem>, DatabaseError> {
+        let mut items: Vec<SelectedItem> = Vec::new();
+        items.extend(self.database.get_deleted_tasks()?.into_iter().map(SelectedItem::Task));
+        items.extend(self.database.get_deleted_notes()?.into_iter().map(SelectedItem::Note));
+        items.extend(self.database.get_deleted_journals()?.into_iter().map(SelectedItem::Journal));
+        items.sort_by(|a, b| b.deleted_at().cmp(&a.deleted_at()));
+        Ok(items)
+    }
+
+    /// Open the trash view
+    pub fn enter_trash_mode(&mut self) -> Result<(), DatabaseError> {
+        let items = self.load_trash_items()?;
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        self.modals.trash = Some(TrashState {
+            items,
+            selected_index: 0,
+            list_state,
+            confirm: None,
+        });
+        self.ui.mode = Mode::Trash;
+        Ok(())
+    }
+
+    /// Close the trash view
+    pub fn exit_trash_mode(&mut self) {
+        self.modals.trash = None;
+        self.ui.mode = Mode::View;
+    }
+
+    /// Move the trash selection up
+    pub fn move_trash_selection_up(&mut self) {
+        if let Some(ref mut state) = self.modals.trash
+            && state.selected_index > 0
+        {
+            state.selected_index -= 1;
+            state.list_state.select(Some(state.selected_index));
+        }
+    }
+
+    /// Move the trash selection down
+    pub fn move_trash_selection_down(&mut self) {
+        if let Some(ref mut state) = self.modals.trash
+            && state.selected_index + 1 < state.items.len()
+        {
+            state.selected_index += 1;
+            state.list_state.select(Some(state.selected_index));
+        }
+    }
+
+    /// Ask for confirmation before purging the selected item or emptying the trash
+    pub fn request_trash_confirm(&mut self, confirm: TrashConfirm) {
+        if let Some(ref mut state) = self.modals.trash
+            && !state.items.is_empty()
+        {
+            state.confirm = Some(confirm);
+        }
+    }
+
+    /// Cancel a pending purge or empty-trash confirmation
+    pub fn cancel_trash_confirm(&mut self) {
+        if let Some(ref mut state) = self.modals.trash {
+            state.confirm = None;
+        }
+    }
+
+    /// Get the item highlighted in the trash view
+    fn get_selected_trash_item(&self) -> Option<SelectedItem> {
+        let state = self.modals.trash.as_ref()?;
+        state.items.get(state.selected_index).cloned()
+    }
+
+    /// Reload the trash view and the main lists after a trash operation
+    fn refresh_after_trash_change(&mut self) -> Result<(), DatabaseError> {
+        let items = self.load_trash_items()?;
+        if let Some(ref mut state) = self.modals.trash {
+            state.items = items;
+            state.confirm = None;
+            state.selected_index = cmp::min(state.selected_index, state.items.len().saturating_sub(1));
+            state.list_state.select(Some(state.selected_index));
+        }
+        self.load_data()?;
+        self.adjust_selected_index();
+        self.select_current_item();
+        // select_current_item switches back to View mode, but the trash view stays open
+        self.ui.mode = Mode::Trash;
+        Ok(())
+    }
+
+    /// Restore the highlighted item from the trash
+    pub fn restore_trash_item(&mut self) -> Result<(), DatabaseError> {
+        let Some(item) = self.get_selected_trash_item() else {
+            return Ok(());
+        };
+        let Some(id) = item.id() else {
+            return Ok(());
+        };
+        self.database.restore_item(item.kind(), id)?;
+        self.refresh_after_trash_change()?;
+        self.set_status_message(format!("Restored {} '{}'", item.kind().label(1), item.display_title()));
+        Ok(())
+    }
+
+    /// Permanently delete the highlighted item from the trash
+    pub fn purge_trash_item(&mut self) -> Result<(), DatabaseError> {
+        let Some(item) = self.get_selected_trash_item() else {
+            return Ok(());
+        };
+        let Some(id) = item.id() else {
+            return Ok(());
+        };
+        self.database.purge_item(item.kind(), id)?;
+        self.refresh_after_trash_change()?;
+        self.set_status_message(format!("Permanently deleted {} '{}'", item.kind().label(1), item.display_title()));
+        Ok(())
+    }
+
+    /// Permanently delete everything in the trash
+    pub fn empty_trash(&mut self) -> Result<(), DatabaseError> {
+        let purged = self.database.empty_trash()?;
+        self.refresh_after_trash_change()?;
+        self.set_status_message(format!("Emptied trash ({} items permanently deleted)", purged));
+        Ok(())
+    }
+
+    /// Open the revision history of the selected note or journal entry
+    pub fn enter_history_mode(&mut self) -> Result<(), DatabaseError> {
+        let Some(item) = self.ui.selected_item.clone() else {
+            return Ok(());
+        };
+        if item.kind() == ItemKind::Task {
+            self.set_status_message("Revision history is only kept for notes and journal entries".to_string());
+            return Ok(());
+        }
+        if matches!(item, SelectedItem::Journal(ref journal) if journal.is_locked()) {
+            self.set_status_message("Revision history isn't shown for locked journal entries".to_string());
+            return Ok(());
+        }
+        let Some(id) = item.id() else {
+            return Ok(());
+        };
+
+        let revisions = self.database.get_revisions(item.kind(), id)?;
+        if revisions.is_empty() {
+            self.set_status_message(format!("No earlier versions of this {}", item.kind().label(1)));
+            return Ok(());
+        }
+
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        self.modals.history = Some(HistoryState {
+            item,
+            revisions,
+            selected_index: 0,
+            list_state,
+            diff_scroll: 0,
+            confirm_restore: false,
+        });
+        self.ui.mode = Mode::History;
+        Ok(())
+    }
+
+    /// Close the revision history view
+    pub fn exit_history_mode(&mut self) {
+        self.modals.history = None;
+        self.ui.mode = Mode::View;
+    }
+
+    /// Move the revision selection up (towards newer revisions)
+    pub fn move_history_selection_up(&mut self) {
+        if let Some(ref mut state) = self.modals.history
+            && state.selected_index > 0
+        {
+            state.selected_index -= 1;
+            state.list_state.select(Some(state.selected_index));
+            state.diff_scroll = 0;
+        }
+    }
+
+    /// Move the revision selection down (towards older revisions)
+    pub fn move_history_selection_down(&mut self) {
+        if let Some(ref mut state) = self.modals.history
+            && state.selected_index + 1 < state.revisions.len()
+        {
+            state.selected_index += 1;
+            state.list_state.select(Some(state.selected_index));
+            state.diff_scroll = 0;
+        }
+    }
+
+    /// Scroll the diff of the selected revision
+    pub fn scroll_history_diff(&mut self, down: bool) {
+        if let Some(ref mut state) = self.modals.history {
+            state.diff_scroll = if down {
+                state.diff_scroll.saturating_add(1)
+            } else {
+                state.diff_scroll.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Ask for confirmation before restoring the selected revision
+    pub fn request_history_restore(&mut self) {
+        if let Some(ref mut state) = self.modals.history {
+            state.confirm_restore = true;
+        }
+    }
+
+    /// Cancel a pending revision restore
+    pub fn cancel_history_restore(&mut self) {
+        if let Some(ref mut state) = self.modals.history {
+            state.confirm_restore = false;
+        }
+    }
+
+    /// Restore the selected revision and close the history view
+    pub fn restore_selected_revision(&mut self) -> Result<(), DatabaseError> {
+        let Some(revision_id) = self.modals.history.as_ref()
+            .and_then(|state| state.revisions.get(state.selected_index))
+            .and_then(|revision| revision.id)
+        else {
+            return Ok(());
+        };
+
+        let revision = self.database.restore_revision(revision_id)?;
+        self.exit_history_mode();
+        self.load_data()?;
+        self.adjust_selected_index();
+        self.select_current_item();
+
+        self.set_status_message(format!(
+            "Restored {} to the version saved {}",
+            revision.item_kind.label(1),
+            revision.saved_at
+        ));
+        Ok(())
+    }
+
+    /// Open the attachments of the selected item
+    pub fn enter_attachments_mode(&mut self) -> Result<(), DatabaseError> {
+        let Some(item) = self.ui.selected_item.clone() else {
+            return Ok(());
+        };
+        let Some(id) = item.id() else {
+            return Ok(());
+        };
+
+        let attachments = self.database.get_attachments(item.kind(), id)?;
+        let mut list_state = ListState::default();
+        list_state.select(if attachments.is_empty() { None } else { Some(0) });
+        self.modals.attachments = Some(AttachmentsState {
+            item,
+            attachments,
+            selected_index: 0,
+            list_state,
+            step: AttachmentsStep::List,
+        });
+        self.ui.mode = Mode::Attachments;
+        Ok(())
+    }
+
+    /// Close the attachments view
+    pub fn exit_attachments_mode(&mut self) {
+        self.modals.attachments = None;
+        self.ui.mode = Mode::View;
+    }
+
+    /// Move the attachment selection up
+    pub fn move_attachments_selection_up(&mut self) {
+        if let Some(ref mut state) = self.modals.attachments
+            && state.selected_index > 0
+        {
+            state.selected_index -= 1;
+            state.list_state.select(Some(state.selected_index));
+        }
+    }
+
+    /// Move the attachment selection down
+    pub fn move_attachments_selection_down(&mut self) {
+        if let Some(ref mut state) = self.modals.attachments
+            && state.selected_index + 1 < state.attachments.len()
+        {
+            state.selected_index += 1;
+            state.list_state.select(Some(state.selected_index));
+        }
+    }
+
+    /// Reload the attachment list after attaching or detaching, keeping the selection in range
+    fn reload_attachments(&mut self) -> Result<(), DatabaseError> {
+        // The item view lists attachments too; make it reload them
+        if let Some((_, _, ref mut updated_at)) = self.links.loaded_for {
+            updated_at.clear();
+        }
+        let Some(ref mut state) = self.modals.attachments else {
+            return Ok(());
+        };
+        let Some(id) = state.item.id() else {
+            return Ok(());
+        };
+        state.attachments = self.database.get_attachments(state.item.kind(), id)?;
+        state.selected_index = state.selected_index.min(state.attachments.len().saturating_sub(1));
+        state.list_state.select(if state.attachments.is_empty() { None } else { Some(state.selected_index) });
+        Ok(())
+    }
+
+    /// Ask for the path of a file to attach
+    pub fn start_add_attachment(&mut self) {
+        if let Some(ref mut state) = self.modals.attachments {
+            state.step = AttachmentsStep::AddPath(Editor::new());
+        }
+    }
+
+    /// Get the path editor when the attachments view is asking for a file
+    pub fn get_attachment_path_editor(&mut self) -> Option<&mut Editor> {
+        match self.modals.attachments {
+            Some(AttachmentsState { step: AttachmentsStep::AddPath(ref mut editor), .. }) => Some(editor),
+            _ => None,
+        }
+    }
+
+    /// Go back to the attachment list from the path prompt or detach confirmation
+    pub fn cancel_attachments_step(&mut self) {
+        if let Some(ref mut state) = self.modals.attachments {
+            state.step = AttachmentsStep::List;
+        }
+    }
+
+    /// Attach the file whose path was typed into the prompt
+    pub fn confirm_add_attachment(&mut self) -> Result<(), DatabaseError> {
+        let Some(AttachmentsState { ref item, step: AttachmentsStep::AddPath(ref editor), .. }) = self.modals.attachments else {
+            return Ok(());
+        };
+        let path_text = editor.lines.first().cloned().unwrap_or_default();
+        let (kind, Some(id)) = (item.kind(), item.id()) else {
+            return Ok(());
+        };
+        if path_text.trim().is_empty() {
+            self.set_status_message("Enter the path of a file to attach".to_string());
+            return Ok(());
+        }
+
+        let path = crate::utils::expand_path(path_text.trim());
+        let file_name = match path.file_name() {
+            Some(name) if path.is_file() => name.to_string_lossy().to_string(),
+            _ => {
+                self.set_status_message(format!("Not a file: {}", path.display()));
+                return Ok(());
+            }
+        };
+        let data = std::fs::read(&path)
+            .map_err(|e| DatabaseError::AttachmentFileError(format!("{}: {}", path.display(), e)))?;
+
+        let attachment = Attachment::new(kind, id, file_name.clone());
+        self.database.add_attachment(&attachment, &data, self.config.get_attachment_storage())?;
+        self.cancel_attachments_step();
+        self.reload_attachments()?;
+        if let Some(ref mut state) = self.modals.attachments {
+            state.selected_index = state.attachments.len().saturating_sub(1);
+            state.list_state.select(Some(state.selected_index));
+        }
+        self.set_status_message(format!(
+            "Attached {} ({})",
+            file_name,
+            crate::utils::format_file_size(data.len() as i64)
+        ));
+        Ok(())
+    }
+
+    /// Open the selected attachment with the system's default application
+    pub fn open_selected_attachment(&mut self) -> Result<(), DatabaseError> {
+        let Some(attachment) = self.modals.attachments.as_ref()
+            .and_then(|state| state.attachments.get(state.selected_index))
+            .cloned()
+        else {
+            return Ok(());
+        };
+        let path = self.database.attachment_path(&attachment)?;
+        crate::utils::open_with_default_app(&path)
+            .map_err(|e| DatabaseError::AttachmentFileError(format!("{}: {}", path.display(), e)))?;
+        self.set_status_message(format!("Opened {}", attachment.file_name));
+        Ok(())
+    }
+
+    /// Ask for confirmation before detaching the selected attachment
+    pub fn request_detach_attachment(&mut self) {
+        if let Some(ref mut state) = self.modals.attachments
+            && !state.attachments.is_empty()
+        {
+            state.step = AttachmentsStep::ConfirmDetach;
+        }
+    }
+
+    /// Detach (and delete) the selected attachment
+    pub fn detach_selected_attachment(&mut self) -> Result<(), DatabaseError> {
+        let Some(attachment_id) = self.modals.attachments.as_ref()
+            .and_then(|state| state.attachments.get(state.selected_index))
+            .and_then(|attachment| attachment.id)
+        else {
+            return Ok(());
+        };
+        let attachment = self.database.delete_attachment(attachment_id)?;
+        self.cancel_attachments_step();
+        self.reload_attachments()?;
+        self.set_status_message(format!("Removed attachment {}", attachment.file_name));
+        Ok(())
+    }
+
+    /// Open the list of conflicts found by the last syncs
+    pub fn enter_sync_conflicts_mode(&mut self) -> Result<(), SyncError> {
+        let conflicts = self.database.get_sync_conflicts()?;
+        if conflicts.is_empty() {
+            self.set_status_message("No sync conflicts".to_string());
+            return Ok(());
+        }
+        let current_versions = conflicts.iter()
+            .map(|conflict| crate::sync::current_version(self.database.as_ref(), conflict.kind, &conflict.uuid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let notebook_names = crate::sync::notebook_names(self.database.as_ref())?;
+
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        self.modals.sync_conflicts = Some(SyncConflictsState {
+            conflicts,
+            current_versions,
+            notebook_names,
+            selected_index: 0,
+            list_state,
+            diff_scroll: 0,
+            confirm_use_other: false,
+        });
+        self.ui.mode = Mode::SyncConflicts;
+        Ok(())
+    }
+
+    /// Close the sync conflicts view
+    pub fn exit_sync_conflicts_mode(&mut self) {
+        self.modals.sync_conflicts = None;
+        self.ui.mode = Mode::View;
+    }
+
+    /// Move the conflict selection up
+    pub fn move_sync_conflicts_selection_up(&mut self) {
+        if let Some(ref mut state) = self.modals.sync_conflicts
+            && state.selected_index > 0
+        {
+            state.selected_index -= 1;
+            state.list_state.select(Some(state.selected_index));
+            state.diff_scroll = 0;
+        }
+    }
+
+    /// Move the conflict selection down
+    pub fn move_sync_conflicts_selection_down(&mut self) {
+        if let Some(ref mut state) = self.modals.sync_conflicts
+            && state.selected_index + 1 < state.conflicts.len()
+        {
+            state.selected_index += 1;
+            state.list_state.select(Some(state.selected_index));
+            state.diff_scroll = 0;
+        }
+    }
+
+    /// Scroll the comparison of the selected conflict
+    pub fn scroll_sync_conflicts_diff(&mut self, down: bool) {
+        if let Some(ref mut state) = self.modals.sync_conflicts {
+            state.diff_scroll = if down {
+                state.diff_scroll.saturating_add(1)
+            } else {
+                state.diff_scroll.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Ask for confirmation before replacing the kept version with the other one
+    pub fn request_use_other_version(&mut self) {
+        if let Some(ref mut state) = self.modals.sync_conflicts {
+            state.confirm_use_other = true;
+        }
+    }
+
+    /// Cancel a pending switch to the other version
+    pub fn cancel_use_other_version(&mut self) {
+        if let Some(ref mut state) = self.modals.sync_conflicts {
+            state.confirm_use_other = false;
+        }
+    }
+
+    /// Resolve the selected conflict with the version that was not kept
+    pub fn use_other_sync_version(&mut self) -> Result<(), SyncError> {
+        let Some(conflict) = self.modals.sync_conflicts.as_ref()
+            .and_then(|state| state.conflicts.get(state.selected_index))
+            .cloned()
+        else {
+            return Ok(());
+        };
+        crate::sync::use_other_version(self.database.as_ref(), &conflict)?;
+        self.after_sync_conflict_resolved()?;
+        self.set_status_message(format!("Switched {} '{}' back to the replaced version", conflict.kind, conflict.title));
+        Ok(())
+    }
+
+    /// Resolve the selected conflict by keeping the current version
+    pub fn keep_current_sync_version(&mut self) -> Result<(), SyncError> {
+        let Some(conflict_id) = self.modals.sync_conflicts.as_ref()
+            .and_then(|state| state.conflicts.get(state.selected_index))
+            .and_then(|conflict| conflict.id)
+        else {
+            return Ok(());
+        };
+        self.database.delete_sync_conflict(conflict_id)?;
+        self.after_sync_conflict_resolved()?;
+        self.set_status_message("Kept the current version".to_string());
+        Ok(())
+    }
+
+    /// Reload the data and the remaining conflicts (closing the view when none are left)
+    fn after_sync_conflict_resolved(&mut self) -> Result<(), SyncError> {
+        let selected_index = self.modals.sync_conflicts.as_ref().map(|state| state.selected_index).unwrap_or(0);
+        self.load_data()?;
+        self.adjust_selected_index();
+        self.select_current_item();
+
+        self.exit_sync_conflicts_mode();
+        self.enter_sync_conflicts_mode()?;
+        if let Some(ref mut state) = self.modals.sync_conflicts {
+            state.selected_index = selected_index.min(state.conflicts.len() - 1);
+            state.list_state.select(Some(state.selected_index));
+        }
+        Ok(())
+    }
+
+    /// Title of an item as shown in the links section (journal entries by date and title)
+    fn item_link_label(&self, kind: ItemKind, id: i64) -> Result<String, DatabaseError> {
+        Ok(match kind {
+            ItemKind::Task => self.database.get_task(id)?.title,
+            ItemKind::Note => self.database.get_note(id)?.title,
+            ItemKind::Journal => {
+                let journal = self.database.get_journal(id)?;
+                match journal.title.filter(|t| !t.trim().is_empty()) {
+                    Some(title) => format!("{} {}", journal.date, title),
+                    None => journal.date,
+                }
+            }
+        })
+    }
+
+    /// Check whether a notebook is locked
+    pub fn is_notebook_locked(&self, notebook_id: i64) -> bool {
+        self.notebooks.notebooks.iter().any(|n| n.id == Some(notebook_id) && n.locked)
+    }
+
+    /// Ask for the passphrase of locked entries, then run `action`
+    pub fn request_passphrase(&mut self, action: LockAction) -> Result<(), DatabaseError> {
+        // Before anything is locked, the passphrase is chosen rather than checked
+        let new_passphrase = !lock::has_passphrase(self.database.as_ref())?;
+        self.lock.prompt = Some(UnlockPrompt {
+            passphrase: String::new(),
+            first_entry: None,
+            new_passphrase,
+            error: None,
+            action,
+        });
+        Ok(())
+    }
+
+    /// Close the passphrase prompt without unlocking
+    pub fn cancel_passphrase_prompt(&mut self) {
+        self.lock.prompt = None;
+    }
+
+    /// Check the passphrase typed in the prompt; if it is right, unlock and run the prompt's action
+    pub fn submit_passphrase(&mut self) -> Result<(), DatabaseError> {
+        let Some(prompt) = self.lock.prompt.as_mut() else {
+            return Ok(());
+        };
+        let passphrase = std::mem::take(&mut prompt.passphrase);
+        if passphrase.is_empty() {
+            prompt.error = Some("The passphrase can't be empty".to_string());
+            return Ok(());
+        }
+        if prompt.new_passphrase {
+            match prompt.first_entry.take() {
+                None => {
+                    prompt.first_entry = Some(passphrase);
+                    prompt.error = None;
+                    return Ok(());
+                }
+                Some(first) if first != passphrase => {
+                    prompt.error = Some("Passphrases don't match, try again".to_string());
+                    return Ok(());
+                }
+                Some(_) => {}
+            }
+        }
+        let action = prompt.action;
+
+        let unlocker = match lock::open(self.database.as_ref(), &passphrase) {
+            Ok(unlocker) => unlocker,
+            Err(LockError::Database(e)) => return Err(e),
+            Err(e) => {
+                if let Some(prompt) = self.lock.prompt.as_mut() {
+                    prompt.error = Some(match e {
+                        LockError::WrongPassphrase => "Wrong passphrase, try again".to_string(),
+                        e => e.to_string(),
+                    });
+                }
+                return Ok(());
+            }
+        };
+
+        self.lock.unlocker = Some(unlocker);
+        self.lock.last_used = Instant::now();
+        self.lock.prompt = None;
+        match action {
+            LockAction::View => {
+                self.set_status_message("Locked entries unlocked".to_string());
+                Ok(())
+            }
+            LockAction::ToggleEntry(id) => self.toggle_journal_lock(id),
+            LockAction::ToggleNotebook(id) => self.toggle_notebook_lock(id),
+            LockAction::Edit => {
+                self.enter_edit_mode();
+                Ok(())
+            }
+            LockAction::SaveForm => self.save_create_form(),
+        }
+    }
+
+    /// Unlock locked entries (asking for the passphrase), or relock them if they are unlocked
+    pub fn toggle_lock_session(&mut self) -> Result<(), DatabaseError> {
+        if self.lock.unlocker.is_some() {
+            self.relock();
+            self.set_status_message("Locked entries relocked".to_string());
+        } else if self.database.get_any_locked_content()?.is_none() {
+            self.set_status_message(format!("No journal entries are locked ({} locks one)", self.config.key_bindings.lock));
+        } else {
+            self.request_passphrase(LockAction::View)?;
+        }
+        Ok(())
+    }
+
+    /// Forget the passphrase of locked entries, along with any content decrypted for viewing
+    pub fn relock(&mut self) {
+        self.lock.unlocker = None;
+        self.lock.decrypted = None;
+    }
+
+    /// In git storage, write and commit the files of whatever changed since the last commit
+    pub fn commit_git_changes(&mut self) {
+        if self.config.get_storage() != crate::git_store::Storage::Git {
+            return;
+        }
+        if self.database.total_changes() == self.committed_changes {
+            return;
+        }
+        if let Err(e) = crate::git_store::save(self.database.as_ref(), &self.config.get_git_dir()) {
+            self.set_status_message(format!("Failed to commit to git: {}", e));
+        }
+        // Saving records the new commit in the database, which is not a change to commit
+        self.committed_changes = self.database.total_changes();
+    }
+
+    /// Reload the data when another process (e.g. `tnj serve`) changed the database
+    /// Only done in the list views, so open forms and dialogs keep the items they were opened with
+    pub fn reload_external_changes(&mut self) -> Result<(), DatabaseError> {
+        if !matches!(self.ui.mode, Mode::View | Mode::Search) {
+            return Ok(());
+        }
+        let version = self.database.data_version()?;
+        if version == self.data_version {
+            return Ok(());
+        }
+        self.data_version = version;
+
+        self.notebooks.notebooks = self.database.get_all_notebooks()?;
+        if let Some(current_id) = self.notebooks.current_notebook_id
+            && !self.notebooks.notebooks.iter().any(|n| n.id == Some(current_id))
+        {
+            self.switch_notebook(None)?;
+        }
+        self.load_data()?;
+        self.adjust_selected_index();
+        self.select_current_item();
+        Ok(())
+    }
+
+    /// Relock locked entries after `lock_timeout_minutes` without a key press (not while a form is open)
+    pub fn check_lock_timeout(&mut self) {
+        let minutes = self.config.lock_timeout_minutes;
+        if self.lock.unlocker.is_none() || minutes == 0 || matches!(self.ui.mode, Mode::Create | Mode::MarkdownHelp) {
+            return;
+        }
+        if self.lock.last_used.elapsed() >= std::time::Duration::from_secs(u64::from(minutes) * 60) {
+            self.relock();
+            self.set_status_message("Locked entries relocked after inactivity".to_string());
+        }
+    }
+
+    /// Decrypt the selected journal entry for the item view if it is locked and locked entries are unlocked
+    pub fn refresh_unlocked_content(&mut self) {
+        let Some(SelectedItem::Journal(ref journal)) = self.ui.selected_item else {
+            return;
+        };
+        let Some(content) = journal.content.as_deref().filter(|content| crate::lock::is_locked(content)) else {
+            return;
+        };
+        let Some(ref mut unlocker) = self.lock.unlocker else {
+            return;
+        };
+        if self.lock.decrypted.as_ref().is_some_and(|(locked, _)| locked == content) {
+            return;
+        }
+        let plaintext = unlocker.unlock(content)
+            .unwrap_or_else(|e| format!("*Failed to unlock this entry: {}*", e));
+        self.lock.decrypted = Some((content.to_string(), plaintext));
+    }
+
+    /// Show the selected journal entry as reloaded (without leaving the current mode)
+    fn refresh_selected_journal(&mut self) {
+        let Some(SelectedItem::Journal(ref journal)) = self.ui.selected_item else {
+            return;
+        };
+        if let Some(updated) = self.journals.iter().find(|j| j.id == journal.id) {
+            self.ui.selected_item = Some(SelectedItem::Journal(updated.clone()));
+        }
+    }
+
+    /// Lock a journal entry, or remove the lock of a locked one (asking for the passphrase if needed)
+    /// Either way the entry's earlier versions are deleted: they are plaintext or locked copies.
+    pub fn toggle_journal_lock(&mut self, id: i64) -> Result<(), DatabaseError> {
+        let journal = self.database.get_journal(id)?;
+        let locked = journal.is_locked();
+        if locked && journal.notebook_id.is_some_and(|notebook_id| self.is_notebook_locked(notebook_id)) {
+            self.set_status_message("Entries of a locked notebook stay locked; unlock the notebook instead".to_string());
+            return Ok(());
+        }
+        let Some(ref mut unlocker) = self.lock.unlocker else {
+            return self.request_passphrase(LockAction::ToggleEntry(id));
+        };
+
+        let content = journal.content.unwrap_or_default();
+        let result = if locked { unlocker.unlock(&content) } else { unlocker.lock(&content) };
+        let content = match result {
+            Ok(content) => content,
+            Err(e) => {
+                self.set_status_message(format!("Failed to {} journal entry: {}", if locked { "unlock" } else { "lock" }, e));
+                return Ok(());
+            }
+        };
+        self.database.set_journal_content(id, Some(content.as_str()).filter(|content| !content.is_empty()), true)?;
+        self.load_data()?;
+        self.refresh_selected_journal();
+        self.set_status_message(if locked { "Journal entry unlocked" } else { "Journal entry locked" }.to_string());
+        Ok(())
+    }
+
+    /// Lock a notebook with all its journal entries, or unlock a locked one (asking for the passphrase if needed)
+    pub fn toggle_notebook_lock(&mut self, id: i64) -> Result<(), DatabaseError> {
+        let notebook = self.database.get_notebook(id)?;
+        let Some(ref mut unlocker) = self.lock.unlocker else {
+            return self.request_passphrase(LockAction::ToggleNotebook(id));
+        };
+
+        // Convert every entry before saving any, so a damaged entry leaves the notebook as it was
+        let mut changes = Vec::new();
+        for (journal_id, content) in self.database.get_notebook_journal_contents(id)? {
+            let content = content.unwrap_or_default();
+            let result = match (notebook.locked, crate::lock::is_locked(&content)) {
+                (false, false) => unlocker.lock(&content),
+                (true, true) => unlocker.unlock(&content),
+                _ => continue,
+            };
+            match result {
+                Ok(content) => changes.push((journal_id, content)),
+                Err(e) => {
+                    self.set_status_message(format!("Failed to {} notebook: {}", if notebook.locked { "unlock" } else { "lock" }, e));
+                    return Ok(());
+                }
+            }
+        }
+        for (journal_id, content) in &changes {
+            self.database.set_journal_content(*journal_id, Some(content.as_str()).filter(|content| !content.is_empty()), true)?;
+        }
+        self.database.set_notebook_locked(id, !notebook.locked)?;
+
+        self.notebooks.notebooks = self.database.get_all_notebooks()?;
+        self.load_data()?;
+        self.refresh_selected_journal();
+        self.set_status_message(format!(
+            "Notebook '{}' {} ({} {})",
+            notebook.name,
+            if notebook.locked { "unlocked" } else { "locked" },
+            changes.len(),
+            ItemKind::Journal.label(changes.len())
+        ));
+        Ok(())
+    }
+
+    /// Load the links and backlinks of the selected item, unless they are loaded already
+    pub fn refresh_item_links(&mut self) -> Result<(), DatabaseError> {
+        let Some((item, id)) = self.ui.selected_item.as_ref().and_then(|item| Some((item, item.id()?))) else {
+            self.links = LinkState::default();
+            return Ok(());
+        };
+        let key = (item.kind(), id, item.updated_at().to_string());
+        if self.links.loaded_for.as_ref() == Some(&key) {
+            return Ok(());
+        }
+        let same_item = matches!(self.links.loaded_for, Some((kind, loaded_id, _)) if kind == key.0 && loaded_id == id);
+
+        let mut links = Vec::new();
+        for link in parse_links(item.link_text().unwrap_or("")) {
+            let target = self.database.resolve_link(&link.target)?;
+            let label = match (link.label, target) {
+                (Some(label), _) => label,
+                (None, Some((kind, target_id))) => self.item_link_label(kind, target_id)?,
+                (None, None) => link.target.to_string(),
+            };
+            links.push(ItemLink { label, target, backlink: false });
+        }
+        for (kind, source_id) in self.database.get_backlinks(key.0, id)? {
+            links.push(ItemLink {
+                label: self.item_link_label(kind, source_id)?,
+                target: Some((kind, source_id)),
+                backlink: true,
+            });
+        }
+
+        let attachments = self.database.get_attachments(key.0, id)?;
+        let selected = if same_item { self.links.selected.filter(|&i| i < links.len()) } else { None };
+        self.links = LinkState { loaded_for: Some(key), links, attachments, selected, reveal_selected: false };
+        Ok(())
+    }
+
+    /// Select the next link of the item view (wrapping around)
+    pub fn select_next_link(&mut self) {
+        let count = self.links.links.len();
+        if count == 0 {
+            self.set_status_message("This item has no links or backlinks".to_string());
+            return;
+        }
+        self.links.selected = Some(match self.links.selected {
+            Some(index) => (index + 1) % count,
+            None => 0,
+        });
+        self.links.reveal_selected = true;
+    }
+
+    /// Select the previous link of the item view (wrapping around)
+    pub fn select_previous_link(&mut self) {
+        let count = self.links.links.len();
+        if count == 0 {
+            self.set_status_message("This item has no links or backlinks".to_string());
+            return;
+        }
+        self.links.selected = Some(match self.links.selected {
+            Some(0) | None => count - 1,
+            Some(index) => index - 1,
+        });
+        self.links.reveal_selected = true;
+    }
+
+    /// Open the item the selected link points at
+    pub fn follow_selected_link(&mut self) -> Result<(), DatabaseError> {
+        let Some(link) = self.links.selected.and_then(|index| self.links.links.get(index)).cloned() else {
+            self.set_status_message(format!("Select a link first ({})", self.config.key_bindings.next_link));
+            return Ok(());
+        };
+        let Some((kind, id)) = link.target else {
+            self.set_status_message(format!("No item found for link '{}'", link.label));
+            return Ok(());
+        };
+        self.open_item(kind, id)
+    }
+
+    /// Show an item: switch to its notebook and tab and select it in the list
+    /// Items the list does not show (e.g. archived ones while archived items are filtered out)
+    /// are shown in the item view without being selected in the list.
+    pub fn open_item(&mut self, kind: ItemKind, id: i64) -> Result<(), DatabaseError> {
+        let item = match kind {
+            ItemKind::Task => SelectedItem::Task(self.database.get_task(id)?),
+            ItemKind::Note => SelectedItem::Note(self.database.get_note(id)?),
+            ItemKind::Journal => SelectedItem::Journal(self.database.get_journal(id)?),
+        };
+        if item.deleted_at().is_some() {
+            self.set_status_message(format!("The linked {} is in the trash", kind.label(1)));
+            return Ok(());
+        }
+
+        if item.notebook_id() != self.notebooks.current_notebook_id {
+            self.switch_notebook(item.notebook_id())?;
+        }
+        let tab = match kind {
+            ItemKind::Task => Tab::Tasks,
+            ItemKind::Note => Tab::Notes,
+            ItemKind::Journal => Tab::Journal,
+        };
+        if self.ui.current_tab != tab {
+            self.switch_tab(tab);
+        }
+
+        let item_index = self.get_current_items().iter().position(|i| i.id() == Some(id));
+        let display_index = match item_index {
+            Some(item_idx) if self.ui.list_view_mode == ListViewMode::GroupedByTags => {
+                let (_, item_indices) = self.get_display_index_mapping();
+                item_indices.iter().position(|&idx| idx == Some(item_idx))
+            }
+            other => other,
+        };
+        match display_index {
+            Some(index) => {
+                self.ui.selected_index = index;
+                self.sync_list_state();
+                self.select_current_item();
+            }
+            None => {
+                self.ui.selected_item = Some(item.clone());
+                self.ui.item_view_scroll = 0;
+            }
+        }
+
+        self.set_status_message(format!("Opened {} '{}'", kind.label(1), item.display_title()));
+        Ok(())
+    }
+
+    /// Find a loaded item by kind and ID
+    fn find_loaded_item(&self, kind: ItemKind, id: i64) -> Option<SelectedItem> {
+        match kind {
+            ItemKind::Task => self.tasks.iter().find(|t| t.id == Some(id)).cloned().map(SelectedItem::Task),
+            ItemKind::Note => self.notes.iter().find(|n| n.id == Some(id)).cloned().map(SelectedItem::Note),
+            ItemKind::Journal => self.journals.iter().find(|j| j.id == Some(id)).cloned().map(SelectedItem::Journal),
+        }
+    }
+
+    /// Record a list action so it can be undone
+    /// Recording a new action clears the redo stack
+    pub fn record_action(&mut self, action: UndoAction) {
+        if action.is_noop() {
+            return;
+        }
+        self.action_journal.undo.push(action);
+        if self.action_journal.undo.len() > UNDO_LIMIT {
+            self.action_journal.undo.remove(0);
+        }
+        self.action_journal.redo.clear();
+    }
+
+    /// Undo the most recent list action
+    pub fn undo_last_action(&mut self) -> Result<(), DatabaseError> {
+        let Some(action) = self.action_journal.undo.pop() else {
+            self.set_status_message("Nothing to undo".to_string());
+            return Ok(());
+        };
+        // An action that fails to revert (e.g. its items were purged from the trash) is dropped
+        self.apply_action(&action, true)?;
+        self.set_status_message(format!("Undid {}", action.description()));
+        self.action_journal.redo.push(action);
+        Ok(())
+    }
+
+    /// Reapply the most recently undone list action
+    pub fn redo_last_action(&mut self) -> Result<(), DatabaseError> {
+        let Some(action) = self.action_journal.redo.pop() else {
+            self.set_status_message("Nothing to redo".to_string());
+            return Ok(());
+        };
+        self.apply_action(&action, false)?;
+        self.set_status_message(format!("Redid {}", action.description()));
+        self.action_journal.undo.push(action);
+        Ok(())
+    }
+
+    /// Apply a recorded action (`revert` = true undoes it, false reapplies it) and refresh the lists
+    fn apply_action(&mut self, action: &UndoAction, revert: bool) -> Result<(), DatabaseError> {
+        match action {
+            UndoAction::Delete { kind, ids } => {
+                if revert {
+                    if self.database.restore_items(*kind, ids)? == 0 {
+                        return Err(DatabaseError::ItemNotFound(format!("{} no longer in the trash", kind.label(ids.len()))));
+                    }
+                } else {
+                    self.database.delete_items(*kind, ids)?;
+                }
+            }
+            UndoAction::Archive { kind, ids, archived } => {
+                if *archived != revert {
+                    self.database.archive_items(*kind, ids)?;
+                } else {
+                    self.database.unarchive_items(*kind, ids)?;
+                }
+            }
+            UndoAction::TaskStatus { changes, status } => {
+                for (id, previous) in changes {
+                    let target = if revert { previous } else { status };
+                    self.database.set_tasks_status(&[*id], target)?;
+                }
+            }
+            UndoAction::Reorder { changes } => {
+                for (id, previous, new) in changes {
+                    self.database.update_task_order(*id, if revert { *previous } else { *new })?;
+                }
+            }
+            UndoAction::DeleteNotebook { notebook, items } => {
+                if revert {
+                    self.database.restore_notebook(notebook, items)?;
+                } else if let Some(id) = notebook.id {
+                    self.database.delete_notebook(id)?;
+                }
+            }
+            UndoAction::MoveToNotebook { kind, changes, notebook_id } => {
+                for (id, previous) in changes {
+                    let target = if revert { *previous } else { *notebook_id };
+                    self.database.move_items_to_notebook(*kind, &[*id], target)?;
+                }
+            }
+        }
+
+        // A redone notebook delete may remove the notebook being viewed
+        self.notebooks.notebooks = self.database.get_all_notebooks()?;
+        if let Some(current_id) = self.notebooks.current_notebook_id
+            && !self.notebooks.notebooks.iter().any(|n| n.id == Some(current_id))
+        {
+            self.switch_notebook(None)?;
+        }
+
+        self.load_data()?;
+        self.clear_selection();
+        self.adjust_selected_index();
+        self.select_current_item();
+        Ok(())
+    }
+
+    /// Navigate notebook modal fields
+    pub fn navigate_notebook_modal(&mut self) {
+        if let Some(ref mut state) = self.notebooks.modal_state {
+            // Tab only switches between NotebookList and ActionsList
+            state.current_field = match state.current_field {
+                NotebookModalField::NotebookList => NotebookModalField::ActionsList,
+                NotebookModalField::ActionsList => NotebookModalField::NotebookList,
+            };
+        }
+    }
+
+    /// Move notebook selection up
+    pub fn move_notebook_selection_up(&mut self) {
+        if let Some(ref mut state) = self.notebooks.modal_state {
+            if state.selected_index > 0 {
+                state.selected_index -= 1;
+                state.list_state.select(Some(state.selected_index));
+            }
+        }
+    }
+
+    /// Move notebook selection down
+    pub fn move_notebook_selection_down(&mut self) {
+        if let Some(ref mut state) = self.notebooks.modal_state {
+            let max_index = self.notebooks.notebooks.len(); // "[None]" + notebooks
+            // Allow incrementing up to and including max_index (the last notebook)
+            // Since index 0 is "[None]" and indices 1+ are notebooks, max_index is the last valid index
+            if state.selected_index <= max_index {
+                state.selected_index = (state.selected_index + 1).min(max_index);
+                state.list_state.select(Some(state.selected_index));
+            }
+        }
+    }
+
+    /// Move actions selection up
+    pub fn move_actions_selection_up(&mut self) {
+        if let Some(ref mut state) = self.notebooks.modal_state {
+            if state.actions_selected_index > 0 {
+                state.actions_selected_index -= 1;
+            }
+        }
+    }
+
+    /// Move actions selection down
+    pub fn move_actions_selection_down(&mut self) {
+        if let Some(ref mut state) = self.notebooks.modal_state {
+            // Actions: Add (0), Rename (1), Delete (2), Switch (3), Lock/Unlock (4)
+            let max_index = 4;
+            if state.actions_selected_index < max_index {
+                state.actions_selected_index += 1;
+            }
+        }
+    }
+
+    /// Get current notebook modal editor (for add/rename)
+    pub fn get_notebook_modal_editor(&mut self) -> Option<&mut Editor> {
+        if let Some(ref mut state) = self.notebooks.modal_state {
+            if matches!(state.mode, NotebookModalMode::Add | NotebookModalMode::Rename) {
+                Some(&mut state.name_editor)
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    // Color editor navigation methods
+    pub fn move_color_field_up(&mut self) {
+        if self.settings.color_field_index > 0 {
+            self.settings.color_field_index -= 1;
+        } else {
+            // Wrap to last field
+            self.settings.color_field_index = 4;
         }
     }
 
@@ -2974,25 +4887,20 @@ pub enum Item {
 }
 
 impl Item {
+    /// Get the database ID of the item
+    pub fn id(&self) -> Option<i64> {
+        match self {
+            Item::Task(task) => task.id,
+            Item::Note(note) => note.id,
+            Item::Journal(journal) => journal.id,
+        }
+    }
+
     pub fn matches_search(&self, query: &str) -> bool {
-        let query_lower = query.to_lowercase();
         match self {
-            Item::Task(task) => {
-                task.title.to_lowercase().contains(&query_lower) ||
-                task.description.as_ref().map(|d| d.to_lowercase().contains(&query_lower)).unwrap_or(false) ||
-                task.tags.as_ref().map(|t| t.to_lowercase().contains(&query_lower)).unwrap_or(false)
-            }
-            Item::Note(note) => {
-                note.title.to_lowercase().contains(&query_lower) ||
-                note.content.as_ref().map(|c| c.to_lowercase().contains(&query_lower)).unwrap_or(false) ||
-                note.tags.as_ref().map(|t| t.to_lowercase().contains(&query_lower)).unwrap_or(false)
-            }
-            Item::Journal(journal) => {
-                journal.date.to_lowercase().contains(&query_lower) ||
-                journal.title.as_ref().map(|t| t.to_lowercase().contains(&query_lower)).unwrap_or(false) ||
-                journal.content.as_ref().map(|c| c.to_lowercase().contains(&query_lower)).unwrap_or(false) ||
-                journal.tags.as_ref().map(|t| t.to_lowercase().contains(&query_lower)).unwrap_or(false)
-            }
+            Item::Task(task) => task.matches_search(query),
+            Item::Note(note) => note.matches_search(query),
+            Item::Journal(journal) => journal.matches_search(query),
         }
     }
 
@@ -90,6 +90,23 @@ pub fn run_event_loop(mut app: App) -> Result<(), TuiError> {
         // Check if status message should be auto-cleared
         app.check_status_message_timeout();
 
+        // Load the links of the selected item if it changed
+        if let Err(e) = app.refresh_item_links() {
+            app.set_status_message(format!("Failed to load links: {}", e));
+        }
+
+        // Relock locked entries after inactivity, and decrypt the selected one while they are unlocked
+        app.check_lock_timeout();
+        app.refresh_unlocked_content();
+
+        // Commit each change in git storage
+        app.commit_git_changes();
+
+        // Show changes made by other processes, such as the API server
+        if let Err(e) = app.reload_external_changes() {
+            app.set_status_message(format!("Failed to reload data: {}", e));
+        }
+
         // Update form editor scroll before rendering
         if app.ui.mode == crate::tui::app::Mode::Create {
             // Extract values before borrowing editor
@@ -171,6 +188,7 @@ pub fn run_event_loop(mut app: App) -> Result<(), TuiError> {
                 Event::Key(key_event) => {
                     // Only process Press events (ignore Release events to prevent double-processing on Windows)
                     if key_event.kind == KeyEventKind::Press {
+                        app.lock.last_used = std::time::Instant::now();
                         if handle_key_event(&mut app, key_event)? {
                             break; // Quit requested
                         }
@@ -194,6 +212,78 @@ pub fn run_event_loop(mut app: App) -> Result<(), TuiError> {
     Ok(())
 }
 
+/// Ask for the passphrase of an encrypted database until it opens
+/// Returns None when the user gives up (Esc or Ctrl+C).
+#[cfg(feature = "encryption")]
+pub fn unlock_database(path: &str, config: &crate::Config) -> Result<Option<crate::Database>, TuiError> {
+    use crate::database::{Database, DatabaseError};
+    use crate::tui::widgets::passphrase_prompt::render_passphrase_prompt;
+
+    let mut guard = TerminalGuard::new()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+    let mut passphrase = String::new();
+    let mut error: Option<String> = None;
+
+    let database = loop {
+        terminal.draw(|f| render_passphrase_prompt(f, f.area(), passphrase.chars().count(), error.as_deref(), config))?;
+
+        let Event::Key(key_event) = event::read()? else {
+            continue;
+        };
+        if key_event.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key_event.code {
+            KeyCode::Esc => break None,
+            KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => break None,
+            KeyCode::Enter => match Database::open(path, Some(&passphrase)) {
+                Ok(db) => break Some(db),
+                Err(DatabaseError::WrongPassphrase) => {
+                    error = Some("Wrong passphrase, try again".to_string());
+                    passphrase.clear();
+                }
+                Err(e) => return Err(e.into()),
+            },
+            KeyCode::Backspace => {
+                passphrase.pop();
+            }
+            KeyCode::Char(c) => {
+                passphrase.push(c);
+                error = None;
+            }
+            _ => {}
+        }
+    };
+
+    guard.restore()?;
+    Ok(database)
+}
+
+fn handle_passphrase_prompt(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    match key_event.code {
+        KeyCode::Esc => app.cancel_passphrase_prompt(),
+        KeyCode::Enter => {
+            if let Err(e) = app.submit_passphrase() {
+                app.cancel_passphrase_prompt();
+                app.set_status_message(format!("Failed to unlock: {}", e));
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(ref mut prompt) = app.lock.prompt {
+                prompt.passphrase.pop();
+            }
+        }
+        KeyCode::Char(c) if !crate::utils::has_primary_modifier(key_event.modifiers) => {
+            if let Some(ref mut prompt) = app.lock.prompt {
+                prompt.passphrase.push(c);
+            }
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
 fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
     match key_event.code {
         KeyCode::Up => {
@@ -226,6 +316,7 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                 match item {
                     crate::tui::app::SelectedItem::Task(task) => {
                         if let Some(id) = task.id {
+                            let was_archived = task.archived;
                             if app.modals.delete_modal_selection == 0 {
                                 // Archive
                                 if let Err(e) = app.database.archive_task(id) {
@@ -236,6 +327,9 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                                     } else {
                                         app.adjust_selected_index();
                                         app.select_current_item();
+                                        if !was_archived {
+                                            app.record_action(crate::tui::app::UndoAction::Archive { kind: crate::models::ItemKind::Task, ids: vec![id], archived: true });
+                                        }
                                         app.set_status_message("Task archived".to_string());
                                     }
                                 }
@@ -249,7 +343,8 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                                     } else {
                                         app.adjust_selected_index();
                                         app.select_current_item();
-                                        app.set_status_message("Task deleted".to_string());
+                                        app.record_action(crate::tui::app::UndoAction::Delete { kind: crate::models::ItemKind::Task, ids: vec![id] });
+                                        app.set_status_message("Task moved to trash".to_string());
                                     }
                                 }
                             }
@@ -259,6 +354,7 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                     }
                     crate::tui::app::SelectedItem::Note(note) => {
                         if let Some(id) = note.id {
+                            let was_archived = note.archived;
                             if app.modals.delete_modal_selection == 0 {
                                 // Archive
                                 if let Err(e) = app.database.archive_note(id) {
@@ -269,6 +365,9 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                                     } else {
                                         app.adjust_selected_index();
                                         app.select_current_item();
+                                        if !was_archived {
+                                            app.record_action(crate::tui::app::UndoAction::Archive { kind: crate::models::ItemKind::Note, ids: vec![id], archived: true });
+                                        }
                                         app.set_status_message("Note archived".to_string());
                                     }
                                 }
@@ -282,7 +381,8 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                                     } else {
                                         app.adjust_selected_index();
                                         app.select_current_item();
-                                        app.set_status_message("Note deleted".to_string());
+                                        app.record_action(crate::tui::app::UndoAction::Delete { kind: crate::models::ItemKind::Note, ids: vec![id] });
+                                        app.set_status_message("Note moved to trash".to_string());
                                     }
                                 }
                             }
@@ -292,6 +392,7 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                     }
                     crate::tui::app::SelectedItem::Journal(journal) => {
                         if let Some(id) = journal.id {
+                            let was_archived = journal.archived;
                             if app.modals.delete_modal_selection == 0 {
                                 // Archive
                                 if let Err(e) = app.database.archive_journal(id) {
@@ -302,6 +403,9 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                                     } else {
                                         app.adjust_selected_index();
                                         app.select_current_item();
+                                        if !was_archived {
+                                            app.record_action(crate::tui::app::UndoAction::Archive { kind: crate::models::ItemKind::Journal, ids: vec![id], archived: true });
+                                        }
                                         app.set_status_message("Journal archived".to_string());
                                     }
                                 }
@@ -315,7 +419,8 @@ fn handle_delete_confirmation_modal(app: &mut App, key_event: KeyEvent) -> Resul
                                     } else {
                                         app.adjust_selected_index();
                                         app.select_current_item();
-                                        app.set_status_message("Journal deleted".to_string());
+                                        app.record_action(crate::tui::app::UndoAction::Delete { kind: crate::models::ItemKind::Journal, ids: vec![id] });
+                                        app.set_status_message("Journal moved to trash".to_string());
                                     }
                                 }
                             }
@@ -431,6 +536,11 @@ fn handle_markdown_help_mode(app: &mut App, key_event: KeyEvent) -> Result<bool,
 }
 
 fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    // The passphrase prompt of locked entries takes all keys while it is open
+    if app.lock.prompt.is_some() {
+        return handle_passphrase_prompt(app, key_event);
+    }
+
     // Handle delete confirmation modal first (before other modes)
     if app.modals.delete_confirmation.is_some() {
         return handle_delete_confirmation_modal(app, key_event);
@@ -728,6 +838,8 @@ fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError
                                 app.move_settings_sidebar_width_up();
                             } else if cat == "Display Settings" {
                                 app.move_settings_display_mode_up();
+                            } else if cat == "Backup Settings" {
+                                app.move_settings_backup_up();
                             }
                         }
                         return Ok(false);
@@ -739,6 +851,8 @@ fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError
                                 app.move_settings_sidebar_width_down();
                             } else if cat == "Display Settings" {
                                 app.move_settings_display_mode_down();
+                            } else if cat == "Backup Settings" {
+                                app.move_settings_backup_down();
                             }
                         }
                         return Ok(false);
@@ -857,6 +971,36 @@ fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError
         return handle_notebook_modal_mode(app, key_event);
     }
 
+    // Handle move-to-notebook mode
+    if app.ui.mode == crate::tui::app::Mode::MoveToNotebook {
+        return handle_move_notebook_mode(app, key_event);
+    }
+
+    // Handle bulk actions mode
+    if app.ui.mode == crate::tui::app::Mode::BulkActions {
+        return handle_bulk_actions_mode(app, key_event);
+    }
+
+    // Handle trash mode
+    if app.ui.mode == crate::tui::app::Mode::Trash {
+        return handle_trash_mode(app, key_event);
+    }
+
+    // Handle revision history mode
+    if app.ui.mode == crate::tui::app::Mode::History {
+        return handle_history_mode(app, key_event);
+    }
+
+    // Handle attachments mode
+    if app.ui.mode == crate::tui::app::Mode::Attachments {
+        return handle_attachments_mode(app, key_event);
+    }
+
+    // Handle sync conflicts mode
+    if app.ui.mode == crate::tui::app::Mode::SyncConflicts {
+        return handle_sync_conflicts_mode(app, key_event);
+    }
+
     // Handle search mode
     if app.ui.mode == crate::tui::app::Mode::Search {
         return handle_search_mode(app, key_event);
@@ -867,6 +1011,11 @@ fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError
         return handle_filter_mode(app, key_event);
     }
 
+    // Handle multi-select keys (unhandled keys fall through to global bindings)
+    if app.ui.mode == crate::tui::app::Mode::View && app.selection.active {
+        return handle_multi_select_mode(app, key_event);
+    }
+
     // Handle view mode specific key bindings (Ctrl+A and Ctrl+C) before global bindings
     // This needs to be early to prevent terminal from intercepting Ctrl+A
     if app.ui.mode == crate::tui::app::Mode::View && app.ui.selected_item.is_some() {
@@ -1348,7 +1497,7 @@ fn handle_notebook_modal_mode(app: &mut App, key_event: KeyEvent) -> Result<bool
                 match state.current_field {
                     crate::tui::app::NotebookModalField::ActionsList => {
                         // Handle action based on actions_selected_index
-                        // 0 = Add, 1 = Rename, 2 = Delete, 3 = Switch
+                        // 0 = Add, 1 = Rename, 2 = Delete, 3 = Switch, 4 = Lock/Unlock
                         match state.actions_selected_index {
                             0 => {
                                 // Add
@@ -1413,6 +1562,16 @@ fn handle_notebook_modal_mode(app: &mut App, key_event: KeyEvent) -> Result<bool
                                 }
                                 return Ok(false);
                             }
+                            4 if state.selected_index > 0 => {
+                                // Lock/Unlock (can't lock "[None]")
+                                let notebook_id = app.notebooks.notebooks.get(state.selected_index - 1)
+                                    .and_then(|n| n.id);
+                                if let Some(id) = notebook_id
+                                    && let Err(e) = app.toggle_notebook_lock(id)
+                                {
+                                    app.set_status_message(format!("Failed to lock notebook: {}", e));
+                                }
+                            }
                             _ => {}
                         }
                     }
@@ -1486,6 +1645,400 @@ fn handle_notebook_modal_mode(app: &mut App, key_event: KeyEvent) -> Result<bool
     Ok(false)
 }
 
+fn handle_move_notebook_mode(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    match key_event.code {
+        KeyCode::Esc => {
+            app.exit_move_notebook_mode();
+            Ok(false)
+        }
+        KeyCode::Up => {
+            app.move_notebook_target_up();
+            Ok(false)
+        }
+        KeyCode::Down => {
+            app.move_notebook_target_down();
+            Ok(false)
+        }
+        KeyCode::Enter => {
+            if let Err(e) = app.confirm_move_to_notebook() {
+                app.set_status_message(format!("Failed to move items: {}", e));
+            }
+            Ok(false)
+        }
+        _ => {
+            // j/k navigate the notebook list like the main list
+            let list_down_binding = parse_key_binding(&app.config.key_bindings.list_down)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_up_binding = parse_key_binding(&app.config.key_bindings.list_up)
+                .map_err(TuiError::KeyBindingError)?;
+            if matches_key_event(key_event, &list_down_binding) {
+                app.move_notebook_target_down();
+            } else if matches_key_event(key_event, &list_up_binding) {
+                app.move_notebook_target_up();
+            }
+            Ok(false)
+        }
+    }
+}
+
+fn handle_multi_select_mode(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    // Shift+Up/Down extend the selection from the anchor to the cursor
+    if key_event.modifiers.contains(KeyModifiers::SHIFT) {
+        match key_event.code {
+            KeyCode::Up => {
+                app.extend_selection(false);
+                return Ok(false);
+            }
+            KeyCode::Down => {
+                app.extend_selection(true);
+                return Ok(false);
+            }
+            _ => {}
+        }
+    }
+
+    if key_event.code == KeyCode::Esc {
+        app.toggle_multi_select_mode();
+        return Ok(false);
+    }
+
+    let multi_select_binding = parse_key_binding(&app.config.key_bindings.multi_select)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &multi_select_binding) {
+        app.toggle_multi_select_mode();
+        return Ok(false);
+    }
+
+    let toggle_selection_binding = parse_key_binding(&app.config.key_bindings.toggle_selection)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &toggle_selection_binding) {
+        app.toggle_item_selection();
+        return Ok(false);
+    }
+
+    let select_all_binding = parse_key_binding(&app.config.key_bindings.select_all)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &select_all_binding) {
+        app.select_all_items();
+        return Ok(false);
+    }
+
+    // Enter opens the bulk actions menu for the selection
+    let select_binding = parse_key_binding(&app.config.key_bindings.select)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &select_binding) {
+        app.enter_bulk_actions_mode();
+        return Ok(false);
+    }
+
+    // Delete asks once for the whole selection
+    let delete_binding = parse_key_binding(&app.config.key_bindings.delete)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &delete_binding) {
+        app.enter_bulk_delete_confirmation();
+        return Ok(false);
+    }
+
+    // Navigation, tabs and move-to-notebook work as usual
+    handle_global_key_bindings(app, key_event)
+}
+
+fn handle_bulk_actions_mode(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    let step = match app.modals.bulk_actions {
+        Some(ref state) => state.step.clone(),
+        None => {
+            app.exit_bulk_actions_mode();
+            return Ok(false);
+        }
+    };
+
+    match step {
+        crate::tui::app::BulkActionStep::Choose => {
+            match key_event.code {
+                KeyCode::Esc => app.exit_bulk_actions_mode(),
+                KeyCode::Up => app.move_bulk_action_up(),
+                KeyCode::Down => app.move_bulk_action_down(),
+                KeyCode::Enter => {
+                    if let Err(e) = app.choose_bulk_action() {
+                        app.set_status_message(format!("Bulk action failed: {}", e));
+                    }
+                }
+                _ => {
+                    let list_down_binding = parse_key_binding(&app.config.key_bindings.list_down)
+                        .map_err(TuiError::KeyBindingError)?;
+                    let list_up_binding = parse_key_binding(&app.config.key_bindings.list_up)
+                        .map_err(TuiError::KeyBindingError)?;
+                    if matches_key_event(key_event, &list_down_binding) {
+                        app.move_bulk_action_down();
+                    } else if matches_key_event(key_event, &list_up_binding) {
+                        app.move_bulk_action_up();
+                    }
+                }
+            }
+        }
+        crate::tui::app::BulkActionStep::TagInput { .. } => {
+            match key_event.code {
+                KeyCode::Esc => {
+                    // Go back to the action list
+                    if let Some(ref mut state) = app.modals.bulk_actions {
+                        state.step = crate::tui::app::BulkActionStep::Choose;
+                    }
+                }
+                KeyCode::Enter => {
+                    if let Err(e) = app.confirm_bulk_tag_input() {
+                        app.set_status_message(format!("Bulk action failed: {}", e));
+                    }
+                }
+                _ => {
+                    if let Some(editor) = app.get_bulk_tag_editor() {
+                        let extend_selection = key_event.modifiers.contains(KeyModifiers::SHIFT);
+                        match key_event.code {
+                            KeyCode::Char(c) if !crate::utils::has_primary_modifier(key_event.modifiers) => {
+                                editor.insert_char(c);
+                            }
+                            KeyCode::Backspace => editor.delete_char(),
+                            KeyCode::Left => editor.move_cursor_left(extend_selection),
+                            KeyCode::Right => editor.move_cursor_right(extend_selection),
+                            KeyCode::Home => editor.move_cursor_home(extend_selection),
+                            KeyCode::End => editor.move_cursor_end(extend_selection),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        crate::tui::app::BulkActionStep::ConfirmDelete { selection } => {
+            match key_event.code {
+                KeyCode::Up | KeyCode::Down => {
+                    // Two options (Delete, Cancel), so up and down both switch
+                    if let Some(ref mut state) = app.modals.bulk_actions {
+                        state.step = crate::tui::app::BulkActionStep::ConfirmDelete { selection: 1 - selection };
+                    }
+                }
+                KeyCode::Enter => {
+                    if selection == 0 {
+                        if let Err(e) = app.apply_bulk_action(crate::tui::app::BulkAction::Delete, None) {
+                            app.set_status_message(format!("Failed to delete items: {}", e));
+                        }
+                    } else {
+                        app.exit_bulk_actions_mode();
+                    }
+                }
+                KeyCode::Esc => app.exit_bulk_actions_mode(),
+                _ => {}
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn handle_trash_mode(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    // A pending purge/empty confirmation takes every key: 'y' confirms, anything else cancels
+    if let Some(confirm) = app.modals.trash.as_ref().and_then(|state| state.confirm) {
+        if key_event.code == KeyCode::Char('y') || key_event.code == KeyCode::Char('Y') {
+            let result = match confirm {
+                crate::tui::app::TrashConfirm::Purge => app.purge_trash_item(),
+                crate::tui::app::TrashConfirm::Empty => app.empty_trash(),
+            };
+            if let Err(e) = result {
+                app.set_status_message(format!("Failed to purge trash: {}", e));
+            }
+        } else {
+            app.cancel_trash_confirm();
+        }
+        return Ok(false);
+    }
+
+    match key_event.code {
+        KeyCode::Esc => app.exit_trash_mode(),
+        KeyCode::Up => app.move_trash_selection_up(),
+        KeyCode::Down => app.move_trash_selection_down(),
+        KeyCode::Enter | KeyCode::Char('r') => {
+            if let Err(e) = app.restore_trash_item() {
+                app.set_status_message(format!("Failed to restore item: {}", e));
+            }
+        }
+        KeyCode::Char('d') | KeyCode::Delete => {
+            app.request_trash_confirm(crate::tui::app::TrashConfirm::Purge);
+        }
+        KeyCode::Char('E') => {
+            app.request_trash_confirm(crate::tui::app::TrashConfirm::Empty);
+        }
+        _ => {
+            let trash_binding = parse_key_binding(&app.config.key_bindings.trash)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_down_binding = parse_key_binding(&app.config.key_bindings.list_down)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_up_binding = parse_key_binding(&app.config.key_bindings.list_up)
+                .map_err(TuiError::KeyBindingError)?;
+            if matches_key_event(key_event, &trash_binding) {
+                app.exit_trash_mode();
+            } else if matches_key_event(key_event, &list_down_binding) {
+                app.move_trash_selection_down();
+            } else if matches_key_event(key_event, &list_up_binding) {
+                app.move_trash_selection_up();
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn handle_history_mode(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    // A pending restore confirmation takes every key: 'y' confirms, anything else cancels
+    if app.modals.history.as_ref().is_some_and(|state| state.confirm_restore) {
+        if key_event.code == KeyCode::Char('y') || key_event.code == KeyCode::Char('Y') {
+            if let Err(e) = app.restore_selected_revision() {
+                app.set_status_message(format!("Failed to restore revision: {}", e));
+            }
+        } else {
+            app.cancel_history_restore();
+        }
+        return Ok(false);
+    }
+
+    match key_event.code {
+        KeyCode::Esc => app.exit_history_mode(),
+        KeyCode::Up => app.move_history_selection_up(),
+        KeyCode::Down => app.move_history_selection_down(),
+        KeyCode::PageUp => app.scroll_history_diff(false),
+        KeyCode::PageDown => app.scroll_history_diff(true),
+        KeyCode::Enter | KeyCode::Char('r') => app.request_history_restore(),
+        _ => {
+            let history_binding = parse_key_binding(&app.config.key_bindings.history)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_down_binding = parse_key_binding(&app.config.key_bindings.list_down)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_up_binding = parse_key_binding(&app.config.key_bindings.list_up)
+                .map_err(TuiError::KeyBindingError)?;
+            if matches_key_event(key_event, &history_binding) {
+                app.exit_history_mode();
+            } else if matches_key_event(key_event, &list_down_binding) {
+                app.move_history_selection_down();
+            } else if matches_key_event(key_event, &list_up_binding) {
+                app.move_history_selection_up();
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn handle_sync_conflicts_mode(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    // A pending confirmation takes every key: 'y' confirms, anything else cancels
+    if app.modals.sync_conflicts.as_ref().is_some_and(|state| state.confirm_use_other) {
+        if key_event.code == KeyCode::Char('y') || key_event.code == KeyCode::Char('Y') {
+            if let Err(e) = app.use_other_sync_version() {
+                app.set_status_message(format!("Failed to resolve conflict: {}", e));
+            }
+        } else {
+            app.cancel_use_other_version();
+        }
+        return Ok(false);
+    }
+
+    match key_event.code {
+        KeyCode::Esc => app.exit_sync_conflicts_mode(),
+        KeyCode::Up => app.move_sync_conflicts_selection_up(),
+        KeyCode::Down => app.move_sync_conflicts_selection_down(),
+        KeyCode::PageUp => app.scroll_sync_conflicts_diff(false),
+        KeyCode::PageDown => app.scroll_sync_conflicts_diff(true),
+        KeyCode::Enter | KeyCode::Char('o') => app.request_use_other_version(),
+        KeyCode::Char('c') => {
+            if let Err(e) = app.keep_current_sync_version() {
+                app.set_status_message(format!("Failed to resolve conflict: {}", e));
+            }
+        }
+        _ => {
+            let sync_conflicts_binding = parse_key_binding(&app.config.key_bindings.sync_conflicts)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_down_binding = parse_key_binding(&app.config.key_bindings.list_down)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_up_binding = parse_key_binding(&app.config.key_bindings.list_up)
+                .map_err(TuiError::KeyBindingError)?;
+            if matches_key_event(key_event, &sync_conflicts_binding) {
+                app.exit_sync_conflicts_mode();
+            } else if matches_key_event(key_event, &list_down_binding) {
+                app.move_sync_conflicts_selection_down();
+            } else if matches_key_event(key_event, &list_up_binding) {
+                app.move_sync_conflicts_selection_up();
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn handle_attachments_mode(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
+    match app.modals.attachments.as_ref().map(|state| &state.step) {
+        // A pending detach confirmation takes every key: 'y' confirms, anything else cancels
+        Some(crate::tui::app::AttachmentsStep::ConfirmDetach) => {
+            if key_event.code == KeyCode::Char('y') || key_event.code == KeyCode::Char('Y') {
+                if let Err(e) = app.detach_selected_attachment() {
+                    app.set_status_message(format!("Failed to remove attachment: {}", e));
+                }
+            } else {
+                app.cancel_attachments_step();
+            }
+            return Ok(false);
+        }
+        Some(crate::tui::app::AttachmentsStep::AddPath(_)) => {
+            match key_event.code {
+                KeyCode::Esc => app.cancel_attachments_step(),
+                KeyCode::Enter => {
+                    if let Err(e) = app.confirm_add_attachment() {
+                        app.set_status_message(format!("Failed to attach file: {}", e));
+                    }
+                }
+                _ => {
+                    if let Some(editor) = app.get_attachment_path_editor() {
+                        let extend_selection = key_event.modifiers.contains(KeyModifiers::SHIFT);
+                        match key_event.code {
+                            KeyCode::Char(c) if !crate::utils::has_primary_modifier(key_event.modifiers) => {
+                                editor.insert_char(c);
+                            }
+                            KeyCode::Backspace => editor.delete_char(),
+                            KeyCode::Left => editor.move_cursor_left(extend_selection),
+                            KeyCode::Right => editor.move_cursor_right(extend_selection),
+                            KeyCode::Home => editor.move_cursor_home(extend_selection),
+                            KeyCode::End => editor.move_cursor_end(extend_selection),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            return Ok(false);
+        }
+        _ => {}
+    }
+
+    match key_event.code {
+        KeyCode::Esc => app.exit_attachments_mode(),
+        KeyCode::Up => app.move_attachments_selection_up(),
+        KeyCode::Down => app.move_attachments_selection_down(),
+        KeyCode::Char('a') => app.start_add_attachment(),
+        KeyCode::Char('d') => app.request_detach_attachment(),
+        KeyCode::Enter | KeyCode::Char('o') => {
+            if let Err(e) = app.open_selected_attachment() {
+                app.set_status_message(format!("Failed to open attachment: {}", e));
+            }
+        }
+        _ => {
+            let attachments_binding = parse_key_binding(&app.config.key_bindings.attachments)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_down_binding = parse_key_binding(&app.config.key_bindings.list_down)
+                .map_err(TuiError::KeyBindingError)?;
+            let list_up_binding = parse_key_binding(&app.config.key_bindings.list_up)
+                .map_err(TuiError::KeyBindingError)?;
+            if matches_key_event(key_event, &attachments_binding) {
+                app.exit_attachments_mode();
+            } else if matches_key_event(key_event, &list_down_binding) {
+                app.move_attachments_selection_down();
+            } else if matches_key_event(key_event, &list_up_binding) {
+                app.move_attachments_selection_up();
+            }
+        }
+    }
+    Ok(false)
+}
+
 fn handle_search_mode(app: &mut App, key_event: KeyEvent) -> Result<bool, TuiError> {
     match key_event.code {
         KeyCode::Esc => {
@@ -1938,6 +2491,8 @@ fn handle_global_key_bindings(app: &mut App, key_event: KeyEvent) -> Result<bool
                     app.move_settings_sidebar_width_down();
                 } else if category == "Display Settings" {
                     app.move_settings_display_mode_down();
+                } else if category == "Backup Settings" {
+                    app.move_settings_backup_down();
                 }
             }
         } else {
@@ -1959,6 +2514,8 @@ fn handle_global_key_bindings(app: &mut App, key_event: KeyEvent) -> Result<bool
                     app.move_settings_sidebar_width_up();
                 } else if category == "Display Settings" {
                     app.move_settings_display_mode_up();
+                } else if category == "Backup Settings" {
+                    app.move_settings_backup_up();
                 }
             }
         } else {
@@ -2035,6 +2592,10 @@ fn handle_global_key_bindings(app: &mut App, key_event: KeyEvent) -> Result<bool
                         if let Err(e) = app.apply_display_mode() {
                             app.set_status_message(format!("Failed to change display mode: {}", e));
                         }
+                    } else if category == "Backup Settings"
+                        && let Err(e) = app.apply_backup_setting()
+                    {
+                        app.set_status_message(format!("Failed to change backup settings: {}", e));
                     }
                 }
             }
@@ -2076,6 +2637,128 @@ fn handle_global_key_bindings(app: &mut App, key_event: KeyEvent) -> Result<bool
         return Ok(false);
     }
 
+    // Check for move-to-notebook binding
+    let move_to_notebook_binding = parse_key_binding(&app.config.key_bindings.move_to_notebook)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &move_to_notebook_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        app.enter_move_notebook_mode();
+        return Ok(false);
+    }
+
+    // Check for trash binding
+    let trash_binding = parse_key_binding(&app.config.key_bindings.trash)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &trash_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        if let Err(e) = app.enter_trash_mode() {
+            app.set_status_message(format!("Failed to open trash: {}", e));
+        }
+        return Ok(false);
+    }
+
+    // Check for revision history binding
+    let history_binding = parse_key_binding(&app.config.key_bindings.history)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &history_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        if let Err(e) = app.enter_history_mode() {
+            app.set_status_message(format!("Failed to load revision history: {}", e));
+        }
+        return Ok(false);
+    }
+
+    // Check for attachments binding
+    let attachments_binding = parse_key_binding(&app.config.key_bindings.attachments)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &attachments_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        if let Err(e) = app.enter_attachments_mode() {
+            app.set_status_message(format!("Failed to load attachments: {}", e));
+        }
+        return Ok(false);
+    }
+
+    // Check for sync conflicts binding
+    let sync_conflicts_binding = parse_key_binding(&app.config.key_bindings.sync_conflicts)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &sync_conflicts_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        if let Err(e) = app.enter_sync_conflicts_mode() {
+            app.set_status_message(format!("Failed to load sync conflicts: {}", e));
+        }
+        return Ok(false);
+    }
+
+    // Check for locking the selected journal entry, and for unlocking or relocking locked entries
+    let lock_binding = parse_key_binding(&app.config.key_bindings.lock)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &lock_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        match app.ui.selected_item {
+            Some(crate::tui::app::SelectedItem::Journal(ref journal)) => {
+                if let Some(id) = journal.id
+                    && let Err(e) = app.toggle_journal_lock(id)
+                {
+                    app.set_status_message(format!("Failed to lock journal entry: {}", e));
+                }
+            }
+            Some(_) => app.set_status_message("Only journal entries can be locked".to_string()),
+            None => app.set_status_message("No item selected".to_string()),
+        }
+        return Ok(false);
+    }
+    let unlock_binding = parse_key_binding(&app.config.key_bindings.unlock)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &unlock_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        if let Err(e) = app.toggle_lock_session() {
+            app.set_status_message(format!("Failed to unlock: {}", e));
+        }
+        return Ok(false);
+    }
+
+    // Check for link navigation in the item view
+    let next_link_binding = parse_key_binding(&app.config.key_bindings.next_link)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &next_link_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        app.select_next_link();
+        return Ok(false);
+    }
+    let previous_link_binding = parse_key_binding(&app.config.key_bindings.previous_link)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &previous_link_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        app.select_previous_link();
+        return Ok(false);
+    }
+    let follow_link_binding = parse_key_binding(&app.config.key_bindings.follow_link)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &follow_link_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        if let Err(e) = app.follow_selected_link() {
+            app.set_status_message(format!("Failed to follow link: {}", e));
+        }
+        return Ok(false);
+    }
+
+    // Check for undo/redo of list actions (the editor handles its own undo in edit mode)
+    let undo_binding = parse_key_binding(&app.config.key_bindings.undo)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &undo_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        if let Err(e) = app.undo_last_action() {
+            app.set_status_message(format!("Failed to undo: {}", e));
+        }
+        return Ok(false);
+    }
+    let redo_binding = parse_key_binding(&app.config.key_bindings.redo)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &redo_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        if let Err(e) = app.redo_last_action() {
+            app.set_status_message(format!("Failed to redo: {}", e));
+        }
+        return Ok(false);
+    }
+
+    // Check for multi-select binding
+    let multi_select_binding = parse_key_binding(&app.config.key_bindings.multi_select)
+        .map_err(TuiError::KeyBindingError)?;
+    if matches_key_event(key_event, &multi_select_binding) && app.ui.mode == crate::tui::app::Mode::View {
+        app.toggle_multi_select_mode();
+        return Ok(false);
+    }
+
     // Check for toggle task status binding
     let toggle_task_status_binding = parse_key_binding(&app.config.key_bindings.toggle_task_status)
         .map_err(|e| TuiError::KeyBindingError(e))?;
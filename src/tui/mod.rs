@@ -8,6 +8,8 @@ pub mod widgets;
 pub use app::{App, Mode, Tab};
 pub use error::TuiError;
 pub use events::run_event_loop;
+#[cfg(feature = "encryption")]
+pub use events::unlock_database;
 pub use layout::Layout;
 pub use render::render;
 
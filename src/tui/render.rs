@@ -37,6 +37,12 @@ pub fn render(f: &mut Frame, app: &mut App, layout: &Layout) {
     // Render sidebar if not collapsed
     if app.ui.sidebar_state == crate::tui::app::SidebarState::Expanded && layout.sidebar_area.width > 0 {
         let items = app.get_current_items();
+        // In multi-select mode, list items show a marker for selected items
+        let selection: Option<std::collections::BTreeSet<i64>> = if app.selection.active {
+            Some(app.get_selected_ids().into_iter().collect())
+        } else {
+            None
+        };
         match app.ui.current_tab {
             crate::tui::app::Tab::Tasks => {
                 let tasks: Vec<_> = items.iter()
@@ -49,7 +55,7 @@ pub fn render(f: &mut Frame, app: &mut App, layout: &Layout) {
                     })
                     .collect();
                 let total_count = app.tasks.len();
-                render_task_list(f, layout.sidebar_area, &tasks, total_count, &mut app.ui.list_state, &app.config, app.ui.list_view_mode);
+                render_task_list(f, layout.sidebar_area, &tasks, total_count, &mut app.ui.list_state, &app.config, app.ui.list_view_mode, selection.as_ref());
             }
             crate::tui::app::Tab::Notes => {
                 let notes: Vec<_> = items.iter()
@@ -62,7 +68,7 @@ pub fn render(f: &mut Frame, app: &mut App, layout: &Layout) {
                     })
                     .collect();
                 let total_count = app.notes.len();
-                render_note_list(f, layout.sidebar_area, &notes, total_count, &mut app.ui.list_state, &app.config, app.ui.list_view_mode);
+                render_note_list(f, layout.sidebar_area, &notes, total_count, &mut app.ui.list_state, &app.config, app.ui.list_view_mode, selection.as_ref());
             }
             crate::tui::app::Tab::Journal => {
                 let journals: Vec<_> = items.iter()
@@ -75,7 +81,7 @@ pub fn render(f: &mut Frame, app: &mut App, layout: &Layout) {
                     })
                     .collect();
                 let total_count = app.journals.len();
-                render_journal_list(f, layout.sidebar_area, &journals, total_count, &mut app.ui.list_state, &app.config, app.ui.list_view_mode);
+                render_journal_list(f, layout.sidebar_area, &journals, total_count, &mut app.ui.list_state, &app.config, app.ui.list_view_mode, selection.as_ref());
             }
         }
     }
@@ -83,10 +89,11 @@ pub fn render(f: &mut Frame, app: &mut App, layout: &Layout) {
     // Render main pane (always render normal content first)
     // Note: Help mode and Settings mode render popup overlays separately after normal content
     match app.ui.mode {
-            crate::tui::app::Mode::Help | crate::tui::app::Mode::View | crate::tui::app::Mode::Filter | crate::tui::app::Mode::NotebookModal => {
+            crate::tui::app::Mode::Help | crate::tui::app::Mode::View | crate::tui::app::Mode::Filter | crate::tui::app::Mode::NotebookModal | crate::tui::app::Mode::MoveToNotebook | crate::tui::app::Mode::BulkActions | crate::tui::app::Mode::Trash | crate::tui::app::Mode::History | crate::tui::app::Mode::Attachments | crate::tui::app::Mode::SyncConflicts => {
                 // View mode - show selected item details (Help mode shows same content with overlay)
                 if let Some(ref item) = app.ui.selected_item {
-                    render_item_view(f, layout.main_area, item, &app.config, app.ui.item_view_scroll);
+                    let unlocked_content = app.lock.unlocked_content(item);
+                    render_item_view(f, layout.main_area, item, unlocked_content, &app.config, &mut app.ui.item_view_scroll, &mut app.links);
                 } else {
                     // Empty state
                     use ratatui::widgets::{Block, Borders, Paragraph};
@@ -137,7 +144,8 @@ pub fn render(f: &mut Frame, app: &mut App, layout: &Layout) {
             crate::tui::app::Mode::Settings => {
                 // Settings mode - show normal content (will be overlaid)
                 if let Some(ref item) = app.ui.selected_item {
-                    render_item_view(f, layout.main_area, item, &app.config, app.ui.item_view_scroll);
+                    let unlocked_content = app.lock.unlocked_content(item);
+                    render_item_view(f, layout.main_area, item, unlocked_content, &app.config, &mut app.ui.item_view_scroll, &mut app.links);
                 } else {
                     // Empty state
                     use ratatui::widgets::{Block, Borders, Paragraph};
@@ -188,12 +196,60 @@ pub fn render(f: &mut Frame, app: &mut App, layout: &Layout) {
         render_notebook_modal(f, f.area(), app);
     }
 
+    // Render move-to-notebook modal overlay if in move mode (after normal content)
+    if app.ui.mode == crate::tui::app::Mode::MoveToNotebook {
+        use crate::tui::widgets::move_notebook_modal::render_move_notebook_modal;
+        render_move_notebook_modal(f, f.area(), app);
+    }
+
+    // Render bulk actions modal overlay if in bulk actions mode (after normal content)
+    if app.ui.mode == crate::tui::app::Mode::BulkActions {
+        use crate::tui::widgets::bulk_actions_modal::render_bulk_actions_modal;
+        render_bulk_actions_modal(f, f.area(), app);
+    }
+
+    // Render trash view overlay if in trash mode (after normal content)
+    if app.ui.mode == crate::tui::app::Mode::Trash {
+        use crate::tui::widgets::trash_view::render_trash_view;
+        render_trash_view(f, f.area(), app);
+    }
+
+    // Render revision history overlay if in history mode (after normal content)
+    if app.ui.mode == crate::tui::app::Mode::History {
+        use crate::tui::widgets::history_view::render_history_view;
+        render_history_view(f, f.area(), app);
+    }
+
+    // Render attachments overlay if in attachments mode (after normal content)
+    if app.ui.mode == crate::tui::app::Mode::Attachments {
+        use crate::tui::widgets::attachments_view::render_attachments_view;
+        render_attachments_view(f, f.area(), app);
+    }
+
+    // Render sync conflicts overlay if in sync conflicts mode (after normal content)
+    if app.ui.mode == crate::tui::app::Mode::SyncConflicts {
+        use crate::tui::widgets::sync_conflicts_view::render_sync_conflicts_view;
+        render_sync_conflicts_view(f, f.area(), app);
+    }
+
+    // Render the passphrase prompt of locked entries over everything else
+    if app.lock.prompt.is_some() {
+        use crate::tui::widgets::passphrase_prompt::render_unlock_prompt;
+        render_unlock_prompt(f, f.area(), app);
+    }
+
     // Render status bar
     let key_hints = get_key_hints(app);
     render_status_bar(f, layout.status_area, app.status.message.as_ref(), &key_hints, &app.config);
 }
 
 fn get_key_hints(app: &App) -> Vec<String> {
+    if app.lock.prompt.is_some() {
+        return vec![
+            "Enter: Unlock".to_string(),
+            "Esc: Cancel".to_string(),
+        ];
+    }
     match app.ui.mode {
         crate::tui::app::Mode::Help => {
             vec![
@@ -285,6 +341,78 @@ fn get_key_hints(app: &App) -> Vec<String> {
                 "Esc: Cancel".to_string(),
             ]
         }
+        crate::tui::app::Mode::MoveToNotebook => {
+            vec![
+                "↑/↓: Choose notebook".to_string(),
+                format!("{}: Move here", crate::utils::format_key_binding_for_display(&app.config.key_bindings.select)),
+                "Esc: Cancel".to_string(),
+            ]
+        }
+        crate::tui::app::Mode::BulkActions => {
+            match app.modals.bulk_actions.as_ref().map(|state| &state.step) {
+                Some(crate::tui::app::BulkActionStep::TagInput { .. }) => vec![
+                    "Enter: Apply".to_string(),
+                    "Esc: Back".to_string(),
+                ],
+                _ => vec![
+                    "↑/↓: Navigate".to_string(),
+                    format!("{}: Confirm", crate::utils::format_key_binding_for_display(&app.config.key_bindings.select)),
+                    "Esc: Cancel".to_string(),
+                ],
+            }
+        }
+        crate::tui::app::Mode::Trash => {
+            vec![
+                "↑/↓: Navigate".to_string(),
+                "Enter/r: Restore".to_string(),
+                "d: Delete permanently".to_string(),
+                "E: Empty trash".to_string(),
+                format!("Esc/{}: Close", crate::utils::format_key_binding_for_display(&app.config.key_bindings.trash)),
+            ]
+        }
+        crate::tui::app::Mode::History => {
+            vec![
+                "↑/↓: Select version".to_string(),
+                "PgUp/PgDn: Scroll diff".to_string(),
+                "Enter/r: Restore".to_string(),
+                format!("Esc/{}: Close", crate::utils::format_key_binding_for_display(&app.config.key_bindings.history)),
+            ]
+        }
+        crate::tui::app::Mode::SyncConflicts => {
+            vec![
+                "↑/↓: Select conflict".to_string(),
+                "PgUp/PgDn: Scroll".to_string(),
+                "Enter/o: Use other version".to_string(),
+                "c: Keep current".to_string(),
+                format!("Esc/{}: Close", crate::utils::format_key_binding_for_display(&app.config.key_bindings.sync_conflicts)),
+            ]
+        }
+        crate::tui::app::Mode::Attachments => {
+            match app.modals.attachments.as_ref().map(|state| &state.step) {
+                Some(crate::tui::app::AttachmentsStep::AddPath(_)) => vec![
+                    "Enter: Attach".to_string(),
+                    "Esc: Back".to_string(),
+                ],
+                _ => vec![
+                    "↑/↓: Navigate".to_string(),
+                    "a: Attach file".to_string(),
+                    "Enter/o: Open".to_string(),
+                    "d: Detach".to_string(),
+                    format!("Esc/{}: Close", crate::utils::format_key_binding_for_display(&app.config.key_bindings.attachments)),
+                ],
+            }
+        }
+        crate::tui::app::Mode::View if app.selection.active => {
+            vec![
+                format!("{}: Toggle", crate::utils::format_key_binding_for_display(&app.config.key_bindings.toggle_selection)),
+                "Shift+↑/↓: Select range".to_string(),
+                format!("{}: All", crate::utils::format_key_binding_for_display(&app.config.key_bindings.select_all)),
+                format!("{}: Actions", crate::utils::format_key_binding_for_display(&app.config.key_bindings.select)),
+                format!("{}: Delete", crate::utils::format_key_binding_for_display(&app.config.key_bindings.delete)),
+                format!("{}: Move", crate::utils::format_key_binding_for_display(&app.config.key_bindings.move_to_notebook)),
+                format!("Esc/{}: Exit multi-select", crate::utils::format_key_binding_for_display(&app.config.key_bindings.multi_select)),
+            ]
+        }
         _ => {
             let mut hints = vec![
                 format!("{}: Quit", crate::utils::format_key_binding_for_display(&app.config.key_bindings.quit)),
@@ -308,12 +436,21 @@ fn get_key_hints(app: &App) -> Vec<String> {
                 }
             }
             
+            // Add move-to-notebook hint (available on all tabs)
+            hints.push(format!("{}: Move", crate::utils::format_key_binding_for_display(&app.config.key_bindings.move_to_notebook)));
+            
+            // Add multi-select hint (available on all tabs)
+            hints.push(format!("{}: Select", crate::utils::format_key_binding_for_display(&app.config.key_bindings.multi_select)));
+            
             // Add tags toggle hint (available on all tabs)
             hints.push(format!("{}: Tags", crate::utils::format_key_binding_for_display(&app.config.key_bindings.toggle_list_view)));
             
             // Add notebook modal hint
             hints.push(format!("{}: Notebooks", crate::utils::format_key_binding_for_display(&app.config.key_bindings.notebook_modal)));
             
+            // Add trash hint
+            hints.push(format!("{}: Trash", crate::utils::format_key_binding_for_display(&app.config.key_bindings.trash)));
+            
             // Add F1 (Help) and F2 (Settings) at the end
             hints.push(format!("{}: Settings", crate::utils::format_key_binding_for_display(&app.config.key_bindings.settings)));
             hints.push(format!("{}: Help", crate::utils::format_key_binding_for_display(&app.config.key_bindings.help)));
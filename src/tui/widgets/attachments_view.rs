@@ -0,0 +1,117 @@
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Clear};
+use ratatui::style::{Style, Modifier};
+use ratatui::Frame;
+use ratatui::layout::{Rect, Alignment, Constraint, Layout, Direction, Flex};
+use crate::tui::App;
+use crate::tui::app::AttachmentsStep;
+use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
+
+/// Calculate popup area (centered, with specified width and height percentages)
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+/// Render the attachments of an item as a popup overlay
+pub fn render_attachments_view(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref state) = app.modals.attachments else {
+        return;
+    };
+
+    let active_theme = app.config.get_active_theme();
+    let fg_color = parse_color(&active_theme.fg);
+    let bg_color = parse_color(&active_theme.bg);
+    let highlight_bg = parse_color(&active_theme.highlight_bg);
+    let highlight_fg = if active_theme.highlight_fg.is_empty() {
+        get_contrast_text_color(highlight_bg)
+    } else {
+        parse_color(&active_theme.highlight_fg)
+    };
+
+    // Calculate popup area (60% width, 50% height, centered)
+    let popup_area = popup_area(area, 60, 50);
+
+    // Clear the background first
+    f.render_widget(Clear, popup_area);
+
+    // Split into attachment list and prompt line
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+        ])
+        .split(popup_area);
+
+    let items: Vec<ListItem> = if state.attachments.is_empty() {
+        vec![ListItem::new("No attachments")]
+    } else {
+        state.attachments.iter().map(|attachment| {
+            ListItem::new(format!(
+                "{}  ({}, added {})",
+                attachment.file_name,
+                crate::utils::format_file_size(attachment.size),
+                attachment.created_at
+            ))
+        }).collect()
+    };
+
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Attachments: {} ({})", state.item.display_title(), state.attachments.len()))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(Style::default().fg(fg_color).bg(bg_color))
+        .highlight_style(
+            Style::default()
+                .fg(highlight_fg)
+                .bg(highlight_bg)
+                .add_modifier(Modifier::BOLD)
+        );
+
+    let mut list_state = state.list_state;
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+    // Prompt line: file path input, detach confirmation or available actions
+    let prompt = match state.step {
+        AttachmentsStep::List => "a: Attach file  Enter/o: Open  d: Detach  Esc: Close".to_string(),
+        AttachmentsStep::AddPath(ref editor) => {
+            format!("File to attach: {}", editor.lines.first().cloned().unwrap_or_default())
+        }
+        AttachmentsStep::ConfirmDetach => {
+            let name = state.attachments.get(state.selected_index)
+                .map(|attachment| attachment.file_name.as_str())
+                .unwrap_or_default();
+            format!("Remove attachment '{}'? The file is deleted from tnj. (y/n)", name)
+        }
+    };
+    let prompt_style = match state.step {
+        AttachmentsStep::List => Style::default().fg(fg_color).bg(bg_color),
+        _ => Style::default().fg(highlight_fg).bg(highlight_bg),
+    };
+    let alignment = match state.step {
+        AttachmentsStep::AddPath(_) => Alignment::Left,
+        _ => Alignment::Center,
+    };
+
+    let paragraph = Paragraph::new(prompt)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(prompt_style)
+        .alignment(alignment);
+    f.render_widget(paragraph, chunks[1]);
+
+    // Place the cursor inside the path text
+    if let AttachmentsStep::AddPath(ref editor) = state.step {
+        let path_len = editor.lines.first().map(|line| line.chars().count()).unwrap_or(0);
+        let cursor_col = "File to attach: ".len() + editor.cursor_col.min(path_len);
+        let max_col = chunks[1].width.saturating_sub(3) as usize;
+        let x = chunks[1].x + 1 + (cursor_col.min(max_col) as u16);
+        f.set_cursor_position((x, chunks[1].y + 1));
+    }
+}
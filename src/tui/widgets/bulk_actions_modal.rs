@@ -0,0 +1,128 @@
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Clear};
+use ratatui::style::{Style, Modifier};
+use ratatui::Frame;
+use ratatui::layout::{Rect, Alignment, Constraint, Layout, Flex};
+use ratatui::text::{Line, Span};
+use crate::tui::App;
+use crate::tui::app::{BulkAction, BulkActionStep};
+use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
+
+/// Calculate popup area (centered, with specified width and height percentages)
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+/// Render the bulk actions menu (and its tag prompt / delete confirmation) as a popup overlay
+pub fn render_bulk_actions_modal(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref state) = app.modals.bulk_actions else {
+        return;
+    };
+
+    let active_theme = app.config.get_active_theme();
+    let fg_color = parse_color(&active_theme.fg);
+    let bg_color = parse_color(&active_theme.bg);
+    let highlight_bg = parse_color(&active_theme.highlight_bg);
+    let highlight_fg = if active_theme.highlight_fg.is_empty() {
+        get_contrast_text_color(highlight_bg)
+    } else {
+        parse_color(&active_theme.highlight_fg)
+    };
+
+    let kind = app.current_kind();
+    let count = app.get_selected_ids().len();
+    let title = format!("{} {} selected", count, kind.label(count));
+
+    // Calculate popup area (40% width, 50% height, centered)
+    let popup_area = popup_area(area, 40, 50);
+
+    // Clear the background first
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .style(Style::default().fg(fg_color).bg(bg_color));
+
+    match state.step {
+        BulkActionStep::Choose => {
+            let items: Vec<ListItem> = BulkAction::available_for(kind)
+                .iter()
+                .map(|action| ListItem::new(action.label()))
+                .collect();
+
+            let list = List::new(items)
+                .block(block)
+                .style(Style::default().fg(fg_color).bg(bg_color))
+                .highlight_style(
+                    Style::default()
+                        .fg(highlight_fg)
+                        .bg(highlight_bg)
+                        .add_modifier(Modifier::BOLD)
+                );
+
+            let mut list_state = state.list_state;
+            f.render_stateful_widget(list, popup_area, &mut list_state);
+        }
+        BulkActionStep::TagInput { add, ref editor } => {
+            let prompt = if add { "Tag to add: " } else { "Tag to remove: " };
+            let tag_text = editor.lines.first().cloned().unwrap_or_default();
+
+            let lines = vec![
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled(prompt, Style::default().fg(fg_color)),
+                    Span::styled(tag_text.clone(), Style::default().fg(highlight_fg).bg(highlight_bg)),
+                ]),
+                Line::from(""),
+                Line::from("Enter to apply, Esc to go back"),
+            ];
+
+            let paragraph = Paragraph::new(lines)
+                .block(block)
+                .style(Style::default().fg(fg_color).bg(bg_color));
+            f.render_widget(paragraph, popup_area);
+
+            // Place the cursor inside the tag text (line 1 of the content, after the prompt)
+            let cursor_col = prompt.chars().count() + editor.cursor_col.min(tag_text.chars().count());
+            let max_col = popup_area.width.saturating_sub(3) as usize;
+            let x = popup_area.x + 1 + (cursor_col.min(max_col) as u16);
+            let y = popup_area.y + 2;
+            if y < popup_area.y + popup_area.height {
+                f.set_cursor_position((x, y));
+            }
+        }
+        BulkActionStep::ConfirmDelete { selection } => {
+            let mut lines = vec![
+                Line::from(""),
+                Line::from(format!("Move {} {} to the trash?", count, kind.label(count))),
+                Line::from(""),
+            ];
+
+            for (index, option) in ["Delete", "Cancel"].iter().enumerate() {
+                let is_selected = index == selection;
+                let prefix = if is_selected { "> " } else { "  " };
+                let style = if is_selected {
+                    Style::default().fg(highlight_fg).bg(highlight_bg)
+                } else {
+                    Style::default().fg(fg_color).bg(bg_color)
+                };
+                lines.push(Line::from(Span::styled(format!("{}{}", prefix, option), style)));
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from("Use ↑↓ to navigate, Enter to confirm, Esc to cancel"));
+
+            let paragraph = Paragraph::new(lines)
+                .block(block)
+                .style(Style::default().fg(fg_color).bg(bg_color))
+                .wrap(ratatui::widgets::Wrap { trim: true })
+                .alignment(Alignment::Center);
+            f.render_widget(paragraph, popup_area);
+        }
+    }
+}
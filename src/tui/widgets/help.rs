@@ -69,6 +69,28 @@ fn build_help_text(config: &Config) -> String {
         crate::utils::format_key_binding_for_display(&config.key_bindings.edit)));
     text.push_str(&format!("  {}: Delete selected item\n", 
         crate::utils::format_key_binding_for_display(&config.key_bindings.delete)));
+    text.push_str(&format!("  {}: Move item to another notebook\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.move_to_notebook)));
+    text.push_str(&format!("  {}: Open trash (restore or permanently delete items)\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.trash)));
+    text.push_str(&format!("  {}: Revision history (Notes and Journal tabs)\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.history)));
+    text.push_str(&format!("  {} / {}: Select next / previous link or backlink of the item\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.next_link),
+        crate::utils::format_key_binding_for_display(&config.key_bindings.previous_link)));
+    text.push_str(&format!("  {}: Open the selected link\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.follow_link)));
+    text.push_str(&format!("  {}: Attachments of the item (attach, open, detach files)\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.attachments)));
+    text.push_str(&format!("  {}: Review conflicts found by `tnj sync`\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.sync_conflicts)));
+    text.push_str(&format!("  {}: Lock or unlock the selected journal entry\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.lock)));
+    text.push_str(&format!("  {}: Enter the passphrase of locked entries, or lock them again\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.unlock)));
+    text.push_str(&format!("  {} / {}: Undo / redo last list action (delete, archive, status, reorder, move)\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.undo),
+        crate::utils::format_key_binding_for_display(&config.key_bindings.redo)));
     text.push_str(&format!("  {}: Toggle task status (Tasks tab only)\n", 
         crate::utils::format_key_binding_for_display(&config.key_bindings.toggle_task_status)));
     #[cfg(target_os = "macos")]
@@ -83,6 +105,22 @@ fn build_help_text(config: &Config) -> String {
         crate::utils::format_key_binding_for_display(&config.key_bindings.search)));
     text.push_str("\n");
     
+    // Multi-select section
+    text.push_str("Multi-select:\n");
+    text.push_str(&format!("  {}: Enter/exit multi-select mode\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.multi_select)));
+    text.push_str(&format!("  {}: Toggle item under cursor\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.toggle_selection)));
+    text.push_str("  Shift+↑ / Shift+↓: Select range\n");
+    text.push_str(&format!("  {}: Select all filtered items\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.select_all)));
+    text.push_str(&format!("  {}: Bulk actions (archive, unarchive, mark done, tags, ...)\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.select)));
+    text.push_str(&format!("  {} / {}: Delete / move selected items\n", 
+        crate::utils::format_key_binding_for_display(&config.key_bindings.delete),
+        crate::utils::format_key_binding_for_display(&config.key_bindings.move_to_notebook)));
+    text.push_str("\n");
+    
     // Editor Mode section
     text.push_str("Editor Mode:\n");
     text.push_str(&format!("  {}: Save and exit\n", 
@@ -0,0 +1,173 @@
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Clear};
+use ratatui::style::{Color, Style, Modifier};
+use ratatui::Frame;
+use ratatui::layout::{Rect, Alignment, Constraint, Layout, Direction, Flex};
+use ratatui::text::{Line, Span};
+use similar::{ChangeTag, TextDiff};
+use crate::tui::App;
+use crate::tui::app::SelectedItem;
+use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
+
+/// Calculate popup area (centered, with specified width and height percentages)
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+/// Build the diff lines between an older revision and the current version
+/// Lines only in the revision are prefixed with "-", lines only in the current version with "+"
+pub fn diff_lines(old: &str, new: &str, fg_color: Color) -> Vec<Line<'static>> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut lines = Vec::new();
+
+    for (index, group) in diff.grouped_ops(3).iter().enumerate() {
+        if index > 0 {
+            lines.push(Line::from(Span::styled("…", Style::default().fg(Color::DarkGray))));
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let (prefix, style) = match change.tag() {
+                    ChangeTag::Delete => ("- ", Style::default().fg(Color::Red)),
+                    ChangeTag::Insert => ("+ ", Style::default().fg(Color::Green)),
+                    ChangeTag::Equal => ("  ", Style::default().fg(fg_color)),
+                };
+                let text = change.value().trim_end_matches(['\n', '\r']).to_string();
+                lines.push(Line::from(Span::styled(format!("{}{}", prefix, text), style)));
+            }
+        }
+    }
+
+    lines
+}
+
+/// Render the revision history of a note or journal entry as a popup overlay
+pub fn render_history_view(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref state) = app.modals.history else {
+        return;
+    };
+
+    let active_theme = app.config.get_active_theme();
+    let fg_color = parse_color(&active_theme.fg);
+    let bg_color = parse_color(&active_theme.bg);
+    let highlight_bg = parse_color(&active_theme.highlight_bg);
+    let highlight_fg = if active_theme.highlight_fg.is_empty() {
+        get_contrast_text_color(highlight_bg)
+    } else {
+        parse_color(&active_theme.highlight_fg)
+    };
+
+    // Calculate popup area (80% width, 80% height, centered)
+    let popup_area = popup_area(area, 80, 80);
+
+    // Clear the background first
+    f.render_widget(Clear, popup_area);
+
+    // Split into content area and prompt line
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+        ])
+        .split(popup_area);
+
+    // Split content into revision list (left) and diff (right)
+    let content_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(70),
+        ])
+        .split(chunks[0]);
+
+    // Revision list
+    let items: Vec<ListItem> = state.revisions.iter()
+        .map(|revision| ListItem::new(revision.saved_at.clone()))
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(format!("History: {} ({} versions)", state.item.display_title(), state.revisions.len()))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(Style::default().fg(fg_color).bg(bg_color))
+        .highlight_style(
+            Style::default()
+                .fg(highlight_fg)
+                .bg(highlight_bg)
+                .add_modifier(Modifier::BOLD)
+        );
+
+    let mut list_state = state.list_state;
+    f.render_stateful_widget(list, content_chunks[0], &mut list_state);
+
+    // Diff of the selected revision against the current version
+    let (current_title, current_content) = match state.item {
+        SelectedItem::Note(ref note) => (Some(note.title.as_str()), note.content.as_deref()),
+        SelectedItem::Journal(ref journal) => (journal.title.as_deref(), journal.content.as_deref()),
+        SelectedItem::Task(_) => (None, None),
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(revision) = state.revisions.get(state.selected_index) {
+        if revision.title.as_deref() != current_title {
+            lines.push(Line::from(Span::styled(
+                format!("- Title: {}", revision.title.as_deref().unwrap_or("")),
+                Style::default().fg(Color::Red),
+            )));
+            lines.push(Line::from(Span::styled(
+                format!("+ Title: {}", current_title.unwrap_or("")),
+                Style::default().fg(Color::Green),
+            )));
+            lines.push(Line::from(""));
+        }
+
+        let content_lines = diff_lines(
+            revision.content.as_deref().unwrap_or(""),
+            current_content.unwrap_or(""),
+            fg_color,
+        );
+        if content_lines.is_empty() {
+            lines.push(Line::from("Content is identical to the current version"));
+        } else {
+            lines.extend(content_lines);
+        }
+    }
+
+    let diff = Paragraph::new(lines)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title("Changes since this version (- this version, + current)")
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(Style::default().fg(fg_color).bg(bg_color))
+        .scroll((state.diff_scroll, 0));
+    f.render_widget(diff, content_chunks[1]);
+
+    // Prompt line: restore confirmation or available actions
+    let prompt = if state.confirm_restore {
+        let saved_at = state.revisions.get(state.selected_index)
+            .map(|revision| revision.saved_at.as_str())
+            .unwrap_or_default();
+        format!("Restore the version saved {}? The current version is kept in history. (y/n)", saved_at)
+    } else {
+        "↑/↓: Select version  PgUp/PgDn: Scroll diff  Enter/r: Restore  Esc: Close".to_string()
+    };
+    let prompt_style = if state.confirm_restore {
+        Style::default().fg(highlight_fg).bg(highlight_bg)
+    } else {
+        Style::default().fg(fg_color).bg(bg_color)
+    };
+
+    let paragraph = Paragraph::new(prompt)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(prompt_style)
+        .alignment(Alignment::Center);
+    f.render_widget(paragraph, chunks[1]);
+}
@@ -1,11 +1,12 @@
 use ratatui::widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarState};
-use ratatui::style::Style;
+use ratatui::style::{Style, Modifier};
 use ratatui::Frame;
 use ratatui::layout::{Rect, Layout as RatLayout, Direction, Constraint};
 use ratatui::text::{Text, Line, Span};
-use crate::tui::app::SelectedItem;
+use crate::tui::app::{ItemLink, LinkState, SelectedItem};
 use crate::Config;
-use crate::tui::widgets::color::parse_color;
+use crate::models::{ItemKind, JournalEntry};
+use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
 use ratskin::RatSkin;
 use termimad::minimad::Text as MinimadText;
 use std::cmp;
@@ -70,7 +71,75 @@ pub fn get_content_string(item: &SelectedItem) -> String {
     }
 }
 
-pub fn render_item_view(f: &mut Frame, area: Rect, item: &SelectedItem, config: &Config, scroll_offset: usize) {
+/// Number of lines the attachments and links sections add below the item content
+pub fn links_section_height(links: &LinkState) -> usize {
+    let outgoing = links.links.iter().filter(|link| !link.backlink).count();
+    let backlinks = links.links.len() - outgoing;
+    [links.attachments.len(), outgoing, backlinks].iter().filter(|&&n| n > 0).map(|n| n + 2).sum()
+}
+
+/// Lines listing the item's attachments, links and backlinks, and the index of the selected link's line
+fn links_section(links: &LinkState, config: &Config) -> (Vec<Line<'static>>, Option<usize>) {
+    let theme = config.get_active_theme();
+    let highlight_bg = parse_color(&theme.highlight_bg);
+    let highlight_fg = if theme.highlight_fg.is_empty() {
+        get_contrast_text_color(highlight_bg)
+    } else {
+        parse_color(&theme.highlight_fg)
+    };
+
+    let mut lines = Vec::new();
+    let mut selected_line = None;
+    if !links.attachments.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled("Attachments", Style::default().add_modifier(Modifier::BOLD))));
+        for attachment in &links.attachments {
+            lines.push(Line::from(format!(
+                "  📎 {} ({})",
+                attachment.file_name,
+                crate::utils::format_file_size(attachment.size)
+            )));
+        }
+    }
+    for (heading, backlinks) in [("Links", false), ("Backlinks", true)] {
+        let section: Vec<(usize, &ItemLink)> = links
+            .links
+            .iter()
+            .enumerate()
+            .filter(|(_, link)| link.backlink == backlinks)
+            .collect();
+        if section.is_empty() {
+            continue;
+        }
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(heading, Style::default().add_modifier(Modifier::BOLD))));
+        for (index, link) in section {
+            let kind = match link.target {
+                Some((ItemKind::Task, _)) => "task",
+                Some((ItemKind::Note, _)) => "note",
+                Some((ItemKind::Journal, _)) => "journal",
+                None => "not found",
+            };
+            let arrow = if backlinks { "←" } else { "→" };
+            let text = format!("  {} {} ({})", arrow, link.label, kind);
+            if links.selected == Some(index) {
+                selected_line = Some(lines.len());
+                lines.push(Line::from(Span::styled(text, Style::default().fg(highlight_fg).bg(highlight_bg))));
+            } else if link.target.is_none() {
+                lines.push(Line::from(Span::styled(text, Style::default().add_modifier(Modifier::DIM))));
+            } else {
+                lines.push(Line::from(text));
+            }
+        }
+    }
+    (lines, selected_line)
+}
+
+/// Render the selected item with its links and backlinks below the content
+/// When a link was just selected, `scroll_offset` is moved so the link is visible.
+/// A locked journal entry shows `unlocked_content` (its decrypted content) if it is unlocked,
+/// and otherwise asks for the passphrase.
+pub fn render_item_view(f: &mut Frame, area: Rect, item: &SelectedItem, unlocked_content: Option<&str>, config: &Config, scroll_offset: &mut usize, links: &mut LinkState) {
     if area.width < 2 || area.height < 2 {
         return;
     }
@@ -90,7 +159,20 @@ pub fn render_item_view(f: &mut Frame, area: Rect, item: &SelectedItem, config:
     let viewport_height = (area.height - 2) as usize; // Account for borders
     
     // Get content as markdown string
-    let content_string = get_content_string(item);
+    let locked = matches!(item, SelectedItem::Journal(journal) if journal.is_locked());
+    let content_string = match item {
+        SelectedItem::Journal(journal) if locked => {
+            let content = match unlocked_content {
+                Some(content) => content.to_string(),
+                None => format!(
+                    "🔒 *This entry is locked. Press {} to enter the passphrase.*",
+                    crate::utils::format_key_binding_for_display(&config.key_bindings.unlock)
+                ),
+            };
+            get_content_string(&SelectedItem::Journal(JournalEntry { content: Some(content), ..journal.clone() }))
+        }
+        _ => get_content_string(item),
+    };
     
     // Calculate text width (content area width minus borders)
     let text_width = (content_area.width.saturating_sub(2)) as usize;
@@ -103,7 +185,7 @@ pub fn render_item_view(f: &mut Frame, area: Rect, item: &SelectedItem, config:
     
     // Convert ratskin lines to ratatui lines, preserving styling from spans
     // With ratskin 0.3.0 and ratatui 0.30.0, types should be compatible
-    let ratatui_lines: Vec<Line> = content_lines.into_iter().map(|line| {
+    let mut ratatui_lines: Vec<Line> = content_lines.into_iter().map(|line| {
         // Convert each span, preserving its style and content
         let spans: Vec<Span> = line.spans.into_iter().map(|span| {
             Span::styled(
@@ -113,14 +195,31 @@ pub fn render_item_view(f: &mut Frame, area: Rect, item: &SelectedItem, config:
         }).collect();
         Line::from(spans)
     }).collect();
+
+    // Attachments, links and backlinks go below the content
+    let (link_lines, selected_link_line) = links_section(links, config);
+    let selected_link_line = selected_link_line.map(|line| line + ratatui_lines.len());
+    ratatui_lines.extend(link_lines);
     let content_text = Text::from(ratatui_lines);
     
     // Calculate total lines (before wrapping)
     let total_lines = content_text.lines.len();
     
+    // Bring a newly selected link into view
+    if links.reveal_selected {
+        links.reveal_selected = false;
+        if let Some(line) = selected_link_line {
+            if line < *scroll_offset {
+                *scroll_offset = line;
+            } else if line >= *scroll_offset + viewport_height {
+                *scroll_offset = line + 1 - viewport_height;
+            }
+        }
+    }
+
     // Clamp scroll offset
     let max_scroll = total_lines.saturating_sub(viewport_height);
-    let scroll_offset = cmp::min(scroll_offset, max_scroll);
+    let scroll_offset = cmp::min(*scroll_offset, max_scroll);
     
     // Slice Text to show only visible lines
     let start_line = scroll_offset;
@@ -135,6 +234,7 @@ pub fn render_item_view(f: &mut Frame, area: Rect, item: &SelectedItem, config:
     let title = match item {
         SelectedItem::Task(_) => "Task",
         SelectedItem::Note(_) => "Note",
+        SelectedItem::Journal(_) if locked => "Journal Entry (locked)",
         SelectedItem::Journal(_) => "Journal Entry",
     };
     
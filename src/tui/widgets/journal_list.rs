@@ -9,9 +9,23 @@ use crate::Config;
 use crate::tui::app::ListViewMode;
 use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
 use crate::tui::widgets::tags::{parse_tags, format_tags_brackets};
-use std::collections::HashMap;
+use crate::tui::widgets::selection::{selection_marker, list_title};
+use std::collections::{BTreeSet, HashMap};
 
-pub fn render_journal_list(f: &mut Frame, area: Rect, journals: &[JournalEntry], total_count: usize, list_state: &mut ListState, config: &Config, view_mode: ListViewMode) {
+/// Markers shown before an entry's date: `[A]` when it is archived, `[L]` when it is locked
+fn entry_markers(journal: &JournalEntry) -> String {
+    let mut markers = String::new();
+    if journal.archived {
+        markers.push_str("[A] ");
+    }
+    if journal.is_locked() {
+        markers.push_str("[L] ");
+    }
+    markers
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn render_journal_list(f: &mut Frame, area: Rect, journals: &[JournalEntry], total_count: usize, list_state: &mut ListState, config: &Config, view_mode: ListViewMode, selection: Option<&BTreeSet<i64>>) {
     // Calculate max width for truncation (account for borders and padding)
     let max_width = area.width.saturating_sub(4) as usize; // 2 for borders, 2 for padding
     
@@ -26,10 +40,11 @@ pub fn render_journal_list(f: &mut Frame, area: Rect, journals: &[JournalEntry],
     let items: Vec<ListItem> = match view_mode {
         ListViewMode::Simple => {
             journals.iter().map(|journal| {
-                let archived_prefix = if journal.archived { "[A] " } else { "" };
+                let markers = entry_markers(journal);
+                let selection_prefix = selection_marker(selection, journal.id);
                 let mut title = journal.title.as_ref()
-                    .map(|t| format!("{}{} - {}", archived_prefix, journal.date, t))
-                    .unwrap_or_else(|| format!("{}{}", archived_prefix, journal.date));
+                    .map(|t| format!("{}{}{} - {}", selection_prefix, markers, journal.date, t))
+                    .unwrap_or_else(|| format!("{}{}{}", selection_prefix, markers, journal.date));
                 
                 // Truncate title if too long
                 if title.chars().count() > max_width {
@@ -41,10 +56,11 @@ pub fn render_journal_list(f: &mut Frame, area: Rect, journals: &[JournalEntry],
         }
         ListViewMode::TwoLine => {
             journals.iter().map(|journal| {
-                let archived_prefix = if journal.archived { "[A] " } else { "" };
+                let markers = entry_markers(journal);
+                let selection_prefix = selection_marker(selection, journal.id);
                 let mut first_line = journal.title.as_ref()
-                    .map(|t| format!("{}{} - {}", archived_prefix, journal.date, t))
-                    .unwrap_or_else(|| format!("{}{}", archived_prefix, journal.date));
+                    .map(|t| format!("{}{}{} - {}", selection_prefix, markers, journal.date, t))
+                    .unwrap_or_else(|| format!("{}{}{}", selection_prefix, markers, journal.date));
                 
                 // Truncate first line if too long
                 if first_line.chars().count() > max_width {
@@ -96,10 +112,11 @@ pub fn render_journal_list(f: &mut Frame, area: Rect, journals: &[JournalEntry],
             if !untagged.is_empty() {
                 items.push(ListItem::new("[Untagged]").style(Style::default().fg(parse_color(&active_theme.tab_bg))));
                 for journal in untagged {
-                    let archived_prefix = if journal.archived { "[A] " } else { "" };
+                    let markers = entry_markers(journal);
+                    let selection_prefix = selection_marker(selection, journal.id);
                     let mut title = journal.title.as_ref()
-                        .map(|t| format!("  {}{} - {}", archived_prefix, journal.date, t))
-                        .unwrap_or_else(|| format!("  {}{}", archived_prefix, journal.date));
+                        .map(|t| format!("  {}{}{} - {}", selection_prefix, markers, journal.date, t))
+                        .unwrap_or_else(|| format!("  {}{}{}", selection_prefix, markers, journal.date));
                     
                     // Truncate title if too long
                     if title.chars().count() > max_width {
@@ -114,10 +131,11 @@ pub fn render_journal_list(f: &mut Frame, area: Rect, journals: &[JournalEntry],
             for tag in sorted_tags {
                 items.push(ListItem::new(format!("[{}]", tag)).style(Style::default().fg(parse_color(&active_theme.tab_bg))));
                 for journal in &tag_map[&tag] {
-                    let archived_prefix = if journal.archived { "[A] " } else { "" };
+                    let markers = entry_markers(journal);
+                    let selection_prefix = selection_marker(selection, journal.id);
                     let mut title = journal.title.as_ref()
-                        .map(|t| format!("  {}{} - {}", archived_prefix, journal.date, t))
-                        .unwrap_or_else(|| format!("  {}{}", archived_prefix, journal.date));
+                        .map(|t| format!("  {}{}{} - {}", selection_prefix, markers, journal.date, t))
+                        .unwrap_or_else(|| format!("  {}{}{}", selection_prefix, markers, journal.date));
                     
                     // Truncate title if too long
                     if title.chars().count() > max_width {
@@ -144,7 +162,7 @@ pub fn render_journal_list(f: &mut Frame, area: Rect, journals: &[JournalEntry],
     let list_area = list_areas[0];
     let scrollbar_area = list_areas[1];
 
-    let title = format!("Items ({} of {})", journals.len(), total_count);
+    let title = list_title(journals.len(), total_count, selection);
     let list = List::new(items.clone())
         .block(Block::default().borders(Borders::ALL).title(title))
         .style(Style::default().fg(parse_color(&active_theme.fg)))
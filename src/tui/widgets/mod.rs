@@ -1,3 +1,4 @@
+pub mod attachments_view;
 pub mod color;
 pub mod confirm_delete;
 pub mod tabs;
@@ -8,6 +9,7 @@ pub mod editor;
 pub mod item_view;
 pub mod status_bar;
 pub mod help;
+pub mod history_view;
 pub mod markdown_help;
 pub mod form;
 pub mod settings_list;
@@ -16,4 +18,10 @@ pub mod tags;
 pub mod filters_box;
 pub mod filter_modal;
 pub mod notebook_modal;
+pub mod passphrase_prompt;
+pub mod move_notebook_modal;
+pub mod bulk_actions_modal;
+pub mod selection;
+pub mod sync_conflicts_view;
+pub mod trash_view;
 
@@ -0,0 +1,66 @@
+use ratatui::widgets::{Block, Borders, List, ListItem, Clear};
+use ratatui::style::{Style, Modifier};
+use ratatui::Frame;
+use ratatui::layout::{Rect, Alignment, Constraint, Layout, Flex};
+use crate::tui::App;
+use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
+
+/// Calculate popup area (centered, with specified width and height percentages)
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+/// Render the move-to-notebook modal as a popup overlay
+pub fn render_move_notebook_modal(f: &mut Frame, area: Rect, app: &App) {
+    let active_theme = app.config.get_active_theme();
+    let fg_color = parse_color(&active_theme.fg);
+    let bg_color = parse_color(&active_theme.bg);
+    let highlight_bg = parse_color(&active_theme.highlight_bg);
+    let highlight_fg = if active_theme.highlight_fg.is_empty() {
+        get_contrast_text_color(highlight_bg)
+    } else {
+        parse_color(&active_theme.highlight_fg)
+    };
+
+    // Calculate popup area (40% width, 50% height, centered)
+    let popup_area = popup_area(area, 40, 50);
+
+    // Clear the background first
+    f.render_widget(Clear, popup_area);
+
+    if let Some(ref state) = app.modals.move_notebook {
+        // Mark the notebook the selected item currently belongs to
+        let current_notebook_id = app.ui.selected_item.as_ref().and_then(|item| item.notebook_id());
+        let items: Vec<ListItem> = app.get_notebook_list_with_none()
+            .into_iter()
+            .map(|(id, name)| {
+                if id == current_notebook_id {
+                    ListItem::new(format!("{} (current)", name))
+                } else {
+                    ListItem::new(name)
+                }
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .title("Move to Notebook")
+                .title_alignment(Alignment::Center)
+                .style(Style::default().fg(fg_color).bg(bg_color)))
+            .style(Style::default().fg(fg_color).bg(bg_color))
+            .highlight_style(
+                Style::default()
+                    .fg(highlight_fg)
+                    .bg(highlight_bg)
+                    .add_modifier(Modifier::BOLD)
+            );
+
+        let mut list_state = state.list_state;
+        f.render_stateful_widget(list, popup_area, &mut list_state);
+    }
+}
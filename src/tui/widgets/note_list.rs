@@ -9,9 +9,11 @@ use crate::Config;
 use crate::tui::app::ListViewMode;
 use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
 use crate::tui::widgets::tags::{parse_tags, format_tags_brackets};
-use std::collections::HashMap;
+use crate::tui::widgets::selection::{selection_marker, list_title};
+use std::collections::{BTreeSet, HashMap};
 
-pub fn render_note_list(f: &mut Frame, area: Rect, notes: &[Note], total_count: usize, list_state: &mut ListState, config: &Config, view_mode: ListViewMode) {
+#[allow(clippy::too_many_arguments)]
+pub fn render_note_list(f: &mut Frame, area: Rect, notes: &[Note], total_count: usize, list_state: &mut ListState, config: &Config, view_mode: ListViewMode, selection: Option<&BTreeSet<i64>>) {
     // Calculate max width for truncation (account for borders and padding)
     let max_width = area.width.saturating_sub(4) as usize; // 2 for borders, 2 for padding
     
@@ -27,7 +29,8 @@ pub fn render_note_list(f: &mut Frame, area: Rect, notes: &[Note], total_count:
         ListViewMode::Simple => {
             notes.iter().map(|note| {
                 let archived_prefix = if note.archived { "[A] " } else { "" };
-                let mut title = format!("{}{}", archived_prefix, note.title);
+                let selection_prefix = selection_marker(selection, note.id);
+                let mut title = format!("{}{}{}", selection_prefix, archived_prefix, note.title);
                 
                 // Truncate title if too long
                 if title.chars().count() > max_width {
@@ -40,7 +43,8 @@ pub fn render_note_list(f: &mut Frame, area: Rect, notes: &[Note], total_count:
         ListViewMode::TwoLine => {
             notes.iter().map(|note| {
                 let archived_prefix = if note.archived { "[A] " } else { "" };
-                let mut first_line = format!("{}{}", archived_prefix, note.title);
+                let selection_prefix = selection_marker(selection, note.id);
+                let mut first_line = format!("{}{}{}", selection_prefix, archived_prefix, note.title);
                 
                 // Truncate first line if too long
                 if first_line.chars().count() > max_width {
@@ -93,7 +97,8 @@ pub fn render_note_list(f: &mut Frame, area: Rect, notes: &[Note], total_count:
                 items.push(ListItem::new("[Untagged]").style(Style::default().fg(parse_color(&active_theme.tab_bg))));
                 for note in untagged {
                     let archived_prefix = if note.archived { "[A] " } else { "" };
-                    let mut title = format!("  {}{}", archived_prefix, note.title);
+                    let selection_prefix = selection_marker(selection, note.id);
+                    let mut title = format!("  {}{}{}", selection_prefix, archived_prefix, note.title);
                     
                     // Truncate title if too long
                     if title.chars().count() > max_width {
@@ -109,7 +114,8 @@ pub fn render_note_list(f: &mut Frame, area: Rect, notes: &[Note], total_count:
                 items.push(ListItem::new(format!("[{}]", tag)).style(Style::default().fg(parse_color(&active_theme.tab_bg))));
                 for note in &tag_map[&tag] {
                     let archived_prefix = if note.archived { "[A] " } else { "" };
-                    let mut title = format!("  {}{}", archived_prefix, note.title);
+                    let selection_prefix = selection_marker(selection, note.id);
+                    let mut title = format!("  {}{}{}", selection_prefix, archived_prefix, note.title);
                     
                     // Truncate title if too long
                     if title.chars().count() > max_width {
@@ -136,7 +142,7 @@ pub fn render_note_list(f: &mut Frame, area: Rect, notes: &[Note], total_count:
     let list_area = list_areas[0];
     let scrollbar_area = list_areas[1];
 
-    let title = format!("Items ({} of {})", notes.len(), total_count);
+    let title = list_title(notes.len(), total_count, selection);
     let list = List::new(items.clone())
         .block(Block::default().borders(Borders::ALL).title(title))
         .style(Style::default().fg(parse_color(&active_theme.fg)))
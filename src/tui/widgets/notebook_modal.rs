@@ -64,7 +64,11 @@ pub fn render_notebook_modal(f: &mut Frame, area: Rect, app: &App) {
         let mut notebook_items: Vec<ListItem> = vec![];
         notebook_items.push(ListItem::new("[None]"));
         for notebook in &app.notebooks.notebooks {
-            notebook_items.push(ListItem::new(notebook.name.clone()));
+            if notebook.locked {
+                notebook_items.push(ListItem::new(format!("[L] {}", notebook.name)));
+            } else {
+                notebook_items.push(ListItem::new(notebook.name.clone()));
+            }
         }
         
         // Highlight current selection
@@ -127,6 +131,18 @@ pub fn render_notebook_modal(f: &mut Frame, area: Rect, app: &App) {
             Span::styled("Switch", switch_style),
         ]));
         
+        // Lock/Unlock action (index 4), labelled for the selected notebook
+        let lock_style = if is_actions_active && state.actions_selected_index == 4 {
+            Style::default().fg(highlight_fg).bg(highlight_bg).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(fg_color).bg(bg_color)
+        };
+        let selected_locked = state.selected_index > 0
+            && app.notebooks.notebooks.get(state.selected_index - 1).is_some_and(|n| n.locked);
+        action_lines.push(Line::from(vec![
+            Span::styled(if selected_locked { "Unlock" } else { "Lock" }, lock_style),
+        ]));
+        
         // Render name editor if in Add or Rename mode
         let name_editor_line_index = if matches!(state.mode, NotebookModalMode::Add | NotebookModalMode::Rename) {
             action_lines.push(Line::from(""));
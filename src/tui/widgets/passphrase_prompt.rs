@@ -0,0 +1,80 @@
+use ratatui::widgets::{Block, Borders, Paragraph, Clear};
+use ratatui::style::{Color, Style};
+use ratatui::Frame;
+use ratatui::layout::{Rect, Alignment, Constraint, Layout, Flex};
+use ratatui::text::{Line, Span};
+use crate::Config;
+use crate::tui::App;
+use crate::tui::widgets::color::parse_color;
+
+/// Render the passphrase prompt shown before an encrypted database is opened
+/// The passphrase itself is never drawn, only one `*` per character typed.
+pub fn render_passphrase_prompt(f: &mut Frame, area: Rect, typed_chars: usize, error: Option<&str>, config: &Config) {
+    f.render_widget(Clear, area);
+    let hint = error
+        .map(|error| Line::from(Span::styled(error.to_string(), Style::default().fg(Color::Red))))
+        .unwrap_or_else(|| Line::from("Enter to unlock, Esc to quit"));
+    render_prompt_box(f, area, "Unlock tnj", "The database is encrypted.", typed_chars, hint, config);
+}
+
+/// Render the prompt for the passphrase of locked journal entries over the current view
+/// Before anything is locked, the passphrase is chosen here and typed twice.
+pub fn render_unlock_prompt(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref prompt) = app.lock.prompt else {
+        return;
+    };
+    let message = match (prompt.new_passphrase, prompt.first_entry.is_some()) {
+        (false, _) => "Enter the passphrase of locked entries.",
+        (true, false) => "Choose a passphrase for locked entries.",
+        (true, true) => "Type the new passphrase again.",
+    };
+    let hint = prompt.error.as_deref()
+        .map(|error| Line::from(Span::styled(error.to_string(), Style::default().fg(Color::Red))))
+        .unwrap_or_else(|| Line::from("Enter to continue, Esc to cancel"));
+    render_prompt_box(f, area, "Locked entries", message, prompt.passphrase.chars().count(), hint, &app.config);
+}
+
+/// Render a centered box with a message, the masked passphrase and a hint (or error) line
+fn render_prompt_box(f: &mut Frame, area: Rect, title: &str, message: &str, typed_chars: usize, hint: Line, config: &Config) {
+    let active_theme = config.get_active_theme();
+    let fg_color = parse_color(&active_theme.fg);
+    let bg_color = parse_color(&active_theme.bg);
+
+    // Fixed-size box (60x8, or the whole screen if smaller), centered
+    let vertical = Layout::vertical([Constraint::Length(8)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center);
+    let [popup_area] = vertical.areas(area);
+    let [popup_area] = horizontal.areas(popup_area);
+
+    f.render_widget(Clear, popup_area);
+
+    let prompt = "Passphrase: ";
+    let masked = "*".repeat(typed_chars);
+    let lines = vec![
+        Line::from(""),
+        Line::from(message.to_string()),
+        Line::from(vec![
+            Span::styled(prompt, Style::default().fg(fg_color)),
+            Span::styled(masked, Style::default().fg(fg_color)),
+        ]),
+        Line::from(""),
+        hint,
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string())
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(Style::default().fg(fg_color).bg(bg_color));
+    f.render_widget(paragraph, popup_area);
+
+    // Place the cursor after the masked passphrase (line 3 of the box, inside the border)
+    let max_col = popup_area.width.saturating_sub(3) as usize;
+    let cursor_col = (prompt.len() + typed_chars).min(max_col);
+    let y = popup_area.y + 3;
+    if y < popup_area.y + popup_area.height {
+        f.set_cursor_position((popup_area.x + 1 + cursor_col as u16, y));
+    }
+}
@@ -0,0 +1,19 @@
+use std::collections::BTreeSet;
+
+/// Get the multi-select marker shown in front of a list item
+/// Returns an empty string when multi-select mode is off
+pub fn selection_marker(selection: Option<&BTreeSet<i64>>, id: Option<i64>) -> &'static str {
+    match (selection, id) {
+        (Some(ids), Some(id)) if ids.contains(&id) => "[x] ",
+        (Some(_), _) => "[ ] ",
+        (None, _) => "",
+    }
+}
+
+/// Format the list title, including the number of selected items in multi-select mode
+pub fn list_title(shown: usize, total: usize, selection: Option<&BTreeSet<i64>>) -> String {
+    match selection {
+        Some(ids) => format!("Items ({} of {}, {} selected)", shown, total, ids.len()),
+        None => format!("Items ({} of {})", shown, total),
+    }
+}
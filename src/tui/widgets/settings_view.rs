@@ -150,6 +150,9 @@ pub fn render_settings_view_modal(f: &mut Frame, area: Rect, app: &App) {
         Some(category) if category == "Display Settings" => {
             render_display_settings(f, main_area, app, fg_color, bg_color, highlight_fg, highlight_bg);
         }
+        Some(category) if category == "Backup Settings" => {
+            render_backup_settings(f, main_area, app, fg_color, bg_color, highlight_fg, highlight_bg);
+        }
         Some(category) if category == "System Settings" => {
             render_system_settings(f, main_area, app, fg_color, bg_color);
         }
@@ -329,6 +332,73 @@ fn render_display_settings(
     StatefulWidget::render(list, mode_area, f.buffer_mut(), &mut list_state);
 }
 
+/// Render backup settings content
+/// One selection moves through both boxes; only the box holding it shows the highlight.
+fn render_backup_settings(
+    f: &mut Frame,
+    main_area: Rect,
+    app: &App,
+    fg_color: ratatui::style::Color,
+    bg_color: ratatui::style::Color,
+    highlight_fg: ratatui::style::Color,
+    highlight_bg: ratatui::style::Color,
+) {
+    let frequency_options = app.get_backup_frequency_options();
+    let count_options = app.get_backup_count_options();
+    let current_frequency = app.config.get_auto_backup();
+
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(frequency_options.len() as u16 + 2), // Frequency box
+            Constraint::Length(count_options.len() as u16 + 2), // Count box
+            Constraint::Min(0), // Latest backup and directory
+        ])
+        .split(main_area);
+
+    let frequency_items: Vec<ListItem> = frequency_options.iter().map(|&(frequency, label)| {
+        let radio = if frequency == current_frequency { "●" } else { "○" };
+        ListItem::new(format!("{} {}", radio, label))
+    }).collect();
+    let count_items: Vec<ListItem> = count_options.iter().map(|&count| {
+        let radio = if count == app.config.backup_count { "●" } else { "○" };
+        ListItem::new(format!("{} {}", radio, count))
+    }).collect();
+
+    let index = app.settings.backup_setting_index;
+    let boxes = [
+        (frequency_items, "Automatic Backups", areas[0], (index < frequency_options.len()).then_some(index)),
+        (count_items, "Backups to Keep", areas[1], index.checked_sub(frequency_options.len())),
+    ];
+    for (items, title, area, selected) in boxes {
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .style(Style::default().fg(fg_color).bg(bg_color))
+            .highlight_style(
+                Style::default()
+                    .fg(highlight_fg)
+                    .bg(highlight_bg)
+            );
+        let mut list_state = ListState::default();
+        list_state.select(selected);
+        StatefulWidget::render(list, area, f.buffer_mut(), &mut list_state);
+    }
+
+    let latest = crate::backups::list(&app.config.get_database_path()).ok()
+        .and_then(|snapshots| snapshots.first().map(|snapshot| snapshot.created.format("%Y-%m-%d %H:%M").to_string()))
+        .unwrap_or_else(|| "none yet".to_string());
+    let content = format!(
+        "Latest backup: {}\nDirectory: {}\nRestore one with `tnj backups restore <n>`.",
+        latest,
+        crate::backups::backups_dir(&app.config.get_database_path()).display()
+    );
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("Backups"))
+        .style(Style::default().fg(fg_color).bg(bg_color))
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    f.render_widget(paragraph, areas[2]);
+}
+
 /// Render system settings content
 fn render_system_settings(
     f: &mut Frame,
@@ -0,0 +1,132 @@
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Clear};
+use ratatui::style::{Style, Modifier};
+use ratatui::Frame;
+use ratatui::layout::{Rect, Alignment, Constraint, Layout, Direction, Flex};
+use ratatui::text::Line;
+use crate::sync::version_text;
+use crate::tui::App;
+use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
+use crate::tui::widgets::history_view::diff_lines;
+
+/// Calculate popup area (centered, with specified width and height percentages)
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+/// Render the unresolved sync conflicts as a popup overlay
+pub fn render_sync_conflicts_view(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref state) = app.modals.sync_conflicts else {
+        return;
+    };
+
+    let active_theme = app.config.get_active_theme();
+    let fg_color = parse_color(&active_theme.fg);
+    let bg_color = parse_color(&active_theme.bg);
+    let highlight_bg = parse_color(&active_theme.highlight_bg);
+    let highlight_fg = if active_theme.highlight_fg.is_empty() {
+        get_contrast_text_color(highlight_bg)
+    } else {
+        parse_color(&active_theme.highlight_fg)
+    };
+
+    // Calculate popup area (80% width, 80% height, centered)
+    let popup_area = popup_area(area, 80, 80);
+
+    // Clear the background first
+    f.render_widget(Clear, popup_area);
+
+    // Split into content area and prompt line
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+        ])
+        .split(popup_area);
+
+    // Split content into conflict list (left) and comparison (right)
+    let content_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(70),
+        ])
+        .split(chunks[0]);
+
+    // Conflict list
+    let items: Vec<ListItem> = state.conflicts.iter()
+        .map(|conflict| ListItem::new(format!("[{}] {}", conflict.kind, conflict.title)))
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Sync Conflicts ({})", state.conflicts.len()))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(Style::default().fg(fg_color).bg(bg_color))
+        .highlight_style(
+            Style::default()
+                .fg(highlight_fg)
+                .bg(highlight_bg)
+                .add_modifier(Modifier::BOLD)
+        );
+
+    let mut list_state = state.list_state;
+    f.render_stateful_widget(list, content_chunks[0], &mut list_state);
+
+    // Comparison of the kept version with the replaced local version
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(conflict) = state.conflicts.get(state.selected_index) {
+        let other_version = conflict.other_version.as_deref()
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok());
+        let current_version = state.current_versions.get(state.selected_index).and_then(Option::as_ref);
+
+        lines.push(Line::from(format!("Found {} while syncing with device {}", conflict.created_at, conflict.other_device)));
+        lines.push(Line::from(""));
+        let diff = diff_lines(
+            &version_text(current_version, &state.notebook_names),
+            &version_text(other_version.as_ref(), &state.notebook_names),
+            fg_color,
+        );
+        if diff.is_empty() {
+            lines.push(Line::from("Both versions are identical"));
+        } else {
+            lines.extend(diff);
+        }
+    }
+
+    let diff = Paragraph::new(lines)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title("Differences (- kept version, + replaced version)")
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(Style::default().fg(fg_color).bg(bg_color))
+        .scroll((state.diff_scroll, 0));
+    f.render_widget(diff, content_chunks[1]);
+
+    // Prompt line: confirmation or available actions
+    let prompt = if state.confirm_use_other {
+        "Replace the kept version with the replaced version? (y/n)".to_string()
+    } else {
+        "↑/↓: Select  PgUp/PgDn: Scroll  Enter/o: Use other version  c: Keep current  Esc: Close".to_string()
+    };
+    let prompt_style = if state.confirm_use_other {
+        Style::default().fg(highlight_fg).bg(highlight_bg)
+    } else {
+        Style::default().fg(fg_color).bg(bg_color)
+    };
+
+    let paragraph = Paragraph::new(prompt)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(prompt_style)
+        .alignment(Alignment::Center);
+    f.render_widget(paragraph, chunks[1]);
+}
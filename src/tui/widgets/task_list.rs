@@ -9,9 +9,11 @@ use crate::Config;
 use crate::tui::app::ListViewMode;
 use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
 use crate::tui::widgets::tags::{parse_tags, format_tags_brackets};
-use std::collections::HashMap;
+use crate::tui::widgets::selection::{selection_marker, list_title};
+use std::collections::{BTreeSet, HashMap};
 
-pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count: usize, list_state: &mut ListState, config: &Config, view_mode: ListViewMode) {
+#[allow(clippy::too_many_arguments)]
+pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count: usize, list_state: &mut ListState, config: &Config, view_mode: ListViewMode, selection: Option<&BTreeSet<i64>>) {
     // Calculate max width for truncation (account for borders and padding)
     let max_width = area.width.saturating_sub(4) as usize; // 2 for borders, 2 for padding
     
@@ -27,6 +29,7 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
         ListViewMode::Simple => {
             tasks.iter().map(|task| {
                 let archived_prefix = if task.archived { "[A] " } else { "" };
+                let selection_prefix = selection_marker(selection, task.id);
                 let status_indicator = match task.status.as_str() {
                     "done" => "✓",
                     _ => "○",
@@ -36,7 +39,8 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
                     .map(|d| format!(" [{}]", d))
                     .unwrap_or_default();
                 
-                let mut title = format!("{} {}{} {}", 
+                let mut title = format!("{}{} {}{} {}", 
+                    selection_prefix,
                     status_indicator,
                     archived_prefix,
                     task.title,
@@ -54,6 +58,7 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
         ListViewMode::TwoLine => {
             tasks.iter().map(|task| {
                 let archived_prefix = if task.archived { "[A] " } else { "" };
+                let selection_prefix = selection_marker(selection, task.id);
                 let status_indicator = match task.status.as_str() {
                     "done" => "✓",
                     _ => "○",
@@ -63,7 +68,8 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
                     .map(|d| format!(" [{}]", d))
                     .unwrap_or_default();
                 
-                let mut first_line = format!("{} {}{} {}", 
+                let mut first_line = format!("{}{} {}{} {}", 
+                    selection_prefix,
                     status_indicator,
                     archived_prefix,
                     task.title,
@@ -121,6 +127,7 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
                 items.push(ListItem::new("[Untagged]").style(Style::default().fg(parse_color(&active_theme.tab_bg))));
                 for task in untagged {
                     let archived_prefix = if task.archived { "[A] " } else { "" };
+                    let selection_prefix = selection_marker(selection, task.id);
                     let status_indicator = match task.status.as_str() {
                         "done" => "✓",
                         _ => "○",
@@ -130,7 +137,8 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
                         .map(|d| format!(" [{}]", d))
                         .unwrap_or_default();
                     
-                    let mut title = format!("  {} {}{} {}", 
+                    let mut title = format!("  {}{} {}{} {}", 
+                        selection_prefix,
                         status_indicator,
                         archived_prefix,
                         task.title,
@@ -151,6 +159,7 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
                 items.push(ListItem::new(format!("[{}]", tag)).style(Style::default().fg(parse_color(&active_theme.tab_bg))));
                 for task in &tag_map[&tag] {
                     let archived_prefix = if task.archived { "[A] " } else { "" };
+                    let selection_prefix = selection_marker(selection, task.id);
                     let status_indicator = match task.status.as_str() {
                         "done" => "✓",
                         _ => "○",
@@ -160,7 +169,8 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
                         .map(|d| format!(" [{}]", d))
                         .unwrap_or_default();
                     
-                    let mut title = format!("  {} {}{} {}", 
+                    let mut title = format!("  {}{} {}{} {}", 
+                        selection_prefix,
                         status_indicator,
                         archived_prefix,
                         task.title,
@@ -192,7 +202,7 @@ pub fn render_task_list(f: &mut Frame, area: Rect, tasks: &[Task], total_count:
     let list_area = list_areas[0];
     let scrollbar_area = list_areas[1];
 
-    let title = format!("Items ({} of {})", tasks.len(), total_count);
+    let title = list_title(tasks.len(), total_count, selection);
     let list = List::new(items.clone())
         .block(Block::default().borders(Borders::ALL).title(title))
         .style(Style::default().fg(parse_color(&active_theme.fg)))
@@ -0,0 +1,120 @@
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Clear};
+use ratatui::style::{Style, Modifier};
+use ratatui::Frame;
+use ratatui::layout::{Rect, Alignment, Constraint, Layout, Direction, Flex};
+use crate::tui::App;
+use crate::tui::app::{SelectedItem, TrashConfirm};
+use crate::tui::widgets::color::{parse_color, get_contrast_text_color};
+
+/// Calculate popup area (centered, with specified width and height percentages)
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+/// Render the trash view as a popup overlay
+pub fn render_trash_view(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref state) = app.modals.trash else {
+        return;
+    };
+
+    let active_theme = app.config.get_active_theme();
+    let fg_color = parse_color(&active_theme.fg);
+    let bg_color = parse_color(&active_theme.bg);
+    let highlight_bg = parse_color(&active_theme.highlight_bg);
+    let highlight_fg = if active_theme.highlight_fg.is_empty() {
+        get_contrast_text_color(highlight_bg)
+    } else {
+        parse_color(&active_theme.highlight_fg)
+    };
+
+    // Calculate popup area (60% width, 60% height, centered)
+    let popup_area = popup_area(area, 60, 60);
+
+    // Clear the background first
+    f.render_widget(Clear, popup_area);
+
+    // Split into item list and prompt line
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+        ])
+        .split(popup_area);
+
+    let items: Vec<ListItem> = if state.items.is_empty() {
+        vec![ListItem::new("Trash is empty")]
+    } else {
+        state.items.iter().map(|item| {
+            let type_label = match item {
+                SelectedItem::Task(_) => "[Task]",
+                SelectedItem::Note(_) => "[Note]",
+                SelectedItem::Journal(_) => "[Journal]",
+            };
+            ListItem::new(format!(
+                "{} {}  (deleted {})",
+                type_label,
+                item.display_title(),
+                item.deleted_at().unwrap_or_default()
+            ))
+        }).collect()
+    };
+
+    let retention = if app.config.trash_retention_days > 0 {
+        format!("purged after {} days", app.config.trash_retention_days)
+    } else {
+        "kept until purged".to_string()
+    };
+    let title = format!("Trash ({} items, {})", state.items.len(), retention);
+
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(Style::default().fg(fg_color).bg(bg_color))
+        .highlight_style(
+            Style::default()
+                .fg(highlight_fg)
+                .bg(highlight_bg)
+                .add_modifier(Modifier::BOLD)
+        );
+
+    let mut list_state = state.list_state;
+    if state.items.is_empty() {
+        list_state.select(None);
+    }
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+    // Prompt line: confirmation question or available actions
+    let prompt = match state.confirm {
+        Some(TrashConfirm::Purge) => {
+            let name = state.items.get(state.selected_index)
+                .map(|item| item.display_title())
+                .unwrap_or_default();
+            format!("Permanently delete '{}'? (y/n)", name)
+        }
+        Some(TrashConfirm::Empty) => {
+            format!("Permanently delete all {} items in the trash? (y/n)", state.items.len())
+        }
+        None => "Enter/r: Restore  d: Delete permanently  E: Empty trash  Esc: Close".to_string(),
+    };
+    let prompt_style = if state.confirm.is_some() {
+        Style::default().fg(highlight_fg).bg(highlight_bg)
+    } else {
+        Style::default().fg(fg_color).bg(bg_color)
+    };
+
+    let paragraph = Paragraph::new(prompt)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(fg_color).bg(bg_color)))
+        .style(prompt_style)
+        .alignment(Alignment::Center);
+    f.render_widget(paragraph, chunks[1]);
+}
@@ -60,6 +60,84 @@ pub fn get_current_date_string() -> String {
     chrono::Utc::now().format("%Y-%m-%d").to_string()
 }
 
+/// Format a file size for display (e.g. "512 B", "1.4 KB", "3.0 MB")
+pub fn format_file_size(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Open a file with the system's default application, without waiting for it to exit
+pub fn open_with_default_app(path: &std::path::Path) -> std::io::Result<()> {
+    use std::process::{Command, Stdio};
+
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    // The opener's output would garble the TUI
+    command
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// Read a passphrase: typed without echo when stdin is a terminal, else the first line of stdin
+pub fn read_passphrase(prompt: &str) -> std::io::Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line)?;
+        return Ok(line.trim_end_matches(['\n', '\r']).to_string());
+    }
+
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+    crossterm::terminal::enable_raw_mode()?;
+    let mut passphrase = String::new();
+    let result = loop {
+        let key_event = match event::read() {
+            Ok(Event::Key(key_event)) if key_event.kind == KeyEventKind::Press => key_event,
+            Ok(_) => continue,
+            Err(e) => break Err(e),
+        };
+        match key_event.code {
+            KeyCode::Enter => break Ok(()),
+            KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                break Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "cancelled"));
+            }
+            KeyCode::Backspace => {
+                passphrase.pop();
+            }
+            KeyCode::Char(c) => passphrase.push(c),
+            _ => {}
+        }
+    };
+    crossterm::terminal::disable_raw_mode()?;
+    eprintln!();
+    result.map(|_| passphrase)
+}
+
 /// Parsed key binding information
 #[derive(Debug, Clone)]
 pub struct ParsedKeyBinding {
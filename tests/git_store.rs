@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use tnj_tui::git_store;
+use tnj_tui::models::{ItemKind, Notebook, SyncKind};
+use tnj_tui::{Database, Note, Store, Task};
+
+fn run_git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@localhost"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Everything a database holds, by UUID, to compare the two clones by
+fn contents(db: &Database) -> Vec<String> {
+    let notebooks = db.get_uuids(SyncKind::Notebook).unwrap();
+    let notebook = |id: Option<i64>| id.and_then(|id| notebooks.get(&id).cloned());
+    let mut contents: Vec<String> = db.get_all_notebooks().unwrap()
+        .into_iter()
+        .map(|n| format!("notebook {} {}", n.uuid.unwrap(), n.name))
+        .collect();
+    contents.extend(db.dump_tasks().unwrap().into_iter().map(|t| {
+        format!("task {} {} {} {:?} {:?}", t.uuid.unwrap(), t.title, t.status, t.description, notebook(t.notebook_id))
+    }));
+    contents.extend(db.dump_notes().unwrap().into_iter().map(|n| {
+        format!("note {} {} {:?} {:?}", n.uuid.unwrap(), n.title, n.content, notebook(n.notebook_id))
+    }));
+    contents.extend(db.dump_journals().unwrap().into_iter().map(|j| {
+        format!("journal {} {} {:?} {:?}", j.uuid.unwrap(), j.date, j.content, notebook(j.notebook_id))
+    }));
+    contents.sort();
+    contents
+}
+
+#[test]
+fn clones_converge_through_a_bare_repository() {
+    let tmp = tempfile::tempdir().unwrap();
+    let remote = tmp.path().join("remote.git");
+    fs::create_dir(&remote).unwrap();
+    run_git(&remote, &["init", "--quiet", "--bare"]);
+    let remote_url = remote.to_string_lossy().to_string();
+    let (dir_a, dir_b) = (tmp.path().join("a"), tmp.path().join("b"));
+
+    // First machine: some data, pushed to the remote
+    let a = Database::new(tmp.path().join("a.db").to_str().unwrap()).unwrap();
+    let work = a.insert_notebook(&Notebook::new("Work".to_string())).unwrap();
+    let mut task = Task::new("Write report".to_string());
+    task.notebook_id = Some(work);
+    let task_a = a.insert_task(&task).unwrap();
+    let mut note = Note::new("Ideas".to_string());
+    note.content = Some("First idea".to_string());
+    let note_a = a.insert_note(&note).unwrap();
+    git_store::init(&a, &dir_a, Some(&remote_url)).unwrap();
+    git_store::push(&a, &dir_a).unwrap();
+
+    // Second machine: clone it
+    let b = Database::new(tmp.path().join("b.db").to_str().unwrap()).unwrap();
+    git_store::clone(&b, &remote_url, &dir_b).unwrap();
+    assert_eq!(contents(&a), contents(&b));
+    assert_eq!(b.get_all_notebooks().unwrap().len(), 1);
+
+    // An edit on the second machine reaches the first
+    let task_b = b.dump_tasks().unwrap()[0].id.unwrap();
+    let mut task = b.get_task(task_b).unwrap();
+    task.status = "done".to_string();
+    task.description = Some("Sent on Friday".to_string());
+    b.update_task(&task).unwrap();
+    git_store::push(&b, &dir_b).unwrap();
+    git_store::pull(&a, &dir_a).unwrap();
+    assert_eq!(a.get_task(task_a).unwrap().status, "done");
+    assert_eq!(contents(&a), contents(&b));
+
+    // An edit and a deletion on the first machine reach the second
+    let mut note = a.get_note(note_a).unwrap();
+    note.content = Some("Second idea".to_string());
+    a.update_note(&note).unwrap();
+    a.delete_task(task_a).unwrap();
+    a.purge_item(ItemKind::Task, task_a).unwrap();
+    git_store::push(&a, &dir_a).unwrap();
+    git_store::pull(&b, &dir_b).unwrap();
+    assert!(b.dump_tasks().unwrap().is_empty());
+    assert_eq!(b.dump_notes().unwrap()[0].content.as_deref(), Some("Second idea"));
+    assert_eq!(contents(&a), contents(&b));
+}
+
+#[test]
+fn file_written_by_hand_is_kept_until_it_is_replaced() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path().join("repo");
+    let db = Database::new(tmp.path().join("app.db").to_str().unwrap()).unwrap();
+    git_store::init(&db, &dir, None).unwrap();
+
+    let hand_written = dir.join("inbox.md");
+    fs::write(&hand_written, "---\ntype: note\ntitle: Written by hand\n---\n\nSome text\n").unwrap();
+    run_git(&dir, &["add", "inbox.md"]);
+    run_git(&dir, &["commit", "--quiet", "-m", "Add a note by hand"]);
+
+    // Reading the commit keeps the file: its text is not yet anywhere else in the repository
+    git_store::refresh(&db, &dir).unwrap();
+    assert!(hand_written.exists());
+    let notes = db.dump_notes().unwrap();
+    assert_eq!(notes.len(), 1);
+    let uuid = notes[0].uuid.clone().unwrap();
+
+    // Reading it again before it is replaced updates the same note
+    fs::write(&hand_written, "---\ntype: note\ntitle: Written by hand\n---\n\nMore text\n").unwrap();
+    run_git(&dir, &["commit", "--quiet", "-am", "Edit the note by hand"]);
+    git_store::refresh(&db, &dir).unwrap();
+    let notes = db.dump_notes().unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].content.as_deref(), Some("More text"));
+
+    git_store::save(&db, &dir).unwrap();
+    assert!(!hand_written.exists());
+    let replacement = markdown_files(&dir).into_iter().find(|name| name.contains(&uuid));
+    assert!(replacement.is_some(), "no file named after {}", uuid);
+    assert_eq!(db.dump_notes().unwrap().len(), 1);
+}
+
+fn markdown_files(dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            if !path.ends_with(".git") {
+                files.extend(markdown_files(&path));
+            }
+        } else if path.extension().is_some_and(|extension| extension == "md") {
+            files.push(path.to_string_lossy().to_string());
+        }
+    }
+    files
+}